@@ -9,6 +9,7 @@
 
 use alloc::vec::Vec;
 use execution_tape::host::Host;
+use execution_tape::vm::Trap;
 
 use crate::access::NodeId;
 use crate::graph::{ExecutionGraph, GraphError};
@@ -59,9 +60,10 @@ impl<H: Host> Dispatcher<H> for InlineDispatcher {
         }
 
         let mut to_run: Vec<NodeId> = plan.take_nodes();
-        for node in to_run.drain(..) {
-            graph.execute_scheduled_node(node)?;
+        for ix in 0..to_run.len() {
+            execute_or_requeue(graph, &to_run, ix)?;
         }
+        to_run.clear();
         Ok(to_run)
     }
 
@@ -80,8 +82,9 @@ impl<H: Host> Dispatcher<H> for InlineDispatcher {
         let mut report = RunDetailReport::default();
         let mut to_run: Vec<NodeId> = plan.take_nodes();
 
-        for node in to_run.drain(..) {
-            graph.execute_scheduled_node(node)?;
+        for ix in 0..to_run.len() {
+            let node = to_run[ix];
+            execute_or_requeue(graph, &to_run, ix)?;
             if let Some(t) = trace.as_mut()
                 && let Some(r) = t.take_report_for(node)
             {
@@ -89,10 +92,30 @@ impl<H: Host> Dispatcher<H> for InlineDispatcher {
             }
         }
 
+        to_run.clear();
         Ok((to_run, report))
     }
 }
 
+/// Executes `to_run[ix]`, re-marking it and every later node dirty if the run was interrupted.
+///
+/// Interrupts are a caller request rather than a node failure, so the interrupted plan must stay
+/// resumable by the next run after the handle is reset.
+#[inline]
+fn execute_or_requeue<H: Host>(
+    graph: &mut ExecutionGraph<H>,
+    to_run: &[NodeId],
+    ix: usize,
+) -> Result<(), GraphError> {
+    let result = graph.execute_scheduled_node(to_run[ix]);
+    if let Err(GraphError::Trap { trap, .. }) = &result
+        && trap.trap == Trap::Interrupted
+    {
+        graph.requeue_nodes(&to_run[ix..]);
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     extern crate std;
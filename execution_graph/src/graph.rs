@@ -16,10 +16,11 @@ use execution_tape::host::AccessSink;
 use execution_tape::host::Host;
 use execution_tape::host::ResourceKeyRef;
 use execution_tape::host::SigHash;
+use execution_tape::host_policy::{HostPolicy, HostPolicyError};
 use execution_tape::trace::{TraceMask, TraceSink};
 use execution_tape::value::{FuncId, Value};
 use execution_tape::verifier::VerifiedProgram;
-use execution_tape::vm::{ExecutionContext, Limits, TrapInfo, Vm};
+use execution_tape::vm::{ExecutionContext, InterruptHandle, Limits, TrapInfo, Vm};
 use hashbrown::HashMap;
 
 use crate::access::{Access, AccessLog, HostOpId, NodeId, ResourceKey};
@@ -96,6 +97,8 @@ pub enum GraphError {
         /// Signature hash carried in bytecode/program.
         sig_hash: SigHash,
     },
+    /// A node program violates the graph's host policy.
+    HostPolicy(HostPolicyError),
     /// VM execution trapped.
     Trap {
         /// Node being executed when the VM trapped.
@@ -166,6 +169,7 @@ impl fmt::Display for GraphError {
                 node.as_u64(),
                 sig_hash.0
             ),
+            Self::HostPolicy(e) => write!(f, "node program rejected: {e}"),
             Self::Trap { node, trap } => {
                 write!(
                     f,
@@ -267,6 +271,7 @@ pub struct ExecutionGraph<H: Host> {
     scratch: Scratch,
     strict_deps: bool,
     collect_access: bool,
+    host_policy: Option<Arc<HostPolicy>>,
 }
 
 #[derive(Debug, Default)]
@@ -329,6 +334,7 @@ impl<H: Host> ExecutionGraph<H> {
             scratch: Scratch::default(),
             strict_deps: false,
             collect_access: false,
+            host_policy: None,
         }
     }
 
@@ -341,6 +347,32 @@ impl<H: Host> ExecutionGraph<H> {
         self.strict_deps = strict;
     }
 
+    /// Sets the host policy that node programs must satisfy.
+    ///
+    /// [`ExecutionGraph::add_node`] rejects programs that violate the policy with
+    /// [`GraphError::HostPolicy`]; nodes added earlier are not re-checked. Call quotas are
+    /// enforced by constructing the graph with a
+    /// [`PolicyHost`](execution_tape::host_policy::PolicyHost).
+    pub fn set_host_policy(&mut self, policy: Option<Arc<HostPolicy>>) {
+        self.host_policy = policy;
+    }
+
+    /// Returns a handle that interrupts graph runs, possibly from another thread.
+    ///
+    /// An interrupt stops the node currently executing with
+    /// [`Trap::Interrupted`](execution_tape::vm::Trap::Interrupted) and prevents any further node
+    /// in the same run from starting. The interrupted node and all nodes not yet executed stay
+    /// dirty, so the next run after [`InterruptHandle::reset`] resumes the remaining work.
+    #[must_use]
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.vm.interrupt_handle()
+    }
+
+    /// Replaces the interrupt handle polled by this graph's VM.
+    pub fn set_interrupt_handle(&mut self, handle: InterruptHandle) {
+        self.vm.set_interrupt_handle(handle);
+    }
+
     /// Enables or disables collection of per-node access logs.
     ///
     /// When enabled, each node's full [`AccessLog`] (bindings, tape accesses, output writes) is
@@ -366,9 +398,9 @@ impl<H: Host> ExecutionGraph<H> {
     ///
     /// `input_names` defines the mapping from per-node binding names to positional function args.
     ///
-    /// Returns [`GraphError::BadEntryFunc`] if `entry` is not present in `program`, or
+    /// Returns [`GraphError::BadEntryFunc`] if `entry` is not present in `program`,
     /// [`GraphError::BadInputArity`] if `input_names` does not match the entry function's
-    /// argument count.
+    /// argument count, or [`GraphError::HostPolicy`] if `program` violates the graph's host policy.
     pub fn add_node(
         &mut self,
         program: Arc<VerifiedProgram>,
@@ -382,6 +414,9 @@ impl<H: Host> ExecutionGraph<H> {
             .functions
             .get(entry.0 as usize)
             .ok_or(GraphError::BadEntryFunc { func: entry })?;
+        if let Some(policy) = &self.host_policy {
+            policy.check(program_ref).map_err(GraphError::HostPolicy)?;
+        }
         let expected_inputs = func.arg_count as usize;
         let actual_inputs = input_names.len();
         if actual_inputs != expected_inputs {
@@ -937,6 +972,21 @@ impl<H: Host> ExecutionGraph<H> {
         self.run_plan_with_report(plan)
     }
 
+    /// Internal dispatch hook: marks every output of `nodes` dirty again.
+    pub(crate) fn requeue_nodes(&mut self, nodes: &[NodeId]) {
+        for &node in nodes {
+            let Ok(index) = usize::try_from(node.as_u64()) else {
+                continue;
+            };
+            let Some(n) = self.nodes.get(index) else {
+                continue;
+            };
+            for &id in &n.output_ids {
+                self.dirty.mark_dirty(id);
+            }
+        }
+    }
+
     /// Internal dispatch hook: executes one already-scheduled node.
     #[inline]
     pub(crate) fn execute_scheduled_node(&mut self, node: NodeId) -> Result<(), GraphError> {
@@ -1572,6 +1622,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn strict_deps_allows_pure_host_calls_without_accesses() {
+        #[derive(Debug, Default)]
+        struct PureHost;
+
+        impl Host for PureHost {
+            fn call(
+                &mut self,
+                _symbol: &str,
+                _sig_hash: SigHash,
+                args: &[ValueRef<'_>],
+                rets: &mut [Value],
+                _ctx: HostContext<'_, '_>,
+            ) -> Result<u64, HostError> {
+                let [ValueRef::I64(x)] = args else {
+                    return Err(HostError::SignatureMismatch);
+                };
+                rets[0] = Value::I64(x * 2);
+                Ok(0)
+            }
+        }
+
+        let mut pb = ProgramBuilder::new();
+        let host_sig = pb.pure_host_sig_for(
+            "double",
+            HostSig {
+                args: vec![ValueType::I64],
+                rets: vec![ValueType::I64],
+            },
+        );
+
+        let mut a = Asm::new();
+        a.const_i64(1, 21);
+        a.host_call_pure(host_sig, &[1], &[2]);
+        a.ret(0, &[2]);
+
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_output_name(f, 0, "value").unwrap();
+
+        let prog = Arc::new(pb.build_verified().unwrap());
+
+        let mut g = ExecutionGraph::new(PureHost, Limits::default());
+        let n = g.add_node(prog, f, vec![]).unwrap();
+        g.set_strict_deps(true);
+
+        g.run_all().unwrap();
+        assert_eq!(
+            g.node_outputs(n).unwrap().get("value"),
+            Some(&Value::I64(42))
+        );
+    }
+
     #[test]
     fn run_all_errors_on_missing_input_binding() {
         let mut pb = ProgramBuilder::new();
@@ -1632,6 +1742,47 @@ mod tests {
         assert_eq!(trap.trap, Trap::DivByZero);
     }
 
+    #[test]
+    fn interrupted_run_all_leaves_remaining_work_dirty() {
+        let mut pb = ProgramBuilder::new();
+        let mut a = Asm::new();
+        a.ret(0, &[1]);
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::I64],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_output_name(f, 0, "value").unwrap();
+        let prog = Arc::new(pb.build_verified().unwrap());
+
+        let mut g = ExecutionGraph::new(HostNoop, Limits::default());
+        let a = g.add_node(prog.clone(), f, vec!["in".into()]).unwrap();
+        let b = g.add_node(prog, f, vec!["in".into()]).unwrap();
+        g.set_input_value(a, "in", Value::I64(7)).unwrap();
+        g.connect(a, "value", b, "in").unwrap();
+
+        let handle = g.interrupt_handle();
+        handle.interrupt();
+        let Err(GraphError::Trap { node, trap }) = g.run_all() else {
+            panic!("interrupt should surface as a graph trap");
+        };
+        assert_eq!(node, a);
+        assert_eq!(trap.trap, Trap::Interrupted);
+        assert_eq!(g.node_run_count(a), Some(0));
+        assert_eq!(g.node_run_count(b), Some(0));
+
+        handle.reset();
+        assert_eq!(g.run_all().unwrap().executed_nodes, 2);
+        assert_eq!(
+            g.node_outputs(b).unwrap().get("value"),
+            Some(&Value::I64(7))
+        );
+    }
+
     #[test]
     fn graph_builder_errors_on_bad_entry_func() {
         let mut pb = ProgramBuilder::new();
@@ -1681,6 +1832,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn add_node_enforces_host_policy() {
+        let mut pb = ProgramBuilder::new();
+        let host_sig = pb.host_sig_for(
+            "fs.read",
+            HostSig {
+                args: vec![],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let mut a = Asm::new();
+        a.host_call(0, host_sig, 0, &[], &[1]);
+        a.ret(0, &[1]);
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        let prog = Arc::new(pb.build_verified().unwrap());
+
+        let mut policy = HostPolicy::new();
+        policy.allow("math.*");
+        let mut g = ExecutionGraph::new(HostNoop, Limits::default());
+        g.set_host_policy(Some(Arc::new(policy.clone())));
+        let Err(GraphError::HostPolicy(err)) = g.add_node(prog.clone(), f, vec![]) else {
+            panic!("expected a host policy error");
+        };
+        assert_eq!(err.violations.len(), 1);
+        assert_eq!(&*err.violations[0].symbol, "fs.read");
+
+        policy.allow("fs.read");
+        g.set_host_policy(Some(Arc::new(policy)));
+        assert!(g.add_node(prog, f, vec![]).is_ok());
+    }
+
     #[test]
     fn set_input_value_errors_on_unknown_input() {
         let mut pb = ProgramBuilder::new();
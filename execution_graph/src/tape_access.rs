@@ -296,7 +296,8 @@ impl AccessSink for NodeAccessSink<'_> {
 #[derive(Debug)]
 pub(crate) struct StrictDepsTrace<'a> {
     counter: &'a Cell<usize>,
-    stack: Vec<(usize, execution_tape::program::SymbolId, SigHash)>,
+    /// Open host-call scopes: access count at entry, symbol, sig hash, and whether it is pure.
+    stack: Vec<(usize, execution_tape::program::SymbolId, SigHash, bool)>,
     violation: Option<StrictDepsViolation>,
 }
 
@@ -325,7 +326,7 @@ impl TraceSink for StrictDepsTrace<'_> {
 
     fn scope_enter(
         &mut self,
-        _program: &execution_tape::program::Program,
+        program: &execution_tape::program::Program,
         kind: ScopeKind,
         _depth: usize,
         _func: execution_tape::value::FuncId,
@@ -333,12 +334,17 @@ impl TraceSink for StrictDepsTrace<'_> {
         _span_id: Option<u64>,
     ) {
         let ScopeKind::HostCall {
-            symbol, sig_hash, ..
+            host_sig,
+            symbol,
+            sig_hash,
         } = kind
         else {
             return;
         };
-        self.stack.push((self.counter.get(), symbol, sig_hash));
+        // Pure host calls depend only on their arguments, so recording no keys is expected.
+        let pure = program.host_sig(host_sig).is_some_and(|hs| hs.pure);
+        self.stack
+            .push((self.counter.get(), symbol, sig_hash, pure));
     }
 
     fn scope_exit(
@@ -354,11 +360,11 @@ impl TraceSink for StrictDepsTrace<'_> {
             return;
         };
 
-        let Some((start, symbol, sig_hash)) = self.stack.pop() else {
+        let Some((start, symbol, sig_hash, pure)) = self.stack.pop() else {
             return;
         };
 
-        if self.violation.is_some() {
+        if pure || self.violation.is_some() {
             return;
         }
         if self.counter.get() != start {
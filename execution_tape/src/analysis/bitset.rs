@@ -1,22 +1,24 @@
 // Copyright 2026 the Execution Tape Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-//! A small bitset used by verifier analyses.
+//! A small fixed-size bitset, used for register sets.
 
 extern crate alloc;
 
 use alloc::vec;
 use alloc::vec::Vec;
 
+/// A fixed-size set of indices `0..len`, e.g. registers.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct BitSet {
+pub struct BitSet {
     bits: Vec<u64>,
     len: usize,
 }
 
 impl BitSet {
+    /// Returns an empty set over `0..len`.
     #[must_use]
-    pub(crate) fn new_empty(len: usize) -> Self {
+    pub fn new_empty(len: usize) -> Self {
         let words = len.div_ceil(64);
         Self {
             bits: vec![0; words],
@@ -24,8 +26,9 @@ impl BitSet {
         }
     }
 
+    /// Returns a set containing all of `0..len`.
     #[must_use]
-    pub(crate) fn new_full(len: usize) -> Self {
+    pub fn new_full(len: usize) -> Self {
         let mut s = Self::new_empty(len);
         for w in &mut s.bits {
             *w = !0;
@@ -41,8 +44,9 @@ impl BitSet {
         s
     }
 
+    /// Returns `true` if `idx` is in the set.
     #[must_use]
-    pub(crate) fn get(&self, idx: usize) -> bool {
+    pub fn get(&self, idx: usize) -> bool {
         if idx >= self.len {
             return false;
         }
@@ -51,7 +55,8 @@ impl BitSet {
         (self.bits[w] >> b) & 1 == 1
     }
 
-    pub(crate) fn set(&mut self, idx: usize) {
+    /// Adds `idx` to the set. Out-of-range indices are ignored.
+    pub fn set(&mut self, idx: usize) {
         if idx >= self.len {
             return;
         }
@@ -60,7 +65,35 @@ impl BitSet {
         self.bits[w] |= 1_u64 << b;
     }
 
-    pub(crate) fn clear(&mut self, idx: usize) {
+    /// Returns `len`: the set ranges over `0..len`.
+    #[must_use]
+    pub fn domain_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set contains no indices.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&w| w == 0)
+    }
+
+    /// Returns the number of indices in the set.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Iterates the indices in the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter().enumerate().flat_map(|(w, &bits)| {
+            (0..64)
+                .filter(move |b| (bits >> b) & 1 == 1)
+                .map(move |b| w * 64 + b)
+        })
+    }
+
+    /// Removes `idx` from the set.
+    pub fn clear(&mut self, idx: usize) {
         if idx >= self.len {
             return;
         }
@@ -69,19 +102,22 @@ impl BitSet {
         self.bits[w] &= !(1_u64 << b);
     }
 
-    pub(crate) fn intersect_with(&mut self, other: &Self) {
+    /// Keeps only the indices also in `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
         for (a, b) in self.bits.iter_mut().zip(other.bits.iter()) {
             *a &= *b;
         }
     }
 
-    pub(crate) fn union_with(&mut self, other: &Self) {
+    /// Adds the indices in `other`.
+    pub fn union_with(&mut self, other: &Self) {
         for (a, b) in self.bits.iter_mut().zip(other.bits.iter()) {
             *a |= *b;
         }
     }
 
-    pub(crate) fn subtract_with(&mut self, other: &Self) {
+    /// Removes the indices in `other`.
+    pub fn subtract_with(&mut self, other: &Self) {
         for (a, b) in self.bits.iter_mut().zip(other.bits.iter()) {
             *a &= !*b;
         }
@@ -7,13 +7,17 @@ extern crate alloc;
 
 use alloc::vec;
 use alloc::vec::Vec;
+use core::ops::Range;
 
 use crate::bytecode::DecodedInstr;
 use crate::instr_operands;
 use crate::opcode::Opcode;
 
+/// A maximal straight-line run of instructions, entered only at its first instruction.
+///
+/// Blocks are numbered in pc order; block 0 is the function entry.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct BasicBlock {
+pub struct BasicBlock {
     pub(crate) start_pc: u32,
     pub(crate) end_pc: u32,
     pub(crate) instr_start: usize,
@@ -21,6 +25,32 @@ pub(crate) struct BasicBlock {
     pub(crate) succs: [Option<usize>; 2],
 }
 
+impl BasicBlock {
+    /// Returns the pc of the first instruction.
+    #[must_use]
+    pub fn start_pc(&self) -> u32 {
+        self.start_pc
+    }
+
+    /// Returns the pc just past the last instruction.
+    #[must_use]
+    pub fn end_pc(&self) -> u32 {
+        self.end_pc
+    }
+
+    /// Returns the indices of the block's instructions in the function's instruction stream.
+    #[must_use]
+    pub fn instrs(&self) -> Range<usize> {
+        self.instr_start..self.instr_end
+    }
+
+    /// Iterates the successor block indices: the fallthrough or jump target, then the `br`
+    /// false target, if any.
+    pub fn succs(&self) -> impl Iterator<Item = usize> + '_ {
+        self.succs.iter().copied().flatten()
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct InvalidJumpTarget {
     pub(crate) src_pc: u32,
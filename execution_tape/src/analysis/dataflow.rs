@@ -5,7 +5,8 @@
 //!
 //! The verifier currently needs several fixpoint computations over a CFG (must-init, must-types,
 //! liveness, etc). This module provides a simple worklist-based engine so analyses can share the
-//! iteration mechanics while keeping their lattice and transfer logic local.
+//! iteration mechanics while keeping their lattice and transfer logic local. Tooling plugs into
+//! the same engine through the [`Dataflow`] trait.
 //!
 //! Notes:
 //!
@@ -21,6 +22,8 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 use crate::analysis::cfg::BasicBlock;
+use crate::analysis::function::FunctionCfg;
+use crate::disasm::InstrView;
 
 /// Computes a forward dataflow fixpoint.
 ///
@@ -193,3 +196,95 @@ where
 
     (in_states, out_states)
 }
+
+/// The direction a [`Dataflow`] analysis propagates in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From the entry block along successor edges, e.g. reaching definitions.
+    Forward,
+    /// From exit blocks along predecessor edges, e.g. liveness.
+    Backward,
+}
+
+/// A dataflow analysis over a [`FunctionCfg`], solved with [`FunctionCfg::solve`].
+///
+/// The analysis defines a lattice of `State`s and a per-instruction transfer function; the
+/// solver iterates blocks to a fixpoint. As with any worklist solver, `meet_into` and `transfer`
+/// must be monotone over a finite-height lattice for iteration to terminate.
+pub trait Dataflow {
+    /// The analysis state at a program point.
+    type State: Clone + PartialEq;
+
+    /// The propagation direction.
+    const DIRECTION: Direction;
+
+    /// Returns the initial state of every block, and the meet over no edges.
+    fn bottom(&self, cfg: &FunctionCfg<'_>) -> Self::State;
+
+    /// Returns the state on entry to the function (forward analyses only).
+    ///
+    /// Defaults to [`Dataflow::bottom`].
+    fn entry(&self, cfg: &FunctionCfg<'_>) -> Self::State {
+        self.bottom(cfg)
+    }
+
+    /// Combines `incoming` into `acc` where control flow joins.
+    fn meet_into(&self, acc: &mut Self::State, incoming: &Self::State);
+
+    /// Applies `instr` to `state`.
+    ///
+    /// Forward analyses receive the state before the instruction and produce the state after it;
+    /// backward analyses the reverse.
+    fn transfer(&self, instr: InstrView<'_>, state: &mut Self::State);
+}
+
+/// Per-block fixpoint states of a [`Dataflow`] analysis.
+///
+/// States are in program order for both directions: `entry[b]` holds before block `b`'s first
+/// instruction and `exit[b]` after its last. Unreachable blocks keep the bottom state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataflowResult<S> {
+    /// State at the start of each block.
+    pub entry: Vec<S>,
+    /// State at the end of each block.
+    pub exit: Vec<S>,
+}
+
+impl FunctionCfg<'_> {
+    /// Solves `analysis` to a fixpoint over this function's blocks.
+    pub fn solve<D: Dataflow>(&self, analysis: &D) -> DataflowResult<D::State> {
+        let blocks = self.blocks();
+        let reachable = self.reachable();
+        let meet = |acc: &mut D::State, incoming: &D::State| analysis.meet_into(acc, incoming);
+        let (entry, exit) = match D::DIRECTION {
+            Direction::Forward => solve_forward(
+                blocks,
+                reachable,
+                analysis.entry(self),
+                analysis.bottom(self),
+                meet,
+                |_, block, state| {
+                    let mut state = state.clone();
+                    for ix in block.instrs() {
+                        analysis.transfer(self.instr(ix), &mut state);
+                    }
+                    state
+                },
+            ),
+            Direction::Backward => solve_backward(
+                blocks,
+                reachable,
+                analysis.bottom(self),
+                meet,
+                |_, block, state| {
+                    let mut state = state.clone();
+                    for ix in block.instrs().rev() {
+                        analysis.transfer(self.instr(ix), &mut state);
+                    }
+                    state
+                },
+            ),
+        };
+        DataflowResult { entry, exit }
+    }
+}
@@ -0,0 +1,662 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Static worst-case fuel bounds.
+//!
+//! A function has a finite bound when its reachable CFG is acyclic, it calls no function that
+//! (transitively) recurses, every host symbol it calls has a declared maximum cost in
+//! [`HostCosts`], and the cost model charges nothing for data-dependent work. The bound is the
+//! costliest path from entry to exit, charging each instruction as the VM does and each call
+//! with its callee's bound; indirect calls are charged with the costliest function whose
+//! signature the call could dispatch to.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::analysis::function::{AnalysisError, FunctionCfg};
+use crate::bytecode::{DecodedInstr, Instr};
+use crate::disasm::DisasmError;
+use crate::format::DecodeError;
+use crate::opcode::Opcode;
+use crate::program::{CallSigId, ConstEntry, ValueType};
+use crate::value::FuncId;
+use crate::verifier::VerifiedProgram;
+use crate::vm::CostModel;
+
+/// Declared worst-case extra fuel charged by host functions, by symbol.
+///
+/// This is the largest cost a host's [`Host::call`](crate::host::Host::call) returns for the
+/// symbol; the VM charges it on top of the `host_call` instruction itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostCosts {
+    costs: BTreeMap<Box<str>, u64>,
+}
+
+impl HostCosts {
+    /// Creates an empty table: every host call is unbounded until declared.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares that calls to `symbol` charge at most `max_cost` extra fuel.
+    pub fn declare(&mut self, symbol: &str, max_cost: u64) -> &mut Self {
+        self.costs.insert(symbol.into(), max_cost);
+        self
+    }
+
+    /// Returns the declared maximum cost of `symbol`.
+    #[must_use]
+    pub fn cost(&self, symbol: &str) -> Option<u64> {
+        self.costs.get(symbol).copied()
+    }
+}
+
+/// The worst-case fuel of a function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FuelBound {
+    /// No run of the function consumes more fuel than this.
+    Bounded(u64),
+    /// No static bound exists, for the given reason.
+    Unbounded(Unbounded),
+}
+
+impl FuelBound {
+    /// Returns the bound, if finite: a [`Limits::fuel`](crate::vm::Limits::fuel) the function
+    /// cannot exhaust.
+    #[must_use]
+    pub fn fuel_limit(&self) -> Option<u64> {
+        match self {
+            Self::Bounded(fuel) => Some(*fuel),
+            Self::Unbounded(_) => None,
+        }
+    }
+}
+
+/// Why a function has no static fuel bound.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Unbounded {
+    /// The function's CFG has a cycle, closed by the edge from the block ending at `from_pc`
+    /// back to the block starting at `header_pc`.
+    Loop {
+        /// Function containing the loop.
+        func: FuncId,
+        /// Pc of the branch closing the loop.
+        from_pc: u32,
+        /// Pc of the loop header.
+        header_pc: u32,
+    },
+    /// The function reaches a recursive call cycle. Each function in `cycle` calls the next,
+    /// and the last calls the first.
+    Recursion {
+        /// The functions on the cycle.
+        cycle: Vec<FuncId>,
+    },
+    /// A host call whose symbol has no declared cost in [`HostCosts`].
+    UndeclaredHostCost {
+        /// Function containing the call.
+        func: FuncId,
+        /// Pc of the call.
+        pc: u32,
+        /// Host symbol.
+        symbol: String,
+    },
+    /// An instruction whose cost scales with runtime data (e.g. `bytes.concat` with a nonzero
+    /// [`CostModel::per_byte`]).
+    DataDependentCost {
+        /// Function containing the instruction.
+        func: FuncId,
+        /// Pc of the instruction.
+        pc: u32,
+        /// The instruction's opcode.
+        opcode: Opcode,
+    },
+}
+
+impl fmt::Display for Unbounded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Loop {
+                func,
+                from_pc,
+                header_pc,
+            } => write!(
+                f,
+                "function {} pc={from_pc}: loop back to pc={header_pc}",
+                func.0
+            ),
+            Self::Recursion { cycle } => {
+                write!(f, "recursive call cycle:")?;
+                for func in cycle {
+                    write!(f, " {}", func.0)?;
+                }
+                Ok(())
+            }
+            Self::UndeclaredHostCost { func, pc, symbol } => write!(
+                f,
+                "function {} pc={pc}: host call {symbol:?} has no declared cost",
+                func.0
+            ),
+            Self::DataDependentCost { func, pc, opcode } => write!(
+                f,
+                "function {} pc={pc}: cost of {} depends on runtime data",
+                func.0,
+                opcode.mnemonic()
+            ),
+        }
+    }
+}
+
+/// Computes the worst-case fuel bound of every function in `program`, indexed by [`FuncId`].
+///
+/// `cost_model` is the run's [`Limits::cost_model`](crate::vm::Limits::cost_model), with `None`
+/// for the flat model.
+pub fn fuel_bounds(
+    program: &VerifiedProgram,
+    cost_model: Option<&CostModel>,
+    host_costs: &HostCosts,
+) -> Result<Vec<FuelBound>, AnalysisError> {
+    let flat = CostModel::new();
+    let mut analysis = FuelAnalysis {
+        program,
+        // The flat model charges 1 per instruction and never scales, like `CostModel::new`.
+        model: cost_model.unwrap_or(&flat),
+        host_costs,
+        state: vec![State::Pending; program.program().functions.len()],
+        stack: Vec::new(),
+    };
+    (0..analysis.state.len())
+        .map(|f| analysis.bound(FuncId(u32::try_from(f).unwrap_or(u32::MAX))))
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+enum State {
+    Pending,
+    InProgress,
+    Done(FuelBound),
+}
+
+struct FuelAnalysis<'a> {
+    program: &'a VerifiedProgram,
+    model: &'a CostModel,
+    host_costs: &'a HostCosts,
+    state: Vec<State>,
+    /// Functions whose bounds are being computed, outermost first.
+    stack: Vec<FuncId>,
+}
+
+/// A function whose bound waits on its callees.
+struct Frame<'a> {
+    func: FuncId,
+    cfg: FunctionCfg<'a>,
+    /// Reachable blocks in postorder.
+    order: Vec<usize>,
+    /// Functions the body may call, directly or indirectly.
+    callees: Vec<FuncId>,
+    /// Index of the next callee to visit.
+    next: usize,
+}
+
+impl<'a> FuelAnalysis<'a> {
+    /// Computes the bound of `root` and of every pending function it calls.
+    ///
+    /// Walks the call graph in post-order with an explicit stack of frames, so a function's
+    /// callees are bounded before it is and deep call chains do not recurse.
+    fn bound(&mut self, root: FuncId) -> Result<FuelBound, AnalysisError> {
+        match self.state.get(root.0 as usize) {
+            None => return Err(AnalysisError::BadFuncId(root)),
+            Some(State::Done(bound)) => return Ok(bound.clone()),
+            Some(_) => {}
+        }
+
+        let mut frames: Vec<Frame<'a>> = Vec::new();
+        let mut pending = Some(root);
+        loop {
+            if let Some(func) = pending.take() {
+                self.state[func.0 as usize] = State::InProgress;
+                self.stack.push(func);
+                match self.enter(func)? {
+                    Ok(frame) => frames.push(frame),
+                    Err(bound) => self.finish(func, bound),
+                }
+            }
+            let Some(top) = frames.last_mut() else {
+                break;
+            };
+            while let Some(&callee) = top.callees.get(top.next) {
+                top.next += 1;
+                if matches!(self.state[callee.0 as usize], State::Pending) {
+                    pending = Some(callee);
+                    break;
+                }
+            }
+            if pending.is_none() {
+                let frame = frames.pop().expect("checked above");
+                let bound = self.function_bound(&frame)?;
+                self.finish(frame.func, bound);
+            }
+        }
+
+        match &self.state[root.0 as usize] {
+            State::Done(bound) => Ok(bound.clone()),
+            _ => unreachable!("the walk finishes its root"),
+        }
+    }
+
+    fn finish(&mut self, func: FuncId, bound: FuelBound) {
+        self.stack.pop();
+        self.state[func.0 as usize] = State::Done(bound);
+    }
+
+    /// Prepares `func` for bounding, or returns its bound directly when its CFG has a cycle.
+    fn enter(&self, func: FuncId) -> Result<Result<Frame<'a>, FuelBound>, AnalysisError> {
+        let cfg = FunctionCfg::new(self.program, func)?;
+        if let Some((from, header)) = cycle_edge(&cfg) {
+            let blocks = cfg.blocks();
+            let last = blocks[from].instrs().end - 1;
+            return Ok(Err(FuelBound::Unbounded(Unbounded::Loop {
+                func,
+                from_pc: cfg.decoded()[last].offset,
+                header_pc: blocks[header].start_pc(),
+            })));
+        }
+
+        let mut order = cfg.reverse_postorder();
+        order.reverse();
+        let mut callees = Vec::new();
+        for &b in &order {
+            for ix in cfg.blocks()[b].instrs() {
+                match &cfg.decoded()[ix].instr {
+                    Instr::Call { func_id, .. } => {
+                        if func_id.0 as usize >= self.state.len() {
+                            return Err(AnalysisError::BadFuncId(*func_id));
+                        }
+                        callees.push(*func_id);
+                    }
+                    Instr::CallIndirect { call_sig, .. } => {
+                        callees.extend(self.indirect_targets(CallSigId(*call_sig))?);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(Ok(Frame {
+            func,
+            cfg,
+            order,
+            callees,
+            next: 0,
+        }))
+    }
+
+    /// Returns the bound of a function that [`FuelAnalysis::bound`] has already visited.
+    fn visited_bound(&self, func: FuncId) -> FuelBound {
+        match &self.state[func.0 as usize] {
+            State::Done(bound) => bound.clone(),
+            State::InProgress => {
+                let start = self.stack.iter().position(|&f| f == func).unwrap_or(0);
+                FuelBound::Unbounded(Unbounded::Recursion {
+                    cycle: self.stack[start..].to_vec(),
+                })
+            }
+            State::Pending => unreachable!("callees are visited before their callers"),
+        }
+    }
+
+    fn function_bound(&self, frame: &Frame<'_>) -> Result<FuelBound, AnalysisError> {
+        let cfg = &frame.cfg;
+        let blocks = cfg.blocks();
+        // Successors come first in postorder, so `worst[s]` is final when block `b` reads it.
+        let mut worst = vec![0_u64; blocks.len()];
+        for &b in &frame.order {
+            let mut cost = 0_u64;
+            for ix in blocks[b].instrs() {
+                match self.instr_cost(frame.func, &cfg.decoded()[ix])? {
+                    FuelBound::Bounded(c) => cost = cost.saturating_add(c),
+                    unbounded @ FuelBound::Unbounded(_) => return Ok(unbounded),
+                }
+            }
+            let tail = cfg.successors(b).map(|s| worst[s]).max().unwrap_or(0);
+            worst[b] = cost.saturating_add(tail);
+        }
+        Ok(FuelBound::Bounded(worst.first().copied().unwrap_or(0)))
+    }
+
+    fn instr_cost(&self, func: FuncId, di: &DecodedInstr) -> Result<FuelBound, AnalysisError> {
+        let program = self.program.program();
+        let opcode = Opcode::from_u8(di.opcode).expect("decoded instruction opcode must be known");
+        let scaled = |per_unit: u64, units: usize| {
+            per_unit.saturating_mul(u64::try_from(units).unwrap_or(u64::MAX))
+        };
+        let mut cost = self.model.base_cost(opcode);
+        let callee = match &di.instr {
+            Instr::ConstPool { idx, .. } => {
+                if let Some(ConstEntry::Bytes(r) | ConstEntry::Str(r)) =
+                    program.const_pool.get(idx.0 as usize)
+                {
+                    cost = cost.saturating_add(scaled(self.model.per_byte, r.len as usize));
+                }
+                FuelBound::Bounded(0)
+            }
+            Instr::BytesConcat { .. }
+            | Instr::StrConcat { .. }
+            | Instr::BytesSlice { .. }
+            | Instr::StrSlice { .. }
+            | Instr::StrToBytes { .. }
+            | Instr::BytesToStr { .. }
+                if self.model.per_byte != 0 =>
+            {
+                return Ok(FuelBound::Unbounded(Unbounded::DataDependentCost {
+                    func,
+                    pc: di.offset,
+                    opcode,
+                }));
+            }
+            Instr::TupleNew { values, .. }
+            | Instr::StructNew { values, .. }
+            | Instr::ArrayNew { values, .. } => {
+                cost = cost.saturating_add(scaled(self.model.per_agg_value, values.len()));
+                FuelBound::Bounded(0)
+            }
+            Instr::Call { func_id, args, .. } => {
+                cost = cost.saturating_add(scaled(self.model.per_arg, args.len()));
+                self.visited_bound(*func_id)
+            }
+            Instr::HostCall { host_sig, args, .. } | Instr::HostCallPure { host_sig, args, .. } => {
+                cost = cost.saturating_add(scaled(self.model.per_arg, args.len()));
+                let symbol = program
+                    .host_sig(*host_sig)
+                    .and_then(|hs| program.symbol_str(hs.symbol).ok())
+                    .unwrap_or("");
+                match self.host_costs.cost(symbol) {
+                    Some(extra) => FuelBound::Bounded(extra),
+                    None => FuelBound::Unbounded(Unbounded::UndeclaredHostCost {
+                        func,
+                        pc: di.offset,
+                        symbol: symbol.into(),
+                    }),
+                }
+            }
+            Instr::CallIndirect { call_sig, args, .. } => {
+                cost = cost.saturating_add(scaled(self.model.per_arg, args.len()));
+                self.indirect_bound(CallSigId(*call_sig))?
+            }
+            _ => FuelBound::Bounded(0),
+        };
+        Ok(match callee {
+            FuelBound::Bounded(c) => FuelBound::Bounded(cost.saturating_add(c)),
+            unbounded @ FuelBound::Unbounded(_) => unbounded,
+        })
+    }
+
+    /// Returns the costliest bound among the functions an indirect call with `call_sig` may
+    /// reach.
+    fn indirect_bound(&self, call_sig: CallSigId) -> Result<FuelBound, AnalysisError> {
+        let mut worst = 0_u64;
+        for func in self.indirect_targets(call_sig)? {
+            match self.visited_bound(func) {
+                FuelBound::Bounded(c) => worst = worst.max(c),
+                unbounded @ FuelBound::Unbounded(_) => return Ok(unbounded),
+            }
+        }
+        Ok(FuelBound::Bounded(worst))
+    }
+
+    /// Returns the functions an indirect call with `call_sig` may reach: functions with that
+    /// signature, called as `Func`s, or with an extra leading `Agg` environment argument, called
+    /// as closures.
+    fn indirect_targets(&self, call_sig: CallSigId) -> Result<Vec<FuncId>, AnalysisError> {
+        let program = self.program.program();
+        let decode = |e| AnalysisError::Disasm(DisasmError::Decode(e));
+        let entry = program
+            .call_sig(call_sig)
+            .ok_or(decode(DecodeError::OutOfBounds))?;
+        let args = program.call_sig_args(entry).map_err(decode)?;
+        let rets = program.call_sig_rets(entry).map_err(decode)?;
+
+        let mut out = Vec::new();
+        for (ix, f) in program.functions.iter().enumerate() {
+            let (Ok(f_args), Ok(f_rets)) =
+                (program.function_arg_types(f), program.function_ret_types(f))
+            else {
+                continue;
+            };
+            let as_func = f_args == args;
+            let as_closure = f_args
+                .split_first()
+                .is_some_and(|(&env, rest)| env == ValueType::Agg && rest == args);
+            if f_rets == rets && (as_func || as_closure) {
+                out.push(FuncId(u32::try_from(ix).unwrap_or(u32::MAX)));
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Returns an edge `(from, to)` that closes a cycle through reachable blocks, if any.
+fn cycle_edge(cfg: &FunctionCfg<'_>) -> Option<(usize, usize)> {
+    if let Some(edge) = cfg.back_edges().first() {
+        return Some((edge.from, edge.header));
+    }
+    // A cycle with several entries has no back edge, but it still has an edge that runs against
+    // reverse postorder; an acyclic CFG has none.
+    let rpo = cfg.reverse_postorder();
+    let mut rank = vec![usize::MAX; cfg.blocks().len()];
+    for (i, &b) in rpo.iter().enumerate() {
+        rank[b] = i;
+    }
+    rpo.iter().find_map(|&b| {
+        cfg.successors(b)
+            .find(|&s| rank[s] <= rank[b])
+            .map(|s| (b, s))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::{Host, HostContext, HostError, HostSig, SigHash, ValueRef};
+    use crate::trace::TraceMask;
+    use crate::value::Value;
+    use crate::vm::{Limits, Trap, Vm};
+
+    /// Charges 10 extra fuel per call.
+    struct Tick;
+
+    impl Host for Tick {
+        fn call(
+            &mut self,
+            _symbol: &str,
+            _sig_hash: SigHash,
+            _args: &[ValueRef<'_>],
+            _rets: &mut [Value],
+            _ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            Ok(10)
+        }
+    }
+
+    fn sig(args: &[ValueType], rets: &[ValueType]) -> FunctionSig {
+        FunctionSig {
+            arg_types: args.to_vec(),
+            ret_types: rets.to_vec(),
+        }
+    }
+
+    #[test]
+    fn bounds_take_the_costliest_path_and_calls() {
+        let mut pb = ProgramBuilder::new();
+        let tick = pb.host_sig_for(
+            "clock.tick",
+            HostSig {
+                args: vec![],
+                rets: vec![],
+            },
+        );
+
+        // f0: `fn(b: bool) -> i64 { if b { 1 } else { host tick; 2 } }`
+        let mut a = Asm::new();
+        let yes = a.label();
+        let no = a.label();
+        a.br(1, yes, no);
+        a.place(yes).unwrap();
+        a.const_i64(2, 1);
+        a.ret(0, &[2]);
+        a.place(no).unwrap();
+        a.host_call(0, tick, 0, &[], &[]);
+        a.const_i64(2, 2);
+        a.ret(0, &[2]);
+        let branchy = pb
+            .push_function_checked(a, sig(&[ValueType::Bool], &[ValueType::I64]))
+            .unwrap();
+
+        // f1: `fn() -> i64 { f0(true) }`
+        let mut a = Asm::new();
+        a.const_bool(1, true);
+        a.call(0, branchy, 0, &[1], &[2]);
+        a.ret(0, &[2]);
+        let caller = pb
+            .push_function_checked(a, sig(&[], &[ValueType::I64]))
+            .unwrap();
+        let program = pb.build_verified().unwrap();
+
+        let bounds = fuel_bounds(&program, None, &HostCosts::new()).unwrap();
+        assert!(matches!(
+            &bounds[caller.0 as usize],
+            FuelBound::Unbounded(Unbounded::UndeclaredHostCost { func, symbol, .. })
+                if *func == branchy && symbol == "clock.tick"
+        ));
+
+        let mut costs = HostCosts::new();
+        costs.declare("clock.tick", 10);
+        // br, host_call (+10), const, ret.
+        let bounds = fuel_bounds(&program, None, &costs).unwrap();
+        assert_eq!(bounds[branchy.0 as usize], FuelBound::Bounded(14));
+        // const, call (+14), ret.
+        assert_eq!(bounds[caller.0 as usize], FuelBound::Bounded(17));
+
+        let mut model = CostModel::new();
+        model.set_base_cost(Opcode::HostCall, 5);
+        model.per_arg = 100;
+        let bounds = fuel_bounds(&program, Some(&model), &costs).unwrap();
+        assert_eq!(bounds[branchy.0 as usize], FuelBound::Bounded(18));
+        assert_eq!(bounds[caller.0 as usize], FuelBound::Bounded(121));
+
+        // The costliest path exhausts exactly the bound.
+        let run = |fuel| {
+            let limits = Limits {
+                fuel,
+                ..Limits::default()
+            };
+            Vm::new(Tick, limits).run(
+                &program,
+                branchy,
+                &[Value::Bool(false)],
+                TraceMask::NONE,
+                None,
+            )
+        };
+        let bound = fuel_bounds(&program, None, &costs).unwrap()[branchy.0 as usize]
+            .fuel_limit()
+            .unwrap();
+        assert_eq!(run(bound), Ok(vec![Value::I64(2)]));
+        assert_eq!(run(bound - 1).unwrap_err().trap, Trap::FuelExceeded);
+    }
+
+    #[test]
+    fn loops_and_recursion_are_unbounded() {
+        let mut pb = ProgramBuilder::new();
+
+        // f0: `loop {}` behind a branch.
+        let mut a = Asm::new();
+        let head = a.label();
+        let exit = a.label();
+        a.place(head).unwrap();
+        a.br(1, head, exit);
+        a.place(exit).unwrap();
+        a.ret(0, &[]);
+        let looping = pb
+            .push_function_checked(a, sig(&[ValueType::Bool], &[]))
+            .unwrap();
+
+        // f1 calls itself.
+        let mut a = Asm::new();
+        a.call(0, FuncId(1), 0, &[], &[]);
+        a.ret(0, &[]);
+        let recursive = pb.push_function_checked(a, sig(&[], &[])).unwrap();
+        let program = pb.build_verified().unwrap();
+
+        let bounds = fuel_bounds(&program, None, &HostCosts::new()).unwrap();
+        assert_eq!(
+            bounds[looping.0 as usize],
+            FuelBound::Unbounded(Unbounded::Loop {
+                func: looping,
+                from_pc: 0,
+                header_pc: 0,
+            })
+        );
+        assert_eq!(
+            bounds[recursive.0 as usize],
+            FuelBound::Unbounded(Unbounded::Recursion {
+                cycle: vec![recursive],
+            })
+        );
+        assert_eq!(bounds[recursive.0 as usize].fuel_limit(), None);
+    }
+
+    #[test]
+    fn deep_call_chains_do_not_overflow_the_stack() {
+        // f(i) calls f(i + 1); the last function just returns.
+        const DEPTH: u32 = 20_000;
+        let mut pb = ProgramBuilder::new();
+        for i in 0..DEPTH {
+            let mut a = Asm::new();
+            if i + 1 < DEPTH {
+                a.call(0, FuncId(i + 1), 0, &[], &[]);
+            }
+            a.ret(0, &[]);
+            pb.push_function_checked(a, sig(&[], &[])).unwrap();
+        }
+        let program = pb.build_verified().unwrap();
+
+        let bounds = fuel_bounds(&program, None, &HostCosts::new()).unwrap();
+        // One call and one ret per level, plus the final ret.
+        assert_eq!(bounds[0], FuelBound::Bounded(2 * u64::from(DEPTH) - 1));
+    }
+
+    #[test]
+    fn cycles_without_a_single_entry_are_unbounded() {
+        // Blocks `a` and `b` branch to each other, and the entry can jump into either one, so
+        // neither dominates the other and the cycle has no back edge.
+        let mut a = Asm::new();
+        let first = a.label();
+        let second = a.label();
+        let exit = a.label();
+        a.br(1, first, second);
+        a.place(first).unwrap();
+        a.br(1, second, exit);
+        a.place(second).unwrap();
+        a.br(1, first, exit);
+        a.place(exit).unwrap();
+        a.ret(0, &[]);
+        let mut pb = ProgramBuilder::new();
+        let f = pb
+            .push_function_checked(a, sig(&[ValueType::Bool], &[]))
+            .unwrap();
+        let program = pb.build_verified().unwrap();
+
+        let bounds = fuel_bounds(&program, None, &HostCosts::new()).unwrap();
+        assert!(matches!(
+            bounds[f.0 as usize],
+            FuelBound::Unbounded(Unbounded::Loop { func, .. }) if func == f
+        ));
+    }
+}
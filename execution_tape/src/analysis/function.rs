@@ -0,0 +1,586 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Per-function control-flow graph with dominator, loop and liveness queries.
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::analysis::bitset::BitSet;
+use crate::analysis::cfg::{self, BasicBlock};
+use crate::analysis::liveness;
+use crate::bytecode::{DecodedInstr, decode_instructions};
+use crate::disasm::{DisasmError, InstrView, instr_view};
+use crate::format::DecodeError;
+use crate::program::Program;
+use crate::value::FuncId;
+use crate::verifier::VerifiedProgram;
+
+/// An error building a [`FunctionCfg`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnalysisError {
+    /// The function id is out of range.
+    BadFuncId(FuncId),
+    /// The function's bytecode failed to decode.
+    Disasm(DisasmError),
+    /// A branch targets a pc that is not an instruction boundary.
+    InvalidJumpTarget {
+        /// Pc of the branch.
+        pc: u32,
+        /// Target pc.
+        target: u32,
+    },
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadFuncId(func) => write!(f, "bad function id {}", func.0),
+            Self::Disasm(DisasmError::Decode(e)) => write!(f, "bytecode decode failed: {e}"),
+            Self::Disasm(DisasmError::UnknownOpcode { opcode }) => {
+                write!(f, "unknown opcode 0x{opcode:02x}")
+            }
+            Self::InvalidJumpTarget { pc, target } => {
+                write!(f, "pc={pc}: invalid jump target {target}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for AnalysisError {}
+
+/// The control-flow graph of one function of a [`VerifiedProgram`].
+///
+/// Blocks are indexed in pc order, with block 0 the entry. Instructions are indexed in stream
+/// order, as by [`BasicBlock::instrs`]. Registers are the function's bytecode registers.
+#[derive(Clone, Debug)]
+pub struct FunctionCfg<'a> {
+    program: &'a Program,
+    func: FuncId,
+    reg_count: usize,
+    decoded: Vec<DecodedInstr>,
+    blocks: Vec<BasicBlock>,
+    preds: Vec<Vec<usize>>,
+    reachable: Vec<bool>,
+}
+
+impl<'a> FunctionCfg<'a> {
+    /// Builds the control-flow graph of `func`.
+    pub fn new(program: &'a VerifiedProgram, func: FuncId) -> Result<Self, AnalysisError> {
+        let program = program.program();
+        let f = program
+            .functions
+            .get(func.0 as usize)
+            .ok_or(AnalysisError::BadFuncId(func))?;
+        let bytecode = f
+            .bytecode(program)
+            .map_err(|e| AnalysisError::Disasm(DisasmError::Decode(e)))?;
+        let decoded = decode_instructions(bytecode)
+            .map_err(|e| AnalysisError::Disasm(DisasmError::from_bytecode(e)))?;
+        let byte_len = u32::try_from(bytecode.len())
+            .map_err(|_| AnalysisError::Disasm(DisasmError::Decode(DecodeError::OutOfBounds)))?;
+        let boundaries = cfg::compute_boundaries(bytecode.len(), &decoded);
+        let blocks = cfg::build_basic_blocks(byte_len, &decoded, &boundaries).map_err(|e| {
+            AnalysisError::InvalidJumpTarget {
+                pc: e.src_pc,
+                target: e.target_pc,
+            }
+        })?;
+        let reachable = cfg::compute_reachable(&blocks);
+        let mut preds = vec![Vec::new(); blocks.len()];
+        for (b, block) in blocks.iter().enumerate() {
+            for succ in block.succs() {
+                if let Some(p) = preds.get_mut(succ) {
+                    p.push(b);
+                }
+            }
+        }
+        Ok(Self {
+            program,
+            func,
+            reg_count: f.reg_count as usize,
+            decoded,
+            blocks,
+            preds,
+            reachable,
+        })
+    }
+
+    /// Returns the function id.
+    #[must_use]
+    pub fn func(&self) -> FuncId {
+        self.func
+    }
+
+    /// Returns the number of registers of the function.
+    #[must_use]
+    pub fn reg_count(&self) -> usize {
+        self.reg_count
+    }
+
+    /// Returns the basic blocks, in pc order.
+    #[must_use]
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+
+    /// Returns the number of instructions.
+    #[must_use]
+    pub fn instr_count(&self) -> usize {
+        self.decoded.len()
+    }
+
+    /// Returns the instruction at index `ix`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ix` is out of range.
+    #[must_use]
+    pub fn instr(&self, ix: usize) -> InstrView<'_> {
+        instr_view(self.program, self.func, &self.decoded[ix])
+    }
+
+    /// Iterates the instructions in stream order.
+    pub fn instrs(&self) -> impl Iterator<Item = InstrView<'_>> + '_ {
+        self.decoded
+            .iter()
+            .map(|di| instr_view(self.program, self.func, di))
+    }
+
+    /// Returns the index of the block containing instruction `ix`.
+    #[must_use]
+    pub fn block_of_instr(&self, ix: usize) -> Option<usize> {
+        let b = self.blocks.partition_point(|b| b.instr_end <= ix);
+        (b < self.blocks.len() && self.blocks[b].instr_start <= ix).then_some(b)
+    }
+
+    /// Iterates the successors of block `b`.
+    pub fn successors(&self, b: usize) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.get(b).into_iter().flat_map(BasicBlock::succs)
+    }
+
+    /// Returns the predecessors of block `b`, in block order.
+    #[must_use]
+    pub fn predecessors(&self, b: usize) -> &[usize] {
+        self.preds.get(b).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `true` if block `b` is reachable from the entry block.
+    #[must_use]
+    pub fn is_reachable(&self, b: usize) -> bool {
+        self.reachable.get(b).copied().unwrap_or(false)
+    }
+
+    pub(crate) fn reachable(&self) -> &[bool] {
+        &self.reachable
+    }
+
+    pub(crate) fn decoded(&self) -> &[DecodedInstr] {
+        &self.decoded
+    }
+
+    /// Computes the dominator tree of the reachable blocks.
+    #[must_use]
+    pub fn dominators(&self) -> Dominators {
+        // Cooper, Harvey and Kennedy, "A Simple, Fast Dominance Algorithm": iterate immediate
+        // dominators in reverse postorder until they stop changing.
+        let n = self.blocks.len();
+        let rpo = self.reverse_postorder();
+        let mut rpo_index = vec![usize::MAX; n];
+        for (i, &b) in rpo.iter().enumerate() {
+            rpo_index[b] = i;
+        }
+        let mut idom: Vec<Option<usize>> = vec![None; n];
+        if let Some(&entry) = rpo.first() {
+            idom[entry] = Some(entry);
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &b in rpo.iter().skip(1) {
+                let mut new_idom: Option<usize> = None;
+                for &p in &self.preds[b] {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => p,
+                        Some(cur) => intersect(&idom, &rpo_index, p, cur),
+                    });
+                }
+                if new_idom.is_some() && idom[b] != new_idom {
+                    idom[b] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        if let Some(&entry) = rpo.first() {
+            idom[entry] = None;
+        }
+        Dominators {
+            idom,
+            reachable: self.reachable.clone(),
+        }
+    }
+
+    /// Returns the loop back edges: edges whose target dominates their source.
+    ///
+    /// Each back edge closes a natural loop headed by its target; see [`FunctionCfg::loop_body`].
+    #[must_use]
+    pub fn back_edges(&self) -> Vec<BackEdge> {
+        let doms = self.dominators();
+        let mut out = Vec::new();
+        for (from, block) in self.blocks.iter().enumerate() {
+            if !self.is_reachable(from) {
+                continue;
+            }
+            for header in block.succs() {
+                if doms.dominates(header, from) {
+                    out.push(BackEdge { from, header });
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns the blocks of the natural loop of `edge`: its header plus every block that reaches
+    /// `edge.from` without passing through the header.
+    #[must_use]
+    pub fn loop_body(&self, edge: BackEdge) -> BitSet {
+        let mut body = BitSet::new_empty(self.blocks.len());
+        body.set(edge.header);
+        let mut stack = vec![edge.from];
+        while let Some(b) = stack.pop() {
+            if body.get(b) {
+                continue;
+            }
+            body.set(b);
+            stack.extend(self.predecessors(b).iter().copied());
+        }
+        body
+    }
+
+    /// Computes the registers live before and after each instruction.
+    ///
+    /// Register 0 (the effect token) is never reported live. Instructions in unreachable blocks
+    /// have empty sets.
+    #[must_use]
+    pub fn liveness(&self) -> Liveness {
+        let blocks = liveness::compute_liveness(
+            self.reg_count,
+            &self.decoded,
+            &self.blocks,
+            &self.reachable,
+        );
+        let empty = BitSet::new_empty(self.reg_count);
+        let mut live_in = vec![empty.clone(); self.decoded.len()];
+        let mut live_out = vec![empty; self.decoded.len()];
+        for (b, block) in self.blocks.iter().enumerate() {
+            if !self.is_reachable(b) {
+                continue;
+            }
+            let mut live = blocks.live_out[b].clone();
+            for ix in block.instrs().rev() {
+                live_out[ix] = live.clone();
+                let instr = &self.decoded[ix].instr;
+                for w in instr.writes().filter(|&w| w != 0) {
+                    live.clear(w as usize);
+                }
+                for r in instr.reads().filter(|&r| r != 0) {
+                    live.set(r as usize);
+                }
+                live_in[ix] = live.clone();
+            }
+        }
+        Liveness { live_in, live_out }
+    }
+
+    /// Returns the reachable blocks in reverse postorder of a depth-first walk from the entry.
+    pub(crate) fn reverse_postorder(&self) -> Vec<usize> {
+        let n = self.blocks.len();
+        let mut post = Vec::with_capacity(n);
+        if n == 0 {
+            return post;
+        }
+        let mut visited = vec![false; n];
+        // Explicit stack of (block, next successor slot) to avoid recursion.
+        let mut stack = vec![(0_usize, 0_usize)];
+        visited[0] = true;
+        while let Some((b, slot)) = stack.pop() {
+            if let Some(s) = self.blocks[b].succs.get(slot).copied() {
+                stack.push((b, slot + 1));
+                if let Some(s) = s.filter(|&s| s < n && !visited[s]) {
+                    visited[s] = true;
+                    stack.push((s, 0));
+                }
+            } else {
+                post.push(b);
+            }
+        }
+        post.reverse();
+        post
+    }
+}
+
+fn intersect(idom: &[Option<usize>], rpo_index: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while rpo_index[a] > rpo_index[b] {
+            a = idom[a].expect("processed blocks have an immediate dominator");
+        }
+        while rpo_index[b] > rpo_index[a] {
+            b = idom[b].expect("processed blocks have an immediate dominator");
+        }
+    }
+    a
+}
+
+/// The dominator tree of a [`FunctionCfg`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dominators {
+    idom: Vec<Option<usize>>,
+    reachable: Vec<bool>,
+}
+
+impl Dominators {
+    /// Returns the immediate dominator of block `b`.
+    ///
+    /// Returns `None` for the entry block and for unreachable blocks.
+    #[must_use]
+    pub fn immediate_dominator(&self, b: usize) -> Option<usize> {
+        self.idom.get(b).copied().flatten()
+    }
+
+    /// Returns `true` if every path from the entry to `b` passes through `a`.
+    ///
+    /// Every reachable block dominates itself; unreachable blocks dominate nothing and are
+    /// dominated by nothing.
+    #[must_use]
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        if !self.reachable.get(a).copied().unwrap_or(false)
+            || !self.reachable.get(b).copied().unwrap_or(false)
+        {
+            return false;
+        }
+        let mut cur = b;
+        loop {
+            if cur == a {
+                return true;
+            }
+            match self.immediate_dominator(cur) {
+                Some(up) => cur = up,
+                None => return false,
+            }
+        }
+    }
+
+    /// Iterates the blocks immediately dominated by `b`: its children in the dominator tree.
+    pub fn children(&self, b: usize) -> impl Iterator<Item = usize> + '_ {
+        self.idom
+            .iter()
+            .enumerate()
+            .filter(move |&(_, &idom)| idom == Some(b))
+            .map(|(child, _)| child)
+    }
+}
+
+/// A control-flow edge from `from` to a loop header that dominates it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BackEdge {
+    /// Source block (the loop latch).
+    pub from: usize,
+    /// Target block (the loop header).
+    pub header: usize,
+}
+
+/// Registers live around each instruction of a [`FunctionCfg`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Liveness {
+    live_in: Vec<BitSet>,
+    live_out: Vec<BitSet>,
+}
+
+impl Liveness {
+    /// Returns the registers live before instruction `ix`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ix` is out of range.
+    #[must_use]
+    pub fn live_in(&self, ix: usize) -> &BitSet {
+        &self.live_in[ix]
+    }
+
+    /// Returns the registers live after instruction `ix`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ix` is out of range.
+    #[must_use]
+    pub fn live_out(&self, ix: usize) -> &BitSet {
+        &self.live_out[ix]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::dataflow::{Dataflow, Direction};
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::program::ValueType;
+    use alloc::vec::Vec;
+
+    /// `fn count(n: i64) -> i64 { let mut i = 0; while i < n { i += 1 } i }`
+    fn counting_loop() -> VerifiedProgram {
+        let mut a = Asm::new();
+        let head = a.label();
+        let body = a.label();
+        let exit = a.label();
+        a.const_i64(2, 0);
+        a.const_i64(3, 1);
+        a.jmp(head);
+        a.place(head).unwrap();
+        a.i64_lt(4, 2, 1);
+        a.br(4, body, exit);
+        a.place(body).unwrap();
+        a.i64_add(2, 2, 3);
+        a.jmp(head);
+        a.place(exit).unwrap();
+        a.ret(0, &[2]);
+        let mut pb = ProgramBuilder::new();
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::I64],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        pb.build_verified().unwrap()
+    }
+
+    fn regs(set: &BitSet) -> Vec<usize> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn cfg_dominators_loops_and_liveness() {
+        let program = counting_loop();
+        let cfg = FunctionCfg::new(&program, FuncId(0)).unwrap();
+
+        let succs: Vec<Vec<usize>> = (0..cfg.blocks().len())
+            .map(|b| cfg.successors(b).collect())
+            .collect();
+        assert_eq!(succs, vec![vec![1], vec![2, 3], vec![1], vec![]]);
+        assert_eq!(cfg.predecessors(1), &[0, 2]);
+        assert!((0..4).all(|b| cfg.is_reachable(b)));
+
+        let doms = cfg.dominators();
+        assert_eq!(doms.immediate_dominator(0), None);
+        assert_eq!(doms.immediate_dominator(2), Some(1));
+        assert_eq!(doms.immediate_dominator(3), Some(1));
+        assert_eq!(doms.children(1).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(doms.dominates(1, 2) && !doms.dominates(2, 3));
+
+        let back = cfg.back_edges();
+        assert_eq!(back, vec![BackEdge { from: 2, header: 1 }]);
+        assert_eq!(regs(&cfg.loop_body(back[0])), vec![1, 2]);
+
+        let live = cfg.liveness();
+        let lt = cfg.blocks()[1].instrs().start;
+        assert_eq!(regs(live.live_in(lt)), vec![1, 2, 3]);
+        assert_eq!(regs(live.live_out(lt)), vec![1, 2, 3, 4]);
+        let ret = cfg.instr_count() - 1;
+        assert_eq!(cfg.block_of_instr(ret), Some(3));
+        assert_eq!(regs(live.live_in(ret)), vec![2]);
+        assert!(live.live_out(ret).is_empty());
+    }
+
+    /// Registers written on some path to each point.
+    struct MayDefine;
+
+    impl Dataflow for MayDefine {
+        type State = BitSet;
+        const DIRECTION: Direction = Direction::Forward;
+
+        fn bottom(&self, cfg: &FunctionCfg<'_>) -> BitSet {
+            BitSet::new_empty(cfg.reg_count())
+        }
+
+        fn entry(&self, cfg: &FunctionCfg<'_>) -> BitSet {
+            let mut s = self.bottom(cfg);
+            s.set(1);
+            s
+        }
+
+        fn meet_into(&self, acc: &mut BitSet, incoming: &BitSet) {
+            acc.union_with(incoming);
+        }
+
+        fn transfer(&self, instr: InstrView<'_>, state: &mut BitSet) {
+            for w in instr.writes() {
+                state.set(w as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn dataflow_trait_solves_over_blocks() {
+        let program = counting_loop();
+        let cfg = FunctionCfg::new(&program, FuncId(0)).unwrap();
+        let result = cfg.solve(&MayDefine);
+        assert_eq!(regs(&result.entry[0]), vec![1]);
+        assert_eq!(regs(&result.exit[0]), vec![1, 2, 3]);
+        assert_eq!(regs(&result.entry[3]), vec![1, 2, 3, 4]);
+        assert_eq!(
+            FunctionCfg::new(&program, FuncId(1)).err(),
+            Some(AnalysisError::BadFuncId(FuncId(1)))
+        );
+    }
+
+    /// Liveness as a backward [`Dataflow`], to compare against [`FunctionCfg::liveness`].
+    struct Live;
+
+    impl Dataflow for Live {
+        type State = BitSet;
+        const DIRECTION: Direction = Direction::Backward;
+
+        fn bottom(&self, cfg: &FunctionCfg<'_>) -> BitSet {
+            BitSet::new_empty(cfg.reg_count())
+        }
+
+        fn meet_into(&self, acc: &mut BitSet, incoming: &BitSet) {
+            acc.union_with(incoming);
+        }
+
+        fn transfer(&self, instr: InstrView<'_>, state: &mut BitSet) {
+            for w in instr.writes().filter(|&w| w != 0) {
+                state.clear(w as usize);
+            }
+            for r in instr.reads().filter(|&r| r != 0) {
+                state.set(r as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn backward_dataflow_states_are_in_program_order() {
+        let program = counting_loop();
+        let cfg = FunctionCfg::new(&program, FuncId(0)).unwrap();
+        let result = cfg.solve(&Live);
+        let live = cfg.liveness();
+
+        // `entry[b]` is before the block's first instruction and `exit[b]` after its last, as for
+        // forward analyses.
+        for (b, block) in cfg.blocks().iter().enumerate() {
+            let instrs = block.instrs();
+            assert_eq!(result.entry[b], *live.live_in(instrs.start), "block {b}");
+            assert_eq!(result.exit[b], *live.live_out(instrs.end - 1), "block {b}");
+        }
+        assert_eq!(regs(&result.entry[0]), vec![1]);
+        assert_eq!(regs(&result.entry[3]), vec![2]);
+        assert!(result.exit[3].is_empty());
+    }
+}
@@ -1,12 +1,27 @@
 // Copyright 2026 the Execution Tape Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-//! Internal analysis utilities (CFG, dataflow).
+//! Control-flow and dataflow analyses over verified functions.
 //!
-//! This module is crate-internal: it exists to keep verifier and tooling analyses coherent and
-//! reusable while the design is still evolving.
+//! The verifier and tooling share these analyses. [`FunctionCfg`] builds a function's
+//! [`BasicBlock`]s with successor and predecessor edges and answers reachability, dominator
+//! ([`Dominators`]), loop ([`BackEdge`]) and per-instruction [`Liveness`] queries. Custom
+//! analyses implement [`Dataflow`] and run on the same worklist solver with
+//! [`FunctionCfg::solve`].
+//!
+//! [`fuel_bounds`] computes each function's worst-case fuel ([`FuelBound`]), or why it has none.
+//!
+//! Registers are bytecode registers; register sets are [`BitSet`]s over `0..reg_count`.
 
 pub(crate) mod bitset;
 pub(crate) mod cfg;
 pub(crate) mod dataflow;
+pub(crate) mod fuel;
+pub(crate) mod function;
 pub(crate) mod liveness;
+
+pub use bitset::BitSet;
+pub use cfg::BasicBlock;
+pub use dataflow::{Dataflow, DataflowResult, Direction};
+pub use fuel::{FuelBound, HostCosts, Unbounded, fuel_bounds};
+pub use function::{AnalysisError, BackEdge, Dominators, FunctionCfg, Liveness};
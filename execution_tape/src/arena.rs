@@ -57,4 +57,10 @@ impl ValueArena {
     pub(crate) fn str(&self, h: StrHandle) -> Option<&str> {
         self.strs.get(h.0 as usize).map(|s| s.as_str())
     }
+
+    /// Number of byte strings and strings currently allocated.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.bytes.len() + self.strs.len()
+    }
 }
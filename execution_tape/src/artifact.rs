@@ -0,0 +1,468 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Cached verification artifacts.
+//!
+//! Full verification runs CFG construction, must-init and type analyses for every function. Its
+//! result is a register-class assignment per function, which the verifier then lowers into the
+//! typed instruction streams the VM executes. [`encode_artifacts`] serializes that assignment
+//! together with a [`ContentHash`] of the program, so a later process can rebuild the
+//! [`VerifiedProgram`] with [`load_artifacts`]: a linear pass that re-runs the program-level table
+//! checks (host and call signatures, value names, aggregate shapes, linkage) and lowers
+//! instructions, but skips the per-function analyses.
+//!
+//! [`verify_program_cached`] is the usual entry point: it takes the artifact when it is intact and
+//! matches the program, and otherwise falls back to [`verify_program_owned`].
+//!
+//! The trailing SHA-256 is an unkeyed checksum against accidental corruption (truncation, bit
+//! flips, a file from another program). It is not a MAC and does not authenticate the artifact:
+//! anyone who can write an artifact can recompute it. Lowering still checks every operand against
+//! the recorded register classes, but only load artifacts from storage you trust as much as the
+//! programs themselves.
+//!
+//! ## Format
+//!
+//! - magic `EXTVART\0` (8 bytes)
+//! - artifact version `u16le` ([`ARTIFACT_VERSION`])
+//! - program container version: major `u16le`, minor `u16le`
+//! - program content hash (32 bytes)
+//! - function count (uleb), then per function: register count (uleb) and one register-class tag
+//!   byte per register
+//! - SHA-256 of all preceding bytes (32 bytes)
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::format::{DecodeError, Reader, Writer};
+use crate::program::{Program, VERSION_MAJOR, VERSION_MINOR};
+use crate::sha256::sha256;
+use crate::typed::{ExecFunc, RegClass};
+use crate::verifier::{
+    VerifiedProgram, VerifyConfig, VerifyError, lower_function_with_classes, verify_program_owned,
+    verify_program_tables,
+};
+
+/// The artifact format version written by [`encode_artifacts`].
+///
+/// Bump this whenever the verifier's register-class assignment or lowering changes meaning, so
+/// stale artifacts are rejected rather than trusted.
+pub const ARTIFACT_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 8] = b"EXTVART\0";
+const HASH_LEN: usize = 32;
+
+/// SHA-256 of a program's canonical encoding ([`Program::encode`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ContentHash(pub [u8; 32]);
+
+impl ContentHash {
+    /// Hashes the canonical encoding of `program`.
+    #[must_use]
+    pub fn of(program: &Program) -> Self {
+        Self(sha256(&program.encode()))
+    }
+}
+
+impl fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a cached artifact was not used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArtifactError {
+    /// The artifact is truncated or structurally invalid.
+    Malformed(DecodeError),
+    /// The artifact was written by an incompatible artifact or container format version.
+    UnsupportedVersion {
+        /// Artifact format version.
+        artifact: u16,
+        /// Program container major version the artifact refers to.
+        major: u16,
+        /// Program container minor version the artifact refers to.
+        minor: u16,
+    },
+    /// The artifact checksum does not match its contents (accidental corruption).
+    ChecksumMismatch,
+    /// The artifact was produced for a different program.
+    ProgramMismatch {
+        /// Hash of the program being loaded.
+        expected: ContentHash,
+        /// Hash recorded in the artifact.
+        found: ContentHash,
+    },
+    /// A function's recorded register classes do not fit its signature.
+    LayoutMismatch {
+        /// Function index within the program.
+        func: u32,
+    },
+    /// Rebuilding execution artifacts failed.
+    Verify(VerifyError),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(e) => write!(f, "malformed artifact: {e}"),
+            Self::UnsupportedVersion {
+                artifact,
+                major,
+                minor,
+            } => write!(
+                f,
+                "unsupported artifact version {artifact} (container {major}.{minor})"
+            ),
+            Self::ChecksumMismatch => write!(f, "artifact checksum mismatch"),
+            Self::ProgramMismatch { expected, found } => {
+                write!(f, "artifact is for program {found}, expected {expected}")
+            }
+            Self::LayoutMismatch { func } => {
+                write!(
+                    f,
+                    "function {func}: register classes do not match signature"
+                )
+            }
+            Self::Verify(e) => write!(f, "artifact rejected: {e}"),
+        }
+    }
+}
+
+impl core::error::Error for ArtifactError {}
+
+impl From<DecodeError> for ArtifactError {
+    fn from(e: DecodeError) -> Self {
+        Self::Malformed(e)
+    }
+}
+
+/// Whether [`verify_program_cached`] used the cached artifact.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheOutcome {
+    /// The artifact matched and full verification was skipped.
+    Hit,
+    /// The artifact was rejected and the program was fully verified instead.
+    Miss(ArtifactError),
+}
+
+/// Serializes the verification artifacts of `verified`.
+#[must_use]
+pub fn encode_artifacts(verified: &VerifiedProgram) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.write_bytes(MAGIC);
+    w.write_u16_le(ARTIFACT_VERSION);
+    w.write_u16_le(VERSION_MAJOR);
+    w.write_u16_le(VERSION_MINOR);
+    w.write_bytes(&ContentHash::of(verified.program()).0);
+    w.write_uleb128_u64(verified.program().functions.len() as u64);
+    for classes in verified.reg_classes() {
+        let tags: Vec<u8> = classes.map(class_tag).collect();
+        w.write_uleb128_u64(tags.len() as u64);
+        w.write_bytes(&tags);
+    }
+    let checksum = sha256(w.as_slice());
+    w.write_bytes(&checksum);
+    w.into_vec()
+}
+
+/// Rebuilds a [`VerifiedProgram`] for `program` from `artifacts` without running the analyses.
+///
+/// Fails if the program's tables are invalid, or if the artifact is damaged, was written by another
+/// format version, or belongs to a different program. Limits in `cfg` are still enforced.
+pub fn load_artifacts(
+    program: Program,
+    artifacts: &[u8],
+    cfg: &VerifyConfig,
+) -> Result<VerifiedProgram, ArtifactError> {
+    verify_program_tables(&program).map_err(ArtifactError::Verify)?;
+    let verified_functions = lower_artifacts(&program, artifacts, cfg)?;
+    VerifiedProgram::from_trusted_parts(program, verified_functions).map_err(ArtifactError::Verify)
+}
+
+/// Verifies `program`, using `artifacts` when they are valid for it.
+///
+/// Rejected artifacts are reported in the returned [`CacheOutcome`]; the program is then verified
+/// in full, so callers can rewrite the artifact with [`encode_artifacts`]. Invalid program tables
+/// are reported as an error whether or not the artifact matches.
+pub fn verify_program_cached(
+    program: Program,
+    artifacts: &[u8],
+    cfg: &VerifyConfig,
+) -> Result<(VerifiedProgram, CacheOutcome), VerifyError> {
+    verify_program_tables(&program)?;
+    let outcome = match lower_artifacts(&program, artifacts, cfg) {
+        Ok(verified_functions) => {
+            let verified = VerifiedProgram::from_trusted_parts(program, verified_functions)?;
+            return Ok((verified, CacheOutcome::Hit));
+        }
+        Err(e) => CacheOutcome::Miss(e),
+    };
+    Ok((verify_program_owned(program, cfg)?, outcome))
+}
+
+fn lower_artifacts(
+    program: &Program,
+    artifacts: &[u8],
+    cfg: &VerifyConfig,
+) -> Result<Vec<ExecFunc>, ArtifactError> {
+    let mut r = Reader::new(artifacts);
+    if r.read_bytes(MAGIC.len())? != MAGIC {
+        return Err(DecodeError::BadMagic.into());
+    }
+    let artifact = r.read_u16_le()?;
+    let major = r.read_u16_le()?;
+    let minor = r.read_u16_le()?;
+    if (artifact, major, minor) != (ARTIFACT_VERSION, VERSION_MAJOR, VERSION_MINOR) {
+        return Err(ArtifactError::UnsupportedVersion {
+            artifact,
+            major,
+            minor,
+        });
+    }
+
+    let body_len = artifacts
+        .len()
+        .checked_sub(HASH_LEN)
+        .ok_or(DecodeError::UnexpectedEof)?;
+    if body_len < r.offset() || sha256(&artifacts[..body_len]) != artifacts[body_len..] {
+        return Err(ArtifactError::ChecksumMismatch);
+    }
+    let mut r = Reader::new(&artifacts[..body_len]);
+    r.read_bytes(MAGIC.len() + 6)?;
+
+    let mut found = [0_u8; HASH_LEN];
+    found.copy_from_slice(r.read_bytes(HASH_LEN)?);
+    let (found, expected) = (ContentHash(found), ContentHash::of(program));
+    if found != expected {
+        return Err(ArtifactError::ProgramMismatch { expected, found });
+    }
+
+    let count = usize::try_from(r.read_uleb128_u64()?).map_err(|_| DecodeError::OutOfBounds)?;
+    if count != program.functions.len() {
+        return Err(DecodeError::OutOfBounds.into());
+    }
+    let mut out = Vec::with_capacity(count);
+    let mut classes: Vec<RegClass> = Vec::new();
+    for (i, func) in program.functions.iter().enumerate() {
+        let func_id = u32::try_from(i).unwrap_or(u32::MAX);
+        let len = usize::try_from(r.read_uleb128_u64()?).map_err(|_| DecodeError::OutOfBounds)?;
+        classes.clear();
+        for &tag in r.read_bytes(len)? {
+            classes.push(class_from_tag(tag)?);
+        }
+
+        // Cheap consistency checks against the signature; the lowering pass checks every operand.
+        let arg_types = program
+            .function_arg_types(func)
+            .map_err(|_| ArtifactError::LayoutMismatch { func: func_id })?;
+        let layout_ok = u32::try_from(classes.len()).ok() == Some(func.reg_count)
+            && classes.first() == Some(&RegClass::Unit)
+            && arg_types
+                .iter()
+                .enumerate()
+                .all(|(a, &t)| classes.get(1 + a) == Some(&RegClass::of(t)));
+        if !layout_ok {
+            return Err(ArtifactError::LayoutMismatch { func: func_id });
+        }
+
+        out.push(
+            lower_function_with_classes(program, func_id, func, &classes, cfg)
+                .map_err(ArtifactError::Verify)?,
+        );
+    }
+    if r.offset() != body_len {
+        return Err(DecodeError::OutOfBounds.into());
+    }
+    Ok(out)
+}
+
+fn class_tag(class: RegClass) -> u8 {
+    match class {
+        RegClass::Unit => 0,
+        RegClass::Bool => 1,
+        RegClass::I64 => 2,
+        RegClass::U64 => 3,
+        RegClass::F64 => 4,
+        RegClass::Decimal => 5,
+        RegClass::Bytes => 6,
+        RegClass::Str => 7,
+        RegClass::Obj => 8,
+        RegClass::Agg => 9,
+        RegClass::Func => 10,
+        RegClass::Closure => 11,
+    }
+}
+
+fn class_from_tag(tag: u8) -> Result<RegClass, DecodeError> {
+    Ok(match tag {
+        0 => RegClass::Unit,
+        1 => RegClass::Bool,
+        2 => RegClass::I64,
+        3 => RegClass::U64,
+        4 => RegClass::F64,
+        5 => RegClass::Decimal,
+        6 => RegClass::Bytes,
+        7 => RegClass::Str,
+        8 => RegClass::Obj,
+        9 => RegClass::Agg,
+        10 => RegClass::Func,
+        11 => RegClass::Closure,
+        _ => return Err(DecodeError::OutOfBounds),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use alloc::vec;
+    use core::num::NonZeroU32;
+
+    use crate::program::{ExportEntry, SymbolId, ValueType};
+
+    fn sample() -> Program {
+        let mut pb = ProgramBuilder::new();
+        let name = pb.const_str("abc");
+        let mut a = Asm::new();
+        let done = a.label();
+        a.const_pool(3, name);
+        a.str_len(4, 3);
+        a.u64_to_i64(5, 4);
+        a.i64_add(6, 1, 5);
+        a.br(2, done, done);
+        a.place(done).unwrap();
+        a.ret(0, &[6]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::I64, ValueType::Bool],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        pb.build_checked().unwrap()
+    }
+
+    fn resign(artifacts: &mut [u8]) {
+        let body_len = artifacts.len() - HASH_LEN;
+        let checksum = sha256(&artifacts[..body_len]);
+        artifacts[body_len..].copy_from_slice(&checksum);
+    }
+
+    #[test]
+    fn cached_load_matches_full_verification() {
+        let cfg = VerifyConfig::default();
+        let verified = verify_program_owned(sample(), &cfg).unwrap();
+        let artifacts = encode_artifacts(&verified);
+
+        let loaded = load_artifacts(sample(), &artifacts, &cfg).unwrap();
+        assert_eq!(loaded, verified);
+
+        let (cached, outcome) = verify_program_cached(sample(), &artifacts, &cfg).unwrap();
+        assert_eq!(outcome, CacheOutcome::Hit);
+        assert_eq!(cached, verified);
+    }
+
+    #[test]
+    fn damaged_or_mismatched_artifacts_fall_back() {
+        let cfg = VerifyConfig::default();
+        let verified = verify_program_owned(sample(), &cfg).unwrap();
+        let artifacts = encode_artifacts(&verified);
+
+        let mut flipped = artifacts.clone();
+        let last_class = flipped.len() - HASH_LEN - 1;
+        flipped[last_class] ^= 1;
+        let (fallback, outcome) = verify_program_cached(sample(), &flipped, &cfg).unwrap();
+        assert_eq!(outcome, CacheOutcome::Miss(ArtifactError::ChecksumMismatch));
+        assert_eq!(fallback, verified);
+
+        let mut other = sample();
+        other.functions[0].reg_count += 1;
+        assert!(matches!(
+            load_artifacts(other, &artifacts, &cfg),
+            Err(ArtifactError::ProgramMismatch { .. })
+        ));
+
+        let mut old = artifacts.clone();
+        old[MAGIC.len()] = 0;
+        resign(&mut old);
+        assert!(matches!(
+            load_artifacts(sample(), &old, &cfg),
+            Err(ArtifactError::UnsupportedVersion { artifact: 0, .. })
+        ));
+
+        assert_eq!(
+            load_artifacts(sample(), &artifacts[..10], &cfg),
+            Err(ArtifactError::Malformed(DecodeError::UnexpectedEof))
+        );
+        assert!(
+            load_artifacts(
+                sample(),
+                &artifacts,
+                &VerifyConfig {
+                    max_regs_per_function: 2
+                }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn forged_classes_are_rejected_not_trusted() {
+        let cfg = VerifyConfig::default();
+        let verified = verify_program_owned(sample(), &cfg).unwrap();
+        let artifacts = encode_artifacts(&verified);
+
+        // r1 is the I64 argument: claiming it is a Str contradicts the signature.
+        let classes_start = artifacts.len() - HASH_LEN - sample().functions[0].reg_count as usize;
+        let mut forged = artifacts.clone();
+        forged[classes_start + 1] = class_tag(RegClass::Str);
+        resign(&mut forged);
+        assert_eq!(
+            load_artifacts(sample(), &forged, &cfg),
+            Err(ArtifactError::LayoutMismatch { func: 0 })
+        );
+
+        // r6 feeds `i64.add`: a wrong class is caught while lowering.
+        let mut forged = artifacts;
+        forged[classes_start + 6] = class_tag(RegClass::Bool);
+        resign(&mut forged);
+        let (_, outcome) = verify_program_cached(sample(), &forged, &cfg).unwrap();
+        assert!(
+            matches!(outcome, CacheOutcome::Miss(ArtifactError::Verify(_))),
+            "{outcome:?}"
+        );
+    }
+
+    #[test]
+    fn invalid_program_tables_are_rejected_before_lowering() {
+        let cfg = VerifyConfig::default();
+        let verified = verify_program_owned(sample(), &cfg).unwrap();
+        let mut artifacts = encode_artifacts(&verified);
+
+        // Same bytecode, but an export pointing past the function table. Re-bind the artifact to
+        // the altered program so only the program-level checks can catch it.
+        let mut bad = sample();
+        bad.exports.push(ExportEntry {
+            func: 7,
+            name: SymbolId(NonZeroU32::MIN),
+        });
+        let hash_start = MAGIC.len() + 6;
+        artifacts[hash_start..hash_start + HASH_LEN].copy_from_slice(&ContentHash::of(&bad).0);
+        resign(&mut artifacts);
+
+        assert_eq!(
+            load_artifacts(bad.clone(), &artifacts, &cfg),
+            Err(ArtifactError::Verify(VerifyError::ExportFuncOutOfBounds {
+                func: 7
+            }))
+        );
+        assert_eq!(
+            verify_program_cached(bad, &artifacts, &cfg),
+            Err(VerifyError::ExportFuncOutOfBounds { func: 7 })
+        );
+    }
+}
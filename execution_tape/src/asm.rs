@@ -16,12 +16,13 @@ use core::fmt;
 
 use crate::format::{write_sleb128_i64, write_uleb128_u64};
 use crate::host::HostSig;
+use crate::linker::UNRESOLVED_IMPORT_TRAP_CODE;
 use crate::opcode::Opcode;
 use crate::program::{
-    AggShape, ByteRange, CallSigEntry, CallSigId, Const, ConstId, ElemTypeId,
-    FunctionArgAggShapeEntry, FunctionDef, FunctionNameEntry, HostSigDef, HostSigId, HostSymbol,
-    LabelNameEntry, Program, SpanEntry, SpanId, StructTypeDef, SymbolId, TypeId, TypeTableDef,
-    ValueType,
+    AggShape, ByteRange, CallSigEntry, CallSigId, Const, ConstId, CustomSection, ElemTypeId,
+    ExportEntry, FunctionArgAggShapeEntry, FunctionDef, FunctionNameEntry, HostSigDef, HostSigId,
+    HostSymbol, ImportEntry, LabelNameEntry, Program, SourceMapEntry, SourceRange, SpanEntry,
+    SpanId, StructTypeDef, SymbolId, TypeId, TypeTableDef, ValueType,
 };
 use crate::value::Decimal;
 use crate::value::FuncId;
@@ -392,6 +393,10 @@ pub struct ProgramBuilder {
     labels: Vec<LabelNameEntry>,
     function_input_names: Vec<FunctionInputNameDef>,
     function_output_names: Vec<FunctionOutputNameDef>,
+    exports: Vec<ExportEntry>,
+    imports: Vec<ImportEntry>,
+    custom_sections: Vec<CustomSection>,
+    source_map: Vec<SourceMapEntry>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -610,11 +615,28 @@ impl ProgramBuilder {
 
     /// Interns a host-call signature for `symbol` and returns its [`HostSigId`].
     pub fn host_sig(&mut self, symbol: SymbolId, sig: HostSig) -> HostSigId {
-        let def = HostSigDef {
+        self.intern_host_sig(HostSigDef {
             symbol,
             args: sig.args,
             rets: sig.rets,
-        };
+            pure: false,
+        })
+    }
+
+    /// Interns a pure host-call signature for `symbol` and returns its [`HostSigId`].
+    ///
+    /// Pure signatures are called with [`Asm::host_call_pure`], without an effect token. They are
+    /// distinct from effectful signatures with the same types.
+    pub fn pure_host_sig(&mut self, symbol: SymbolId, sig: HostSig) -> HostSigId {
+        self.intern_host_sig(HostSigDef {
+            symbol,
+            args: sig.args,
+            rets: sig.rets,
+            pure: true,
+        })
+    }
+
+    fn intern_host_sig(&mut self, def: HostSigDef) -> HostSigId {
         if let Some(i) = self.host_sigs.iter().position(|x| *x == def) {
             return HostSigId(u32::try_from(i).unwrap_or(u32::MAX));
         }
@@ -630,6 +652,13 @@ impl ProgramBuilder {
         self.host_sig(sym, sig)
     }
 
+    /// Interns a pure host-call signature for `symbol` (interning the symbol string) and returns
+    /// its [`HostSigId`].
+    pub fn pure_host_sig_for(&mut self, symbol: &str, sig: HostSig) -> HostSigId {
+        let sym = self.symbol(symbol);
+        self.pure_host_sig(sym, sig)
+    }
+
     /// Interns a call signature and returns its [`CallSigId`].
     pub fn call_sig(&mut self, args: &[ValueType], rets: &[ValueType]) -> CallSigId {
         if let Some(i) = self
@@ -722,6 +751,107 @@ impl ProgramBuilder {
         id
     }
 
+    /// Exports `func` under `name` so other modules can import it.
+    ///
+    /// Exporting a different function under an existing name replaces the earlier export.
+    pub fn export_function(&mut self, func: FuncId, name: &str) -> Result<SymbolId, BuildError> {
+        if self.functions.get(func.0 as usize).is_none() {
+            return Err(BuildError::BadFuncId { func: func.0 });
+        }
+        let sym = self.symbol(name);
+        if let Some(entry) = self.exports.iter_mut().find(|e| e.name == sym) {
+            entry.func = func.0;
+        } else {
+            self.exports.push(ExportEntry {
+                func: func.0,
+                name: sym,
+            });
+        }
+        Ok(sym)
+    }
+
+    /// Declares a function exported as `name` by module `module` and returns the [`FuncId`] of its
+    /// local placeholder.
+    ///
+    /// Call the placeholder like any other function. Its body traps with
+    /// [`UNRESOLVED_IMPORT_TRAP_CODE`] until the program is linked with a module providing the
+    /// export (see [`Linker`](crate::linker::Linker)), which replaces calls to the placeholder with
+    /// calls to the exported function.
+    pub fn import_function(
+        &mut self,
+        module: &str,
+        name: &str,
+        abi: impl Into<FunctionAbi>,
+    ) -> FuncId {
+        let func = self.declare_function(abi);
+        let mut body = Asm::new();
+        body.trap(UNRESOLVED_IMPORT_TRAP_CODE);
+        if let Ok(parts) = body.finish_parts()
+            && let Some(slot) = self.functions.get_mut(func.0 as usize)
+        {
+            slot.bytecode = parts.bytecode;
+        }
+        let module = self.symbol(module);
+        let name = self.symbol(name);
+        self.imports.push(ImportEntry {
+            func: func.0,
+            module,
+            name,
+        });
+        func
+    }
+
+    /// Records where the code tagged with `span` came from, for diagnostics.
+    ///
+    /// `file` names the source file or document; `node_guid` optionally names the authoring-graph
+    /// node. Setting the info for a span again replaces the earlier entry.
+    pub fn set_span_info(
+        &mut self,
+        span: SpanId,
+        file: &str,
+        range: SourceRange,
+        node_guid: Option<&str>,
+    ) {
+        let entry = SourceMapEntry {
+            span,
+            file: self.symbol(file),
+            range,
+            node_guid: node_guid.map(|guid| self.symbol(guid)),
+        };
+        match self.source_map.binary_search_by_key(&span, |e| e.span) {
+            Ok(i) => self.source_map[i] = entry,
+            Err(i) => self.source_map.insert(i, entry),
+        }
+    }
+
+    /// Attaches an embedder-defined section named `name` to the program.
+    ///
+    /// Custom sections are opaque to the VM and verifier and survive encode/decode round trips.
+    /// Adding a section under an existing name replaces the earlier payload.
+    pub fn add_custom_section(&mut self, name: &str, bytes: impl Into<Vec<u8>>) {
+        let bytes = bytes.into();
+        match self
+            .custom_sections
+            .binary_search_by(|s| s.name.as_str().cmp(name))
+        {
+            Ok(i) => self.custom_sections[i].bytes = bytes,
+            Err(i) => self.custom_sections.insert(
+                i,
+                CustomSection {
+                    name: name.into(),
+                    bytes,
+                },
+            ),
+        }
+    }
+
+    /// Appends a function with already-encoded bytecode and returns its [`FuncId`].
+    pub(crate) fn push_function_def(&mut self, def: FunctionDef) -> FuncId {
+        let id = FuncId(u32::try_from(self.functions.len()).unwrap_or(u32::MAX));
+        self.functions.push(def);
+        id
+    }
+
     /// Defines the body of a previously declared function.
     pub fn define_function(&mut self, func: FuncId, a: Asm) -> Result<(), BuildError> {
         let inferred_reg_count = a.inferred_reg_count();
@@ -838,6 +968,10 @@ impl ProgramBuilder {
         p.function_names = self.function_names;
         p.labels = self.labels;
         p.function_arg_agg_shapes = self.function_arg_agg_shapes;
+        p.exports = self.exports;
+        p.imports = self.imports;
+        p.custom_sections = self.custom_sections;
+        p.source_map = self.source_map;
 
         let func_count = p.functions.len();
         let mut has_arg_names = vec![false; func_count];
@@ -1842,6 +1976,21 @@ impl Asm {
         self
     }
 
+    /// Encodes `host_call.pure host_sig, argc, args..., retc, rets...` (no effect token).
+    pub fn host_call_pure(&mut self, host_sig: HostSigId, args: &[u32], rets: &[u32]) -> &mut Self {
+        self.opcode(Opcode::HostCallPure);
+        self.uleb(host_sig.0);
+        self.uleb(u32::try_from(args.len()).unwrap_or(u32::MAX));
+        for &a in args {
+            self.reg(a);
+        }
+        self.uleb(u32::try_from(rets.len()).unwrap_or(u32::MAX));
+        for &r in rets {
+            self.reg(r);
+        }
+        self
+    }
+
     /// Encodes `tuple_new dst, arity, values...`.
     pub fn tuple_new(&mut self, dst: u32, values: &[u32]) -> &mut Self {
         self.opcode(Opcode::TupleNew);
@@ -2196,7 +2345,7 @@ impl Asm {
     }
 }
 
-fn patch_uleb128_u32(bytes: &mut [u8], at: usize, value: u32) {
+pub(crate) fn patch_uleb128_u32(bytes: &mut [u8], at: usize, value: u32) {
     // Patch into the reserved 5-byte window using a fixed-width ULEB128 encoding.
     //
     // This is intentionally not canonical/minimal: it keeps instruction byte offsets stable so we
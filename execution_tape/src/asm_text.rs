@@ -0,0 +1,1894 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Textual `.tape` assembly.
+//!
+//! [`parse`] reads the text written by the [`disasm`](crate::disasm) `Display` impls back into a
+//! [`Program`]. For any program that disassembles without decode errors and whose bytecode uses
+//! canonical LEB128 operands (as emitted by [`Asm`](crate::asm::Asm) or normalized by
+//! [`codec::canonicalize_program`](crate::codec::canonicalize_program)),
+//! `parse(&disassemble(&p).to_string())` yields a program whose [`Program::encode`] output is
+//! identical to `p.encode()`. The text does not record padded operands, so other programs come
+//! back with canonical operands.
+//!
+//! The syntax is line-oriented and `;` starts a comment:
+//!
+//! ```text
+//! .program "demo"
+//! .symbol 1 "demo"
+//! .const 0 str "hello"
+//! .struct 0 [x: I64, y: Str]
+//! .elem 0 I64
+//! .host_sig 0 "math.add" [I64, I64] -> [I64]
+//! .host_sig 1 pure "math.neg" [I64] -> [I64]
+//! .call_sig 0 [I64] -> [I64]
+//! .arg_shape f0 1 tuple [I64, _]
+//! .export f0 "main"
+//! .import f1 "lib" "helper"
+//! .source 7 "main.graph" 3 5 3 20 "node-7f3a"
+//! .custom "build.info" 0x0102
+//!
+//! func f0:
+//!   .name "main"
+//!   .sig [x: I64] -> [out: I64]
+//!   .regs 4
+//!   .span 7
+//!   000000: const.i64 r2, imm=1
+//!   000003: br r1, @L1, @L1
+//!   @L1:
+//!   .label "done"
+//!   000006: ret eff=r0, rets=[out=r2]
+//! ```
+//!
+//! - Program tables are written as numbered directives (`.symbol`, `.const`, `.struct`, `.elem`,
+//!   `.host_sig`, `.call_sig`); ids must appear in order, starting at `1` for symbols and `0` for
+//!   everything else. Symbols are referenced by quoted string (resolving to the first matching
+//!   entry, or appended if absent) or by `#<id>`.
+//! - `.export f<n> <name>` and `.import f<n> <module> <name>` list module exports and imports (see
+//!   [`linker`](crate::linker)), in table order.
+//! - `.source <span> <file> <start_line> <start_col> <end_line> <end_col> [<node_guid>]` adds a
+//!   source-map entry (see [`Program::span_info`]).
+//! - `.custom <name> 0x<hex>` attaches a custom section (see [`CustomSection`]); a later
+//!   `.custom` with the same name replaces the earlier payload.
+//! - `func f<n>:` starts a function. `.sig` gives argument and return types (with optional
+//!   `name:` prefixes, `_` for an unnamed slot), `.regs` the register count (inferred when
+//!   omitted), and `.pcs wide` requests fixed-width 5-byte branch targets as emitted by
+//!   [`Asm`](crate::asm::Asm).
+//! - `.label` and `.span` attach to the next instruction (or the end of the function) unless
+//!   given an explicit `pc=<n>`.
+//! - Instructions use the disassembler's operand syntax. A leading `<pc>:` is informational and
+//!   ignored; `@name:` defines a branch label and `@name` / `@<pc>` refer to one.
+//!
+//! Bytecode is encoded with canonical LEB128 operands (apart from `.pcs wide` branch targets).
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::num::{NonZeroU32, NonZeroU64};
+
+use crate::asm::patch_uleb128_u32;
+use crate::codec_primitives::{
+    write_bool_u8, write_i64_sleb, write_reg, write_u8_raw, write_u32_uleb, write_u64_le,
+    write_u64_uleb,
+};
+use crate::opcode::{Opcode, OperandAccess, OperandEncoding, OperandKind};
+use crate::program::{
+    AggShape, ByteRange, CallSigEntry, Const, CustomSection, ElemTypeId, ExportEntry,
+    FunctionArgAggShapeEntry, FunctionDef, FunctionNameEntry, HostSigDef, HostSymbol, HostTypeId,
+    ImportEntry, LabelNameEntry, Program, SourceMapEntry, SourceRange, SpanEntry, SpanId,
+    StructTypeDef, SymbolId, TypeId, TypeTableDef, ValueType,
+};
+
+/// Parses `.tape` assembly text into a [`Program`].
+pub fn parse(src: &str) -> Result<Program, ParseError> {
+    let mut asm = TextAssembler::default();
+    for (ix, text) in src.lines().enumerate() {
+        let mut line = Line::lex(text, ix + 1)?;
+        if line.at_end() {
+            continue;
+        }
+        asm.line(&mut line)?;
+    }
+    asm.finish()
+}
+
+/// A `.tape` parse error with a 1-based source position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column (in characters).
+    pub column: usize,
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+}
+
+/// The kind of a [`ParseError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character that does not start any token.
+    UnexpectedChar(char),
+    /// A string literal is missing its closing quote.
+    UnterminatedString,
+    /// A string literal contains an unknown or malformed escape sequence.
+    InvalidEscape,
+    /// A different token was expected.
+    Expected(&'static str),
+    /// An integer literal is malformed or out of range for its use.
+    InvalidInteger,
+    /// Unknown `.directive`.
+    UnknownDirective,
+    /// Unknown instruction mnemonic.
+    UnknownMnemonic,
+    /// Unknown value type name.
+    UnknownType,
+    /// A numbered table entry or function is out of sequence.
+    UnexpectedId {
+        /// The next id in sequence.
+        expected: u32,
+        /// The id that was written.
+        found: u32,
+    },
+    /// A `#<id>` symbol reference is outside the symbol table.
+    UnknownSymbol(u32),
+    /// A label is defined twice in the same function.
+    DuplicateLabel,
+    /// A label reference has no definition in its function.
+    UndefinedLabel,
+    /// A function-level directive or instruction appears before any `func`.
+    OutsideFunction,
+    /// A span id is zero.
+    ZeroSpanId,
+    /// Span entries are not in non-decreasing pc order.
+    SpanOrder,
+    /// An instruction has the wrong number of operands.
+    OperandCount {
+        /// Operands required by the opcode.
+        expected: usize,
+        /// Operands written.
+        found: usize,
+    },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+            Self::UnterminatedString => write!(f, "unterminated string literal"),
+            Self::InvalidEscape => write!(f, "invalid escape sequence"),
+            Self::Expected(what) => write!(f, "expected {what}"),
+            Self::InvalidInteger => write!(f, "invalid or out-of-range integer"),
+            Self::UnknownDirective => write!(f, "unknown directive"),
+            Self::UnknownMnemonic => write!(f, "unknown instruction mnemonic"),
+            Self::UnknownType => write!(f, "unknown value type"),
+            Self::UnexpectedId { expected, found } => {
+                write!(f, "expected id {expected}, found {found}")
+            }
+            Self::UnknownSymbol(id) => write!(f, "symbol #{id} is not in the symbol table"),
+            Self::DuplicateLabel => write!(f, "label defined twice"),
+            Self::UndefinedLabel => write!(f, "undefined label"),
+            Self::OutsideFunction => write!(f, "must appear inside a function"),
+            Self::ZeroSpanId => write!(f, "span ids must be non-zero"),
+            Self::SpanOrder => write!(f, "span pcs must be non-decreasing"),
+            Self::OperandCount { expected, found } => {
+                write!(f, "expected {expected} operands, found {found}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Pos {
+    line: usize,
+    column: usize,
+}
+
+impl Pos {
+    fn err(self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Tok<'a> {
+    Ident(&'a str),
+    Num(&'a str),
+    Str(String),
+    Punct(char),
+    Arrow,
+}
+
+#[derive(Clone, Debug)]
+struct Token<'a> {
+    tok: Tok<'a>,
+    column: usize,
+}
+
+/// A lexed source line with a read cursor.
+struct Line<'a> {
+    toks: Vec<Token<'a>>,
+    next: usize,
+    line: usize,
+    end_column: usize,
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+impl<'a> Line<'a> {
+    fn lex(text: &'a str, line: usize) -> Result<Self, ParseError> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let mut toks = Vec::new();
+        let mut i = 0;
+        while let Some(&(start, c)) = chars.get(i) {
+            let column = i + 1;
+            let byte_end = |j: usize| chars.get(j).map_or(text.len(), |&(b, _)| b);
+            let next_is_digit = chars.get(i + 1).is_some_and(|&(_, n)| n.is_ascii_digit());
+            if c.is_whitespace() {
+                i += 1;
+            } else if c == ';' {
+                break;
+            } else if c == '"' {
+                let (s, len) = lex_string(&chars[i + 1..]).map_err(|(kind, at)| ParseError {
+                    line,
+                    column: column + 1 + at,
+                    kind,
+                })?;
+                toks.push(Token {
+                    tok: Tok::Str(s),
+                    column,
+                });
+                i += len + 2;
+            } else if c.is_ascii_digit() || (c == '-' && next_is_digit) {
+                let mut j = i + 1;
+                while chars
+                    .get(j)
+                    .is_some_and(|&(_, n)| n.is_ascii_alphanumeric() || n == '_')
+                {
+                    j += 1;
+                }
+                toks.push(Token {
+                    tok: Tok::Num(&text[start..byte_end(j)]),
+                    column,
+                });
+                i = j;
+            } else if c.is_ascii_alphabetic() || c == '_' || c == '.' {
+                let mut j = i + 1;
+                while chars.get(j).is_some_and(|&(_, n)| is_ident_char(n)) {
+                    j += 1;
+                }
+                toks.push(Token {
+                    tok: Tok::Ident(&text[start..byte_end(j)]),
+                    column,
+                });
+                i = j;
+            } else if c == '-' && chars.get(i + 1).is_some_and(|&(_, n)| n == '>') {
+                toks.push(Token {
+                    tok: Tok::Arrow,
+                    column,
+                });
+                i += 2;
+            } else if matches!(c, ',' | '[' | ']' | '(' | ')' | ':' | '=' | '#' | '@') {
+                toks.push(Token {
+                    tok: Tok::Punct(c),
+                    column,
+                });
+                i += 1;
+            } else {
+                return Err(ParseError {
+                    line,
+                    column,
+                    kind: ParseErrorKind::UnexpectedChar(c),
+                });
+            }
+        }
+        Ok(Self {
+            toks,
+            next: 0,
+            line,
+            end_column: chars.len() + 1,
+        })
+    }
+
+    fn pos(&self) -> Pos {
+        Pos {
+            line: self.line,
+            column: self
+                .toks
+                .get(self.next)
+                .map_or(self.end_column, |t| t.column),
+        }
+    }
+
+    fn err(&self, kind: ParseErrorKind) -> ParseError {
+        self.pos().err(kind)
+    }
+
+    fn peek(&self) -> Option<&Tok<'a>> {
+        self.toks.get(self.next).map(|t| &t.tok)
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<&Tok<'a>> {
+        self.toks.get(self.next + ahead).map(|t| &t.tok)
+    }
+
+    fn at_end(&self) -> bool {
+        self.next >= self.toks.len()
+    }
+
+    fn bump(&mut self) -> Option<Tok<'a>> {
+        let t = self.toks.get(self.next)?.tok.clone();
+        self.next += 1;
+        Some(t)
+    }
+
+    fn end(&self) -> Result<(), ParseError> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.err(ParseErrorKind::Expected("end of line")))
+        }
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Tok::Punct(c)) {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, c: char, what: &'static str) -> Result<(), ParseError> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.err(ParseErrorKind::Expected(what)))
+        }
+    }
+
+    fn ident(&mut self, what: &'static str) -> Result<&'a str, ParseError> {
+        match self.peek() {
+            Some(&Tok::Ident(s)) => {
+                self.next += 1;
+                Ok(s)
+            }
+            _ => Err(self.err(ParseErrorKind::Expected(what))),
+        }
+    }
+
+    fn keyword(&mut self, kw: &'static str) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(&Tok::Ident(s)) if s == kw => {
+                self.next += 1;
+                Ok(())
+            }
+            _ => Err(self.err(ParseErrorKind::Expected(kw))),
+        }
+    }
+
+    /// Parses `key=` for a keyword operand such as `eff_out=`.
+    fn key(&mut self, kw: &'static str) -> Result<(), ParseError> {
+        self.keyword(kw)?;
+        self.expect_punct('=', "'='")
+    }
+
+    fn num(&mut self, what: &'static str) -> Result<(&'a str, Pos), ParseError> {
+        let pos = self.pos();
+        match self.peek() {
+            Some(&Tok::Num(s)) => {
+                self.next += 1;
+                Ok((s, pos))
+            }
+            _ => Err(self.err(ParseErrorKind::Expected(what))),
+        }
+    }
+
+    fn u64(&mut self) -> Result<u64, ParseError> {
+        let (s, pos) = self.num("integer")?;
+        parse_u64(s).ok_or(pos.err(ParseErrorKind::InvalidInteger))
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        let (s, pos) = self.num("integer")?;
+        parse_u64(s)
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or(pos.err(ParseErrorKind::InvalidInteger))
+    }
+
+    fn i64(&mut self) -> Result<i64, ParseError> {
+        let (s, pos) = self.num("integer")?;
+        parse_i64(s).ok_or(pos.err(ParseErrorKind::InvalidInteger))
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Tok::Str(_)) => match self.bump() {
+                Some(Tok::Str(s)) => Ok(s),
+                _ => unreachable!("peeked a string token"),
+            },
+            _ => Err(self.err(ParseErrorKind::Expected("string literal"))),
+        }
+    }
+
+    /// Parses a numbered table entry id and checks it is next in sequence.
+    fn table_id(&mut self, expected: usize) -> Result<(), ParseError> {
+        let pos = self.pos();
+        let found = self.u32()?;
+        let expected =
+            u32::try_from(expected).map_err(|_| pos.err(ParseErrorKind::InvalidInteger))?;
+        if found != expected {
+            return Err(pos.err(ParseErrorKind::UnexpectedId { expected, found }));
+        }
+        Ok(())
+    }
+
+    /// Parses `<prefix>#<n>`, e.g. `host_sig#3`.
+    fn id_ref(&mut self, prefix: &'static str) -> Result<u32, ParseError> {
+        self.keyword(prefix)?;
+        self.expect_punct('#', "'#'")?;
+        self.u32()
+    }
+
+    /// Parses a function reference: `f<n>` or `func#<n>`.
+    fn func_ref(&mut self) -> Result<u32, ParseError> {
+        let pos = self.pos();
+        if self.peek() == Some(&Tok::Ident("func")) {
+            return self.id_ref("func");
+        }
+        let s = self.ident("function reference")?;
+        s.strip_prefix('f')
+            .and_then(|n| n.parse().ok())
+            .ok_or(pos.err(ParseErrorKind::Expected("function reference")))
+    }
+
+    fn reg(&mut self) -> Result<u32, ParseError> {
+        let pos = self.pos();
+        let s = self.ident("register")?;
+        s.strip_prefix('r')
+            .filter(|n| n.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|n| n.parse().ok())
+            .ok_or(pos.err(ParseErrorKind::Expected("register")))
+    }
+
+    /// Parses `[r1, name=r2, "odd name"=r3]`; names are informational and ignored.
+    fn reg_list(&mut self) -> Result<Vec<u32>, ParseError> {
+        self.expect_punct('[', "'['")?;
+        let mut regs = Vec::new();
+        if self.eat_punct(']') {
+            return Ok(regs);
+        }
+        loop {
+            if matches!(self.peek(), Some(Tok::Str(_)))
+                || (matches!(self.peek(), Some(Tok::Ident(_)))
+                    && self.peek_at(1) == Some(&Tok::Punct('=')))
+            {
+                self.next += 1;
+                self.expect_punct('=', "'='")?;
+            }
+            regs.push(self.reg()?);
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        self.expect_punct(']', "']'")?;
+        Ok(regs)
+    }
+
+    /// Parses a symbol reference: a string (or bare name) or `#<id>`.
+    fn sym_ref(&mut self) -> Result<Located<SymRef>, ParseError> {
+        let pos = self.pos();
+        let value = if self.eat_punct('#') {
+            SymRef::Id(self.u32()?)
+        } else if let Some(&Tok::Ident(s)) = self.peek() {
+            self.next += 1;
+            SymRef::Str(String::from(s))
+        } else {
+            SymRef::Str(self.string()?)
+        };
+        Ok(Located { value, pos })
+    }
+
+    /// Parses a field or value name: a bare name or string.
+    fn name(&mut self) -> Result<String, ParseError> {
+        if let Some(&Tok::Ident(s)) = self.peek() {
+            self.next += 1;
+            return Ok(String::from(s));
+        }
+        self.string()
+    }
+
+    fn value_type(&mut self) -> Result<ValueType, ParseError> {
+        let pos = self.pos();
+        let ty = match self.ident("value type")? {
+            "Unit" => ValueType::Unit,
+            "Bool" => ValueType::Bool,
+            "I64" => ValueType::I64,
+            "U64" => ValueType::U64,
+            "F64" => ValueType::F64,
+            "Decimal" => ValueType::Decimal,
+            "Bytes" => ValueType::Bytes,
+            "Str" => ValueType::Str,
+            "Agg" => ValueType::Agg,
+            "Func" => ValueType::Func,
+            "Closure" => ValueType::Closure,
+            "Obj" => {
+                self.expect_punct('(', "'('")?;
+                let id = self.u64()?;
+                self.expect_punct(')', "')'")?;
+                ValueType::Obj(HostTypeId(id))
+            }
+            _ => return Err(pos.err(ParseErrorKind::UnknownType)),
+        };
+        Ok(ty)
+    }
+
+    fn type_list(&mut self) -> Result<Vec<ValueType>, ParseError> {
+        self.expect_punct('[', "'['")?;
+        let mut types = Vec::new();
+        if self.eat_punct(']') {
+            return Ok(types);
+        }
+        loop {
+            types.push(self.value_type()?);
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        self.expect_punct(']', "']'")?;
+        Ok(types)
+    }
+
+    fn signature(&mut self) -> Result<(Vec<ValueType>, Vec<ValueType>), ParseError> {
+        let args = self.type_list()?;
+        if self.bump() != Some(Tok::Arrow) {
+            self.next -= 1;
+            return Err(self.err(ParseErrorKind::Expected("'->'")));
+        }
+        Ok((args, self.type_list()?))
+    }
+
+    /// Parses an optional trailing `pc=<n>`.
+    fn anchor(&mut self, next_instr: usize) -> Result<Anchor, ParseError> {
+        if self.at_end() {
+            return Ok(Anchor::Next(next_instr));
+        }
+        self.key("pc")?;
+        Ok(Anchor::Pc(self.u32()?))
+    }
+}
+
+/// Lexes a string literal body (after the opening quote).
+///
+/// Returns the decoded string and the number of source chars consumed (excluding both quotes), or
+/// the error and its char offset.
+fn lex_string(chars: &[(usize, char)]) -> Result<(String, usize), (ParseErrorKind, usize)> {
+    let mut out = String::new();
+    let mut i = 0;
+    loop {
+        let Some(&(_, c)) = chars.get(i) else {
+            return Err((ParseErrorKind::UnterminatedString, i));
+        };
+        match c {
+            '"' => return Ok((out, i)),
+            '\\' => {
+                let at = i;
+                let esc = chars.get(i + 1).map(|&(_, e)| e);
+                i += 2;
+                let decoded = match esc {
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('0') => '\0',
+                    Some('\\') => '\\',
+                    Some('"') => '"',
+                    Some('\'') => '\'',
+                    Some('u') => {
+                        if chars.get(i).map(|&(_, c)| c) != Some('{') {
+                            return Err((ParseErrorKind::InvalidEscape, at));
+                        }
+                        let start = i + 1;
+                        let mut end = start;
+                        while chars.get(end).is_some_and(|&(_, c)| c.is_ascii_hexdigit()) {
+                            end += 1;
+                        }
+                        if chars.get(end).map(|&(_, c)| c) != Some('}') {
+                            return Err((ParseErrorKind::InvalidEscape, at));
+                        }
+                        let hex: String = chars[start..end].iter().map(|&(_, c)| c).collect();
+                        i = end + 1;
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or((ParseErrorKind::InvalidEscape, at))?
+                    }
+                    _ => return Err((ParseErrorKind::InvalidEscape, at)),
+                };
+                out.push(decoded);
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+}
+
+fn parse_u64(s: &str) -> Option<u64> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn parse_i64(s: &str) -> Option<i64> {
+    if s.starts_with('-') {
+        s.parse().ok()
+    } else {
+        parse_u64(s).and_then(|v| i64::try_from(v).ok())
+    }
+}
+
+fn parse_hex_bytes(s: &str) -> Option<Vec<u8>> {
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+struct Located<T> {
+    value: T,
+    pos: Pos,
+}
+
+#[derive(Clone, Debug)]
+enum SymRef {
+    Str(String),
+    Id(u32),
+}
+
+/// Where a `.label` or `.span` applies.
+#[derive(Copy, Clone, Debug)]
+enum Anchor {
+    /// The pc of the instruction at this index (or the end of the function).
+    Next(usize),
+    /// An explicit pc.
+    Pc(u32),
+}
+
+#[derive(Clone, Debug)]
+enum Operand {
+    Reg(u32),
+    Regs(Vec<u32>),
+    Imm(u64),
+    Pc(u32),
+    Label(Located<String>),
+}
+
+#[derive(Clone, Debug)]
+struct InstrText {
+    opcode: Opcode,
+    operands: Vec<Operand>,
+    pos: Pos,
+}
+
+type SigNames = Option<Vec<Option<Located<SymRef>>>>;
+
+#[derive(Default)]
+struct FuncText {
+    names: Vec<Located<SymRef>>,
+    arg_types: Vec<ValueType>,
+    arg_names: SigNames,
+    ret_types: Vec<ValueType>,
+    ret_names: SigNames,
+    regs: Option<u32>,
+    wide_pcs: bool,
+    instrs: Vec<InstrText>,
+    labels: BTreeMap<String, usize>,
+    label_names: Vec<(Located<SymRef>, Anchor)>,
+    spans: Vec<(SpanId, Anchor, Pos)>,
+    max_reg: Option<u32>,
+}
+
+struct HostSigText {
+    symbol: Located<SymRef>,
+    args: Vec<ValueType>,
+    rets: Vec<ValueType>,
+    pure: bool,
+}
+
+struct SourceText {
+    file: Located<SymRef>,
+    range: SourceRange,
+    node_guid: Option<Located<SymRef>>,
+}
+
+struct ImportText {
+    func: u32,
+    module: Located<SymRef>,
+    name: Located<SymRef>,
+}
+
+#[derive(Default)]
+struct TextAssembler {
+    symbols: Vec<String>,
+    consts: Vec<Const>,
+    types: TypeTableDef,
+    host_sigs: Vec<HostSigText>,
+    call_sigs: Vec<(Vec<ValueType>, Vec<ValueType>)>,
+    arg_shapes: Vec<FunctionArgAggShapeEntry>,
+    exports: Vec<(u32, Located<SymRef>)>,
+    imports: Vec<ImportText>,
+    source_map: BTreeMap<SpanId, SourceText>,
+    custom_sections: BTreeMap<String, Vec<u8>>,
+    program_name: Option<Located<SymRef>>,
+    funcs: Vec<FuncText>,
+}
+
+impl TextAssembler {
+    fn line(&mut self, line: &mut Line<'_>) -> Result<(), ParseError> {
+        match line.peek() {
+            Some(Tok::Num(_)) if line.peek_at(1) == Some(&Tok::Punct(':')) => {
+                // `000012:` pc prefix (informational).
+                line.next += 2;
+                self.instr(line)
+            }
+            Some(&Tok::Ident("func")) => {
+                line.next += 1;
+                let pos = line.pos();
+                let id = line.func_ref()?;
+                let expected = u32::try_from(self.funcs.len())
+                    .map_err(|_| pos.err(ParseErrorKind::InvalidInteger))?;
+                if id != expected {
+                    return Err(pos.err(ParseErrorKind::UnexpectedId {
+                        expected,
+                        found: id,
+                    }));
+                }
+                line.expect_punct(':', "':'")?;
+                line.end()?;
+                self.funcs.push(FuncText::default());
+                Ok(())
+            }
+            Some(&Tok::Ident(s)) if s.starts_with('.') => self.directive(line),
+            Some(Tok::Punct('@')) => {
+                let func = self.current(line)?;
+                line.next += 1;
+                let pos = line.pos();
+                let name = line.ident("label name")?;
+                line.expect_punct(':', "':'")?;
+                line.end()?;
+                let at = func.instrs.len();
+                if func.labels.insert(String::from(name), at).is_some() {
+                    return Err(pos.err(ParseErrorKind::DuplicateLabel));
+                }
+                Ok(())
+            }
+            _ => self.instr(line),
+        }
+    }
+
+    fn current(&mut self, line: &Line<'_>) -> Result<&mut FuncText, ParseError> {
+        self.funcs
+            .last_mut()
+            .ok_or(line.err(ParseErrorKind::OutsideFunction))
+    }
+
+    fn directive(&mut self, line: &mut Line<'_>) -> Result<(), ParseError> {
+        let pos = line.pos();
+        let name = line.ident("directive")?;
+        match name {
+            ".program" => self.program_name = Some(line.sym_ref()?),
+            ".symbol" => {
+                line.table_id(self.symbols.len() + 1)?;
+                self.symbols.push(line.string()?);
+            }
+            ".const" => {
+                line.table_id(self.consts.len())?;
+                let c = self.constant(line)?;
+                self.consts.push(c);
+            }
+            ".struct" => {
+                line.table_id(self.types.structs.len())?;
+                let mut st = StructTypeDef {
+                    field_names: Vec::new(),
+                    field_types: Vec::new(),
+                };
+                line.expect_punct('[', "'['")?;
+                if !line.eat_punct(']') {
+                    loop {
+                        st.field_names.push(line.name()?);
+                        line.expect_punct(':', "':'")?;
+                        st.field_types.push(line.value_type()?);
+                        if !line.eat_punct(',') {
+                            break;
+                        }
+                    }
+                    line.expect_punct(']', "']'")?;
+                }
+                self.types.structs.push(st);
+            }
+            ".elem" => {
+                line.table_id(self.types.array_elems.len())?;
+                let ty = line.value_type()?;
+                self.types.array_elems.push(ty);
+            }
+            ".host_sig" => {
+                line.table_id(self.host_sigs.len())?;
+                let pure = line.peek() == Some(&Tok::Ident("pure"));
+                if pure {
+                    line.next += 1;
+                }
+                let symbol = line.sym_ref()?;
+                let (args, rets) = line.signature()?;
+                self.host_sigs.push(HostSigText {
+                    symbol,
+                    args,
+                    rets,
+                    pure,
+                });
+            }
+            ".call_sig" => {
+                line.table_id(self.call_sigs.len())?;
+                let sig = line.signature()?;
+                self.call_sigs.push(sig);
+            }
+            ".arg_shape" => {
+                let func = line.func_ref()?;
+                let arg = line.u32()?;
+                let shape = match line.ident("aggregate shape")? {
+                    "tuple" => {
+                        line.expect_punct('[', "'['")?;
+                        let mut elems = Vec::new();
+                        if !line.eat_punct(']') {
+                            loop {
+                                if line.peek() == Some(&Tok::Ident("_")) {
+                                    line.next += 1;
+                                    elems.push(None);
+                                } else {
+                                    elems.push(Some(line.value_type()?));
+                                }
+                                if !line.eat_punct(',') {
+                                    break;
+                                }
+                            }
+                            line.expect_punct(']', "']'")?;
+                        }
+                        AggShape::Tuple { elems }
+                    }
+                    "struct" => AggShape::Struct(TypeId(line.id_ref("type")?)),
+                    "array" => AggShape::Array(ElemTypeId(line.id_ref("elem_type")?)),
+                    _ => return Err(pos.err(ParseErrorKind::Expected("aggregate shape"))),
+                };
+                self.arg_shapes
+                    .push(FunctionArgAggShapeEntry { func, arg, shape });
+            }
+            ".export" => {
+                let func = line.func_ref()?;
+                let name = line.sym_ref()?;
+                self.exports.push((func, name));
+            }
+            ".import" => {
+                let func = line.func_ref()?;
+                let module = line.sym_ref()?;
+                let name = line.sym_ref()?;
+                self.imports.push(ImportText { func, module, name });
+            }
+            ".source" => {
+                let id_pos = line.pos();
+                let span = NonZeroU64::new(line.u64()?)
+                    .map(SpanId)
+                    .ok_or(id_pos.err(ParseErrorKind::ZeroSpanId))?;
+                let file = line.sym_ref()?;
+                let range = SourceRange {
+                    start_line: line.u32()?,
+                    start_col: line.u32()?,
+                    end_line: line.u32()?,
+                    end_col: line.u32()?,
+                };
+                let node_guid = if line.at_end() {
+                    None
+                } else {
+                    Some(line.sym_ref()?)
+                };
+                self.source_map.insert(
+                    span,
+                    SourceText {
+                        file,
+                        range,
+                        node_guid,
+                    },
+                );
+            }
+            ".custom" => {
+                let name = line.string()?;
+                let (s, pos) = line.num("hex bytes")?;
+                let bytes = parse_hex_bytes(s).ok_or(pos.err(ParseErrorKind::InvalidInteger))?;
+                self.custom_sections.insert(name, bytes);
+            }
+            ".name" => {
+                let name = line.sym_ref()?;
+                self.current(line)?.names.push(name);
+            }
+            ".sig" => {
+                let (arg_types, arg_names) = sig_list(line)?;
+                if line.bump() != Some(Tok::Arrow) {
+                    line.next -= 1;
+                    return Err(line.err(ParseErrorKind::Expected("'->'")));
+                }
+                let (ret_types, ret_names) = sig_list(line)?;
+                let func = self.current(line)?;
+                func.arg_types = arg_types;
+                func.arg_names = arg_names;
+                func.ret_types = ret_types;
+                func.ret_names = ret_names;
+            }
+            ".regs" => {
+                let regs = line.u32()?;
+                self.current(line)?.regs = Some(regs);
+            }
+            ".pcs" => {
+                line.keyword("wide")?;
+                self.current(line)?.wide_pcs = true;
+            }
+            ".label" => {
+                let name = line.sym_ref()?;
+                let func = self.current(line)?;
+                let next = func.instrs.len();
+                let anchor = line.anchor(next)?;
+                self.current(line)?.label_names.push((name, anchor));
+            }
+            ".span" => {
+                let id_pos = line.pos();
+                let id = NonZeroU64::new(line.u64()?)
+                    .map(SpanId)
+                    .ok_or(id_pos.err(ParseErrorKind::ZeroSpanId))?;
+                let func = self.current(line)?;
+                let next = func.instrs.len();
+                let anchor = line.anchor(next)?;
+                self.current(line)?.spans.push((id, anchor, id_pos));
+            }
+            _ => return Err(pos.err(ParseErrorKind::UnknownDirective)),
+        }
+        line.end()
+    }
+
+    fn constant(&mut self, line: &mut Line<'_>) -> Result<Const, ParseError> {
+        let pos = line.pos();
+        let c = match line.ident("constant kind")? {
+            "unit" => Const::Unit,
+            "bool" => Const::Bool(bool_lit(line)?),
+            "i64" => Const::I64(line.i64()?),
+            "u64" => Const::U64(line.u64()?),
+            "f64" => Const::F64(line.u64()?),
+            "decimal" => {
+                let mantissa = line.i64()?;
+                let scale_pos = line.pos();
+                let scale = u8::try_from(line.u32()?)
+                    .map_err(|_| scale_pos.err(ParseErrorKind::InvalidInteger))?;
+                Const::Decimal { mantissa, scale }
+            }
+            "bytes" => {
+                let (s, pos) = line.num("hex bytes")?;
+                Const::Bytes(parse_hex_bytes(s).ok_or(pos.err(ParseErrorKind::InvalidInteger))?)
+            }
+            "str" => Const::Str(line.string()?),
+            _ => return Err(pos.err(ParseErrorKind::Expected("constant kind"))),
+        };
+        Ok(c)
+    }
+
+    fn instr(&mut self, line: &mut Line<'_>) -> Result<(), ParseError> {
+        let pos = line.pos();
+        let mnemonic = line.ident("instruction")?;
+        let opcode = (0..=u8::MAX)
+            .filter_map(Opcode::from_u8)
+            .find(|op| op.mnemonic() == mnemonic)
+            .ok_or(pos.err(ParseErrorKind::UnknownMnemonic))?;
+        let func = self.current(line)?;
+        let operands = match opcode {
+            Opcode::Br => {
+                let cond = line.reg()?;
+                line.expect_punct(',', "','")?;
+                let pc_true = target(line)?;
+                line.expect_punct(',', "','")?;
+                let pc_false = target(line)?;
+                vec![Operand::Reg(cond), pc_true, pc_false]
+            }
+            Opcode::Jmp => vec![target(line)?],
+            Opcode::Call | Opcode::HostCall | Opcode::CallIndirect => {
+                line.key("eff_out")?;
+                let eff_out = line.reg()?;
+                line.expect_punct(',', "','")?;
+                let mut operands = vec![Operand::Reg(eff_out)];
+                match opcode {
+                    Opcode::Call => operands.push(Operand::Imm(u64::from(line.func_ref()?))),
+                    Opcode::HostCall => operands.push(host_sig_ref(line)?),
+                    _ => {
+                        operands.push(Operand::Imm(u64::from(line.id_ref("call_sig")?)));
+                        line.expect_punct(',', "','")?;
+                        line.key("callee")?;
+                        operands.push(Operand::Reg(line.reg()?));
+                    }
+                }
+                line.expect_punct(',', "','")?;
+                line.key("eff_in")?;
+                operands.push(Operand::Reg(line.reg()?));
+                line.expect_punct(',', "','")?;
+                operands.extend(args_rets(line)?);
+                operands
+            }
+            Opcode::HostCallPure => {
+                let mut operands = vec![host_sig_ref(line)?];
+                line.expect_punct(',', "','")?;
+                operands.extend(args_rets(line)?);
+                operands
+            }
+            Opcode::Ret => {
+                line.key("eff")?;
+                let eff = line.reg()?;
+                line.expect_punct(',', "','")?;
+                line.key("rets")?;
+                vec![Operand::Reg(eff), Operand::Regs(line.reg_list()?)]
+            }
+            _ => simple_operands(line, opcode, pos)?,
+        };
+        line.end()?;
+        for op in &operands {
+            let regs: &[u32] = match op {
+                Operand::Reg(r) => core::slice::from_ref(r),
+                Operand::Regs(rs) => rs,
+                _ => &[],
+            };
+            if let Some(&max) = regs.iter().max() {
+                func.max_reg = Some(func.max_reg.map_or(max, |m| m.max(max)));
+            }
+        }
+        func.instrs.push(InstrText {
+            opcode,
+            operands,
+            pos,
+        });
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Program, ParseError> {
+        let mut symbols = SymbolTable::new(self.symbols);
+        let program_name = self
+            .program_name
+            .as_ref()
+            .map(|r| symbols.resolve(r))
+            .transpose()?;
+        let mut exports = Vec::with_capacity(self.exports.len());
+        for (func, name) in &self.exports {
+            exports.push(ExportEntry {
+                func: *func,
+                name: symbols.resolve(name)?,
+            });
+        }
+        let mut imports = Vec::with_capacity(self.imports.len());
+        for import in &self.imports {
+            imports.push(ImportEntry {
+                func: import.func,
+                module: symbols.resolve(&import.module)?,
+                name: symbols.resolve(&import.name)?,
+            });
+        }
+        let mut source_map = Vec::with_capacity(self.source_map.len());
+        for (span, e) in &self.source_map {
+            source_map.push(SourceMapEntry {
+                span: *span,
+                file: symbols.resolve(&e.file)?,
+                range: e.range,
+                node_guid: e
+                    .node_guid
+                    .as_ref()
+                    .map(|r| symbols.resolve(r))
+                    .transpose()?,
+            });
+        }
+        let mut host_sigs = Vec::with_capacity(self.host_sigs.len());
+        for sig in self.host_sigs {
+            host_sigs.push(HostSigDef {
+                symbol: symbols.resolve(&sig.symbol)?,
+                args: sig.args,
+                rets: sig.rets,
+                pure: sig.pure,
+            });
+        }
+
+        let mut defs = Vec::with_capacity(self.funcs.len());
+        let mut function_names = Vec::new();
+        let mut labels = Vec::new();
+        let mut value_names = Vec::new();
+        for (ix, f) in self.funcs.into_iter().enumerate() {
+            let func = u32::try_from(ix).unwrap_or(u32::MAX);
+            for name in &f.names {
+                function_names.push(FunctionNameEntry {
+                    func,
+                    name: symbols.resolve(name)?,
+                });
+            }
+            value_names.push((
+                symbols.resolve_names(f.arg_names.as_deref())?,
+                symbols.resolve_names(f.ret_names.as_deref())?,
+            ));
+            let (bytecode, pcs) = layout(&f)?;
+            let pc_of = |anchor: Anchor| match anchor {
+                Anchor::Next(i) => pcs[i],
+                Anchor::Pc(pc) => pc,
+            };
+            for (name, anchor) in &f.label_names {
+                labels.push(LabelNameEntry {
+                    func,
+                    pc: pc_of(*anchor),
+                    name: symbols.resolve(name)?,
+                });
+            }
+            let mut spans = Vec::with_capacity(f.spans.len());
+            let mut prev = 0_u32;
+            for &(span_id, anchor, pos) in &f.spans {
+                let pc = pc_of(anchor);
+                let pc_delta = pc
+                    .checked_sub(prev)
+                    .ok_or(pos.err(ParseErrorKind::SpanOrder))?;
+                spans.push(SpanEntry {
+                    pc_delta: u64::from(pc_delta),
+                    span_id,
+                });
+                prev = pc;
+            }
+            let min_regs = u32::try_from(f.arg_types.len())
+                .unwrap_or(u32::MAX)
+                .saturating_add(1);
+            let used_regs = f.max_reg.map_or(0, |r| r.saturating_add(1));
+            defs.push(FunctionDef {
+                arg_types: f.arg_types,
+                ret_types: f.ret_types,
+                reg_count: f.regs.unwrap_or(min_regs.max(used_regs)),
+                bytecode,
+                spans,
+            });
+        }
+
+        let types = self.types;
+        let mut p = Program::new(
+            symbols
+                .table
+                .into_iter()
+                .map(|symbol| HostSymbol { symbol })
+                .collect(),
+            self.consts,
+            host_sigs,
+            types,
+            defs,
+        );
+        for (args, rets) in self.call_sigs {
+            let args_range = push_types(&mut p.value_types, &args);
+            let rets_range = push_types(&mut p.value_types, &rets);
+            p.call_sigs.push(CallSigEntry {
+                args: args_range,
+                rets: rets_range,
+            });
+        }
+        for (f, (arg_names, ret_names)) in p.functions.iter_mut().zip(value_names) {
+            if let Some(names) = arg_names {
+                f.arg_name_ids = push_names(&mut p.value_name_ids, &names);
+            }
+            if let Some(names) = ret_names {
+                f.ret_name_ids = push_names(&mut p.value_name_ids, &names);
+            }
+        }
+        p.function_arg_agg_shapes = self.arg_shapes;
+        p.program_name = program_name;
+        p.function_names = function_names;
+        p.labels = labels;
+        p.exports = exports;
+        p.imports = imports;
+        p.source_map = source_map;
+        p.custom_sections = self
+            .custom_sections
+            .into_iter()
+            .map(|(name, bytes)| CustomSection { name, bytes })
+            .collect();
+        Ok(p)
+    }
+}
+
+fn bool_lit(line: &mut Line<'_>) -> Result<bool, ParseError> {
+    match line.ident("'true' or 'false'")? {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => {
+            line.next -= 1;
+            Err(line.err(ParseErrorKind::Expected("'true' or 'false'")))
+        }
+    }
+}
+
+/// Parses a `.sig` list: `[name: Ty, _: Ty]` or `[Ty, Ty]`.
+fn sig_list(line: &mut Line<'_>) -> Result<(Vec<ValueType>, SigNames), ParseError> {
+    line.expect_punct('[', "'['")?;
+    let mut types = Vec::new();
+    let mut names: Vec<Option<Located<SymRef>>> = Vec::new();
+    let mut named = false;
+    if !line.eat_punct(']') {
+        loop {
+            let has_name = line.peek_at(1) == Some(&Tok::Punct(':'))
+                || (line.peek() == Some(&Tok::Punct('#'))
+                    && line.peek_at(2) == Some(&Tok::Punct(':')));
+            if has_name {
+                named = true;
+                if line.peek() == Some(&Tok::Ident("_")) {
+                    line.next += 1;
+                    names.push(None);
+                } else {
+                    names.push(Some(line.sym_ref()?));
+                }
+                line.expect_punct(':', "':'")?;
+            } else {
+                names.push(None);
+            }
+            types.push(line.value_type()?);
+            if !line.eat_punct(',') {
+                break;
+            }
+        }
+        line.expect_punct(']', "']'")?;
+    }
+    Ok((types, named.then_some(names)))
+}
+
+/// Parses a branch target: `@label` or `@<pc>`.
+fn target(line: &mut Line<'_>) -> Result<Operand, ParseError> {
+    line.expect_punct('@', "branch target")?;
+    let pos = line.pos();
+    if let Some(Tok::Num(_)) = line.peek() {
+        return Ok(Operand::Pc(line.u32()?));
+    }
+    let name = line.ident("label name")?;
+    Ok(Operand::Label(Located {
+        value: String::from(name),
+        pos,
+    }))
+}
+
+/// Parses `host_sig#<n>` with an optional informational `("symbol")` suffix.
+fn host_sig_ref(line: &mut Line<'_>) -> Result<Operand, ParseError> {
+    let id = line.id_ref("host_sig")?;
+    if line.eat_punct('(') {
+        line.string()?;
+        line.expect_punct(')', "')'")?;
+    }
+    Ok(Operand::Imm(u64::from(id)))
+}
+
+fn args_rets(line: &mut Line<'_>) -> Result<[Operand; 2], ParseError> {
+    line.key("args")?;
+    let args = line.reg_list()?;
+    line.expect_punct(',', "','")?;
+    line.key("rets")?;
+    let rets = line.reg_list()?;
+    Ok([Operand::Regs(args), Operand::Regs(rets)])
+}
+
+enum SimpleItem<'a> {
+    Reg(u32),
+    Regs(Vec<u32>),
+    Imm(Tok<'a>, Pos),
+}
+
+/// Parses `[dst][, [reads...]][, immediates...]` and maps it onto `opcode`'s operand schema.
+fn simple_operands(
+    line: &mut Line<'_>,
+    opcode: Opcode,
+    pos: Pos,
+) -> Result<Vec<Operand>, ParseError> {
+    let mut items = Vec::new();
+    while !line.at_end() {
+        if !items.is_empty() {
+            line.expect_punct(',', "','")?;
+        }
+        let item = match line.peek() {
+            Some(Tok::Punct('[')) => SimpleItem::Regs(line.reg_list()?),
+            Some(Tok::Ident(_)) if matches!(line.peek_at(1), Some(Tok::Punct('=' | '#'))) => {
+                line.next += 2;
+                let pos = line.pos();
+                let tok = line
+                    .bump()
+                    .ok_or(pos.err(ParseErrorKind::Expected("immediate")))?;
+                SimpleItem::Imm(tok, pos)
+            }
+            Some(&Tok::Ident(s)) if s.starts_with('r') && s.len() > 1 => {
+                SimpleItem::Reg(line.reg()?)
+            }
+            _ => {
+                let pos = line.pos();
+                let tok = line
+                    .bump()
+                    .ok_or(pos.err(ParseErrorKind::Expected("operand")))?;
+                SimpleItem::Imm(tok, pos)
+            }
+        };
+        items.push(item);
+    }
+
+    let schema = opcode.operands();
+    let has_dst = schema
+        .iter()
+        .any(|o| o.access == Some(OperandAccess::Write));
+    let fixed_reads = schema
+        .iter()
+        .filter(|o| o.kind == OperandKind::Reg && o.access == Some(OperandAccess::Read))
+        .count();
+    let has_read_list = schema.iter().any(|o| o.kind == OperandKind::RegList);
+    let imm_count = schema
+        .iter()
+        .filter(|o| !matches!(o.kind, OperandKind::Reg | OperandKind::RegList))
+        .count();
+    let expected = usize::from(has_dst) + usize::from(fixed_reads > 0 || has_read_list) + imm_count;
+    let count_err = || {
+        pos.err(ParseErrorKind::OperandCount {
+            expected,
+            found: items.len(),
+        })
+    };
+    if items.len() != expected {
+        return Err(count_err());
+    }
+
+    let mut items = items.into_iter();
+    let dst = if has_dst {
+        match items.next() {
+            Some(SimpleItem::Reg(r)) => Some(r),
+            _ => return Err(pos.err(ParseErrorKind::Expected("destination register"))),
+        }
+    } else {
+        None
+    };
+    let mut reads = Vec::new();
+    if fixed_reads > 0 || has_read_list {
+        match items.next() {
+            Some(SimpleItem::Regs(rs))
+                if rs.len() == fixed_reads || (has_read_list && rs.len() >= fixed_reads) =>
+            {
+                reads = rs;
+            }
+            _ => return Err(pos.err(ParseErrorKind::Expected("source register list"))),
+        }
+    }
+    let mut reads = reads.into_iter();
+    let mut operands = Vec::with_capacity(schema.len());
+    for o in schema {
+        let operand = match (o.kind, o.access) {
+            (OperandKind::Reg, Some(OperandAccess::Write)) => Operand::Reg(dst.unwrap_or(0)),
+            (OperandKind::Reg, _) => Operand::Reg(reads.next().unwrap_or(0)),
+            (OperandKind::RegList, _) => Operand::Regs(reads.by_ref().collect()),
+            (kind, _) => {
+                let Some(SimpleItem::Imm(tok, pos)) = items.next() else {
+                    return Err(pos.err(ParseErrorKind::Expected("immediate")));
+                };
+                Operand::Imm(immediate(kind, &tok, pos)?)
+            }
+        };
+        operands.push(operand);
+    }
+    Ok(operands)
+}
+
+/// Converts an immediate token to its raw `u64` operand value for `kind`.
+fn immediate(kind: OperandKind, tok: &Tok<'_>, pos: Pos) -> Result<u64, ParseError> {
+    let invalid = || pos.err(ParseErrorKind::InvalidInteger);
+    let num = match tok {
+        Tok::Num(s) => *s,
+        Tok::Ident("true") if kind == OperandKind::ImmBool => return Ok(1),
+        Tok::Ident("false") if kind == OperandKind::ImmBool => return Ok(0),
+        _ => return Err(pos.err(ParseErrorKind::Expected("immediate"))),
+    };
+    let v = match kind {
+        OperandKind::ImmI64 => parse_i64(num).ok_or_else(invalid)?.cast_unsigned(),
+        _ => parse_u64(num).ok_or_else(invalid)?,
+    };
+    let max = match kind {
+        OperandKind::ImmBool => 1,
+        OperandKind::ImmU8 => u64::from(u8::MAX),
+        OperandKind::ImmI64 | OperandKind::ImmU64 => u64::MAX,
+        _ => u64::from(u32::MAX),
+    };
+    if v > max {
+        return Err(invalid());
+    }
+    Ok(v)
+}
+
+/// Lays out a function's instructions, resolving labels to pcs.
+///
+/// Label pcs depend on the (variable-width) encoding of earlier branch targets, so this re-encodes
+/// until the layout is stable. Starting from all-zero pcs, pcs only grow, so this terminates.
+fn layout(f: &FuncText) -> Result<(Vec<u8>, Vec<u32>), ParseError> {
+    let mut targets: Vec<Vec<Option<usize>>> = Vec::with_capacity(f.instrs.len());
+    for instr in &f.instrs {
+        let mut resolved = Vec::with_capacity(instr.operands.len());
+        for op in &instr.operands {
+            resolved.push(match op {
+                Operand::Label(name) => Some(
+                    *f.labels
+                        .get(&name.value)
+                        .ok_or(name.pos.err(ParseErrorKind::UndefinedLabel))?,
+                ),
+                _ => None,
+            });
+        }
+        targets.push(resolved);
+    }
+
+    let mut pcs = vec![0_u32; f.instrs.len() + 1];
+    loop {
+        let mut out = Vec::new();
+        let mut next_pcs = Vec::with_capacity(pcs.len());
+        for (instr, targets) in f.instrs.iter().zip(&targets) {
+            next_pcs.push(offset(&out, instr.pos)?);
+            encode(&mut out, instr, targets, &pcs, f.wide_pcs)?;
+        }
+        next_pcs.push(offset(
+            &out,
+            f.instrs
+                .last()
+                .map_or(Pos { line: 0, column: 0 }, |i| i.pos),
+        )?);
+        if next_pcs == pcs {
+            return Ok((out, pcs));
+        }
+        pcs = next_pcs;
+    }
+}
+
+fn offset(out: &[u8], pos: Pos) -> Result<u32, ParseError> {
+    u32::try_from(out.len()).map_err(|_| pos.err(ParseErrorKind::InvalidInteger))
+}
+
+fn encode(
+    out: &mut Vec<u8>,
+    instr: &InstrText,
+    targets: &[Option<usize>],
+    pcs: &[u32],
+    wide_pcs: bool,
+) -> Result<(), ParseError> {
+    let schema = instr.opcode.operands();
+    if schema.len() != instr.operands.len() {
+        return Err(instr.pos.err(ParseErrorKind::OperandCount {
+            expected: schema.len(),
+            found: instr.operands.len(),
+        }));
+    }
+    out.push(instr.opcode.byte());
+    for ((o, op), target) in schema.iter().zip(&instr.operands).zip(targets) {
+        match (o.encoding, op) {
+            (OperandEncoding::RegU32Uleb, Operand::Reg(r)) => write_reg(out, *r),
+            (OperandEncoding::RegListU32UlebCountThenRegs, Operand::Regs(regs)) => {
+                let n = u32::try_from(regs.len())
+                    .map_err(|_| instr.pos.err(ParseErrorKind::InvalidInteger))?;
+                write_u32_uleb(out, n);
+                for &r in regs {
+                    write_reg(out, r);
+                }
+            }
+            (OperandEncoding::U32Uleb, Operand::Pc(_) | Operand::Label(_)) => {
+                let pc = match (op, target) {
+                    (Operand::Pc(pc), _) => *pc,
+                    (_, Some(ix)) => pcs[*ix],
+                    _ => 0,
+                };
+                if wide_pcs {
+                    let at = out.len();
+                    out.extend_from_slice(&[0; 5]);
+                    patch_uleb128_u32(out, at, pc);
+                } else {
+                    write_u32_uleb(out, pc);
+                }
+            }
+            (OperandEncoding::U32Uleb, Operand::Imm(v)) => {
+                write_u32_uleb(out, u32::try_from(*v).unwrap_or(u32::MAX));
+            }
+            (OperandEncoding::BoolU8, Operand::Imm(v)) => write_bool_u8(out, *v != 0),
+            (OperandEncoding::U8Raw, Operand::Imm(v)) => {
+                write_u8_raw(out, u8::try_from(*v).unwrap_or(u8::MAX));
+            }
+            (OperandEncoding::I64Sleb, Operand::Imm(v)) => write_i64_sleb(out, v.cast_signed()),
+            (OperandEncoding::U64Uleb, Operand::Imm(v)) => write_u64_uleb(out, *v),
+            (OperandEncoding::U64Le, Operand::Imm(v)) => write_u64_le(out, *v),
+            _ => {
+                return Err(instr
+                    .pos
+                    .err(ParseErrorKind::Expected("operand of matching kind")));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_types(arena: &mut Vec<ValueType>, types: &[ValueType]) -> ByteRange {
+    let offset = u32::try_from(arena.len()).unwrap_or(u32::MAX);
+    arena.extend_from_slice(types);
+    ByteRange {
+        offset,
+        len: u32::try_from(types.len()).unwrap_or(u32::MAX),
+    }
+}
+
+fn push_names(arena: &mut Vec<u32>, names: &[u32]) -> ByteRange {
+    let offset = u32::try_from(arena.len()).unwrap_or(u32::MAX);
+    arena.extend_from_slice(names);
+    ByteRange {
+        offset,
+        len: u32::try_from(names.len()).unwrap_or(u32::MAX),
+    }
+}
+
+/// The symbol table being assembled (without the reserved index `0`).
+struct SymbolTable {
+    table: Vec<String>,
+    first: BTreeMap<String, u32>,
+}
+
+impl SymbolTable {
+    fn new(table: Vec<String>) -> Self {
+        let mut first = BTreeMap::new();
+        for (ix, s) in table.iter().enumerate() {
+            let id = u32::try_from(ix + 1).unwrap_or(u32::MAX);
+            first.entry(s.clone()).or_insert(id);
+        }
+        Self { table, first }
+    }
+
+    fn resolve(&mut self, r: &Located<SymRef>) -> Result<SymbolId, ParseError> {
+        let id = match &r.value {
+            SymRef::Id(id) => {
+                if (*id as usize) > self.table.len() {
+                    return Err(r.pos.err(ParseErrorKind::UnknownSymbol(*id)));
+                }
+                *id
+            }
+            SymRef::Str(s) => match self.first.get(s) {
+                Some(&id) => id,
+                None => {
+                    self.table.push(s.clone());
+                    let id = u32::try_from(self.table.len())
+                        .map_err(|_| r.pos.err(ParseErrorKind::InvalidInteger))?;
+                    self.first.insert(s.clone(), id);
+                    id
+                }
+            },
+        };
+        NonZeroU32::new(id)
+            .map(SymbolId)
+            .ok_or(r.pos.err(ParseErrorKind::UnknownSymbol(id)))
+    }
+
+    fn resolve_names(
+        &mut self,
+        names: Option<&[Option<Located<SymRef>>]>,
+    ) -> Result<Option<Vec<u32>>, ParseError> {
+        let Some(names) = names else {
+            return Ok(None);
+        };
+        let mut ids = Vec::with_capacity(names.len());
+        for name in names {
+            ids.push(match name {
+                Some(r) => self.resolve(r)?.index(),
+                None => 0,
+            });
+        }
+        Ok(Some(ids))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::disasm::disassemble;
+    use crate::host::HostSig;
+    use crate::program::StructTypeDef;
+    use crate::verifier::{VerifyConfig, verify_program};
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn span(id: u64) -> SpanId {
+        SpanId(NonZeroU64::new(id).unwrap())
+    }
+
+    fn assert_roundtrips(p: &Program) {
+        let text = disassemble(p).to_string();
+        let parsed = parse(&text).unwrap_or_else(|e| panic!("{e}\n{text}"));
+        assert_eq!(parsed.encode(), p.encode(), "\n{text}");
+        // Re-disassembling the parsed program is a fixed point.
+        assert_eq!(disassemble(&parsed).to_string(), text);
+    }
+
+    #[test]
+    fn disassembly_roundtrips_through_parse() {
+        let mut pb = ProgramBuilder::new();
+        pb.set_program_name("demo \"quoted\"\n");
+        let point = pb.struct_type(StructTypeDef {
+            field_names: vec!["x".into(), "y z".into()],
+            field_types: vec![ValueType::I64, ValueType::Str],
+        });
+        let elems = pb.array_elem(ValueType::F64);
+        let add = pb.host_sig_for(
+            "math.add",
+            HostSig {
+                args: vec![ValueType::I64, ValueType::I64],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let neg = pb.pure_host_sig_for(
+            "math.neg",
+            HostSig {
+                args: vec![ValueType::I64],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let greeting = pb.constant(Const::Str("héllo\t".into()));
+        let blob = pb.constant(Const::Bytes(vec![0, 0xAB, 0xFF]));
+
+        let mut callee = Asm::new();
+        callee.ret(0, &[1]);
+        let callee_id = pb
+            .push_function_checked(
+                callee,
+                FunctionSig {
+                    arg_types: vec![ValueType::I64],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_input_name(callee_id, 0, "x").unwrap();
+        pb.set_function_output_name(callee_id, 0, "value").unwrap();
+        let call_sig = pb.call_sig(&[ValueType::I64], &[ValueType::I64]);
+
+        let mut a = Asm::new();
+        let l_loop = a.label_named("loop");
+        let l_done = a.label();
+        a.span(span(7));
+        a.const_i64(2, -3);
+        a.const_u64(3, 1 << 40);
+        a.const_f64(4, 1.5);
+        a.const_decimal(5, -1234, 2);
+        a.const_pool(6, greeting);
+        a.const_pool(7, blob);
+        a.const_bool(8, false);
+        a.const_unit(9);
+        a.jmp(l_loop);
+        a.place(l_loop).unwrap();
+        a.span(span(9));
+        a.host_call(0, add, 0, &[2, 2], &[10]);
+        a.host_call_pure(neg, &[10], &[11]);
+        a.call(0, callee_id, 0, &[11], &[12]);
+        a.const_func(13, callee_id);
+        a.call_indirect(0, call_sig, 13, 0, &[12], &[14]);
+        a.i64_to_dec(15, 14, 3);
+        a.tuple_new(16, &[2, 3]);
+        a.tuple_get(17, 16, 1);
+        a.struct_new(18, point, &[2, 6]);
+        a.struct_get(19, 18, 0);
+        a.array_new(20, elems, &[4, 4]);
+        a.array_get_imm(21, 20, 1);
+        a.i64_lt(22, 14, 2);
+        a.br(22, l_loop, l_done);
+        a.place(l_done).unwrap();
+        a.ret(0, &[14]);
+        let main = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::Bool],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_name(main, "main").unwrap();
+        pb.set_function_input_name(main, 0, "flag").unwrap();
+
+        let p = pb.build();
+        verify_program(&p, &VerifyConfig::default())
+            .unwrap_or_else(|e| panic!("{e:?}\n{}", disassemble(&p)));
+        assert_roundtrips(&p);
+    }
+
+    #[test]
+    fn canonical_branch_targets_roundtrip() {
+        let bytecode = crate::bytecode::encode_instructions(&[
+            crate::bytecode::Instr::ConstBool { dst: 1, imm: true },
+            crate::bytecode::Instr::Br {
+                cond: 1,
+                pc_true: 6,
+                pc_false: 6,
+            },
+            crate::bytecode::Instr::Ret {
+                eff_in: 0,
+                rets: vec![],
+            },
+        ])
+        .unwrap();
+        let p = Program::new(
+            vec![],
+            vec![],
+            vec![],
+            TypeTableDef::default(),
+            vec![FunctionDef {
+                arg_types: vec![],
+                ret_types: vec![],
+                reg_count: 2,
+                bytecode,
+                spans: vec![SpanEntry {
+                    pc_delta: 1,
+                    span_id: span(3),
+                }],
+            }],
+        );
+        let text = disassemble(&p).to_string();
+        assert!(!text.contains(".pcs wide"));
+        assert!(text.contains(".span 3 pc=1"));
+        assert_roundtrips(&p);
+    }
+
+    #[test]
+    fn padded_operands_roundtrip_canonically() {
+        // `mov r1, r1` with a padded dst and `const.pool r2, c0` with a padded const id.
+        let mut bytecode = vec![0x01, 0x81, 0x00, 0x01, 0x16, 0x02, 0x80, 0x00];
+        bytecode.extend(
+            crate::bytecode::encode_instructions(&[crate::bytecode::Instr::Ret {
+                eff_in: 0,
+                rets: vec![1, 2],
+            }])
+            .unwrap(),
+        );
+        let p = Program::new(
+            vec![],
+            vec![Const::I64(7)],
+            vec![],
+            TypeTableDef::default(),
+            vec![FunctionDef {
+                arg_types: vec![ValueType::I64],
+                ret_types: vec![ValueType::I64, ValueType::I64],
+                reg_count: 3,
+                bytecode,
+                spans: vec![SpanEntry {
+                    pc_delta: 4,
+                    span_id: span(3),
+                }],
+            }],
+        );
+        let canonical = crate::codec::canonicalize_program(&p).unwrap();
+        assert_ne!(canonical.encode(), p.encode());
+
+        let text = disassemble(&p).to_string();
+        let parsed = parse(&text).unwrap_or_else(|e| panic!("{e}\n{text}"));
+        assert_eq!(parsed.encode(), canonical.encode(), "\n{text}");
+        assert_roundtrips(&canonical);
+    }
+
+    #[test]
+    fn parses_hand_written_text() {
+        let p = parse(
+            "
+            .host_sig 0 \"inc\" [I64] -> [I64]  ; trailing comment
+            func f0:
+              .sig [n: I64] -> [I64]
+              @top:
+              host_call eff_out=r0, host_sig#0, eff_in=r0, args=[r1], rets=[r2]
+              i64.lt r3, [r2, r1]
+              br r3, @top, @out
+              @out:
+              ret eff=r0, rets=[r2]
+            ",
+        )
+        .unwrap();
+        assert_eq!(p.symbol_str(p.host_sigs[0].symbol).unwrap(), "inc");
+        assert_eq!(p.functions[0].reg_count, 4);
+        assert_eq!(p.function_input_name(0, 0), Some("n"));
+        verify_program(&p, &VerifyConfig::default()).unwrap_or_else(|e| panic!("{e:?}"));
+    }
+
+    #[test]
+    fn parsed_directives_roundtrip() {
+        let p = parse(
+            r#"
+            .symbol 1 "dup"
+            .symbol 2 "dup"
+            .struct 0 [a: Bool]
+            .elem 0 Obj(9)
+            .arg_shape f0 1 tuple [I64, _]
+            .arg_shape f0 2 struct type#0
+            .arg_shape f0 3 array elem_type#0
+            .export f0 "entry"
+            .import f0 "lib" #1
+            .source 6 "doc" 1 2 3 4 #2
+            .source 5 #1 0 0 0 0
+            .custom "zz" 0x
+            .custom "build" 0x00
+            .custom "build" 0xCAFE
+            func f0:
+              .name #2
+              .sig [_: Agg, #2: Agg, _: Agg] -> []
+              .span 5
+              ret eff=r0, rets=[]
+              .label "tail" pc=0
+              .span 6 pc=3
+            "#,
+        )
+        .unwrap();
+        assert_eq!(p.function_names[0].name.index(), 2);
+        assert_eq!(p.function_arg_agg_shapes.len(), 3);
+        assert_eq!((p.exports.len(), p.imports.len()), (1, 1));
+        assert_eq!(p.source_map.len(), 2);
+        assert_eq!(
+            p.span_info(SpanId::try_from(6).unwrap())
+                .unwrap()
+                .to_string(),
+            "doc:1:2 (node dup)"
+        );
+        let text = disassemble(&p).to_string();
+        assert!(text.contains("  .span 6 ; doc:1:2 (node dup)\n"), "{text}");
+        assert_eq!(p.custom_sections.len(), 2);
+        assert_eq!(p.custom_section("build"), Some(&[0xCA, 0xFE][..]));
+        assert_roundtrips(&p);
+    }
+
+    #[test]
+    fn parse_errors_carry_line_and_column() {
+        let err = parse("func f0:\n  .regs 2\n  i64.add r1, [r2]\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+        assert_eq!(err.kind, ParseErrorKind::Expected("source register list"));
+
+        let err = parse("func f0:\n  jmp @nowhere\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.kind),
+            (2, 8, ParseErrorKind::UndefinedLabel)
+        );
+
+        let err = parse(".const 1 unit\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.kind),
+            (
+                1,
+                8,
+                ParseErrorKind::UnexpectedId {
+                    expected: 0,
+                    found: 1
+                }
+            )
+        );
+
+        let err = parse("func f0:\n  bogus r1\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.to_string(), "2:3: unknown instruction mnemonic");
+
+        let err = parse(".symbol 1 \"abc\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.kind),
+            (1, 15, ParseErrorKind::UnterminatedString)
+        );
+    }
+}
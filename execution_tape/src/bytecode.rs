@@ -265,6 +265,13 @@ pub(crate) enum Instr {
         rets: Vec<u32>,
     },
 
+    /// Call a pure host function (no effect token).
+    HostCallPure {
+        host_sig: HostSigId,
+        args: Vec<u32>,
+        rets: Vec<u32>,
+    },
+
     /// Indirect call through a callee register with an expected call signature id.
     CallIndirect {
         eff_out: u32,
@@ -495,6 +502,14 @@ impl<'a> WritesIter<'a> {
         }
     }
 
+    const fn slice(rest: &'a [u32]) -> Self {
+        Self {
+            first: None,
+            rest,
+            idx: 0,
+        }
+    }
+
     const fn one_plus_slice(first: u32, rest: &'a [u32]) -> Self {
         Self {
             first: Some(first),
@@ -676,6 +691,14 @@ mod tests {
                     rets: vec![2, 3],
                 },
             ),
+            (
+                Opcode::HostCallPure,
+                Instr::HostCallPure {
+                    host_sig: HostSigId(1),
+                    args: vec![1, 2],
+                    rets: vec![3],
+                },
+            ),
             (
                 Opcode::CallIndirect,
                 Instr::CallIndirect {
@@ -519,6 +519,16 @@ pub(crate) fn decode_instr(opcode: Opcode, r: &mut Reader<'_>) -> Result<Instr,
                 env,
             }
         },
+        Opcode::HostCallPure => {
+            let host_sig = HostSigId(crate::codec_primitives::read_u32_uleb(r)?);
+            let args = crate::codec_primitives::read_reg_list(r)?;
+            let rets = crate::codec_primitives::read_reg_list(r)?;
+            Instr::HostCallPure {
+                host_sig,
+                args,
+                rets,
+            }
+        },
         Opcode::TupleNew => {
             let dst = crate::codec_primitives::read_reg(r)?;
             let values = crate::codec_primitives::read_reg_list(r)?;
@@ -369,6 +369,13 @@ pub(crate) fn encode_instr(instr: &Instr, out: &mut Vec<u8>) -> Result<(), Encod
             crate::codec_primitives::write_reg(out, *env);
             Ok(())
         },
+        Instr::HostCallPure { args, host_sig, rets } => {
+            out.push(Opcode::HostCallPure as u8);
+            crate::codec_primitives::write_u32_uleb(out, host_sig.0);
+            crate::codec_primitives::write_reg_list(out, args).map_err(|_| EncodeError::OutOfBounds)?;
+            crate::codec_primitives::write_reg_list(out, rets).map_err(|_| EncodeError::OutOfBounds)?;
+            Ok(())
+        },
         Instr::TupleNew { dst, values } => {
             out.push(Opcode::TupleNew as u8);
             crate::codec_primitives::write_reg(out, *dst);
@@ -62,6 +62,7 @@ impl Instr {
             Self::ConstFunc { .. } => Opcode::ConstFunc,
             Self::CallIndirect { .. } => Opcode::CallIndirect,
             Self::ClosureNew { .. } => Opcode::ClosureNew,
+            Self::HostCallPure { .. } => Opcode::HostCallPure,
             Self::TupleNew { .. } => Opcode::TupleNew,
             Self::TupleGet { .. } => Opcode::TupleGet,
             Self::StructNew { .. } => Opcode::StructNew,
@@ -62,6 +62,7 @@ impl Instr {
             Self::ConstFunc { .. } => ReadsIter::none(),
             Self::CallIndirect { callee, eff_in, args: rest, .. } => ReadsIter::two_plus_slice(*callee, *eff_in, rest.as_slice()),
             Self::ClosureNew { func, env, .. } => ReadsIter::two(*func, *env),
+            Self::HostCallPure { args: rest, .. } => ReadsIter::slice(rest.as_slice()),
             Self::TupleNew { values: rest, .. } => ReadsIter::slice(rest.as_slice()),
             Self::TupleGet { tuple, .. } => ReadsIter::one(*tuple),
             Self::StructNew { values: rest, .. } => ReadsIter::slice(rest.as_slice()),
@@ -175,6 +176,7 @@ impl Instr {
             Self::ConstFunc { dst, .. } => WritesIter::one(*dst),
             Self::CallIndirect { eff_out, rets: rest, .. } => WritesIter::one_plus_slice(*eff_out, rest.as_slice()),
             Self::ClosureNew { dst, .. } => WritesIter::one(*dst),
+            Self::HostCallPure { rets: rest, .. } => WritesIter::slice(rest.as_slice()),
             Self::TupleNew { dst, .. } => WritesIter::one(*dst),
             Self::TupleGet { dst, .. } => WritesIter::one(*dst),
             Self::StructNew { dst, .. } => WritesIter::one(*dst),
@@ -12,6 +12,7 @@
 
 extern crate alloc;
 
+use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt;
 
@@ -19,7 +20,7 @@ use crate::format::{
     DecodeError as FormatDecodeError, Reader, write_sleb128_i64, write_uleb128_u64,
 };
 use crate::opcode::{Opcode, OperandEncoding, OperandKind};
-use crate::program::{ConstId, ElemTypeId, HostSigId, TypeId};
+use crate::program::{ByteRange, ConstId, ElemTypeId, HostSigId, Program, SpanEntry, TypeId};
 use crate::value::FuncId;
 
 /// An operand value for encoding an instruction.
@@ -237,6 +238,13 @@ pub enum DecodeError {
     },
     /// A decoded size was too large to represent.
     OutOfBounds,
+    /// A branch target, span or label pc is not an instruction boundary.
+    BadPcTarget {
+        /// Function index within the program.
+        func: u32,
+        /// The offending pc.
+        pc: u32,
+    },
 }
 
 impl fmt::Display for DecodeError {
@@ -254,6 +262,12 @@ impl fmt::Display for DecodeError {
                 "unsupported operand encoding for {opcode:?} operand {index}: {kind:?}/{encoding:?}"
             ),
             Self::OutOfBounds => write!(f, "out of bounds"),
+            Self::BadPcTarget { func, pc } => {
+                write!(
+                    f,
+                    "pc {pc} in function {func} is not an instruction boundary"
+                )
+            }
         }
     }
 }
@@ -456,36 +470,181 @@ pub fn decode_all(bytes: &[u8]) -> Result<Vec<DecodedInstruction>, DecodeError>
 ///
 /// This is useful for normalizing non-canonical (but accepted) encodings like ULEB128 values with
 /// redundant continuation bytes.
+///
+/// Branch targets are copied as-is, so if re-encoding shrinks any instruction, targets past it
+/// become stale. Use [`canonicalize_program`] to also remap branch targets and pc tables.
 pub fn canonicalize(bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
     let decoded = decode_all(bytes)?;
     let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
-
     for di in &decoded {
-        let mut operands: Vec<Operand<'_>> = Vec::with_capacity(di.operands.len());
-        for o in &di.operands {
-            operands.push(match o {
-                DecodedOperand::Reg(r) => Operand::Reg(*r),
-                DecodedOperand::RegList(rs) => Operand::RegList(rs.as_slice()),
-                DecodedOperand::Pc(pc) => Operand::Pc(*pc),
-                DecodedOperand::ImmBool(b) => Operand::ImmBool(*b),
-                DecodedOperand::ImmU8(v) => Operand::ImmU8(*v),
-                DecodedOperand::ImmU32(v) => Operand::ImmU32(*v),
-                DecodedOperand::ImmI64(v) => Operand::ImmI64(*v),
-                DecodedOperand::ImmU64(v) => Operand::ImmU64(*v),
-                DecodedOperand::ConstId(id) => Operand::ConstId(*id),
-                DecodedOperand::FuncId(id) => Operand::FuncId(*id),
-                DecodedOperand::HostSigId(id) => Operand::HostSigId(*id),
-                DecodedOperand::TypeId(id) => Operand::TypeId(*id),
-                DecodedOperand::ElemTypeId(id) => Operand::ElemTypeId(*id),
-            });
+        reencode(di, Ok, &mut out)?;
+    }
+    Ok(out)
+}
+
+/// Returns a copy of `program` with every function's bytecode canonicalized.
+///
+/// Unlike [`canonicalize`], this remaps branch targets, span tables and label names to the
+/// re-encoded pcs, so the result behaves identically to `program`. Other tables are unchanged.
+///
+/// Fails with [`DecodeError::BadPcTarget`] if a branch target, span or label does not fall on an
+/// instruction boundary.
+pub fn canonicalize_program(program: &Program) -> Result<Program, DecodeError> {
+    let mut out = program.clone();
+    out.bytecode_data.clear();
+    out.spans.clear();
+    for func_ix in 0..program.functions.len() {
+        let func_id = u32::try_from(func_ix).map_err(|_| DecodeError::OutOfBounds)?;
+        let rewritten = rewrite_function(program, func_id, |_, _, _| Ok::<_, DecodeError>(()))?;
+
+        for label in out.labels.iter_mut().filter(|l| l.func == func_id) {
+            label.pc = rewritten.remap_pc(label.pc)?;
         }
 
-        encode_instruction(di.opcode, &operands, &mut out).map_err(|_| DecodeError::OutOfBounds)?;
+        let spans_offset = u32::try_from(out.spans.len()).map_err(|_| DecodeError::OutOfBounds)?;
+        out.spans.extend_from_slice(&rewritten.spans);
+        let bytecode_offset =
+            u32::try_from(out.bytecode_data.len()).map_err(|_| DecodeError::OutOfBounds)?;
+        out.bytecode_data.extend_from_slice(&rewritten.bytecode);
+        let f = &mut out.functions[func_ix];
+        f.bytecode = ByteRange {
+            offset: bytecode_offset,
+            len: u32::try_from(rewritten.bytecode.len()).map_err(|_| DecodeError::OutOfBounds)?,
+        };
+        f.spans = ByteRange {
+            offset: spans_offset,
+            len: u32::try_from(rewritten.spans.len()).map_err(|_| DecodeError::OutOfBounds)?,
+        };
+    }
+    Ok(out)
+}
+
+/// A function body re-encoded by [`rewrite_function`].
+#[derive(Clone, Debug)]
+pub(crate) struct RewrittenFunction {
+    /// Function index within the source program.
+    func: u32,
+    /// Re-encoded bytecode.
+    pub(crate) bytecode: Vec<u8>,
+    /// Span table with pcs remapped to `bytecode`.
+    pub(crate) spans: Vec<SpanEntry>,
+    /// `old_pcs[i]` is the source pc of instruction `i`; the final entry is the function's end.
+    old_pcs: Vec<u32>,
+    /// Re-encoded pcs, parallel to `old_pcs`.
+    new_pcs: Vec<u32>,
+}
+
+impl RewrittenFunction {
+    /// Maps an instruction-boundary pc of the source function to the re-encoded bytecode.
+    pub(crate) fn remap_pc(&self, pc: u32) -> Result<u32, DecodeError> {
+        self.old_pcs
+            .binary_search(&pc)
+            .map(|i| self.new_pcs[i])
+            .map_err(|_| DecodeError::BadPcTarget {
+                func: self.func,
+                pc,
+            })
+    }
+}
+
+/// Decodes function `func` of `program`, passes every non-pc operand through `map_operand`, and
+/// re-encodes the result with branch targets and spans remapped to the new layout.
+///
+/// `map_operand` receives the opcode and operand index alongside the operand so callers can tell
+/// apart ids that share an operand kind (such as the call signature of `call.indirect`).
+pub(crate) fn rewrite_function<E: From<DecodeError>>(
+    program: &Program,
+    func: u32,
+    mut map_operand: impl FnMut(Opcode, usize, &mut DecodedOperand) -> Result<(), E>,
+) -> Result<RewrittenFunction, E> {
+    let f = program
+        .functions
+        .get(func as usize)
+        .ok_or(DecodeError::OutOfBounds)?;
+    let bytes = program.function_bytecode(f).map_err(DecodeError::from)?;
+    let mut decoded = decode_all(bytes)?;
+    for di in &mut decoded {
+        for (i, o) in di.operands.iter_mut().enumerate() {
+            if !matches!(o, DecodedOperand::Pc(_)) {
+                map_operand(di.opcode, i, o)?;
+            }
+        }
     }
 
+    let mut out = RewrittenFunction {
+        func,
+        bytecode: Vec::new(),
+        spans: Vec::new(),
+        old_pcs: Vec::with_capacity(decoded.len() + 1),
+        new_pcs: Vec::new(),
+    };
+    let mut pc = 0_usize;
+    for di in &decoded {
+        out.old_pcs
+            .push(u32::try_from(pc).map_err(|_| DecodeError::OutOfBounds)?);
+        pc += di.byte_len;
+    }
+    out.old_pcs
+        .push(u32::try_from(pc).map_err(|_| DecodeError::OutOfBounds)?);
+
+    // Branch targets are variable-width, so lay out until the pcs are stable. Starting from
+    // all-zero pcs, pcs only grow, so this terminates.
+    out.new_pcs = vec![0_u32; out.old_pcs.len()];
+    loop {
+        let mut bytecode = Vec::with_capacity(bytes.len());
+        let mut next_pcs = Vec::with_capacity(out.old_pcs.len());
+        for di in &decoded {
+            next_pcs.push(u32::try_from(bytecode.len()).map_err(|_| DecodeError::OutOfBounds)?);
+            reencode(di, |pc| out.remap_pc(pc), &mut bytecode)?;
+        }
+        next_pcs.push(u32::try_from(bytecode.len()).map_err(|_| DecodeError::OutOfBounds)?);
+        if next_pcs == out.new_pcs {
+            out.bytecode = bytecode;
+            break;
+        }
+        out.new_pcs = next_pcs;
+    }
+
+    let (mut old_pc, mut new_prev) = (0_u64, 0_u32);
+    for span in program.function_spans(f).map_err(DecodeError::from)? {
+        old_pc += span.pc_delta;
+        let new_pc = out.remap_pc(u32::try_from(old_pc).map_err(|_| DecodeError::OutOfBounds)?)?;
+        out.spans.push(SpanEntry {
+            pc_delta: u64::from(new_pc - new_prev),
+            span_id: span.span_id,
+        });
+        new_prev = new_pc;
+    }
     Ok(out)
 }
 
+/// Re-encodes a decoded instruction, mapping `Pc` operands through `map_pc`.
+fn reencode(
+    di: &DecodedInstruction,
+    map_pc: impl Fn(u32) -> Result<u32, DecodeError>,
+    out: &mut Vec<u8>,
+) -> Result<(), DecodeError> {
+    let mut operands: Vec<Operand<'_>> = Vec::with_capacity(di.operands.len());
+    for o in &di.operands {
+        operands.push(match o {
+            DecodedOperand::Reg(r) => Operand::Reg(*r),
+            DecodedOperand::RegList(rs) => Operand::RegList(rs.as_slice()),
+            DecodedOperand::Pc(pc) => Operand::Pc(map_pc(*pc)?),
+            DecodedOperand::ImmBool(b) => Operand::ImmBool(*b),
+            DecodedOperand::ImmU8(v) => Operand::ImmU8(*v),
+            DecodedOperand::ImmU32(v) => Operand::ImmU32(*v),
+            DecodedOperand::ImmI64(v) => Operand::ImmI64(*v),
+            DecodedOperand::ImmU64(v) => Operand::ImmU64(*v),
+            DecodedOperand::ConstId(id) => Operand::ConstId(*id),
+            DecodedOperand::FuncId(id) => Operand::FuncId(*id),
+            DecodedOperand::HostSigId(id) => Operand::HostSigId(*id),
+            DecodedOperand::TypeId(id) => Operand::TypeId(*id),
+            DecodedOperand::ElemTypeId(id) => Operand::ElemTypeId(*id),
+        });
+    }
+    encode_instruction(di.opcode, &operands, out).map_err(|_| DecodeError::OutOfBounds)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -562,4 +721,57 @@ mod tests {
         let out2 = canonicalize(&out).unwrap();
         assert_eq!(out2, out);
     }
+
+    #[test]
+    fn canonicalize_program_remaps_branch_targets_spans_and_labels() {
+        use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+        use crate::program::{SpanId, ValueType};
+        use crate::verifier::{VerifyConfig, verify_program};
+        use core::num::NonZeroU64;
+
+        let mut a = Asm::new();
+        let l_then = a.label();
+        let l_else = a.label_named("else");
+        a.br(1, l_then, l_else);
+        a.place(l_then).unwrap();
+        a.const_i64(2, 1);
+        a.ret(0, &[2]);
+        a.place(l_else).unwrap();
+        a.span(SpanId(NonZeroU64::new(4).unwrap()));
+        a.const_i64(2, 2);
+        a.ret(0, &[2]);
+        let mut pb = ProgramBuilder::new();
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::Bool],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        let p = pb.build();
+
+        let c = canonicalize_program(&p).unwrap();
+        verify_program(&c, &VerifyConfig::default()).unwrap();
+        let bytes = c.function_bytecode(&c.functions[0]).unwrap();
+        assert!(
+            bytes.len() < p.bytecode_data.len(),
+            "branch targets should shrink"
+        );
+        let decoded = decode_all(bytes).unwrap();
+        let then_pc = u32::try_from(decoded[0].byte_len).unwrap();
+        let else_pc = then_pc + u32::try_from(decoded[1].byte_len + decoded[2].byte_len).unwrap();
+        assert_eq!(
+            decoded[0].operands,
+            vec![
+                DecodedOperand::Reg(1),
+                DecodedOperand::Pc(then_pc),
+                DecodedOperand::Pc(else_pc)
+            ]
+        );
+        assert_eq!(c.label_name(0, else_pc), Some("else"));
+        let spans = c.function_spans(&c.functions[0]).unwrap();
+        assert_eq!(spans[0].pc_delta, u64::from(else_pc));
+        assert_eq!(canonicalize_program(&c).unwrap().encode(), c.encode());
+    }
 }
@@ -0,0 +1,448 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversions between Rust types and VM [`Value`]s, including aggregates.
+//!
+//! [`IntoValue`] builds a [`Value`] from a Rust value, allocating any aggregates in an
+//! [`AggHeap`]; [`FromValue`] reads one back. Struct and array aggregates carry type-table ids, so
+//! both directions take the program's [`TypeTable`] and look the ids up there: a Rust struct maps
+//! to the struct type with the same field names and types ([`TypeTable::struct_id`]) and a
+//! `Vec<T>` to the array element type of `T` ([`TypeTable::array_elem_id`]).
+//! [`TapeType::register_types`] adds those entries to a [`ProgramBuilder`].
+//!
+//! | Rust type | [`ValueType`] |
+//! |---|---|
+//! | `()` | `Unit` |
+//! | `bool` | `Bool` |
+//! | `i64` | `I64` |
+//! | `u64` | `U64` |
+//! | `f64` | `F64` |
+//! | [`Decimal`] | `Decimal` |
+//! | `Vec<u8>` | `Bytes` |
+//! | `String` | `Str` |
+//! | [`FuncId`] | `Func` |
+//! | [`Closure`] | `Closure` |
+//! | [`AggHandle`] | `Agg` (passed through unchanged) |
+//! | `(A, B, ..)` (up to 6 elements) | `Agg` (tuple) |
+//! | `Vec<T>` | `Agg` (array of `T`) |
+//! | [`TapeStruct`] types | `Agg` (struct) |
+//!
+//! [`TapeStruct`] is usually derived with `#[derive(TapeStruct)]` from the
+//! `execution_tape_derive` crate, which also implements [`TapeType`], [`IntoValue`] and
+//! [`FromValue`] field by field.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::aggregates::{AggError, AggHeap};
+use crate::asm::ProgramBuilder;
+use crate::host::ValueRef;
+use crate::host_typed::{HostArg, HostRet};
+use crate::program::{StructTypeDef, TypeId, TypeTable, ValueType};
+use crate::value::{AggHandle, AggType, Closure, Decimal, FuncId, Value};
+
+/// A conversion error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConvertError {
+    /// The value has a different type than the Rust type expects.
+    TypeMismatch {
+        /// Expected value type.
+        expected: ValueType,
+        /// Actual value type.
+        actual: ValueType,
+    },
+    /// The type table has no struct type matching the Rust struct.
+    MissingStructType,
+    /// The type table has no array element type for the Rust element type.
+    MissingElemType(ValueType),
+    /// A struct aggregate has a different type than the Rust struct maps to.
+    StructTypeMismatch {
+        /// Type id of the Rust struct.
+        expected: TypeId,
+        /// Type id of the aggregate.
+        actual: TypeId,
+    },
+    /// Aggregate heap error, including aggregates of the wrong kind
+    /// ([`AggError::WrongKind`]) or arity ([`AggError::BadArity`]).
+    Agg(AggError),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeMismatch { expected, actual } => {
+                write!(f, "type mismatch (expected {expected:?}, got {actual:?})")
+            }
+            Self::MissingStructType => write!(f, "no matching struct type in the type table"),
+            Self::MissingElemType(ty) => {
+                write!(f, "no array element type {ty:?} in the type table")
+            }
+            Self::StructTypeMismatch { expected, actual } => write!(
+                f,
+                "struct type mismatch (expected {}, got {})",
+                expected.0, actual.0
+            ),
+            Self::Agg(e) => write!(f, "aggregate error: {e}"),
+        }
+    }
+}
+
+impl core::error::Error for ConvertError {}
+
+impl From<AggError> for ConvertError {
+    fn from(e: AggError) -> Self {
+        Self::Agg(e)
+    }
+}
+
+/// A Rust type with a tape [`ValueType`].
+pub trait TapeType {
+    /// The value type of converted values.
+    const VALUE_TYPE: ValueType;
+
+    /// Adds the struct and array element types this type needs to `pb`.
+    ///
+    /// Types without aggregates need nothing, which is the default.
+    fn register_types(pb: &mut ProgramBuilder) {
+        let _ = pb;
+    }
+}
+
+/// A Rust type that converts into a [`Value`].
+pub trait IntoValue: TapeType {
+    /// Converts into a value, allocating aggregates in `aggregates`.
+    fn into_value(self, types: &TypeTable, aggregates: &mut AggHeap)
+    -> Result<Value, ConvertError>;
+
+    /// Converts the elements of a `Vec<Self>`.
+    ///
+    /// The default converts them one by one. Struct types override it to look their struct type
+    /// up once for the whole array.
+    fn into_values(
+        items: Vec<Self>,
+        types: &TypeTable,
+        aggregates: &mut AggHeap,
+    ) -> Result<Vec<Value>, ConvertError>
+    where
+        Self: Sized,
+    {
+        items
+            .into_iter()
+            .map(|v| v.into_value(types, aggregates))
+            .collect()
+    }
+}
+
+/// A Rust type that can be read from a [`Value`].
+pub trait FromValue: TapeType + Sized {
+    /// Reads a value, following aggregates in `aggregates`.
+    fn from_value(
+        value: &Value,
+        types: &TypeTable,
+        aggregates: &AggHeap,
+    ) -> Result<Self, ConvertError>;
+
+    /// Reads every element of the array aggregate `array`.
+    ///
+    /// The default reads them one by one. Struct types override it to look their struct type up
+    /// once for the whole array.
+    fn from_elements(
+        array: AggHandle,
+        types: &TypeTable,
+        aggregates: &AggHeap,
+    ) -> Result<Vec<Self>, ConvertError> {
+        (0..aggregates.array_len(array)?)
+            .map(|i| Self::from_value(aggregates.array_get_ref(array, i)?, types, aggregates))
+            .collect()
+    }
+}
+
+/// A Rust struct that maps to a tape struct type.
+///
+/// The conversions take the struct's [`TypeId`], resolved with [`struct_type_id`], so converting
+/// many values of the same struct looks the type up only once.
+pub trait TapeStruct: IntoValue + FromValue {
+    /// Returns the struct type definition: the field names and types in declaration order.
+    fn struct_type_def() -> StructTypeDef;
+
+    /// Converts into a struct aggregate of type `type_id`.
+    fn into_struct(
+        self,
+        type_id: TypeId,
+        types: &TypeTable,
+        aggregates: &mut AggHeap,
+    ) -> Result<Value, ConvertError>;
+
+    /// Reads a struct aggregate, which must have type `type_id`.
+    fn from_struct(
+        value: &Value,
+        type_id: TypeId,
+        types: &TypeTable,
+        aggregates: &AggHeap,
+    ) -> Result<Self, ConvertError>;
+}
+
+/// Returns the id of the struct type `T` maps to in `types`.
+pub fn struct_type_id<T: TapeStruct>(types: &TypeTable) -> Result<TypeId, ConvertError> {
+    types
+        .struct_id(&T::struct_type_def())
+        .ok_or(ConvertError::MissingStructType)
+}
+
+/// Checks that `value` is a struct of type `expected` with `field_count` fields and returns its
+/// handle.
+///
+/// Used by [`TapeStruct`] implementations, which then read the fields with
+/// [`AggHeap::struct_get_ref`].
+pub fn struct_handle(
+    value: &Value,
+    expected: TypeId,
+    field_count: usize,
+    aggregates: &AggHeap,
+) -> Result<AggHandle, ConvertError> {
+    let handle = agg_handle(value)?;
+    let AggType::Struct { type_id } = aggregates.agg_type(handle)? else {
+        return Err(AggError::WrongKind.into());
+    };
+    if type_id != expected {
+        return Err(ConvertError::StructTypeMismatch {
+            expected,
+            actual: type_id,
+        });
+    }
+    if aggregates.struct_field_count(handle)? != field_count {
+        return Err(AggError::BadArity.into());
+    }
+    Ok(handle)
+}
+
+#[doc(hidden)]
+pub mod __derive {
+    //! Re-exports used by `#[derive(TapeStruct)]` expansions.
+
+    pub use alloc::vec::Vec;
+}
+
+fn agg_handle(value: &Value) -> Result<AggHandle, ConvertError> {
+    match value {
+        Value::Agg(handle) => Ok(*handle),
+        _ => Err(mismatch(ValueType::Agg, value)),
+    }
+}
+
+fn mismatch(expected: ValueType, value: &Value) -> ConvertError {
+    ConvertError::TypeMismatch {
+        expected,
+        actual: value.value_type(),
+    }
+}
+
+// Scalars convert exactly as typed host functions marshal them, so these impls defer to
+// `HostRet` for the value type and `into_value`, and to `HostArg` (via the borrowed form for
+// `Vec<u8>` and `String`) for `from_value`.
+macro_rules! impl_scalar {
+    ($($ty:ty => $arg:ty),* $(,)?) => {
+        $(
+            impl TapeType for $ty {
+                const VALUE_TYPE: ValueType = <$ty as HostRet>::VALUE_TYPE;
+            }
+
+            impl IntoValue for $ty {
+                fn into_value(
+                    self,
+                    _types: &TypeTable,
+                    _aggregates: &mut AggHeap,
+                ) -> Result<Value, ConvertError> {
+                    Ok(HostRet::into_value(self))
+                }
+            }
+
+            impl FromValue for $ty {
+                fn from_value(
+                    value: &Value,
+                    _types: &TypeTable,
+                    _aggregates: &AggHeap,
+                ) -> Result<Self, ConvertError> {
+                    <$arg as HostArg>::from_value_ref(ValueRef::from_value(value))
+                        .map(Into::into)
+                        .ok_or_else(|| mismatch(<$ty as TapeType>::VALUE_TYPE, value))
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar!(
+    bool => bool,
+    i64 => i64,
+    u64 => u64,
+    f64 => f64,
+    Decimal => Decimal,
+    Vec<u8> => &[u8],
+    String => &str,
+    AggHandle => AggHandle,
+    FuncId => FuncId,
+    Closure => Closure,
+);
+
+impl TapeType for () {
+    const VALUE_TYPE: ValueType = ValueType::Unit;
+}
+
+impl IntoValue for () {
+    fn into_value(self, _: &TypeTable, _: &mut AggHeap) -> Result<Value, ConvertError> {
+        Ok(Value::Unit)
+    }
+}
+
+impl FromValue for () {
+    fn from_value(value: &Value, _: &TypeTable, _: &AggHeap) -> Result<Self, ConvertError> {
+        match value {
+            Value::Unit => Ok(()),
+            _ => Err(mismatch(ValueType::Unit, value)),
+        }
+    }
+}
+
+/// Arrays of `T`. `Vec<u8>` is `Bytes` instead, so `u8` has no [`TapeType`] impl.
+impl<T: TapeType> TapeType for Vec<T> {
+    const VALUE_TYPE: ValueType = ValueType::Agg;
+
+    fn register_types(pb: &mut ProgramBuilder) {
+        T::register_types(pb);
+        pb.array_elem(T::VALUE_TYPE);
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(
+        self,
+        types: &TypeTable,
+        aggregates: &mut AggHeap,
+    ) -> Result<Value, ConvertError> {
+        let elem_type_id = types
+            .array_elem_id(T::VALUE_TYPE)
+            .ok_or(ConvertError::MissingElemType(T::VALUE_TYPE))?;
+        let values = T::into_values(self, types, aggregates)?;
+        Ok(Value::Agg(aggregates.array_new(elem_type_id, values)))
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(
+        value: &Value,
+        types: &TypeTable,
+        aggregates: &AggHeap,
+    ) -> Result<Self, ConvertError> {
+        let handle = agg_handle(value)?;
+        let AggType::Array { elem_type_id } = aggregates.agg_type(handle)? else {
+            return Err(AggError::WrongKind.into());
+        };
+        let elem = types.array_elems.get(elem_type_id.0 as usize).copied();
+        if elem != Some(T::VALUE_TYPE) {
+            return Err(ConvertError::MissingElemType(T::VALUE_TYPE));
+        }
+        T::from_elements(handle, types, aggregates)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($n:literal; $($i:tt $t:ident),*) => {
+        impl<$($t: TapeType),*> TapeType for ($($t,)*) {
+            const VALUE_TYPE: ValueType = ValueType::Agg;
+
+            fn register_types(pb: &mut ProgramBuilder) {
+                $($t::register_types(pb);)*
+            }
+        }
+
+        impl<$($t: IntoValue),*> IntoValue for ($($t,)*) {
+            fn into_value(
+                self,
+                types: &TypeTable,
+                aggregates: &mut AggHeap,
+            ) -> Result<Value, ConvertError> {
+                let values = alloc::vec![$(self.$i.into_value(types, aggregates)?),*];
+                Ok(Value::Agg(aggregates.tuple_new(values)))
+            }
+        }
+
+        impl<$($t: FromValue),*> FromValue for ($($t,)*) {
+            fn from_value(
+                value: &Value,
+                types: &TypeTable,
+                aggregates: &AggHeap,
+            ) -> Result<Self, ConvertError> {
+                let handle = agg_handle(value)?;
+                if aggregates.tuple_len(handle)? != $n {
+                    return Err(AggError::BadArity.into());
+                }
+                Ok(($(
+                    $t::from_value(aggregates.tuple_get_ref(handle, $i)?, types, aggregates)?,
+                )*))
+            }
+        }
+    };
+}
+
+impl_tuple!(1; 0 A0);
+impl_tuple!(2; 0 A0, 1 A1);
+impl_tuple!(3; 0 A0, 1 A1, 2 A2);
+impl_tuple!(4; 0 A0, 1 A1, 2 A2, 3 A3);
+impl_tuple!(5; 0 A0, 1 A1, 2 A2, 3 A3, 4 A4);
+impl_tuple!(6; 0 A0, 1 A1, 2 A2, 3 A3, 4 A4, 5 A5);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn tuples_and_arrays_roundtrip() {
+        let mut pb = ProgramBuilder::new();
+        <(i64, Vec<(String, bool)>, Vec<u8>)>::register_types(&mut pb);
+        let types = pb.build().types;
+
+        let mut heap = AggHeap::new();
+        let rust = (
+            -3_i64,
+            vec![("a".into(), true), ("b".into(), false)],
+            vec![1, 2],
+        );
+        let value = rust.clone().into_value(&types, &mut heap).unwrap();
+        let back = <(i64, Vec<(String, bool)>, Vec<u8>)>::from_value(&value, &types, &heap);
+        assert_eq!(back, Ok(rust));
+
+        let Value::Agg(tuple) = value else {
+            panic!("expected a tuple, got {value:?}");
+        };
+        assert_eq!(heap.tuple_get(tuple, 2).unwrap(), Value::Bytes(vec![1, 2]));
+    }
+
+    #[test]
+    fn mismatches_are_reported() {
+        let types = TypeTable::default();
+        let mut heap = AggHeap::new();
+        assert_eq!(
+            i64::from_value(&Value::U64(1), &types, &heap),
+            Err(ConvertError::TypeMismatch {
+                expected: ValueType::I64,
+                actual: ValueType::U64,
+            })
+        );
+        assert_eq!(
+            vec![1_i64].into_value(&types, &mut heap),
+            Err(ConvertError::MissingElemType(ValueType::I64))
+        );
+        let pair = (1_i64, 2_i64).into_value(&types, &mut heap).unwrap();
+        assert_eq!(
+            <(i64, i64, i64)>::from_value(&pair, &types, &heap),
+            Err(ConvertError::Agg(AggError::BadArity))
+        );
+        assert_eq!(
+            Vec::<i64>::from_value(&pair, &types, &heap),
+            Err(ConvertError::Agg(AggError::WrongKind))
+        );
+    }
+}
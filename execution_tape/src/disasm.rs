@@ -8,22 +8,29 @@
 //! - A stable, human-readable text format via [`core::fmt::Display`].
 //!
 //! The disassembly format is intentionally “assembly-like” (one instruction per line, label
-//! resolution for branch targets) so it can plausibly be parsed in the future.
+//! resolution for branch targets). Program tables and per-function metadata are written as
+//! directives, so the text parses back with [`crate::asm_text::parse`].
 
 #![allow(clippy::module_name_repetitions, reason = "public API module")]
 
 extern crate alloc;
 
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
+use core::num::NonZeroU32;
 
 use crate::bytecode::{
     BytecodeError, DecodedInstr, Instr, ReadsIter, WritesIter, decode_instructions,
+    encode_instructions,
 };
 use crate::format::DecodeError;
 use crate::opcode::{Opcode, OperandRole};
-use crate::program::{CallSigId, ConstId, ElemTypeId, HostSigId, Program, TypeId, ValueType};
+use crate::program::{
+    AggShape, CallSigId, ConstEntry, ConstId, ElemTypeId, HostSigId, Program, SpanEntry, SpanId,
+    SymbolId, TypeId, ValueType,
+};
 use crate::value::FuncId;
 use crate::verifier::VerifiedProgram;
 
@@ -110,6 +117,30 @@ impl<'a> FunctionDisassembly<'a> {
             .map(move |di| instr_view(self.program, self.func, di))
     }
 
+    /// Returns `true` if this function's `pc_*` operands use the fixed-width (5-byte) LEB128 form.
+    ///
+    /// Fixup-based producers such as [`crate::asm::Asm`] reserve a fixed-width window for branch
+    /// targets; the text format records this so re-assembly reproduces the same bytes.
+    fn has_wide_pcs(&self) -> bool {
+        let Ok(decoded) = &self.decoded else {
+            return false;
+        };
+        let end = self
+            .program
+            .functions
+            .get(self.func.0 as usize)
+            .map_or(0, |f| f.bytecode.len);
+        decoded.iter().enumerate().any(|(ix, di)| {
+            if !matches!(di.instr, Instr::Br { .. } | Instr::Jmp { .. }) {
+                return false;
+            }
+            let next = decoded.get(ix + 1).map_or(end, |n| n.offset);
+            let canonical =
+                encode_instructions(core::slice::from_ref(&di.instr)).map_or(0, |b| b.len());
+            next.saturating_sub(di.offset) as usize > canonical
+        })
+    }
+
     /// Computes label indices for this function.
     ///
     /// Labels are derived from control-flow targets (`br`/`jmp`) plus the function entry (`pc=0`).
@@ -192,7 +223,7 @@ pub enum DisasmError {
 }
 
 impl DisasmError {
-    fn from_bytecode(e: BytecodeError) -> Self {
+    pub(crate) fn from_bytecode(e: BytecodeError) -> Self {
         match e {
             BytecodeError::Decode(e) => Self::Decode(e),
             BytecodeError::UnknownOpcode { opcode } => Self::UnknownOpcode { opcode },
@@ -259,6 +290,17 @@ pub enum Operands<'a> {
         /// Return registers.
         rets: &'a [u32],
     },
+    /// `host_call.pure host_sig, args..., rets...`.
+    HostCallPure {
+        /// Host signature table entry.
+        host_sig: HostSigId,
+        /// Best-effort resolved host symbol.
+        symbol: Option<&'a str>,
+        /// Argument registers.
+        args: &'a [u32],
+        /// Return registers.
+        rets: &'a [u32],
+    },
 }
 
 /// A table/index-like immediate operand.
@@ -449,7 +491,9 @@ impl<'a> InstrView<'a> {
             .iter()
             .any(|o| matches!(o.role, OperandRole::HostSig))
         {
-            let Instr::HostCall { host_sig, .. } = &self.decoded.instr else {
+            let (Instr::HostCall { host_sig, .. } | Instr::HostCallPure { host_sig, .. }) =
+                &self.decoded.instr
+            else {
                 return None;
             };
             return Some(InputIndex::HostSig(*host_sig));
@@ -503,10 +547,12 @@ impl<'a> InstrView<'a> {
         None
     }
 
-    /// Resolved host symbol for `host_call` (best-effort).
+    /// Resolved host symbol for `host_call`/`host_call.pure` (best-effort).
     #[must_use]
     pub fn host_op_symbol(&self) -> Option<&'a str> {
-        let Instr::HostCall { host_sig, .. } = &self.decoded.instr else {
+        let (Instr::HostCall { host_sig, .. } | Instr::HostCallPure { host_sig, .. }) =
+            &self.decoded.instr
+        else {
             return None;
         };
         host_sig_symbol(self.program, *host_sig)
@@ -582,6 +628,16 @@ impl<'a> InstrView<'a> {
                     rets,
                 }),
                 Instr::Ret { eff_in, rets } => Operands::Ret { eff: *eff_in, rets },
+                Instr::HostCallPure {
+                    host_sig,
+                    args,
+                    rets,
+                } => Operands::HostCallPure {
+                    host_sig: *host_sig,
+                    symbol: self.host_op_symbol(),
+                    args,
+                    rets,
+                },
                 _ => Operands::Simple,
             };
         }
@@ -892,6 +948,16 @@ fn instr_view<'a>(program: &'a Program, func: FuncId, di: DecodedInstr) -> Instr
             view.srcs.extend(args.iter().copied());
             view.srcs.extend(rets.iter().copied());
         }
+        Instr::HostCallPure {
+            host_sig,
+            args,
+            rets,
+        } => {
+            view.input_index = Some(InputIndex::HostSig(host_sig));
+            view.srcs = Vec::with_capacity(args.len() + rets.len());
+            view.srcs.extend(args.iter().copied());
+            view.srcs.extend(rets.iter().copied());
+        }
         Instr::ClosureNew { dst, func, env } => {
             view.dst = Some(dst);
             view.srcs = vec![func, env];
@@ -1006,7 +1072,11 @@ fn instr_view<'a>(program: &'a Program, func: FuncId, di: DecodedInstr) -> Instr
     view
 }
 
-fn instr_view<'a>(program: &'a Program, func: FuncId, di: &'a DecodedInstr) -> InstrView<'a> {
+pub(crate) fn instr_view<'a>(
+    program: &'a Program,
+    func: FuncId,
+    di: &'a DecodedInstr,
+) -> InstrView<'a> {
     InstrView {
         program,
         func,
@@ -1022,17 +1092,17 @@ fn host_sig_symbol(program: &Program, id: HostSigId) -> Option<&str> {
 fn const_pool_value<'a>(program: &'a Program, id: ConstId) -> Option<ConstValue<'a>> {
     let entry = program.const_pool.get(id.0 as usize)?;
     match entry {
-        crate::program::ConstEntry::Unit => Some(ConstValue::Unit),
-        crate::program::ConstEntry::Bool(b) => Some(ConstValue::Bool(*b)),
-        crate::program::ConstEntry::I64(i) => Some(ConstValue::I64(*i)),
-        crate::program::ConstEntry::U64(u) => Some(ConstValue::U64(*u)),
-        crate::program::ConstEntry::F64(bits) => Some(ConstValue::F64Bits(*bits)),
-        crate::program::ConstEntry::Decimal { mantissa, scale } => Some(ConstValue::Decimal {
+        ConstEntry::Unit => Some(ConstValue::Unit),
+        ConstEntry::Bool(b) => Some(ConstValue::Bool(*b)),
+        ConstEntry::I64(i) => Some(ConstValue::I64(*i)),
+        ConstEntry::U64(u) => Some(ConstValue::U64(*u)),
+        ConstEntry::F64(bits) => Some(ConstValue::F64Bits(*bits)),
+        ConstEntry::Decimal { mantissa, scale } => Some(ConstValue::Decimal {
             mantissa: *mantissa,
             scale: *scale,
         }),
-        crate::program::ConstEntry::Bytes(_) => program.const_bytes(id).ok().map(ConstValue::Bytes),
-        crate::program::ConstEntry::Str(_) => program.const_str(id).ok().map(ConstValue::Str),
+        ConstEntry::Bytes(_) => program.const_bytes(id).ok().map(ConstValue::Bytes),
+        ConstEntry::Str(_) => program.const_str(id).ok().map(ConstValue::Str),
     }
 }
 
@@ -1064,7 +1134,8 @@ fn fmt_named_ret_list(
         }
         let ret = u32::try_from(i).unwrap_or(u32::MAX);
         if let Some(name) = program.function_output_name(func.0, ret) {
-            write!(w, "{name}=")?;
+            fmt_name(w, name)?;
+            write!(w, "=")?;
         }
         fmt_reg(w, *r)?;
     }
@@ -1084,7 +1155,8 @@ fn fmt_named_arg_list(
         }
         let arg = u32::try_from(i).unwrap_or(u32::MAX);
         if let Some(name) = program.function_input_name(func.0, arg) {
-            write!(w, "{name}=")?;
+            fmt_name(w, name)?;
+            write!(w, "=")?;
         }
         fmt_reg(w, *r)?;
     }
@@ -1117,7 +1189,8 @@ fn fmt_named_func_arg_bindings(
             write!(w, ", ")?;
         }
         if let Some(name) = program.function_input_name(func.0, arg) {
-            write!(w, "{name}=")?;
+            fmt_name(w, name)?;
+            write!(w, "=")?;
         }
         fmt_reg(w, 1 + arg)?;
     }
@@ -1189,88 +1262,459 @@ fn fmt_call_sig_annotation(
 
 impl fmt::Display for Disassembly<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(name) = self.program.name() {
-            writeln!(f, "; program=\"{name}\"")?;
+        let program = self.program;
+        let syms = SymbolRefs::new(program);
+        if let Some(name) = program.name() {
+            write!(f, "; program=")?;
+            fmt_str_lit(f, name)?;
+            writeln!(f)?;
+        }
+        if fmt_program_directives(f, program, &syms)? || program.name().is_some() {
             writeln!(f)?;
         }
         for (i, fd) in self.functions.iter().enumerate() {
             if i != 0 {
                 writeln!(f)?;
             }
-            write!(f, "func f{}:", fd.func().0)?;
-            if let Some(name) = self.program.function_name(fd.func().0) {
-                write!(f, " ; name=\"{name}\"")?;
+            let func = fd.func();
+            write!(f, "func f{}:", func.0)?;
+            if let Some(name) = program.function_name(func.0) {
+                write!(f, " ; name=")?;
+                fmt_str_lit(f, name)?;
             }
             writeln!(f)?;
-            fmt_named_func_arg_bindings(f, self.program, fd.func())?;
+            fmt_named_func_arg_bindings(f, program, func)?;
+            fmt_function_directives(f, fd, &syms)?;
             if let Some(e) = fd.error() {
                 writeln!(f, "  <decode error: {e:?}>")?;
                 continue;
             }
 
             let labels = fd.labels();
+            let instrs: Vec<InstrView<'_>> = fd.instrs().collect();
+            let mut boundaries: Vec<u32> = instrs.iter().map(InstrView::pc).collect();
+            let end_pc = program
+                .functions
+                .get(func.0 as usize)
+                .map_or(0, |func| func.bytecode.len);
+            boundaries.push(end_pc);
+            let targets = LabelTargets {
+                label_pcs: labels.pcs(),
+                boundaries: &boundaries,
+            };
+            let label_names: Vec<_> = program.labels.iter().filter(|e| e.func == func.0).collect();
+            let mut spans = SpanCursor::new(program, func);
 
-            for iv in fd.instrs() {
-                let pc = iv.pc();
+            for (ix, &pc) in boundaries.iter().enumerate() {
+                spans.fmt_until(f, pc)?;
                 if let Some(label_ix) = labels.label_index(pc) {
                     write!(f, "  @L{label_ix}:")?;
-                    if let Some(name) = self.program.label_name(fd.func().0, pc) {
-                        write!(f, " ; name=\"{name}\"")?;
+                    if let Some(name) = program.label_name(func.0, pc) {
+                        write!(f, " ; name=")?;
+                        fmt_str_lit(f, name)?;
                     }
                     writeln!(f)?;
                 }
-                fmt_instr_with_labels(f, &iv, labels.pcs())?;
-                writeln!(f)?;
+                for e in label_names.iter().filter(|e| e.pc == pc) {
+                    write!(f, "  .label ")?;
+                    syms.fmt(f, e.name)?;
+                    writeln!(f)?;
+                }
+                if let Some(iv) = instrs.get(ix) {
+                    fmt_instr_with_labels(f, iv, &targets)?;
+                    writeln!(f)?;
+                }
+            }
+            spans.fmt_rest(f)?;
+            for e in &label_names {
+                if boundaries.binary_search(&e.pc).is_err() {
+                    write!(f, "  .label ")?;
+                    syms.fmt(f, e.name)?;
+                    writeln!(f, " pc={}", e.pc)?;
+                }
             }
         }
         Ok(())
     }
 }
 
-fn fmt_label_ref(f: &mut fmt::Formatter<'_>, pc: u32, label_pcs: &[u32]) -> fmt::Result {
-    match label_pcs.binary_search(&pc) {
-        Ok(ix) => write!(f, "@L{ix}"),
-        Err(_) => write!(f, "@{:06}", pc),
+/// Branch-target resolution for the text format.
+///
+/// Targets that land on an instruction boundary (or the end of the stream) are written as `@L<n>`
+/// label references; any other target is written as a raw `@<pc>`.
+struct LabelTargets<'a> {
+    label_pcs: &'a [u32],
+    boundaries: &'a [u32],
+}
+
+fn fmt_label_ref(f: &mut fmt::Formatter<'_>, pc: u32, targets: &LabelTargets<'_>) -> fmt::Result {
+    match targets.label_pcs.binary_search(&pc) {
+        Ok(ix) if targets.boundaries.binary_search(&pc).is_ok() => write!(f, "@L{ix}"),
+        _ => write!(f, "@{:06}", pc),
     }
 }
 
-fn fmt_instr_with_labels(
-    f: &mut fmt::Formatter<'_>,
-    iv: &InstrView<'_>,
-    label_pcs: &[u32],
-) -> fmt::Result {
-    write!(f, "  {:06}: {}", iv.pc(), iv.opcode().mnemonic())?;
-    match iv.operands() {
-        Operands::Simple => {
-            if let Some(dst) = iv.dst() {
-                write!(f, " ")?;
-                fmt_reg(f, dst)?;
+/// Symbol references for the text format.
+///
+/// A symbol is written as its quoted string when that string resolves back to the same symbol id
+/// (its first occurrence in the table), and as `#<id>` otherwise.
+struct SymbolRefs<'a> {
+    program: &'a Program,
+    first: BTreeMap<&'a str, u32>,
+}
+
+impl<'a> SymbolRefs<'a> {
+    fn new(program: &'a Program) -> Self {
+        let mut first = BTreeMap::new();
+        for ix in 1..program.symbols.len() {
+            let Some(id) = u32::try_from(ix).ok().and_then(NonZeroU32::new) else {
+                break;
+            };
+            if let Ok(s) = program.symbol_str(SymbolId(id)) {
+                first.entry(s).or_insert(id.get());
             }
-            let reads = iv.reads();
-            if reads.clone().next().is_some() {
-                write!(f, ", ")?;
-                fmt_reg_iter(f, reads)?;
+        }
+        Self { program, first }
+    }
+
+    fn canonical(&self, id: SymbolId) -> Option<&'a str> {
+        let s = self.program.symbol_str(id).ok()?;
+        (self.first.get(s) == Some(&id.index())).then_some(s)
+    }
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>, id: SymbolId) -> fmt::Result {
+        match self.canonical(id) {
+            Some(s) => fmt_str_lit(f, s),
+            None => write!(f, "#{}", id.index()),
+        }
+    }
+
+    /// Formats a value name (a symbol index where `0` means "unnamed").
+    fn fmt_value_name(&self, f: &mut fmt::Formatter<'_>, raw: u32) -> fmt::Result {
+        let Some(id) = NonZeroU32::new(raw).map(SymbolId) else {
+            return write!(f, "_");
+        };
+        match self.canonical(id) {
+            Some(s) => fmt_name(f, s),
+            None => write!(f, "#{raw}"),
+        }
+    }
+}
+
+/// Walks a function's span table in pc order.
+struct SpanCursor<'a> {
+    program: &'a Program,
+    spans: &'a [SpanEntry],
+    next: usize,
+    pc: u64,
+}
+
+impl<'a> SpanCursor<'a> {
+    fn new(program: &'a Program, func: FuncId) -> Self {
+        let spans = program
+            .functions
+            .get(func.0 as usize)
+            .and_then(|f| f.spans(program).ok())
+            .unwrap_or(&[]);
+        Self {
+            program,
+            spans,
+            next: 0,
+            pc: 0,
+        }
+    }
+
+    /// Writes the source-map location of `span` as a trailing comment, if it has one.
+    fn fmt_source(&self, f: &mut fmt::Formatter<'_>, span: SpanId) -> fmt::Result {
+        match self.program.span_info(span) {
+            Some(info) => write!(f, " ; {info}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes every span entry starting at or before `pc`.
+    ///
+    /// Entries that start between instruction boundaries carry an explicit `pc=`.
+    fn fmt_until(&mut self, f: &mut fmt::Formatter<'_>, pc: u32) -> fmt::Result {
+        while let Some(e) = self.spans.get(self.next) {
+            let at = self.pc.saturating_add(e.pc_delta);
+            if at > u64::from(pc) {
+                break;
             }
-            if let Some(ix) = iv.input_index() {
-                if iv
-                    .opcode()
-                    .operands()
-                    .iter()
-                    .any(|o| matches!(o.role, OperandRole::Const))
-                {
-                    write!(f, ", {ix}")?;
-                } else {
-                    write!(f, " ; {ix}")?;
+            write!(f, "  .span {}", e.span_id)?;
+            if at != u64::from(pc) {
+                write!(f, " pc={at}")?;
+            }
+            self.fmt_source(f, e.span_id)?;
+            writeln!(f)?;
+            self.pc = at;
+            self.next += 1;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining entries (past the end of the bytecode stream).
+    fn fmt_rest(&mut self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        while let Some(e) = self.spans.get(self.next) {
+            let at = self.pc.saturating_add(e.pc_delta);
+            write!(f, "  .span {} pc={at}", e.span_id)?;
+            self.fmt_source(f, e.span_id)?;
+            writeln!(f)?;
+            self.pc = at;
+            self.next += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if `s` can be written unquoted as a name (`[A-Za-z_][A-Za-z0-9_]*`, but not `_`).
+fn is_bare_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    s != "_"
+        && (first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn fmt_name(w: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    if is_bare_name(s) {
+        write!(w, "{s}")
+    } else {
+        fmt_str_lit(w, s)
+    }
+}
+
+fn fmt_str_lit(w: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(w, "\"{}\"", s.escape_default())
+}
+
+fn fmt_sig_list(
+    w: &mut fmt::Formatter<'_>,
+    syms: &SymbolRefs<'_>,
+    types: &[ValueType],
+    names: &[u32],
+) -> fmt::Result {
+    let named = !names.is_empty() && names.len() == types.len();
+    write!(w, "[")?;
+    for (i, ty) in types.iter().enumerate() {
+        if i != 0 {
+            write!(w, ", ")?;
+        }
+        if named {
+            syms.fmt_value_name(w, names[i])?;
+            write!(w, ": ")?;
+        }
+        fmt_value_type(w, *ty)?;
+    }
+    write!(w, "]")
+}
+
+/// Writes the program-level tables as `.program`/`.symbol`/`.const`/... directives.
+///
+/// Returns `true` if anything was written.
+fn fmt_program_directives(
+    f: &mut fmt::Formatter<'_>,
+    program: &Program,
+    syms: &SymbolRefs<'_>,
+) -> Result<bool, fmt::Error> {
+    let mut wrote = false;
+    if let Some(name) = program.program_name {
+        write!(f, ".program ")?;
+        syms.fmt(f, name)?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for ix in 1..program.symbols.len() {
+        let Some(id) = u32::try_from(ix).ok().and_then(NonZeroU32::new) else {
+            break;
+        };
+        write!(f, ".symbol {ix} ")?;
+        fmt_str_lit(f, program.symbol_str(SymbolId(id)).unwrap_or_default())?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for (ix, c) in program.const_pool.iter().enumerate() {
+        let id = ConstId(u32::try_from(ix).unwrap_or(u32::MAX));
+        write!(f, ".const {ix} ")?;
+        match c {
+            ConstEntry::Unit => write!(f, "unit")?,
+            ConstEntry::Bool(b) => write!(f, "bool {b}")?,
+            ConstEntry::I64(i) => write!(f, "i64 {i}")?,
+            ConstEntry::U64(u) => write!(f, "u64 {u}")?,
+            ConstEntry::F64(bits) => {
+                write!(f, "f64 0x{bits:016X} ; {}", f64::from_bits(*bits))?;
+            }
+            ConstEntry::Decimal { mantissa, scale } => write!(f, "decimal {mantissa} {scale}")?,
+            ConstEntry::Bytes(_) => {
+                write!(f, "bytes 0x")?;
+                for byte in program.const_bytes(id).unwrap_or_default() {
+                    write!(f, "{byte:02X}")?;
                 }
             }
-            if let Some(sym) = iv.host_op_symbol() {
-                write!(f, " ; host=\"{sym}\"")?;
+            ConstEntry::Str(_) => {
+                write!(f, "str ")?;
+                fmt_str_lit(f, program.const_str(id).unwrap_or_default())?;
             }
-            if let Some(v) = iv.const_value() {
-                write!(f, " ; ")?;
-                fmt_const_value(f, v)?;
+        }
+        writeln!(f)?;
+        wrote = true;
+    }
+    let types = &program.types;
+    for (ix, st) in types.structs.iter().enumerate() {
+        write!(f, ".struct {ix} [")?;
+        let names = types.struct_field_name_ids(st).unwrap_or_default();
+        let field_types = types.struct_field_types(st).unwrap_or_default();
+        for (i, (name, ty)) in names.iter().zip(field_types).enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            fmt_name(f, types.field_name_str(*name).unwrap_or_default())?;
+            write!(f, ": ")?;
+            fmt_value_type(f, *ty)?;
+        }
+        writeln!(f, "]")?;
+        wrote = true;
+    }
+    for (ix, ty) in types.array_elems.iter().enumerate() {
+        write!(f, ".elem {ix} ")?;
+        fmt_value_type(f, *ty)?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for (ix, hs) in program.host_sigs.iter().enumerate() {
+        write!(f, ".host_sig {ix} ")?;
+        if hs.pure {
+            write!(f, "pure ")?;
+        }
+        syms.fmt(f, hs.symbol)?;
+        write!(f, " ")?;
+        fmt_value_type_list(f, program.host_sig_args(hs).unwrap_or_default())?;
+        write!(f, " -> ")?;
+        fmt_value_type_list(f, program.host_sig_rets(hs).unwrap_or_default())?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for (ix, cs) in program.call_sigs.iter().enumerate() {
+        write!(f, ".call_sig {ix} ")?;
+        fmt_value_type_list(f, program.call_sig_args(cs).unwrap_or_default())?;
+        write!(f, " -> ")?;
+        fmt_value_type_list(f, program.call_sig_rets(cs).unwrap_or_default())?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for e in &program.function_arg_agg_shapes {
+        write!(f, ".arg_shape f{} {} ", e.func, e.arg)?;
+        match &e.shape {
+            AggShape::Tuple { elems } => {
+                write!(f, "tuple [")?;
+                for (i, elem) in elems.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    match elem {
+                        Some(ty) => fmt_value_type(f, *ty)?,
+                        None => write!(f, "_")?,
+                    }
+                }
+                write!(f, "]")?;
             }
+            AggShape::Struct(id) => write!(f, "struct type#{}", id.0)?,
+            AggShape::Array(id) => write!(f, "array elem_type#{}", id.0)?,
+        }
+        writeln!(f)?;
+        wrote = true;
+    }
+    for e in &program.exports {
+        write!(f, ".export f{} ", e.func)?;
+        syms.fmt(f, e.name)?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for e in &program.imports {
+        write!(f, ".import f{} ", e.func)?;
+        syms.fmt(f, e.module)?;
+        write!(f, " ")?;
+        syms.fmt(f, e.name)?;
+        writeln!(f)?;
+        wrote = true;
+    }
+    for e in &program.source_map {
+        write!(f, ".source {} ", e.span)?;
+        syms.fmt(f, e.file)?;
+        let r = e.range;
+        write!(
+            f,
+            " {} {} {} {}",
+            r.start_line, r.start_col, r.end_line, r.end_col
+        )?;
+        if let Some(guid) = e.node_guid {
+            write!(f, " ")?;
+            syms.fmt(f, guid)?;
         }
+        writeln!(f)?;
+        wrote = true;
+    }
+    for section in &program.custom_sections {
+        write!(f, ".custom ")?;
+        fmt_str_lit(f, &section.name)?;
+        write!(f, " 0x")?;
+        for byte in &section.bytes {
+            write!(f, "{byte:02X}")?;
+        }
+        writeln!(f)?;
+        wrote = true;
+    }
+    Ok(wrote)
+}
+
+/// Writes the per-function `.name`/`.sig`/`.regs`/`.pcs` directives.
+fn fmt_function_directives(
+    f: &mut fmt::Formatter<'_>,
+    fd: &FunctionDisassembly<'_>,
+    syms: &SymbolRefs<'_>,
+) -> fmt::Result {
+    let program = fd.program;
+    let func = fd.func;
+    for e in program.function_names.iter().filter(|e| e.func == func.0) {
+        write!(f, "  .name ")?;
+        syms.fmt(f, e.name)?;
+        writeln!(f)?;
+    }
+    let Some(def) = program.functions.get(func.0 as usize) else {
+        return Ok(());
+    };
+    write!(f, "  .sig ")?;
+    fmt_sig_list(
+        f,
+        syms,
+        def.arg_types(program).unwrap_or_default(),
+        program.function_arg_name_ids(def).unwrap_or_default(),
+    )?;
+    write!(f, " -> ")?;
+    fmt_sig_list(
+        f,
+        syms,
+        def.ret_types(program).unwrap_or_default(),
+        program.function_ret_name_ids(def).unwrap_or_default(),
+    )?;
+    writeln!(f)?;
+    writeln!(f, "  .regs {}", def.reg_count)?;
+    if fd.has_wide_pcs() {
+        writeln!(f, "  .pcs wide")?;
+    }
+    Ok(())
+}
+
+fn fmt_instr_with_labels(
+    f: &mut fmt::Formatter<'_>,
+    iv: &InstrView<'_>,
+    targets: &LabelTargets<'_>,
+) -> fmt::Result {
+    write!(f, "  {:06}: {}", iv.pc(), iv.opcode().mnemonic())?;
+    match iv.operands() {
+        Operands::Simple => fmt_simple_operands(f, iv)?,
         Operands::Br {
             cond,
             pc_true,
@@ -1279,13 +1723,13 @@ fn fmt_instr_with_labels(
             write!(f, " ")?;
             fmt_reg(f, cond)?;
             write!(f, ", ")?;
-            fmt_label_ref(f, pc_true, label_pcs)?;
+            fmt_label_ref(f, pc_true, targets)?;
             write!(f, ", ")?;
-            fmt_label_ref(f, pc_false, label_pcs)?;
+            fmt_label_ref(f, pc_false, targets)?;
         }
         Operands::Jmp { pc_target } => {
             write!(f, " ")?;
-            fmt_label_ref(f, pc_target, label_pcs)?;
+            fmt_label_ref(f, pc_target, targets)?;
         }
         Operands::Call(call) => {
             write!(f, " eff_out=")?;
@@ -1335,6 +1779,71 @@ fn fmt_instr_with_labels(
             write!(f, ", rets=")?;
             fmt_named_ret_list(f, iv.program, iv.func(), rets)?;
         }
+        Operands::HostCallPure {
+            host_sig,
+            symbol,
+            args,
+            rets,
+        } => fmt_host_call_pure(f, host_sig, symbol, args, rets)?,
+    }
+    Ok(())
+}
+
+fn fmt_host_call_pure(
+    f: &mut fmt::Formatter<'_>,
+    host_sig: HostSigId,
+    symbol: Option<&str>,
+    args: &[u32],
+    rets: &[u32],
+) -> fmt::Result {
+    write!(f, " host_sig#{}", host_sig.0)?;
+    if let Some(s) = symbol {
+        write!(f, "(\"{s}\")")?;
+    }
+    write!(f, ", args=")?;
+    fmt_reg_list(f, args)?;
+    write!(f, ", rets=")?;
+    fmt_reg_list(f, rets)
+}
+
+/// Formats `dst, [reads...], index, immediates` for instructions with [`Operands::Simple`].
+///
+/// Every encoded operand is written inline (so the text can be parsed back); comments only carry
+/// derived information such as resolved constant-pool values.
+fn fmt_simple_operands(f: &mut fmt::Formatter<'_>, iv: &InstrView<'_>) -> fmt::Result {
+    let mut sep = " ";
+    if let Some(dst) = iv.dst() {
+        write!(f, "{sep}")?;
+        fmt_reg(f, dst)?;
+        sep = ", ";
+    }
+    let reads = iv.reads();
+    if reads.clone().next().is_some() {
+        write!(f, "{sep}")?;
+        fmt_reg_iter(f, reads)?;
+        sep = ", ";
+    }
+    if let Some(ix) = iv.input_index() {
+        write!(f, "{sep}{ix}")?;
+        sep = ", ";
+    }
+    match &iv.decoded.instr {
+        Instr::ConstBool { imm, .. } => write!(f, "{sep}imm={imm}")?,
+        Instr::ConstI64 { imm, .. } => write!(f, "{sep}imm={imm}")?,
+        Instr::ConstU64 { imm, .. } => write!(f, "{sep}imm={imm}")?,
+        Instr::ConstF64 { bits, .. } => {
+            write!(f, "{sep}bits=0x{bits:016X} ; {}", f64::from_bits(*bits))?;
+        }
+        Instr::ConstDecimal {
+            mantissa, scale, ..
+        } => write!(f, "{sep}mantissa={mantissa}, scale={scale}")?,
+        Instr::ConstPool { .. } => {
+            if let Some(v) = iv.const_value() {
+                write!(f, " ; ")?;
+                fmt_const_value(f, v)?;
+            }
+        }
+        _ => {}
     }
     Ok(())
 }
@@ -1381,36 +1890,7 @@ impl fmt::Display for InstrView<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:06}: {}", self.pc(), self.opcode().mnemonic())?;
         match self.operands() {
-            Operands::Simple => {
-                if let Some(dst) = self.dst() {
-                    write!(f, " ")?;
-                    fmt_reg(f, dst)?;
-                }
-                let reads = self.reads();
-                if reads.clone().next().is_some() {
-                    write!(f, ", ")?;
-                    fmt_reg_iter(f, reads)?;
-                }
-                if let Some(ix) = self.input_index() {
-                    if self
-                        .opcode()
-                        .operands()
-                        .iter()
-                        .any(|o| matches!(o.role, OperandRole::Const))
-                    {
-                        write!(f, ", {ix}")?;
-                    } else {
-                        write!(f, " ; {ix}")?;
-                    }
-                }
-                if let Some(sym) = self.host_op_symbol() {
-                    write!(f, " ; host=\"{sym}\"")?;
-                }
-                if let Some(v) = self.const_value() {
-                    write!(f, " ; ")?;
-                    fmt_const_value(f, v)?;
-                }
-            }
+            Operands::Simple => fmt_simple_operands(f, self)?,
             Operands::Br {
                 cond,
                 pc_true,
@@ -1467,6 +1947,12 @@ impl fmt::Display for InstrView<'_> {
                 write!(f, ", rets=")?;
                 fmt_named_ret_list(f, self.program, self.func, rets)?;
             }
+            Operands::HostCallPure {
+                host_sig,
+                symbol,
+                args,
+                rets,
+            } => fmt_host_call_pure(f, host_sig, symbol, args, rets)?,
         }
         Ok(())
     }
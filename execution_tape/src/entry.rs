@@ -0,0 +1,782 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Typed entrypoints with Rust argument and return types.
+//!
+//! [`VerifiedProgram::typed_entry`] checks an entry function's signature against Rust types once
+//! and returns a [`TypedEntry`]. Its [`call`](TypedEntry::call) takes the arguments as a Rust
+//! tuple and returns the results as one, converting with [`IntoValue`] and [`FromValue`]. Argument
+//! and return values are passed in fixed-size arrays, so a call allocates no `Vec<Value>`.
+//!
+//! [`Vm::run_named`] instead runs a function found by name, passing arguments by input name and
+//! returning results by output name.
+//!
+//! Both look names up the same way: among the program's exports and its function names. A name
+//! that refers to more than one function is rejected as ambiguous rather than picking one.
+//!
+//! Argument and return lists are tuples of up to six elements, with `()` for none. Each element
+//! is one value, so `(i64, f64)` is two arguments and `((i64, f64),)` is one tuple aggregate.
+//!
+//! ```
+//! use execution_tape::asm::{Asm, FunctionSig, ProgramBuilder};
+//! use execution_tape::host::{Host, HostContext, HostError, SigHash, ValueRef};
+//! use execution_tape::program::ValueType;
+//! use execution_tape::value::Value;
+//! use execution_tape::vm::{Limits, Vm};
+//!
+//! struct NoHost;
+//! impl Host for NoHost {
+//!     fn call(
+//!         &mut self,
+//!         _symbol: &str,
+//!         _sig_hash: SigHash,
+//!         _args: &[ValueRef<'_>],
+//!         _rets: &mut [Value],
+//!         _ctx: HostContext<'_, '_>,
+//!     ) -> Result<u64, HostError> {
+//!         Err(HostError::UnknownSymbol)
+//!     }
+//! }
+//!
+//! let mut a = Asm::new();
+//! a.i64_add(3, 1, 2);
+//! a.ret(0, &[3]);
+//! let mut pb = ProgramBuilder::new();
+//! let add = pb.push_function_checked(
+//!     a,
+//!     FunctionSig {
+//!         arg_types: vec![ValueType::I64, ValueType::I64],
+//!         ret_types: vec![ValueType::I64],
+//!     },
+//! )?;
+//! pb.export_function(add, "add")?;
+//! let program = pb.build_verified()?;
+//!
+//! let add = program.typed_entry::<(i64, i64), (i64,)>("add").unwrap();
+//! let mut vm = Vm::new(NoHost, Limits::default());
+//! assert_eq!(add.call(&mut vm, (5, 3)).unwrap(), (8,));
+//!
+//! // Signatures are checked up front.
+//! assert!(program.typed_entry::<(i64,), (i64,)>("add").is_err());
+//! # Ok::<(), execution_tape::asm::BuildError>(())
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::aggregates::AggHeap;
+use crate::convert::{ConvertError, FromValue, IntoValue};
+use crate::format::DecodeError;
+use crate::host::Host;
+use crate::program::{Program, TypeTable, ValueType};
+use crate::trace::TraceMask;
+use crate::value::{FuncId, Value};
+use crate::verifier::VerifiedProgram;
+use crate::vm::{ExecutionContext, TrapInfo, Vm};
+
+/// A typed entry signature does not match the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SigMismatch {
+    /// The function id is out of range.
+    UnknownFunction(FuncId),
+    /// No exported or named function has this name.
+    UnknownName(String),
+    /// Several functions are exported or named with this name.
+    AmbiguousName {
+        /// The function name.
+        name: String,
+        /// The functions with that name.
+        funcs: Vec<FuncId>,
+    },
+    /// The function's signature could not be decoded.
+    Decode(DecodeError),
+    /// The argument types differ.
+    ArgTypes {
+        /// Argument types of the function.
+        expected: Vec<ValueType>,
+        /// Argument types of the Rust tuple.
+        actual: Vec<ValueType>,
+    },
+    /// The return types differ.
+    RetTypes {
+        /// Return types of the function.
+        expected: Vec<ValueType>,
+        /// Return types of the Rust tuple.
+        actual: Vec<ValueType>,
+    },
+}
+
+impl fmt::Display for SigMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFunction(func) => write!(f, "unknown function {}", func.0),
+            Self::UnknownName(name) => write!(f, "no function named {name:?}"),
+            Self::AmbiguousName { name, funcs } => write_ambiguous(f, name, funcs),
+            Self::Decode(e) => write!(f, "malformed function signature: {e}"),
+            Self::ArgTypes { expected, actual } => write!(
+                f,
+                "argument type mismatch (function takes {expected:?}, got {actual:?})"
+            ),
+            Self::RetTypes { expected, actual } => write!(
+                f,
+                "return type mismatch (function returns {expected:?}, got {actual:?})"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SigMismatch {}
+
+impl From<DecodeError> for SigMismatch {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+/// An error from [`TypedEntry::call`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryError {
+    /// Converting the arguments or the results failed.
+    Convert(ConvertError),
+    /// The run trapped.
+    Trap(TrapInfo),
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Convert(e) => write!(f, "conversion failed: {e}"),
+            Self::Trap(info) => write!(f, "trap: {info}"),
+        }
+    }
+}
+
+impl core::error::Error for EntryError {}
+
+impl From<ConvertError> for EntryError {
+    fn from(e: ConvertError) -> Self {
+        Self::Convert(e)
+    }
+}
+
+impl From<TrapInfo> for EntryError {
+    fn from(info: TrapInfo) -> Self {
+        Self::Trap(info)
+    }
+}
+
+/// An error from [`Vm::run_named`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NamedRunError {
+    /// No function has this name.
+    UnknownFunction(String),
+    /// Several functions have this name.
+    AmbiguousFunction {
+        /// The function name.
+        name: String,
+        /// The functions with that name.
+        funcs: Vec<FuncId>,
+    },
+    /// An input of the function has no name, so it cannot be passed by name.
+    UnnamedInput {
+        /// Argument index.
+        arg: u32,
+    },
+    /// An output of the function has no name, so it cannot be returned by name.
+    UnnamedOutput {
+        /// Return index.
+        ret: u32,
+    },
+    /// No argument was passed for this input.
+    MissingArgument(String),
+    /// An argument was passed for an input the function does not have.
+    UnknownArgument(String),
+    /// Several arguments were passed for this input.
+    DuplicateArgument(String),
+    /// The run trapped.
+    Trap(TrapInfo),
+}
+
+impl fmt::Display for NamedRunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFunction(name) => write!(f, "no function named {name:?}"),
+            Self::AmbiguousFunction { name, funcs } => write_ambiguous(f, name, funcs),
+            Self::UnnamedInput { arg } => write!(f, "input {arg} has no name"),
+            Self::UnnamedOutput { ret } => write!(f, "output {ret} has no name"),
+            Self::MissingArgument(name) => write!(f, "missing argument {name:?}"),
+            Self::UnknownArgument(name) => write!(f, "unknown argument {name:?}"),
+            Self::DuplicateArgument(name) => write!(f, "argument {name:?} passed more than once"),
+            Self::Trap(info) => write!(f, "trap: {info}"),
+        }
+    }
+}
+
+impl core::error::Error for NamedRunError {}
+
+fn write_ambiguous(f: &mut fmt::Formatter<'_>, name: &str, funcs: &[FuncId]) -> fmt::Result {
+    write!(f, "function name {name:?} is ambiguous (functions")?;
+    for func in funcs {
+        write!(f, " {}", func.0)?;
+    }
+    write!(f, ")")
+}
+
+impl From<TrapInfo> for NamedRunError {
+    fn from(info: TrapInfo) -> Self {
+        Self::Trap(info)
+    }
+}
+
+/// Identifies an entry function: a [`FuncId`], or a name.
+///
+/// Names are looked up among the program's exports and function names, and must refer to exactly
+/// one function.
+pub trait EntryPoint {
+    /// Resolves the entry function in `program`.
+    fn resolve(self, program: &Program) -> Result<FuncId, SigMismatch>;
+}
+
+impl EntryPoint for FuncId {
+    fn resolve(self, program: &Program) -> Result<FuncId, SigMismatch> {
+        if program.functions.get(self.0 as usize).is_none() {
+            return Err(SigMismatch::UnknownFunction(self));
+        }
+        Ok(self)
+    }
+}
+
+impl EntryPoint for &str {
+    fn resolve(self, program: &Program) -> Result<FuncId, SigMismatch> {
+        let funcs = functions_named(program, self);
+        match funcs[..] {
+            [] => Err(SigMismatch::UnknownName(self.into())),
+            [func] => Ok(func),
+            _ => Err(SigMismatch::AmbiguousName {
+                name: self.into(),
+                funcs,
+            }),
+        }
+    }
+}
+
+/// Returns the functions exported or named as `name`, in ascending order and without duplicates.
+///
+/// This is the lookup [`VerifiedProgram::typed_entry`] and [`Vm::run_named`] use; more than one
+/// result means the name is ambiguous.
+pub fn functions_named(program: &Program, name: &str) -> Vec<FuncId> {
+    let sym = |id| program.symbol_str(id).ok();
+    let exports = program.exports.iter().map(|e| (e.func, e.name));
+    let names = program.function_names.iter().map(|e| (e.func, e.name));
+    let mut funcs: Vec<FuncId> = exports
+        .chain(names)
+        .filter(|&(_, n)| sym(n) == Some(name))
+        .map(|(func, _)| FuncId(func))
+        .collect();
+    funcs.sort_unstable_by_key(|f| f.0);
+    funcs.dedup();
+    funcs
+}
+
+/// The arguments of a typed entry: a tuple of [`IntoValue`] types.
+pub trait EntryArgs {
+    /// The argument values, as a fixed-size array.
+    type Values: AsRef<[Value]>;
+
+    /// The argument types, in order.
+    const TYPES: &'static [ValueType];
+
+    /// Converts the arguments, allocating aggregates in `aggregates`.
+    fn into_values(
+        self,
+        types: &TypeTable,
+        aggregates: &mut AggHeap,
+    ) -> Result<Self::Values, ConvertError>;
+}
+
+/// The results of a typed entry: a tuple of [`FromValue`] types.
+pub trait EntryRets: Sized {
+    /// The return values, as a fixed-size array.
+    type Values: AsMut<[Value]>;
+
+    /// The return types, in order.
+    const TYPES: &'static [ValueType];
+
+    /// Returns a buffer for the return values, filled with `Unit`.
+    fn buffer() -> Self::Values;
+
+    /// Reads the results, following aggregates in `aggregates`.
+    fn from_values(
+        values: &Self::Values,
+        types: &TypeTable,
+        aggregates: &AggHeap,
+    ) -> Result<Self, ConvertError>;
+}
+
+impl EntryArgs for () {
+    type Values = [Value; 0];
+    const TYPES: &'static [ValueType] = &[];
+
+    fn into_values(self, _: &TypeTable, _: &mut AggHeap) -> Result<Self::Values, ConvertError> {
+        Ok([])
+    }
+}
+
+impl EntryRets for () {
+    type Values = [Value; 0];
+    const TYPES: &'static [ValueType] = &[];
+
+    fn buffer() -> Self::Values {
+        []
+    }
+
+    fn from_values(_: &Self::Values, _: &TypeTable, _: &AggHeap) -> Result<Self, ConvertError> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_entry_tuple {
+    ($n:literal; $($i:tt $t:ident),*) => {
+        impl<$($t: IntoValue),*> EntryArgs for ($($t,)*) {
+            type Values = [Value; $n];
+            const TYPES: &'static [ValueType] = &[$($t::VALUE_TYPE),*];
+
+            fn into_values(
+                self,
+                types: &TypeTable,
+                aggregates: &mut AggHeap,
+            ) -> Result<Self::Values, ConvertError> {
+                Ok([$(self.$i.into_value(types, aggregates)?),*])
+            }
+        }
+
+        impl<$($t: FromValue),*> EntryRets for ($($t,)*) {
+            type Values = [Value; $n];
+            const TYPES: &'static [ValueType] = &[$($t::VALUE_TYPE),*];
+
+            fn buffer() -> Self::Values {
+                [const { Value::Unit }; $n]
+            }
+
+            fn from_values(
+                values: &Self::Values,
+                types: &TypeTable,
+                aggregates: &AggHeap,
+            ) -> Result<Self, ConvertError> {
+                Ok(($($t::from_value(&values[$i], types, aggregates)?,)*))
+            }
+        }
+    };
+}
+
+impl_entry_tuple!(1; 0 A0);
+impl_entry_tuple!(2; 0 A0, 1 A1);
+impl_entry_tuple!(3; 0 A0, 1 A1, 2 A2);
+impl_entry_tuple!(4; 0 A0, 1 A1, 2 A2, 3 A3);
+impl_entry_tuple!(5; 0 A0, 1 A1, 2 A2, 3 A3, 4 A4);
+impl_entry_tuple!(6; 0 A0, 1 A1, 2 A2, 3 A3, 4 A4, 5 A5);
+
+/// An entry function whose signature was checked against the Rust types `A` and `R`.
+///
+/// Created by [`VerifiedProgram::typed_entry`].
+pub struct TypedEntry<'p, A, R> {
+    program: &'p VerifiedProgram,
+    func: FuncId,
+    _sig: PhantomData<fn(A) -> R>,
+}
+
+impl<A, R> Clone for TypedEntry<'_, A, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, R> Copy for TypedEntry<'_, A, R> {}
+
+impl<A, R> fmt::Debug for TypedEntry<'_, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedEntry")
+            .field("func", &self.func)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: EntryArgs, R: EntryRets> TypedEntry<'_, A, R> {
+    /// Returns the entry function id.
+    #[must_use]
+    pub fn func(&self) -> FuncId {
+        self.func
+    }
+
+    /// Runs the entry function on `vm` with `args`, without tracing.
+    ///
+    /// Aggregate arguments are allocated in, and aggregate results read from, the VM's heap.
+    pub fn call<H: Host>(&self, vm: &mut Vm<H>, args: A) -> Result<R, EntryError> {
+        let mut ctx = ExecutionContext::new();
+        self.call_with_ctx(vm, &mut ctx, args)
+    }
+
+    /// Like [`TypedEntry::call`], reusing the allocations of `ctx` across runs.
+    pub fn call_with_ctx<H: Host>(
+        &self,
+        vm: &mut Vm<H>,
+        ctx: &mut ExecutionContext,
+        args: A,
+    ) -> Result<R, EntryError> {
+        let types = &self.program.program().types;
+        let args = args.into_values(types, vm.aggregates_mut())?;
+        let mut rets = R::buffer();
+        vm.run_into(
+            ctx,
+            self.program,
+            self.func,
+            args.as_ref(),
+            rets.as_mut(),
+            TraceMask::NONE,
+            None,
+            None,
+        )?;
+        Ok(R::from_values(&rets, types, vm.aggregates())?)
+    }
+}
+
+impl VerifiedProgram {
+    /// Returns a typed handle to the entry function `entry`, after checking that its argument and
+    /// return types are those of `A` and `R`.
+    ///
+    /// See the [`entry`](crate::entry) module for the supported types.
+    pub fn typed_entry<A: EntryArgs, R: EntryRets>(
+        &self,
+        entry: impl EntryPoint,
+    ) -> Result<TypedEntry<'_, A, R>, SigMismatch> {
+        let program = self.program();
+        let func = entry.resolve(program)?;
+        let f = program
+            .functions
+            .get(func.0 as usize)
+            .ok_or(SigMismatch::UnknownFunction(func))?;
+        let arg_types = program.function_arg_types(f)?;
+        if arg_types != A::TYPES {
+            return Err(SigMismatch::ArgTypes {
+                expected: arg_types.to_vec(),
+                actual: A::TYPES.to_vec(),
+            });
+        }
+        let ret_types = program.function_ret_types(f)?;
+        if ret_types != R::TYPES {
+            return Err(SigMismatch::RetTypes {
+                expected: ret_types.to_vec(),
+                actual: R::TYPES.to_vec(),
+            });
+        }
+        Ok(TypedEntry {
+            program: self,
+            func,
+            _sig: PhantomData,
+        })
+    }
+}
+
+impl<H: Host> Vm<H> {
+    /// Runs the function exported or named as `name`, without tracing.
+    ///
+    /// The name is resolved as for [`VerifiedProgram::typed_entry`].
+    /// Every input and output of the function must be named. `args` pairs input names with
+    /// values, in any order, and the results are keyed by output name.
+    pub fn run_named<'p>(
+        &mut self,
+        program: &'p VerifiedProgram,
+        name: &str,
+        args: &[(&str, Value)],
+    ) -> Result<BTreeMap<&'p str, Value>, NamedRunError> {
+        let p = program.program();
+        let funcs = functions_named(p, name);
+        let func = match funcs[..] {
+            [] => return Err(NamedRunError::UnknownFunction(name.into())),
+            [func] => func,
+            _ => {
+                return Err(NamedRunError::AmbiguousFunction {
+                    name: name.into(),
+                    funcs,
+                });
+            }
+        };
+        let f = p
+            .functions
+            .get(func.0 as usize)
+            .ok_or_else(|| NamedRunError::UnknownFunction(name.into()))?;
+
+        let output_names = (0..f.ret_count)
+            .map(|ret| {
+                p.function_output_name(func.0, ret)
+                    .ok_or(NamedRunError::UnnamedOutput { ret })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut positional = Vec::with_capacity(f.arg_count as usize);
+        for arg in 0..f.arg_count {
+            let input = p
+                .function_input_name(func.0, arg)
+                .ok_or(NamedRunError::UnnamedInput { arg })?;
+            let mut matching = args.iter().filter(|(n, _)| *n == input);
+            let (_, value) = matching
+                .next()
+                .ok_or_else(|| NamedRunError::MissingArgument(input.into()))?;
+            if matching.next().is_some() {
+                return Err(NamedRunError::DuplicateArgument(input.into()));
+            }
+            positional.push(value.clone());
+        }
+        if let Some((unknown, _)) = args.iter().find(|(n, _)| {
+            !(0..f.arg_count).any(|arg| p.function_input_name(func.0, arg) == Some(*n))
+        }) {
+            return Err(NamedRunError::UnknownArgument((*unknown).into()));
+        }
+
+        let rets = self.run(program, func, &positional, TraceMask::NONE, None)?;
+        Ok(output_names.into_iter().zip(rets).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::{HostContext, HostError, SigHash, ValueRef};
+    use crate::value::Decimal;
+    use crate::vm::{Limits, Trap};
+    use alloc::vec;
+
+    struct NoHost;
+
+    impl Host for NoHost {
+        fn call(
+            &mut self,
+            _symbol: &str,
+            _sig_hash: SigHash,
+            _args: &[ValueRef<'_>],
+            _rets: &mut [Value],
+            _ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            Err(HostError::UnknownSymbol)
+        }
+    }
+
+    #[test]
+    fn typed_entry_checks_signature_once_and_converts() {
+        // `fn scale(x: i64, d: Decimal) -> (d, (x, x))`
+        let mut a = Asm::new();
+        a.tuple_new(3, &[1, 1]);
+        a.ret(0, &[2, 3]);
+        let mut pb = ProgramBuilder::new();
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::I64, ValueType::Decimal],
+                    ret_types: vec![ValueType::Decimal, ValueType::Agg],
+                },
+            )
+            .unwrap();
+        pb.set_function_name(f, "scale").unwrap();
+        let program = pb.build_verified().unwrap();
+
+        let entry = program
+            .typed_entry::<(i64, Decimal), (Decimal, (i64, i64))>("scale")
+            .unwrap();
+        assert_eq!(entry.func(), f);
+        let d = Decimal {
+            mantissa: 125,
+            scale: 2,
+        };
+        let mut vm = Vm::new(NoHost, Limits::default());
+        assert_eq!(entry.call(&mut vm, (4, d)), Ok((d, (4, 4))));
+
+        assert_eq!(
+            program
+                .typed_entry::<(i64, f64), (Decimal, (i64, i64))>(f)
+                .err(),
+            Some(SigMismatch::ArgTypes {
+                expected: vec![ValueType::I64, ValueType::Decimal],
+                actual: vec![ValueType::I64, ValueType::F64],
+            })
+        );
+        assert_eq!(
+            program.typed_entry::<(i64, Decimal), (Decimal,)>(f).err(),
+            Some(SigMismatch::RetTypes {
+                expected: vec![ValueType::Decimal, ValueType::Agg],
+                actual: vec![ValueType::Decimal],
+            })
+        );
+        assert_eq!(
+            program.typed_entry::<(), ()>("missing").err(),
+            Some(SigMismatch::UnknownName("missing".into()))
+        );
+        assert_eq!(
+            program.typed_entry::<(), ()>(FuncId(9)).err(),
+            Some(SigMismatch::UnknownFunction(FuncId(9)))
+        );
+    }
+
+    #[test]
+    fn call_reports_traps_and_conversion_errors() {
+        // `fn(x: u64) -> x / 0` traps; `fn() -> (1, 2)` returns a tuple.
+        let mut div = Asm::new();
+        div.const_u64(2, 0);
+        div.u64_div(3, 1, 2);
+        div.ret(0, &[3]);
+        let mut pair = Asm::new();
+        pair.const_i64(1, 1);
+        pair.tuple_new(2, &[1, 1]);
+        pair.ret(0, &[2]);
+        let mut pb = ProgramBuilder::new();
+        let div = pb
+            .push_function_checked(
+                div,
+                FunctionSig {
+                    arg_types: vec![ValueType::U64],
+                    ret_types: vec![ValueType::U64],
+                },
+            )
+            .unwrap();
+        let pair = pb
+            .push_function_checked(
+                pair,
+                FunctionSig {
+                    arg_types: vec![],
+                    ret_types: vec![ValueType::Agg],
+                },
+            )
+            .unwrap();
+        let program = pb.build_verified().unwrap();
+        let mut vm = Vm::new(NoHost, Limits::default());
+
+        let div = program.typed_entry::<(u64,), (u64,)>(div).unwrap();
+        let Err(EntryError::Trap(info)) = div.call(&mut vm, (7,)) else {
+            panic!("expected a trap");
+        };
+        assert_eq!(info.trap, Trap::DivByZero);
+
+        // The signature only says `Agg`; the tuple's arity is checked when reading it back.
+        let pair = program.typed_entry::<(), ((i64, i64, i64),)>(pair).unwrap();
+        assert!(matches!(
+            pair.call(&mut vm, ()),
+            Err(EntryError::Convert(ConvertError::Agg(_)))
+        ));
+    }
+
+    #[test]
+    fn run_named_maps_arguments_and_results_by_name() {
+        // `fn compute_total(qty: i64, price: i64) -> (total, qty)`
+        let mut a = Asm::new();
+        a.i64_mul(3, 1, 2);
+        a.ret(0, &[3, 1]);
+        let mut pb = ProgramBuilder::new();
+        let sig = FunctionSig {
+            arg_types: vec![ValueType::I64, ValueType::I64],
+            ret_types: vec![ValueType::I64, ValueType::I64],
+        };
+        let f = pb.push_function_checked(a.clone(), sig.clone()).unwrap();
+        pb.set_function_name(f, "compute_total").unwrap();
+        pb.set_function_input_name(f, 0, "qty").unwrap();
+        pb.set_function_input_name(f, 1, "price").unwrap();
+        pb.set_function_output_name(f, 0, "total").unwrap();
+        pb.set_function_output_name(f, 1, "qty").unwrap();
+        let g = pb.push_function_checked(a.clone(), sig.clone()).unwrap();
+        pb.set_function_name(g, "twin").unwrap();
+        let h = pb.push_function_checked(a, sig).unwrap();
+        pb.set_function_name(h, "twin").unwrap();
+        let program = pb.build_verified().unwrap();
+        let mut vm = Vm::new(NoHost, Limits::default());
+
+        let out = vm
+            .run_named(
+                &program,
+                "compute_total",
+                &[("price", Value::I64(7)), ("qty", Value::I64(3))],
+            )
+            .unwrap();
+        assert_eq!(
+            out.into_iter().collect::<Vec<_>>(),
+            vec![("qty", Value::I64(3)), ("total", Value::I64(21))]
+        );
+
+        let run = |vm: &mut Vm<NoHost>, name, args: &[(&str, Value)]| {
+            vm.run_named(&program, name, args).unwrap_err()
+        };
+        let qty = ("qty", Value::I64(1));
+        let price = ("price", Value::I64(2));
+        assert_eq!(
+            run(&mut vm, "compute_total", core::slice::from_ref(&qty)),
+            NamedRunError::MissingArgument("price".into())
+        );
+        assert_eq!(
+            run(
+                &mut vm,
+                "compute_total",
+                &[qty.clone(), price.clone(), ("tax", Value::I64(0))]
+            ),
+            NamedRunError::UnknownArgument("tax".into())
+        );
+        assert_eq!(
+            run(&mut vm, "compute_total", &[qty.clone(), price, qty]),
+            NamedRunError::DuplicateArgument("qty".into())
+        );
+        assert_eq!(
+            run(&mut vm, "nope", &[]),
+            NamedRunError::UnknownFunction("nope".into())
+        );
+        assert_eq!(
+            run(&mut vm, "twin", &[]),
+            NamedRunError::AmbiguousFunction {
+                name: "twin".into(),
+                funcs: vec![g, h],
+            }
+        );
+    }
+
+    #[test]
+    fn names_resolve_the_same_for_typed_entry_and_run_named() {
+        let sig = FunctionSig {
+            arg_types: vec![],
+            ret_types: vec![],
+        };
+        let ret = || {
+            let mut a = Asm::new();
+            a.ret(0, &[]);
+            a
+        };
+        // `solo` is both exported and named; `main` is exported by one function and names another.
+        let mut pb = ProgramBuilder::new();
+        let solo = pb.push_function_checked(ret(), sig.clone()).unwrap();
+        pb.set_function_name(solo, "solo").unwrap();
+        pb.export_function(solo, "solo").unwrap();
+        let f = pb.push_function_checked(ret(), sig.clone()).unwrap();
+        pb.export_function(f, "main").unwrap();
+        let g = pb.push_function_checked(ret(), sig).unwrap();
+        pb.set_function_name(g, "main").unwrap();
+        let program = pb.build_verified().unwrap();
+        let mut vm = Vm::new(NoHost, Limits::default());
+
+        assert_eq!(program.typed_entry::<(), ()>("solo").unwrap().func(), solo);
+        assert_eq!(vm.run_named(&program, "solo", &[]), Ok(BTreeMap::new()));
+
+        assert_eq!(
+            program.typed_entry::<(), ()>("main").err(),
+            Some(SigMismatch::AmbiguousName {
+                name: "main".into(),
+                funcs: vec![f, g],
+            })
+        );
+        assert_eq!(
+            vm.run_named(&program, "main", &[]),
+            Err(NamedRunError::AmbiguousFunction {
+                name: "main".into(),
+                funcs: vec![f, g],
+            })
+        );
+    }
+}
@@ -10,7 +10,7 @@ use alloc::vec::Vec;
 use core::fmt;
 
 use crate::aggregates::{AggError, AggHeap};
-use crate::program::{Program, ValueType};
+use crate::program::{HostSigId, Program, ValueType};
 use crate::value::AggHandle;
 use crate::value::AggType;
 use crate::value::Closure;
@@ -257,6 +257,17 @@ impl<'vm, 'access> HostContext<'vm, 'access> {
         }
     }
 
+    /// Splits the context so a wrapping host can forward it with a different access sink.
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        &'vm Program,
+        &'vm AggHeap,
+        Option<&'access mut dyn AccessSink>,
+    ) {
+        (self.program, self.aggregates, self.access)
+    }
+
     /// Returns the verified program being executed.
     #[must_use]
     pub fn program(&self) -> &Program {
@@ -434,6 +445,20 @@ pub fn sig_hash(sig: &HostSig) -> SigHash {
 /// Computes a stable signature hash from argument/return type slices.
 #[must_use]
 pub fn sig_hash_slices(args: &[ValueType], rets: &[ValueType]) -> SigHash {
+    hash_sig(args, rets, false)
+}
+
+/// Computes a stable signature hash for a pure host signature (one called without an effect
+/// token) from argument/return type slices.
+///
+/// The effect token is part of a signature's identity, so a pure signature never hashes equal to
+/// the effectful signature with the same types.
+#[must_use]
+pub fn pure_sig_hash_slices(args: &[ValueType], rets: &[ValueType]) -> SigHash {
+    hash_sig(args, rets, true)
+}
+
+fn hash_sig(args: &[ValueType], rets: &[ValueType], pure: bool) -> SigHash {
     const PREFIX: &[u8] = b"execution_tape:v1\0";
     let mut h = Fnv1a64::new();
     h.update(PREFIX);
@@ -446,6 +471,10 @@ pub fn sig_hash_slices(args: &[ValueType], rets: &[ValueType]) -> SigHash {
     for t in rets {
         hash_value_type(&mut h, *t);
     }
+    // Effectful signatures (the v1 default) hash nothing extra so their hashes stay stable.
+    if pure {
+        h.update(b"pure");
+    }
 
     SigHash(h.finish())
 }
@@ -459,6 +488,9 @@ pub enum HostError {
     SignatureMismatch,
     /// The host failed during execution.
     Failed,
+    /// The call was refused by a [`HostPolicy`](crate::host_policy::HostPolicy): the symbol is
+    /// not allowed, or its call quota is exhausted.
+    Denied,
 }
 
 impl fmt::Display for HostError {
@@ -467,6 +499,7 @@ impl fmt::Display for HostError {
             Self::UnknownSymbol => write!(f, "unknown host symbol"),
             Self::SignatureMismatch => write!(f, "host signature mismatch"),
             Self::Failed => write!(f, "host call failed"),
+            Self::Denied => write!(f, "host call denied by policy"),
         }
     }
 }
@@ -596,27 +629,48 @@ pub trait Host {
         rets: &mut [Value],
         ctx: HostContext<'_, '_>,
     ) -> Result<u64, HostError>;
+
+    /// Performs a host call identified by its [`HostSigId`] in the executing program.
+    ///
+    /// The VM always dispatches through this method. The default forwards to [`Host::call`];
+    /// hosts that pre-resolve signatures (see
+    /// [`LinkedHost`](crate::host_linker::LinkedHost)) override it to index a table instead of
+    /// matching on `symbol`. `host_sig` indexes the host signature table of
+    /// [`HostContext::program`], so it is only meaningful together with that program.
+    #[inline]
+    fn call_with_id(
+        &mut self,
+        host_sig: HostSigId,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        let _ = host_sig;
+        self.call(symbol, sig_hash, args, rets, ctx)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
-struct Fnv1a64(u64);
+pub(crate) struct Fnv1a64(u64);
 
 impl Fnv1a64 {
     const OFFSET: u64 = 0xcbf29ce484222325;
     const PRIME: u64 = 0x100000001b3;
 
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self(Self::OFFSET)
     }
 
-    fn update(&mut self, bytes: &[u8]) {
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
         for &b in bytes {
             self.0 ^= u64::from(b);
             self.0 = self.0.wrapping_mul(Self::PRIME);
         }
     }
 
-    fn finish(self) -> u64 {
+    pub(crate) fn finish(self) -> u64 {
         self.0
     }
 }
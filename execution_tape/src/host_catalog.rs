@@ -13,7 +13,7 @@ use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 
 use crate::asm::ProgramBuilder;
-use crate::host::{HostSig, SigHash, sig_hash_slices};
+use crate::host::{HostSig, SigHash, pure_sig_hash_slices, sig_hash_slices};
 use crate::program::{HostSigId, ValueType};
 
 /// A host-call signature specification.
@@ -25,9 +25,11 @@ pub struct HostSpec {
     pub args: Box<[ValueType]>,
     /// Return types for the host call.
     pub rets: Box<[ValueType]>,
+    /// Whether the host call is pure (called via `host_call.pure`, without an effect token).
+    pub pure: bool,
 }
 
-type SigSpec = (Box<[ValueType]>, Box<[ValueType]>);
+type SigSpec = (Box<[ValueType]>, Box<[ValueType]>, bool);
 type SigSpecMap = BTreeMap<SigHash, SigSpec>;
 type SymbolSpecMap = BTreeMap<Box<str>, SigSpecMap>;
 type SigIdMap = BTreeMap<SigHash, HostSigId>;
@@ -40,6 +42,24 @@ impl HostSpec {
             symbol: symbol.into(),
             args: Box::from(args),
             rets: Box::from(rets),
+            pure: false,
+        }
+    }
+
+    /// Construct a pure host spec from symbol + arg/ret types.
+    pub fn new_pure(symbol: impl Into<Box<str>>, args: &[ValueType], rets: &[ValueType]) -> Self {
+        Self {
+            pure: true,
+            ..Self::new(symbol, args, rets)
+        }
+    }
+
+    /// Returns the signature hash, which accounts for purity.
+    pub fn sig_hash(&self) -> SigHash {
+        if self.pure {
+            pure_sig_hash_slices(&self.args, &self.rets)
+        } else {
+            sig_hash_slices(&self.args, &self.rets)
         }
     }
 }
@@ -113,9 +133,15 @@ impl HostCatalog {
 
         // Pass 1: build a deterministic map keyed by (symbol, sig_hash),
         // validating duplicates and hash collisions as we go.
-        for HostSpec { symbol, args, rets } in self.specs {
-            let sig_hash = sig_hash_slices(args.as_ref(), rets.as_ref());
-            if let Some((existing_args, existing_rets)) = specs_by_symbol
+        for spec in self.specs {
+            let sig_hash = spec.sig_hash();
+            let HostSpec {
+                symbol,
+                args,
+                rets,
+                pure,
+            } = spec;
+            if let Some((existing_args, existing_rets, _)) = specs_by_symbol
                 .get(symbol.as_ref())
                 .and_then(|sigs| sigs.get(&sig_hash))
             {
@@ -130,21 +156,23 @@ impl HostCatalog {
             specs_by_symbol
                 .entry(symbol)
                 .or_default()
-                .insert(sig_hash, (args, rets));
+                .insert(sig_hash, (args, rets, pure));
         }
 
         let mut by_symbol: SymbolIdMap = BTreeMap::new();
         // Pass 2: register signatures in deterministic order from the map.
         for (symbol, sigs) in specs_by_symbol {
             let mut ids: SigIdMap = BTreeMap::new();
-            for (sig_hash, (args, rets)) in sigs {
-                let sig_id = pb.host_sig_for(
-                    &symbol,
-                    HostSig {
-                        args: Vec::from(args),
-                        rets: Vec::from(rets),
-                    },
-                );
+            for (sig_hash, (args, rets, pure)) in sigs {
+                let sig = HostSig {
+                    args: Vec::from(args),
+                    rets: Vec::from(rets),
+                };
+                let sig_id = if pure {
+                    pb.pure_host_sig_for(&symbol, sig)
+                } else {
+                    pb.host_sig_for(&symbol, sig)
+                };
                 ids.insert(sig_hash, sig_id);
             }
             by_symbol.insert(symbol, ids);
@@ -179,6 +207,17 @@ impl HostSigRegistry {
         let sig_hash = sig_hash_slices(args, rets);
         self.sig_id(symbol, sig_hash)
     }
+
+    /// Look up a pure host signature id by `(symbol, args, rets)`.
+    pub fn pure_sig_id_for(
+        &self,
+        symbol: &str,
+        args: &[ValueType],
+        rets: &[ValueType],
+    ) -> Option<HostSigId> {
+        let sig_hash = pure_sig_hash_slices(args, rets);
+        self.sig_id(symbol, sig_hash)
+    }
 }
 
 /// Errors when registering a host catalog.
@@ -307,4 +346,29 @@ mod tests {
             reg_b.sig_id_for("foo", &[ValueType::I64], &[ValueType::F64])
         );
     }
+
+    #[test]
+    fn register_all_keeps_pure_and_effectful_signatures_apart() {
+        let mut cat = HostCatalog::new();
+        cat.push(HostSpec::new("foo", &[ValueType::F64], &[ValueType::F64]));
+        cat.push(HostSpec::new_pure(
+            "foo",
+            &[ValueType::F64],
+            &[ValueType::F64],
+        ));
+
+        let mut pb = ProgramBuilder::new();
+        let reg = cat.register_all(&mut pb).expect("register");
+        let effectful = reg
+            .sig_id_for("foo", &[ValueType::F64], &[ValueType::F64])
+            .unwrap();
+        let pure = reg
+            .pure_sig_id_for("foo", &[ValueType::F64], &[ValueType::F64])
+            .unwrap();
+        assert_ne!(effectful, pure);
+
+        let p = pb.build_checked().unwrap();
+        assert!(!p.host_sig(effectful).unwrap().pure);
+        assert!(p.host_sig(pure).unwrap().pure);
+    }
 }
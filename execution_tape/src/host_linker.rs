@@ -0,0 +1,816 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Link-time resolution of host calls to embedder-provided handlers.
+//!
+//! A [`Host`] implementation normally matches on the symbol string of every call and reports
+//! unknown symbols as a runtime [`HostError::UnknownSymbol`]. [`HostLinker`] instead collects typed
+//! handlers up front; [`LinkedHost::link`] then resolves every [`HostSigEntry`] of a
+//! [`VerifiedProgram`] to a handler once, reporting all unresolved symbols as a [`LinkError`]
+//! before the program runs. Calls from a linked program are dispatched by [`HostSigId`] index.
+//!
+//! [`HostSigEntry`]: crate::program::HostSigEntry
+//!
+//! # Example
+//! ```
+//! extern crate alloc;
+//!
+//! use alloc::sync::Arc;
+//! use alloc::vec;
+//!
+//! use execution_tape::asm::{Asm, FunctionSig, ProgramBuilder};
+//! use execution_tape::host::{HostError, ValueRef};
+//! use execution_tape::host_linker::HostLinker;
+//! use execution_tape::program::ValueType;
+//! use execution_tape::trace::TraceMask;
+//! use execution_tape::value::{FuncId, Value};
+//! use execution_tape::vm::{Limits, Vm};
+//!
+//! let mut linker = HostLinker::<i64>::new();
+//! linker
+//!     .define("add_bias", &[ValueType::I64], &[ValueType::I64], |bias, args, rets, _ctx| {
+//!         let [ValueRef::I64(x)] = args else {
+//!             return Err(HostError::Failed);
+//!         };
+//!         rets[0] = Value::I64(x + *bias);
+//!         Ok(0)
+//!     })
+//!     .unwrap();
+//!
+//! let mut pb = ProgramBuilder::new();
+//! let reg = linker.catalog().register_all(&mut pb).unwrap();
+//! let add_bias = reg
+//!     .sig_id_for("add_bias", &[ValueType::I64], &[ValueType::I64])
+//!     .unwrap();
+//! let mut a = Asm::new();
+//! a.host_call(0, add_bias, 0, &[1], &[2]);
+//! a.ret(0, &[2]);
+//! pb.push_function_checked(
+//!     a,
+//!     FunctionSig {
+//!         arg_types: vec![ValueType::I64],
+//!         ret_types: vec![ValueType::I64],
+//!     },
+//! )
+//! .unwrap();
+//! let program = Arc::new(pb.build_verified().unwrap());
+//!
+//! let mut host = linker.instantiate(10);
+//! host.link(&program).unwrap();
+//! let mut vm = Vm::new(host, Limits::default());
+//! let out = vm.run(&program, FuncId(0), &[Value::I64(5)], TraceMask::NONE, None);
+//! assert_eq!(out.unwrap(), vec![Value::I64(15)]);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::host::{Host, HostContext, HostError, SigHash, ValueRef};
+use crate::host_catalog::{HostCatalog, HostCatalogError, HostSpec};
+use crate::host_policy::{HostPolicy, HostPolicyError};
+use crate::host_typed::{HostFunction, WithState};
+use crate::program::{HostSigId, ValueType};
+use crate::value::Value;
+use crate::verifier::VerifiedProgram;
+
+/// A host-call handler operating on embedder state `S`.
+///
+/// Returns additional fuel to charge, like [`Host::call`]. The VM has already checked that the
+/// call's signature hash matches the signature the handler was defined with.
+pub type HostFn<S> = Box<
+    dyn FnMut(&mut S, &[ValueRef<'_>], &mut [Value], HostContext<'_, '_>) -> Result<u64, HostError>,
+>;
+
+struct HostDef<S> {
+    spec: HostSpec,
+    handler: HostFn<S>,
+}
+
+type SigIndexMap = BTreeMap<SigHash, usize>;
+type SymbolIndexMap = BTreeMap<Box<str>, SigIndexMap>;
+
+/// A set of host handlers keyed by `(symbol, signature)`.
+///
+/// Build the set with [`HostLinker::define`], then turn it into a [`Host`] with
+/// [`HostLinker::instantiate`].
+pub struct HostLinker<S> {
+    defs: Vec<HostDef<S>>,
+    by_symbol: SymbolIndexMap,
+}
+
+impl<S> fmt::Debug for HostLinker<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostLinker")
+            .field("specs", &DebugSpecs(&self.defs))
+            .finish_non_exhaustive()
+    }
+}
+
+struct DebugSpecs<'a, S>(&'a [HostDef<S>]);
+
+impl<S> fmt::Debug for DebugSpecs<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|d| &d.spec))
+            .finish()
+    }
+}
+
+impl<S> Default for HostLinker<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> HostLinker<S> {
+    /// Creates an empty linker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            defs: Vec::new(),
+            by_symbol: BTreeMap::new(),
+        }
+    }
+
+    /// Defines the handler for `symbol` with the given argument/return types.
+    ///
+    /// A symbol may be defined once per signature; overloads with different signatures are
+    /// allowed. Duplicate definitions and signature hash collisions are rejected.
+    pub fn define<F>(
+        &mut self,
+        symbol: impl Into<Box<str>>,
+        args: &[ValueType],
+        rets: &[ValueType],
+        handler: F,
+    ) -> Result<&mut Self, HostCatalogError>
+    where
+        F: FnMut(
+                &mut S,
+                &[ValueRef<'_>],
+                &mut [Value],
+                HostContext<'_, '_>,
+            ) -> Result<u64, HostError>
+            + 'static,
+    {
+        self.define_spec(HostSpec::new(symbol, args, rets), Box::new(handler))
+    }
+
+    /// Like [`HostLinker::define`], for a pure host function.
+    ///
+    /// Pure functions are called with `host_call.pure`. They are matched by their pure signature
+    /// hash, so a symbol may have both a pure and an effectful definition with the same types.
+    pub fn define_pure<F>(
+        &mut self,
+        symbol: impl Into<Box<str>>,
+        args: &[ValueType],
+        rets: &[ValueType],
+        handler: F,
+    ) -> Result<&mut Self, HostCatalogError>
+    where
+        F: FnMut(
+                &mut S,
+                &[ValueRef<'_>],
+                &mut [Value],
+                HostContext<'_, '_>,
+            ) -> Result<u64, HostError>
+            + 'static,
+    {
+        self.define_spec(HostSpec::new_pure(symbol, args, rets), Box::new(handler))
+    }
+
+    fn define_spec(
+        &mut self,
+        spec: HostSpec,
+        handler: HostFn<S>,
+    ) -> Result<&mut Self, HostCatalogError> {
+        let sig_hash = spec.sig_hash();
+        if let Some(&existing) = self
+            .by_symbol
+            .get(spec.symbol.as_ref())
+            .and_then(|sigs| sigs.get(&sig_hash))
+        {
+            let existing = &self.defs[existing].spec;
+            let symbol = spec.symbol;
+            if existing.args == spec.args && existing.rets == spec.rets {
+                return Err(HostCatalogError::DuplicateSignature { symbol, sig_hash });
+            }
+            return Err(HostCatalogError::HashCollision { symbol, sig_hash });
+        }
+
+        self.by_symbol
+            .entry(spec.symbol.clone())
+            .or_default()
+            .insert(sig_hash, self.defs.len());
+        self.defs.push(HostDef { spec, handler });
+        Ok(self)
+    }
+
+    /// Defines a typed handler for `symbol`, deriving its signature from the Rust types.
+    ///
+    /// Arguments and returns are converted as described in [`host_typed`](crate::host_typed).
+    /// Typed handlers charge no additional fuel.
+    ///
+    /// ```
+    /// use execution_tape::host_linker::HostLinker;
+    ///
+    /// let mut linker = HostLinker::<()>::new();
+    /// linker
+    ///     .register("math.hypot", |a: f64, b: f64| -> f64 { (a * a + b * b).sqrt() })
+    ///     .unwrap()
+    ///     .register("str.len", |s: &str| -> u64 { s.len() as u64 })
+    ///     .unwrap();
+    /// ```
+    pub fn register<M, F>(
+        &mut self,
+        symbol: impl Into<Box<str>>,
+        mut f: F,
+    ) -> Result<&mut Self, HostCatalogError>
+    where
+        S: 'static,
+        F: HostFunction<S, M>,
+    {
+        let args = F::arg_types();
+        let rets = F::ret_types();
+        self.define(symbol, &args, &rets, move |state, args, rets, _ctx| {
+            f.call(state, args, rets).map(|()| 0)
+        })
+    }
+
+    /// Like [`HostLinker::register`], for handlers that take the embedder state as `&mut S`
+    /// before their arguments.
+    pub fn register_with_state<M, F>(
+        &mut self,
+        symbol: impl Into<Box<str>>,
+        f: F,
+    ) -> Result<&mut Self, HostCatalogError>
+    where
+        S: 'static,
+        F: HostFunction<S, WithState<M>>,
+    {
+        self.register(symbol, f)
+    }
+
+    /// Like [`HostLinker::register`], for a pure host function called with `host_call.pure`.
+    pub fn register_pure<M, F>(
+        &mut self,
+        symbol: impl Into<Box<str>>,
+        mut f: F,
+    ) -> Result<&mut Self, HostCatalogError>
+    where
+        S: 'static,
+        F: HostFunction<S, M>,
+    {
+        let args = F::arg_types();
+        let rets = F::ret_types();
+        self.define_pure(symbol, &args, &rets, move |state, args, rets, _ctx| {
+            f.call(state, args, rets).map(|()| 0)
+        })
+    }
+
+    /// Returns a catalog of every defined signature, for registering into a
+    /// [`ProgramBuilder`](crate::asm::ProgramBuilder).
+    #[must_use]
+    pub fn catalog(&self) -> HostCatalog {
+        let mut cat = HostCatalog::new();
+        cat.extend(self.defs.iter().map(|d| d.spec.clone()));
+        cat
+    }
+
+    /// Turns the handler set into a [`Host`] operating on `state`.
+    #[must_use]
+    pub fn instantiate(self, state: S) -> LinkedHost<S> {
+        LinkedHost {
+            state,
+            defs: self.defs,
+            by_symbol: self.by_symbol,
+            linked: BTreeMap::new(),
+            policy: None,
+        }
+    }
+}
+
+struct LinkedProgram {
+    // Held so the address used as the map key stays unique while the table exists.
+    _program: Arc<VerifiedProgram>,
+    /// Handler index per `HostSigId`.
+    table: Box<[usize]>,
+}
+
+/// A [`Host`] that dispatches calls to handlers defined on a [`HostLinker`].
+///
+/// Programs passed to [`LinkedHost::link`] are dispatched by [`HostSigId`] through a table built
+/// at link time. Calls from programs that were never linked fall back to a lookup by
+/// `(symbol, sig_hash)`, and report [`HostError::UnknownSymbol`] if no handler matches.
+pub struct LinkedHost<S> {
+    state: S,
+    defs: Vec<HostDef<S>>,
+    by_symbol: SymbolIndexMap,
+    /// Linked programs keyed by the address of their [`Program`](crate::program::Program).
+    linked: BTreeMap<usize, LinkedProgram>,
+    policy: Option<Arc<HostPolicy>>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for LinkedHost<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkedHost")
+            .field("state", &self.state)
+            .field("specs", &DebugSpecs(&self.defs))
+            .field("linked_programs", &self.linked.len())
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> LinkedHost<S> {
+    /// Sets the policy that programs must satisfy to be linked.
+    ///
+    /// Only affects later calls to [`LinkedHost::link`]; already linked programs stay linked.
+    /// Call quotas are not enforced here; wrap the host in a
+    /// [`PolicyHost`](crate::host_policy::PolicyHost) for that.
+    pub fn set_policy(&mut self, policy: Option<Arc<HostPolicy>>) {
+        self.policy = policy;
+    }
+
+    /// Resolves every host signature of `program` to a handler.
+    ///
+    /// On success, calls made while running `program` dispatch by [`HostSigId`]. On failure, all
+    /// unresolved signatures (or policy violations) are reported and the program is left
+    /// unlinked. Linking the same program again is a no-op.
+    pub fn link(&mut self, program: &Arc<VerifiedProgram>) -> Result<(), LinkError> {
+        let key = program_key(program.program());
+        if self.linked.contains_key(&key) {
+            return Ok(());
+        }
+
+        let p = program.program();
+        if let Some(policy) = &self.policy {
+            policy.check(p).map_err(LinkError::Policy)?;
+        }
+        let mut table = Vec::with_capacity(p.host_sigs.len());
+        let mut unresolved = Vec::new();
+        for (i, hs) in p.host_sigs.iter().enumerate() {
+            let host_sig = HostSigId(u32::try_from(i).unwrap_or(u32::MAX));
+            let symbol = p
+                .symbol_str(hs.symbol)
+                .map_err(|_| LinkError::Malformed { host_sig })?;
+            match self.lookup(symbol, hs.sig_hash) {
+                Some(ix) => table.push(ix),
+                None => unresolved.push(UnresolvedHostSig {
+                    host_sig,
+                    symbol: symbol.into(),
+                    sig_hash: hs.sig_hash,
+                }),
+            }
+        }
+        if !unresolved.is_empty() {
+            return Err(LinkError::Unresolved(unresolved));
+        }
+
+        self.linked.insert(
+            key,
+            LinkedProgram {
+                _program: Arc::clone(program),
+                table: table.into_boxed_slice(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Drops the dispatch table for `program`, if it was linked.
+    pub fn unlink(&mut self, program: &VerifiedProgram) {
+        self.linked.remove(&program_key(program.program()));
+    }
+
+    /// Returns `true` if `program` has been linked.
+    #[must_use]
+    pub fn is_linked(&self, program: &VerifiedProgram) -> bool {
+        self.linked.contains_key(&program_key(program.program()))
+    }
+
+    /// Returns the embedder state.
+    #[must_use]
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns the embedder state mutably.
+    #[must_use]
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Consumes the host and returns the embedder state.
+    #[must_use]
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    fn lookup(&self, symbol: &str, sig_hash: SigHash) -> Option<usize> {
+        self.by_symbol
+            .get(symbol)
+            .and_then(|sigs| sigs.get(&sig_hash))
+            .copied()
+    }
+
+    fn dispatch(
+        &mut self,
+        ix: usize,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        (self.defs[ix].handler)(&mut self.state, args, rets, ctx)
+    }
+}
+
+fn program_key(program: &crate::program::Program) -> usize {
+    core::ptr::from_ref(program) as usize
+}
+
+impl<S> Host for LinkedHost<S> {
+    fn call(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        let ix = self
+            .lookup(symbol, sig_hash)
+            .ok_or(HostError::UnknownSymbol)?;
+        self.dispatch(ix, args, rets, ctx)
+    }
+
+    fn call_with_id(
+        &mut self,
+        host_sig: HostSigId,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        let linked = self
+            .linked
+            .get(&program_key(ctx.program()))
+            .and_then(|lp| lp.table.get(host_sig.0 as usize))
+            .copied();
+        match linked {
+            Some(ix) => self.dispatch(ix, args, rets, ctx),
+            None => self.call(symbol, sig_hash, args, rets, ctx),
+        }
+    }
+}
+
+/// A host signature that has no handler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnresolvedHostSig {
+    /// The signature's id in the program.
+    pub host_sig: HostSigId,
+    /// Host symbol string.
+    pub symbol: Box<str>,
+    /// Signature hash.
+    pub sig_hash: SigHash,
+}
+
+/// Errors when linking a program against a [`LinkedHost`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkError {
+    /// One or more host signatures have no matching handler.
+    Unresolved(Vec<UnresolvedHostSig>),
+    /// A host signature's symbol could not be decoded.
+    Malformed {
+        /// The malformed signature's id.
+        host_sig: HostSigId,
+    },
+    /// The program violates the host's [`HostPolicy`].
+    Policy(HostPolicyError),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unresolved(sigs) => {
+                write!(f, "unresolved host symbols:")?;
+                for s in sigs {
+                    write!(f, " '{}' (sig_hash=0x{:016x})", s.symbol, s.sig_hash.0)?;
+                }
+                Ok(())
+            }
+            Self::Malformed { host_sig } => write!(f, "host_sig {} malformed", host_sig.0),
+            Self::Policy(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for LinkError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::{HostSig, sig_hash_slices};
+    use crate::trace::TraceMask;
+    use crate::value::FuncId;
+    use crate::vm::{Limits, Trap, Vm};
+    use alloc::vec;
+
+    fn linker() -> HostLinker<Vec<&'static str>> {
+        let mut linker = HostLinker::new();
+        linker
+            .define(
+                "neg",
+                &[ValueType::I64],
+                &[ValueType::I64],
+                |log: &mut Vec<&'static str>, args, rets, _ctx| {
+                    log.push("neg/i64");
+                    let [ValueRef::I64(x)] = args else {
+                        return Err(HostError::Failed);
+                    };
+                    rets[0] = Value::I64(-x);
+                    Ok(0)
+                },
+            )
+            .unwrap()
+            .define(
+                "neg",
+                &[ValueType::F64],
+                &[ValueType::F64],
+                |log: &mut Vec<&'static str>, args, rets, _ctx| {
+                    log.push("neg/f64");
+                    let [ValueRef::F64(x)] = args else {
+                        return Err(HostError::Failed);
+                    };
+                    rets[0] = Value::F64(-x);
+                    Ok(0)
+                },
+            )
+            .unwrap();
+        linker
+    }
+
+    fn neg_program(symbol: &str) -> Arc<VerifiedProgram> {
+        let mut pb = ProgramBuilder::new();
+        let host_sig = pb.host_sig_for(
+            symbol,
+            HostSig {
+                args: vec![ValueType::I64],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let mut a = Asm::new();
+        a.host_call(0, host_sig, 0, &[1], &[2]);
+        a.ret(0, &[2]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::I64],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        Arc::new(pb.build_verified().unwrap())
+    }
+
+    #[test]
+    fn define_rejects_duplicate_signatures() {
+        let mut linker = linker();
+        let err = linker
+            .define("neg", &[ValueType::I64], &[ValueType::I64], |_, _, _, _| {
+                Ok(0)
+            })
+            .unwrap_err();
+        assert_eq!(
+            err,
+            HostCatalogError::DuplicateSignature {
+                symbol: "neg".into(),
+                sig_hash: sig_hash_slices(&[ValueType::I64], &[ValueType::I64]),
+            }
+        );
+        assert_eq!(linker.catalog().iter().count(), 2);
+    }
+
+    #[test]
+    fn link_resolves_overloads_and_dispatches_by_id() {
+        let program = neg_program("neg");
+        let mut host = linker().instantiate(Vec::new());
+        host.link(&program).unwrap();
+        assert!(host.is_linked(&program));
+
+        let mut vm = Vm::new(host, Limits::default());
+        let out = vm
+            .run(&program, FuncId(0), &[Value::I64(7)], TraceMask::NONE, None)
+            .unwrap();
+        assert_eq!(out, vec![Value::I64(-7)]);
+        assert_eq!(vm.host().state(), &vec!["neg/i64"]);
+    }
+
+    #[test]
+    fn link_checks_policy() {
+        let program = neg_program("neg");
+        let mut host = linker().instantiate(Vec::new());
+        let mut policy = HostPolicy::new();
+        policy.allow("math.*");
+        host.set_policy(Some(Arc::new(policy)));
+        let Err(LinkError::Policy(err)) = host.link(&program) else {
+            panic!("expected a policy violation");
+        };
+        assert_eq!(&*err.violations[0].symbol, "neg");
+        assert!(!host.is_linked(&program));
+    }
+
+    #[test]
+    fn register_derives_signatures_and_marshals_values() {
+        let mut linker = HostLinker::<u64>::new();
+        linker
+            .register("math.hypot", |a: f64, b: f64| -> f64 {
+                (a * a + b * b).sqrt()
+            })
+            .unwrap()
+            .register("str.len", |s: &str| -> u64 { s.len() as u64 })
+            .unwrap()
+            .register(
+                "i64.divmod",
+                |a: i64, b: i64| -> Result<(i64, i64), HostError> {
+                    if b == 0 {
+                        return Err(HostError::Failed);
+                    }
+                    Ok((a / b, a % b))
+                },
+            )
+            .unwrap()
+            .register_with_state("counter.next", |n: &mut u64| -> u64 {
+                *n += 1;
+                *n
+            })
+            .unwrap();
+
+        let mut pb = ProgramBuilder::new();
+        let reg = linker.catalog().register_all(&mut pb).unwrap();
+        let hypot = reg
+            .sig_id_for(
+                "math.hypot",
+                &[ValueType::F64, ValueType::F64],
+                &[ValueType::F64],
+            )
+            .unwrap();
+        let len = reg
+            .sig_id_for("str.len", &[ValueType::Str], &[ValueType::U64])
+            .unwrap();
+        let divmod = reg
+            .sig_id_for(
+                "i64.divmod",
+                &[ValueType::I64, ValueType::I64],
+                &[ValueType::I64, ValueType::I64],
+            )
+            .unwrap();
+        let next = reg
+            .sig_id_for("counter.next", &[], &[ValueType::U64])
+            .unwrap();
+
+        let mut a = Asm::new();
+        a.host_call(0, hypot, 0, &[1, 2], &[6]);
+        a.host_call(0, len, 0, &[3], &[7]);
+        a.host_call(0, divmod, 0, &[4, 5], &[8, 9]);
+        a.host_call(0, next, 0, &[], &[10]);
+        a.host_call(0, next, 0, &[], &[11]);
+        a.ret(0, &[6, 7, 8, 9, 11]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![
+                    ValueType::F64,
+                    ValueType::F64,
+                    ValueType::Str,
+                    ValueType::I64,
+                    ValueType::I64,
+                ],
+                ret_types: vec![
+                    ValueType::F64,
+                    ValueType::U64,
+                    ValueType::I64,
+                    ValueType::I64,
+                    ValueType::U64,
+                ],
+            },
+        )
+        .unwrap();
+        let program = Arc::new(pb.build_verified().unwrap());
+
+        let mut host = linker.instantiate(0);
+        host.link(&program).unwrap();
+        let mut vm = Vm::new(host, Limits::default());
+        let args = |d: i64| {
+            [
+                Value::F64(3.0),
+                Value::F64(4.0),
+                Value::Str("tape".into()),
+                Value::I64(17),
+                Value::I64(d),
+            ]
+        };
+        let out = vm
+            .run(&program, FuncId(0), &args(5), TraceMask::NONE, None)
+            .unwrap();
+        assert_eq!(
+            out,
+            vec![
+                Value::F64(5.0),
+                Value::U64(4),
+                Value::I64(3),
+                Value::I64(2),
+                Value::U64(2),
+            ]
+        );
+        assert_eq!(*vm.host().state(), 2);
+
+        let err = vm
+            .run(&program, FuncId(0), &args(0), TraceMask::NONE, None)
+            .unwrap_err();
+        assert!(matches!(
+            err.trap,
+            Trap::HostCallFailed {
+                error: HostError::Failed,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn link_reports_unresolved_symbols_before_running() {
+        let program = neg_program("abs");
+        let mut host = linker().instantiate(Vec::new());
+        let err = host.link(&program).unwrap_err();
+        assert_eq!(
+            err,
+            LinkError::Unresolved(vec![UnresolvedHostSig {
+                host_sig: HostSigId(0),
+                symbol: "abs".into(),
+                sig_hash: sig_hash_slices(&[ValueType::I64], &[ValueType::I64]),
+            }])
+        );
+        assert!(!host.is_linked(&program));
+
+        // An unlinked program still runs through the by-name fallback and traps at the call.
+        let mut vm = Vm::new(host, Limits::default());
+        let err = vm
+            .run(&program, FuncId(0), &[Value::I64(7)], TraceMask::NONE, None)
+            .unwrap_err();
+        assert!(matches!(
+            err.trap,
+            Trap::HostCallFailed {
+                error: HostError::UnknownSymbol,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn pure_definitions_dispatch_host_call_pure() {
+        let mut linker = HostLinker::<()>::new();
+        linker
+            .register("f64.sqrt", |x: f64| -> f64 { -x })
+            .unwrap()
+            .register_pure("f64.sqrt", |x: f64| -> f64 { x.sqrt() })
+            .unwrap();
+
+        let mut pb = ProgramBuilder::new();
+        let reg = linker.catalog().register_all(&mut pb).unwrap();
+        let sqrt = reg
+            .pure_sig_id_for("f64.sqrt", &[ValueType::F64], &[ValueType::F64])
+            .unwrap();
+        let mut a = Asm::new();
+        a.host_call_pure(sqrt, &[1], &[2]);
+        a.ret(0, &[2]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::F64],
+                ret_types: vec![ValueType::F64],
+            },
+        )
+        .unwrap();
+        let program = Arc::new(pb.build_verified().unwrap());
+
+        let mut vm = Vm::new(linker.instantiate(()), Limits::default());
+        for linked in [false, true] {
+            if linked {
+                vm.host_mut().link(&program).unwrap();
+            }
+            let out = vm
+                .run(
+                    &program,
+                    FuncId(0),
+                    &[Value::F64(9.0)],
+                    TraceMask::NONE,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(out, vec![Value::F64(3.0)]);
+        }
+    }
+}
@@ -0,0 +1,428 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Host capability policies for sandboxing untrusted programs.
+//!
+//! A [`HostPolicy`] restricts which host symbols a program may call. [`HostPolicy::check`] runs
+//! when a program is loaded or linked and reports every offending host signature at once.
+//! Per-symbol call quotas are enforced at run time by wrapping the embedder's host in a
+//! [`PolicyHost`].
+//!
+//! # Example
+//! ```
+//! use execution_tape::host_policy::HostPolicy;
+//!
+//! let mut policy = HostPolicy::new();
+//! policy.allow("math.*").allow("log.info").quota("log.info", 100);
+//! assert!(policy.allows("math.hypot"));
+//! assert!(!policy.allows("fs.read"));
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::host::{Host, HostContext, HostError, SigHash, ValueRef};
+use crate::program::{HostSigId, Program};
+use crate::value::Value;
+
+/// An allowlist of host symbols, with optional per-symbol call quotas.
+///
+/// A new policy allows no host symbols.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostPolicy {
+    allow: Vec<Box<str>>,
+    quotas: BTreeMap<Box<str>, u64>,
+    pure_only: bool,
+}
+
+impl HostPolicy {
+    /// Creates a policy that allows no host symbols.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows symbols matching `pattern`.
+    ///
+    /// A pattern ending in `*` allows every symbol starting with the text before it (so
+    /// `"math.*"` allows a namespace and `"*"` allows everything); any other pattern allows
+    /// exactly that symbol.
+    pub fn allow(&mut self, pattern: impl Into<Box<str>>) -> &mut Self {
+        self.allow.push(pattern.into());
+        self
+    }
+
+    /// Limits `symbol` to at most `max_calls` calls through a [`PolicyHost`].
+    pub fn quota(&mut self, symbol: impl Into<Box<str>>, max_calls: u64) -> &mut Self {
+        self.quotas.insert(symbol.into(), max_calls);
+        self
+    }
+
+    /// Rejects every host signature that is not pure.
+    ///
+    /// Only signatures declared pure (and so callable via `host_call.pure`) are accepted in this
+    /// mode.
+    pub fn pure_only(&mut self, pure_only: bool) -> &mut Self {
+        self.pure_only = pure_only;
+        self
+    }
+
+    /// Returns `true` if `symbol` matches an allow pattern.
+    #[must_use]
+    pub fn allows(&self, symbol: &str) -> bool {
+        self.allow
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => symbol.starts_with(prefix),
+                None => symbol == pattern.as_ref(),
+            })
+    }
+
+    /// Returns the call quota for `symbol`, if one is set.
+    #[must_use]
+    pub fn quota_for(&self, symbol: &str) -> Option<u64> {
+        self.quotas.get(symbol).copied()
+    }
+
+    /// Checks every host signature of `program` against the policy.
+    ///
+    /// All violations are collected, so a rejected program can be reported in one pass.
+    pub fn check(&self, program: &Program) -> Result<(), HostPolicyError> {
+        let mut violations = Vec::new();
+        for (i, hs) in program.host_sigs.iter().enumerate() {
+            let host_sig = HostSigId(u32::try_from(i).unwrap_or(u32::MAX));
+            let Ok(symbol) = program.symbol_str(hs.symbol) else {
+                violations.push(PolicyViolation {
+                    host_sig,
+                    symbol: "".into(),
+                    sig_hash: hs.sig_hash,
+                    reason: ViolationReason::Malformed,
+                });
+                continue;
+            };
+            let reason = if !self.allows(symbol) {
+                ViolationReason::NotAllowed
+            } else if self.pure_only && !hs.pure {
+                ViolationReason::NotPure
+            } else {
+                continue;
+            };
+            violations.push(PolicyViolation {
+                host_sig,
+                symbol: symbol.into(),
+                sig_hash: hs.sig_hash,
+                reason,
+            });
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(HostPolicyError { violations })
+        }
+    }
+}
+
+/// Why a host signature violates a [`HostPolicy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ViolationReason {
+    /// The symbol matches no allow pattern.
+    NotAllowed,
+    /// The policy is pure-only and the signature is effectful.
+    NotPure,
+    /// The signature's symbol could not be decoded.
+    Malformed,
+}
+
+impl fmt::Display for ViolationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAllowed => write!(f, "not allowed"),
+            Self::NotPure => write!(f, "not pure"),
+            Self::Malformed => write!(f, "malformed"),
+        }
+    }
+}
+
+/// A host signature that violates a [`HostPolicy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyViolation {
+    /// The signature's id in the program.
+    pub host_sig: HostSigId,
+    /// Host symbol string (empty if malformed).
+    pub symbol: Box<str>,
+    /// Signature hash.
+    pub sig_hash: SigHash,
+    /// Why the signature was rejected.
+    pub reason: ViolationReason,
+}
+
+/// A program was rejected by a [`HostPolicy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostPolicyError {
+    /// Every offending host signature, in `HostSigId` order.
+    pub violations: Vec<PolicyViolation>,
+}
+
+impl fmt::Display for HostPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "host policy violation:")?;
+        for v in &self.violations {
+            write!(
+                f,
+                " host_sig {} '{}' (sig_hash=0x{:016x}) {};",
+                v.host_sig.0, v.symbol, v.sig_hash.0, v.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for HostPolicyError {}
+
+/// A [`Host`] wrapper that enforces a [`HostPolicy`] on every call.
+///
+/// Calls to symbols the policy does not allow, calls to effectful host signatures in pure-only
+/// mode, and calls beyond a symbol's quota fail with [`HostError::Denied`] without reaching the
+/// inner host. Quota counts accumulate across runs until [`PolicyHost::reset_quotas`].
+#[derive(Debug)]
+pub struct PolicyHost<H> {
+    inner: H,
+    policy: Arc<HostPolicy>,
+    calls: BTreeMap<Box<str>, u64>,
+}
+
+impl<H: Host> PolicyHost<H> {
+    /// Wraps `inner`, enforcing `policy`.
+    #[must_use]
+    pub fn new(inner: H, policy: Arc<HostPolicy>) -> Self {
+        Self {
+            inner,
+            policy,
+            calls: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the enforced policy.
+    #[must_use]
+    pub fn policy(&self) -> &Arc<HostPolicy> {
+        &self.policy
+    }
+
+    /// Returns how many calls to `symbol` have counted against its quota.
+    #[must_use]
+    pub fn calls(&self, symbol: &str) -> u64 {
+        self.calls.get(symbol).copied().unwrap_or(0)
+    }
+
+    /// Resets all quota counts.
+    pub fn reset_quotas(&mut self) {
+        self.calls.clear();
+    }
+
+    /// Returns the wrapped host.
+    #[must_use]
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    /// Returns the wrapped host mutably.
+    #[must_use]
+    pub fn inner_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    /// Consumes the wrapper and returns the wrapped host.
+    #[must_use]
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+
+    fn admit(&mut self, symbol: &str, pure: bool) -> Result<(), HostError> {
+        if (self.policy.pure_only && !pure) || !self.policy.allows(symbol) {
+            return Err(HostError::Denied);
+        }
+        if let Some(max_calls) = self.policy.quota_for(symbol) {
+            let count = match self.calls.get_mut(symbol) {
+                Some(count) => count,
+                None => self.calls.entry(symbol.into()).or_insert(0),
+            };
+            if *count >= max_calls {
+                return Err(HostError::Denied);
+            }
+            *count += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<H: Host> Host for PolicyHost<H> {
+    fn call(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        self.admit(symbol, false)?;
+        self.inner.call(symbol, sig_hash, args, rets, ctx)
+    }
+
+    fn call_with_id(
+        &mut self,
+        host_sig: HostSigId,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        let pure = ctx.program().host_sig(host_sig).is_some_and(|hs| hs.pure);
+        self.admit(symbol, pure)?;
+        self.inner
+            .call_with_id(host_sig, symbol, sig_hash, args, rets, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::{HostSig, sig_hash_slices};
+    use crate::program::ValueType;
+    use crate::trace::TraceMask;
+    use crate::value::FuncId;
+    use crate::verifier::VerifiedProgram;
+    use crate::vm::{Limits, Trap, Vm};
+    use alloc::vec;
+
+    fn program(symbols: &[&str]) -> VerifiedProgram {
+        let mut pb = ProgramBuilder::new();
+        let mut a = Asm::new();
+        for symbol in symbols {
+            let host_sig = pb.host_sig_for(
+                symbol,
+                HostSig {
+                    args: vec![],
+                    rets: vec![ValueType::I64],
+                },
+            );
+            a.host_call(0, host_sig, 0, &[], &[1]);
+        }
+        a.ret(0, &[1]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        pb.build_verified().unwrap()
+    }
+
+    struct One;
+
+    impl Host for One {
+        fn call(
+            &mut self,
+            _symbol: &str,
+            _sig_hash: SigHash,
+            _args: &[ValueRef<'_>],
+            rets: &mut [Value],
+            _ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            rets[0] = Value::I64(1);
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn check_lists_every_offending_host_sig() {
+        let p = program(&["math.abs", "fs.read", "math", "net.send"]);
+        let mut policy = HostPolicy::new();
+        policy.allow("math.*");
+        assert_eq!(policy.check(p.program()), {
+            let sig_hash = sig_hash_slices(&[], &[ValueType::I64]);
+            let violation = |ix, symbol: &str| PolicyViolation {
+                host_sig: HostSigId(ix),
+                symbol: symbol.into(),
+                sig_hash,
+                reason: ViolationReason::NotAllowed,
+            };
+            Err(HostPolicyError {
+                violations: vec![
+                    violation(1, "fs.read"),
+                    violation(2, "math"),
+                    violation(3, "net.send"),
+                ],
+            })
+        });
+
+        policy.allow("*");
+        assert_eq!(policy.check(p.program()), Ok(()));
+        policy.pure_only(true);
+        let err = policy.check(p.program()).unwrap_err();
+        assert_eq!(err.violations.len(), 4);
+        assert!(
+            err.violations
+                .iter()
+                .all(|v| v.reason == ViolationReason::NotPure)
+        );
+    }
+
+    #[test]
+    fn policy_host_enforces_quotas_across_runs() {
+        let p = program(&["log.info", "log.info"]);
+        let mut policy = HostPolicy::new();
+        policy.allow("log.*").quota("log.info", 3);
+        let mut vm = Vm::new(PolicyHost::new(One, Arc::new(policy)), Limits::default());
+
+        let run = |vm: &mut Vm<PolicyHost<One>>| vm.run(&p, FuncId(0), &[], TraceMask::NONE, None);
+        assert_eq!(run(&mut vm).unwrap(), vec![Value::I64(1)]);
+        let err = run(&mut vm).unwrap_err();
+        assert!(matches!(
+            err.trap,
+            Trap::HostCallFailed {
+                error: HostError::Denied,
+                ..
+            }
+        ));
+        assert_eq!(vm.host().calls("log.info"), 3);
+
+        vm.host_mut().reset_quotas();
+        assert_eq!(run(&mut vm).unwrap(), vec![Value::I64(1)]);
+    }
+
+    #[test]
+    fn pure_only_admits_pure_host_calls() {
+        let mut pb = ProgramBuilder::new();
+        let mut a = Asm::new();
+        let sig = HostSig {
+            args: vec![],
+            rets: vec![ValueType::I64],
+        };
+        let host_sig = pb.pure_host_sig_for("math.one", sig);
+        a.host_call_pure(host_sig, &[], &[1]);
+        a.ret(0, &[1]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        let p = pb.build_verified().unwrap();
+
+        let mut policy = HostPolicy::new();
+        policy.allow("math.*").pure_only(true);
+        assert_eq!(policy.check(p.program()), Ok(()));
+        let mut vm = Vm::new(PolicyHost::new(One, Arc::new(policy)), Limits::default());
+        let out = vm.run(&p, FuncId(0), &[], TraceMask::NONE, None).unwrap();
+        assert_eq!(out, vec![Value::I64(1)]);
+    }
+}
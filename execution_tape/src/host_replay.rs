@@ -0,0 +1,1467 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Record/replay of host calls for deterministic reproduction.
+//!
+//! [`RecordingHost`] wraps an embedder [`Host`] and logs every call it serves: symbol, signature
+//! hash, arguments, returns, extra fuel charge and the accesses the host recorded. Aggregate
+//! arguments and returns are captured by value through [`HostContext`], so a log is meaningful
+//! without the VM heap it was recorded against.
+//!
+//! [`ReplayHost`] serves the same results back from a [`HostCallLog`] and rejects any call that
+//! diverges from the recording. Since the VM itself is deterministic, replaying a log against the
+//! same program, entry and arguments reproduces the recorded run exactly, including traps.
+//!
+//! Host objects are opaque, so by default only their handles are logged. With a
+//! [`HostTypeRegistry`] attached ([`RecordingHost::set_host_types`],
+//! [`ReplayHost::set_host_types`]), objects whose type can be serialized are logged with their
+//! contents, compared by contents during replay, and recreated when returned.
+//!
+//! Logs have a versioned binary encoding ([`HostCallLog::encode`] / [`HostCallLog::decode`]) built
+//! on [`format::Writer`](crate::format::Writer) and [`format::Reader`](crate::format::Reader).
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::aggregates::{AggError, AggHeap};
+use crate::format::{DecodeError, Reader, Writer};
+use crate::host::{AccessSink, Host, HostContext, HostError, ResourceKeyRef, SigHash, ValueRef};
+use crate::host_types::{HostTypeCapability, HostTypeError, HostTypeRegistry};
+use crate::program::{ElemTypeId, HostSigId, HostTypeId, TypeId};
+use crate::value::{AggHandle, AggType, Closure, Decimal, FuncId, Obj, ObjHandle, Value};
+
+/// Host call log format version supported by this crate.
+pub const LOG_VERSION_MAJOR: u16 = 0;
+/// Host call log format version supported by this crate.
+pub const LOG_VERSION_MINOR: u16 = 1;
+
+const LOG_MAGIC: &[u8; 8] = b"EXTHLOG\0";
+
+/// Maximum aggregate nesting accepted when decoding a log.
+const MAX_DECODE_DEPTH: u32 = 256;
+
+/// A host-call value captured by value.
+///
+/// Unlike [`Value`], aggregates and closure environments carry their contents, so two captures
+/// compare equal only if they are structurally identical.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoggedValue {
+    /// `()`.
+    Unit,
+    /// Boolean.
+    Bool(bool),
+    /// Signed 64-bit integer.
+    I64(i64),
+    /// Unsigned 64-bit integer.
+    U64(u64),
+    /// 64-bit float, stored as raw bits so NaN payloads compare exactly.
+    F64(u64),
+    /// Decimal.
+    Decimal(Decimal),
+    /// Byte string.
+    Bytes(Vec<u8>),
+    /// UTF-8 string.
+    Str(String),
+    /// Host object. Objects are opaque to the VM, so only the handle is captured.
+    Obj(Obj),
+    /// Host object captured with its serialized contents (see
+    /// [`HostTypeRegistry::serialize_obj`]).
+    ObjData {
+        /// The object in the recording run.
+        obj: Obj,
+        /// Serialized contents.
+        data: Vec<u8>,
+    },
+    /// Aggregate handle plus captured contents.
+    Agg(LoggedAgg),
+    /// Function reference.
+    Func(FuncId),
+    /// Closure with its captured environment.
+    Closure {
+        /// Closure function.
+        func: FuncId,
+        /// Captured environment aggregate.
+        env: LoggedAgg,
+    },
+}
+
+/// An aggregate captured by value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoggedAgg {
+    /// Handle of the aggregate in the recording VM's heap.
+    pub handle: AggHandle,
+    /// Aggregate kind.
+    pub agg_type: AggType,
+    /// Captured elements (tuple elements, struct fields or array elements).
+    pub elems: Vec<LoggedValue>,
+}
+
+impl LoggedValue {
+    /// Captures `value`, reading aggregate contents from `aggregates`.
+    pub fn capture(value: ValueRef<'_>, aggregates: &AggHeap) -> Result<Self, AggError> {
+        Self::capture_with(value, aggregates, &mut |o| Ok(Self::Obj(o)))
+    }
+
+    /// Like [`LoggedValue::capture`], also capturing the contents of objects whose type `types`
+    /// can serialize.
+    pub fn capture_with_types(
+        value: ValueRef<'_>,
+        aggregates: &AggHeap,
+        types: &HostTypeRegistry,
+    ) -> Result<Self, HostTypeError> {
+        Self::capture_with(value, aggregates, &mut |o| capture_obj(o, types))
+    }
+
+    fn capture_with<E: From<AggError>>(
+        value: ValueRef<'_>,
+        aggregates: &AggHeap,
+        obj: &mut impl FnMut(Obj) -> Result<Self, E>,
+    ) -> Result<Self, E> {
+        Ok(match value {
+            ValueRef::Unit => Self::Unit,
+            ValueRef::Bool(b) => Self::Bool(b),
+            ValueRef::I64(i) => Self::I64(i),
+            ValueRef::U64(u) => Self::U64(u),
+            ValueRef::F64(f) => Self::F64(f.to_bits()),
+            ValueRef::Decimal(d) => Self::Decimal(d),
+            ValueRef::Bytes(b) => Self::Bytes(b.to_vec()),
+            ValueRef::Str(s) => Self::Str(s.into()),
+            ValueRef::Obj(o) => obj(o)?,
+            ValueRef::Agg(h) => Self::Agg(LoggedAgg::capture_with(h, aggregates, obj)?),
+            ValueRef::Func(f) => Self::Func(f),
+            ValueRef::Closure(c) => Self::Closure {
+                func: c.func,
+                env: LoggedAgg::capture_with(c.env, aggregates, obj)?,
+            },
+        })
+    }
+
+    /// Converts this capture back into a VM [`Value`].
+    ///
+    /// Aggregates become handles into the heap they were recorded from.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Unit => Value::Unit,
+            Self::Bool(b) => Value::Bool(*b),
+            Self::I64(i) => Value::I64(*i),
+            Self::U64(u) => Value::U64(*u),
+            Self::F64(bits) => Value::F64(f64::from_bits(*bits)),
+            Self::Decimal(d) => Value::Decimal(*d),
+            Self::Bytes(b) => Value::Bytes(b.clone()),
+            Self::Str(s) => Value::Str(s.clone()),
+            Self::Obj(o) | Self::ObjData { obj: o, .. } => Value::Obj(*o),
+            Self::Agg(agg) => Value::Agg(agg.handle),
+            Self::Func(f) => Value::Func(*f),
+            Self::Closure { func, env } => Value::Closure(Closure {
+                func: *func,
+                env: env.handle,
+            }),
+        }
+    }
+}
+
+impl LoggedAgg {
+    /// Captures the aggregate at `handle`, including nested aggregates.
+    pub fn capture(handle: AggHandle, aggregates: &AggHeap) -> Result<Self, AggError> {
+        Self::capture_with(handle, aggregates, &mut |o| Ok(LoggedValue::Obj(o)))
+    }
+
+    fn capture_with<E: From<AggError>>(
+        handle: AggHandle,
+        aggregates: &AggHeap,
+        obj: &mut impl FnMut(Obj) -> Result<LoggedValue, E>,
+    ) -> Result<Self, E> {
+        let agg_type = aggregates.agg_type(handle)?;
+        let len = match agg_type {
+            AggType::Tuple { .. } => aggregates.tuple_len(handle)?,
+            AggType::Struct { .. } => aggregates.struct_field_count(handle)?,
+            AggType::Array { .. } => aggregates.array_len(handle)?,
+        };
+        let mut elems = Vec::with_capacity(len);
+        for index in 0..len {
+            let elem = match agg_type {
+                AggType::Tuple { .. } => aggregates.tuple_get_ref(handle, index)?,
+                AggType::Struct { .. } => aggregates.struct_get_ref(handle, index)?,
+                AggType::Array { .. } => aggregates.array_get_ref(handle, index)?,
+            };
+            elems.push(LoggedValue::capture_with(
+                ValueRef::from_value(elem),
+                aggregates,
+                obj,
+            )?);
+        }
+        Ok(Self {
+            handle,
+            agg_type,
+            elems,
+        })
+    }
+}
+
+fn capture_obj(obj: Obj, types: &HostTypeRegistry) -> Result<LoggedValue, HostTypeError> {
+    if !types.supports(obj.host_type, HostTypeCapability::Serialize) {
+        return Ok(LoggedValue::Obj(obj));
+    }
+    let mut data = Vec::new();
+    types.serialize_obj(obj, &mut data)?;
+    Ok(LoggedValue::ObjData { obj, data })
+}
+
+/// Compares a capture from a replaying run with a recorded one.
+///
+/// Objects captured with contents match by type and contents, since replay may recreate them
+/// under different handles.
+fn replayed_matches(actual: &LoggedValue, recorded: &LoggedValue) -> bool {
+    match (actual, recorded) {
+        (LoggedValue::ObjData { obj: a, data: da }, LoggedValue::ObjData { obj: b, data: db }) => {
+            a.host_type == b.host_type && da == db
+        }
+        (LoggedValue::Agg(a), LoggedValue::Agg(b)) => replayed_agg_matches(a, b),
+        (
+            LoggedValue::Closure { func: fa, env: ea },
+            LoggedValue::Closure { func: fb, env: eb },
+        ) => fa == fb && replayed_agg_matches(ea, eb),
+        (a, b) => a == b,
+    }
+}
+
+fn replayed_agg_matches(actual: &LoggedAgg, recorded: &LoggedAgg) -> bool {
+    actual.handle == recorded.handle
+        && actual.agg_type == recorded.agg_type
+        && actual.elems.len() == recorded.elems.len()
+        && actual
+            .elems
+            .iter()
+            .zip(&recorded.elems)
+            .all(|(a, b)| replayed_matches(a, b))
+}
+
+/// An owned [`ResourceKeyRef`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoggedKey {
+    /// See [`ResourceKeyRef::Input`].
+    Input(Box<str>),
+    /// See [`ResourceKeyRef::HostState`].
+    HostState {
+        /// Host operation identifier.
+        op: SigHash,
+        /// Opaque per-op key.
+        key: u64,
+    },
+    /// See [`ResourceKeyRef::OpaqueHost`].
+    OpaqueHost {
+        /// Host operation identifier.
+        op: SigHash,
+    },
+}
+
+impl LoggedKey {
+    /// Copies a borrowed key.
+    #[must_use]
+    pub fn from_key_ref(key: ResourceKeyRef<'_>) -> Self {
+        match key {
+            ResourceKeyRef::Input(name) => Self::Input(name.into()),
+            ResourceKeyRef::HostState { op, key } => Self::HostState { op, key },
+            ResourceKeyRef::OpaqueHost { op } => Self::OpaqueHost { op },
+        }
+    }
+
+    /// Borrows this key as a [`ResourceKeyRef`].
+    #[must_use]
+    pub fn as_key_ref(&self) -> ResourceKeyRef<'_> {
+        match self {
+            Self::Input(name) => ResourceKeyRef::Input(name),
+            Self::HostState { op, key } => ResourceKeyRef::HostState { op: *op, key: *key },
+            Self::OpaqueHost { op } => ResourceKeyRef::OpaqueHost { op: *op },
+        }
+    }
+}
+
+/// An access recorded by a host during one call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoggedAccess {
+    /// [`AccessSink::read`].
+    Read(LoggedKey),
+    /// [`AccessSink::write`].
+    Write(LoggedKey),
+}
+
+/// One recorded host call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostCallRecord {
+    /// Host symbol.
+    pub symbol: Box<str>,
+    /// Signature hash carried by the program.
+    pub sig_hash: SigHash,
+    /// Captured arguments.
+    pub args: Vec<LoggedValue>,
+    /// Whether every argument could be captured. When `false`, `args` is empty and replay only
+    /// matches a call whose arguments also fail to capture.
+    pub args_captured: bool,
+    /// Extra fuel charged by the host, or the error it returned.
+    pub result: Result<u64, HostError>,
+    /// Captured return values (empty when `result` is an error).
+    pub rets: Vec<LoggedValue>,
+    /// Accesses the host recorded, in order.
+    pub accesses: Vec<LoggedAccess>,
+}
+
+/// An ordered log of host calls.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostCallLog {
+    /// Recorded calls in execution order.
+    pub records: Vec<HostCallRecord>,
+}
+
+impl HostCallLog {
+    /// Creates an empty log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes this log in the versioned binary log format.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut w = Writer::new();
+        w.write_bytes(LOG_MAGIC);
+        w.write_u16_le(LOG_VERSION_MAJOR);
+        w.write_u16_le(LOG_VERSION_MINOR);
+        write_len(&mut w, self.records.len());
+        for record in &self.records {
+            write_str(&mut w, &record.symbol);
+            w.write_u64_le(record.sig_hash.0);
+            w.write_u8(u8::from(record.args_captured));
+            write_values(&mut w, &record.args);
+            match &record.result {
+                Ok(fuel) => {
+                    w.write_u8(0);
+                    w.write_uleb128_u64(*fuel);
+                    write_values(&mut w, &record.rets);
+                }
+                Err(e) => {
+                    w.write_u8(1);
+                    w.write_u8(encode_host_error(e));
+                }
+            }
+            write_len(&mut w, record.accesses.len());
+            for access in &record.accesses {
+                let (kind, key) = match access {
+                    LoggedAccess::Read(key) => (0, key),
+                    LoggedAccess::Write(key) => (1, key),
+                };
+                w.write_u8(kind);
+                write_key(&mut w, key);
+            }
+        }
+        w.into_vec()
+    }
+
+    /// Decodes a log produced by [`HostCallLog::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader::new(bytes);
+        let magic = r.read_bytes(LOG_MAGIC.len())?;
+        if magic != LOG_MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let major = r.read_u16_le()?;
+        let minor = r.read_u16_le()?;
+        if major != LOG_VERSION_MAJOR || minor != LOG_VERSION_MINOR {
+            return Err(DecodeError::UnsupportedVersion { major, minor });
+        }
+
+        let count = read_len(&mut r)?;
+        let mut records = Vec::new();
+        for _ in 0..count {
+            let symbol = read_str(&mut r)?.into();
+            let sig_hash = SigHash(r.read_u64_le()?);
+            let args_captured = match r.read_u8()? {
+                0 => false,
+                1 => true,
+                _ => return Err(DecodeError::OutOfBounds),
+            };
+            let args = read_values(&mut r)?;
+            let (result, rets) = match r.read_u8()? {
+                0 => {
+                    let fuel = r.read_uleb128_u64()?;
+                    (Ok(fuel), read_values(&mut r)?)
+                }
+                1 => (Err(decode_host_error(r.read_u8()?)?), Vec::new()),
+                _ => return Err(DecodeError::OutOfBounds),
+            };
+            let access_count = read_len(&mut r)?;
+            let mut accesses = Vec::new();
+            for _ in 0..access_count {
+                let kind = r.read_u8()?;
+                let key = read_key(&mut r)?;
+                accesses.push(match kind {
+                    0 => LoggedAccess::Read(key),
+                    1 => LoggedAccess::Write(key),
+                    _ => return Err(DecodeError::OutOfBounds),
+                });
+            }
+            records.push(HostCallRecord {
+                symbol,
+                sig_hash,
+                args,
+                args_captured,
+                result,
+                rets,
+                accesses,
+            });
+        }
+        if r.offset() != bytes.len() {
+            return Err(DecodeError::OutOfBounds);
+        }
+        Ok(Self { records })
+    }
+}
+
+/// A [`Host`] wrapper that records every call served by `H` into a [`HostCallLog`].
+///
+/// The wrapped host always sees an access sink, so accesses it records are logged even when the
+/// VM run has no sink attached; they are forwarded to the run's sink when there is one.
+#[derive(Debug)]
+pub struct RecordingHost<H> {
+    inner: H,
+    log: HostCallLog,
+    host_types: Option<Arc<HostTypeRegistry>>,
+}
+
+impl<H: Host> RecordingHost<H> {
+    /// Wraps `inner` with an empty log.
+    #[must_use]
+    pub fn new(inner: H) -> Self {
+        Self {
+            inner,
+            log: HostCallLog::new(),
+            host_types: None,
+        }
+    }
+
+    /// Sets the registry used to capture the contents of host objects.
+    ///
+    /// Objects whose type cannot be serialized are still logged by handle. A call whose arguments
+    /// fail to serialize is still served and logged without arguments (see
+    /// [`HostCallRecord::args_captured`]); a call whose returns fail to serialize is treated as a
+    /// host failure.
+    pub fn set_host_types(&mut self, host_types: Option<Arc<HostTypeRegistry>>) {
+        self.host_types = host_types;
+    }
+
+    /// Returns the calls recorded so far.
+    #[must_use]
+    pub fn log(&self) -> &HostCallLog {
+        &self.log
+    }
+
+    /// Takes the recorded log, leaving an empty one in its place.
+    pub fn take_log(&mut self) -> HostCallLog {
+        core::mem::take(&mut self.log)
+    }
+
+    /// Returns the wrapped host.
+    #[must_use]
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    /// Returns the wrapped host mutably.
+    pub fn inner_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    /// Consumes the wrapper, returning the wrapped host and the recorded log.
+    pub fn into_parts(self) -> (H, HostCallLog) {
+        (self.inner, self.log)
+    }
+}
+
+impl<H: Host> Host for RecordingHost<H> {
+    fn call(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        self.record(symbol, sig_hash, args, rets, ctx, |inner, rets, ctx| {
+            inner.call(symbol, sig_hash, args, rets, ctx)
+        })
+    }
+
+    fn call_with_id(
+        &mut self,
+        host_sig: HostSigId,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        self.record(symbol, sig_hash, args, rets, ctx, |inner, rets, ctx| {
+            inner.call_with_id(host_sig, symbol, sig_hash, args, rets, ctx)
+        })
+    }
+}
+
+impl<H: Host> RecordingHost<H> {
+    fn record(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+        dispatch: impl FnOnce(&mut H, &mut [Value], HostContext<'_, '_>) -> Result<u64, HostError>,
+    ) -> Result<u64, HostError> {
+        let (program, aggregates, access) = ctx.into_parts();
+        let host_types = self.host_types.as_deref();
+        let capture = |v: ValueRef<'_>| match host_types {
+            Some(types) => LoggedValue::capture_with_types(v, aggregates, types).ok(),
+            None => LoggedValue::capture(v, aggregates).ok(),
+        };
+        // Arguments that cannot be captured do not change what the wrapped host sees; the record
+        // only notes that they were not captured, and replay checks that they fail again.
+        let logged_args = args.iter().map(|a| capture(*a)).collect::<Option<Vec<_>>>();
+
+        let mut tee = TeeAccessSink {
+            forward: access,
+            accesses: Vec::new(),
+        };
+        let mut result = dispatch(
+            &mut self.inner,
+            rets,
+            HostContext::new(program, aggregates, Some(&mut tee)),
+        );
+
+        let mut logged_rets = Vec::new();
+        if result.is_ok() {
+            // A handle that does not resolve cannot be replayed; treat it as a host failure.
+            match rets
+                .iter()
+                .map(|v| capture(ValueRef::from_value(v)))
+                .collect::<Option<Vec<_>>>()
+            {
+                Some(captured) => logged_rets = captured,
+                None => result = Err(HostError::Failed),
+            }
+        }
+
+        self.log.records.push(HostCallRecord {
+            symbol: symbol.into(),
+            sig_hash,
+            args_captured: logged_args.is_some(),
+            args: logged_args.unwrap_or_default(),
+            result: result.clone(),
+            rets: logged_rets,
+            accesses: tee.accesses,
+        });
+        result
+    }
+}
+
+struct TeeAccessSink<'a> {
+    forward: Option<&'a mut dyn AccessSink>,
+    accesses: Vec<LoggedAccess>,
+}
+
+impl AccessSink for TeeAccessSink<'_> {
+    fn read(&mut self, key: ResourceKeyRef<'_>) {
+        self.accesses
+            .push(LoggedAccess::Read(LoggedKey::from_key_ref(key)));
+        if let Some(forward) = self.forward.as_mut() {
+            forward.read(key);
+        }
+    }
+
+    fn write(&mut self, key: ResourceKeyRef<'_>) {
+        self.accesses
+            .push(LoggedAccess::Write(LoggedKey::from_key_ref(key)));
+        if let Some(forward) = self.forward.as_mut() {
+            forward.write(key);
+        }
+    }
+}
+
+/// Why a replayed call diverged from the recording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The run made more host calls than were recorded.
+    LogExhausted {
+        /// Zero-based index of the unexpected call.
+        call: usize,
+    },
+    /// The call targeted a different symbol.
+    SymbolMismatch {
+        /// Zero-based call index.
+        call: usize,
+        /// Recorded symbol.
+        expected: Box<str>,
+        /// Symbol of the replayed call.
+        actual: Box<str>,
+    },
+    /// The call carried a different signature hash.
+    SigHashMismatch {
+        /// Zero-based call index.
+        call: usize,
+        /// Recorded signature hash.
+        expected: SigHash,
+        /// Signature hash of the replayed call.
+        actual: SigHash,
+    },
+    /// The call was made with different arguments.
+    ArgsMismatch {
+        /// Zero-based call index.
+        call: usize,
+    },
+    /// Recorded returns do not fit the replayed call, or a recorded aggregate return does not
+    /// match the replaying VM's heap.
+    RetsMismatch {
+        /// Zero-based call index.
+        call: usize,
+    },
+    /// The run ended before consuming every recorded call.
+    UnconsumedCalls {
+        /// Number of recorded calls that were never replayed.
+        remaining: usize,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LogExhausted { call } => {
+                write!(f, "replay diverged at call {call}: log has no more calls")
+            }
+            Self::SymbolMismatch {
+                call,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "replay diverged at call {call}: expected host_call={expected}, got {actual}"
+            ),
+            Self::SigHashMismatch {
+                call,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "replay diverged at call {call}: expected sig_hash={}, got {}",
+                expected.0, actual.0
+            ),
+            Self::ArgsMismatch { call } => {
+                write!(f, "replay diverged at call {call}: arguments differ")
+            }
+            Self::RetsMismatch { call } => {
+                write!(
+                    f,
+                    "replay diverged at call {call}: recorded returns do not fit"
+                )
+            }
+            Self::UnconsumedCalls { remaining } => {
+                write!(f, "replay ended with {remaining} recorded calls unconsumed")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ReplayError {}
+
+/// A [`Host`] that serves calls from a [`HostCallLog`].
+///
+/// Each call must match the next recorded call (symbol, signature hash and captured arguments).
+/// A divergent call fails with [`HostError::Failed`], and so does every later call; the reason is
+/// available from [`ReplayHost::divergence`]. Recorded accesses are re-emitted to the run's access
+/// sink so incremental executors observe the same dependencies.
+#[derive(Clone, Debug)]
+pub struct ReplayHost {
+    log: HostCallLog,
+    next: usize,
+    divergence: Option<ReplayError>,
+    host_types: Option<Arc<HostTypeRegistry>>,
+}
+
+impl ReplayHost {
+    /// Creates a host replaying `log` from the first call.
+    #[must_use]
+    pub fn new(log: HostCallLog) -> Self {
+        Self {
+            log,
+            next: 0,
+            divergence: None,
+            host_types: None,
+        }
+    }
+
+    /// Sets the registry used to compare and recreate host objects logged with their contents.
+    ///
+    /// Without a registry, objects returned by recorded calls are replayed under their recorded
+    /// handles.
+    pub fn set_host_types(&mut self, host_types: Option<Arc<HostTypeRegistry>>) {
+        self.host_types = host_types;
+    }
+
+    /// Returns the first divergence, if replay diverged.
+    #[must_use]
+    pub fn divergence(&self) -> Option<&ReplayError> {
+        self.divergence.as_ref()
+    }
+
+    /// Returns the number of recorded calls not yet replayed.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.log.records.len().saturating_sub(self.next)
+    }
+
+    /// Checks that replay neither diverged nor left recorded calls unconsumed.
+    pub fn finish(&self) -> Result<(), ReplayError> {
+        if let Some(divergence) = &self.divergence {
+            return Err(divergence.clone());
+        }
+        match self.remaining() {
+            0 => Ok(()),
+            remaining => Err(ReplayError::UnconsumedCalls { remaining }),
+        }
+    }
+
+    fn replay_one(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        mut ctx: HostContext<'_, '_>,
+    ) -> Result<Result<u64, HostError>, ReplayError> {
+        let call = self.next;
+        let record = self
+            .log
+            .records
+            .get(call)
+            .ok_or(ReplayError::LogExhausted { call })?;
+        if *record.symbol != *symbol {
+            return Err(ReplayError::SymbolMismatch {
+                call,
+                expected: record.symbol.clone(),
+                actual: symbol.into(),
+            });
+        }
+        if record.sig_hash != sig_hash {
+            return Err(ReplayError::SigHashMismatch {
+                call,
+                expected: record.sig_hash,
+                actual: sig_hash,
+            });
+        }
+        let host_types = self.host_types.as_deref();
+        let capture = |v: ValueRef<'_>, aggregates: &AggHeap| match host_types {
+            Some(types) => LoggedValue::capture_with_types(v, aggregates, types).ok(),
+            None => LoggedValue::capture(v, aggregates).ok(),
+        };
+        let args_match = if record.args_captured {
+            args.len() == record.args.len()
+                && args.iter().zip(&record.args).all(|(actual, expected)| {
+                    capture(*actual, ctx.aggregates())
+                        .is_some_and(|actual| replayed_matches(&actual, expected))
+                })
+        } else {
+            args.iter()
+                .any(|actual| capture(*actual, ctx.aggregates()).is_none())
+        };
+        if !args_match {
+            return Err(ReplayError::ArgsMismatch { call });
+        }
+
+        if record.result.is_ok() {
+            if rets.len() != record.rets.len() {
+                return Err(ReplayError::RetsMismatch { call });
+            }
+            for (slot, recorded) in rets.iter_mut().zip(&record.rets) {
+                let value = match (recorded, host_types) {
+                    (LoggedValue::ObjData { obj, data }, Some(types)) => Value::Obj(
+                        types
+                            .deserialize_obj(obj.host_type, data)
+                            .map_err(|_| ReplayError::RetsMismatch { call })?,
+                    ),
+                    _ => recorded.to_value(),
+                };
+                let resolves = match recorded {
+                    LoggedValue::Agg(_) | LoggedValue::Closure { .. } => {
+                        capture(ValueRef::from_value(&value), ctx.aggregates())
+                            .is_some_and(|actual| replayed_matches(&actual, recorded))
+                    }
+                    _ => true,
+                };
+                if !resolves {
+                    return Err(ReplayError::RetsMismatch { call });
+                }
+                *slot = value;
+            }
+        }
+
+        for access in &record.accesses {
+            match access {
+                LoggedAccess::Read(key) => ctx.record_read(key.as_key_ref()),
+                LoggedAccess::Write(key) => ctx.record_write(key.as_key_ref()),
+            }
+        }
+        self.next += 1;
+        Ok(record.result.clone())
+    }
+}
+
+impl Host for ReplayHost {
+    fn call(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        if self.divergence.is_some() {
+            return Err(HostError::Failed);
+        }
+        match self.replay_one(symbol, sig_hash, args, rets, ctx) {
+            Ok(result) => result,
+            Err(divergence) => {
+                self.divergence = Some(divergence);
+                Err(HostError::Failed)
+            }
+        }
+    }
+}
+
+fn encode_host_error(e: &HostError) -> u8 {
+    match e {
+        HostError::UnknownSymbol => 0,
+        HostError::SignatureMismatch => 1,
+        HostError::Failed => 2,
+        HostError::Denied => 3,
+    }
+}
+
+fn decode_host_error(tag: u8) -> Result<HostError, DecodeError> {
+    match tag {
+        0 => Ok(HostError::UnknownSymbol),
+        1 => Ok(HostError::SignatureMismatch),
+        2 => Ok(HostError::Failed),
+        3 => Ok(HostError::Denied),
+        _ => Err(DecodeError::OutOfBounds),
+    }
+}
+
+fn write_len(w: &mut Writer, len: usize) {
+    w.write_uleb128_u64(u64::try_from(len).unwrap_or(u64::MAX));
+}
+
+fn read_len(r: &mut Reader<'_>) -> Result<usize, DecodeError> {
+    usize::try_from(r.read_uleb128_u64()?).map_err(|_| DecodeError::OutOfBounds)
+}
+
+fn write_str(w: &mut Writer, s: &str) {
+    write_len(w, s.len());
+    w.write_bytes(s.as_bytes());
+}
+
+fn read_str<'a>(r: &mut Reader<'a>) -> Result<&'a str, DecodeError> {
+    let len = read_len(r)?;
+    r.read_str(len)
+}
+
+fn write_key(w: &mut Writer, key: &LoggedKey) {
+    match key {
+        LoggedKey::Input(name) => {
+            w.write_u8(0);
+            write_str(w, name);
+        }
+        LoggedKey::HostState { op, key } => {
+            w.write_u8(1);
+            w.write_u64_le(op.0);
+            w.write_uleb128_u64(*key);
+        }
+        LoggedKey::OpaqueHost { op } => {
+            w.write_u8(2);
+            w.write_u64_le(op.0);
+        }
+    }
+}
+
+fn read_key(r: &mut Reader<'_>) -> Result<LoggedKey, DecodeError> {
+    match r.read_u8()? {
+        0 => Ok(LoggedKey::Input(read_str(r)?.into())),
+        1 => {
+            let op = SigHash(r.read_u64_le()?);
+            let key = r.read_uleb128_u64()?;
+            Ok(LoggedKey::HostState { op, key })
+        }
+        2 => Ok(LoggedKey::OpaqueHost {
+            op: SigHash(r.read_u64_le()?),
+        }),
+        _ => Err(DecodeError::OutOfBounds),
+    }
+}
+
+fn write_values(w: &mut Writer, values: &[LoggedValue]) {
+    write_len(w, values.len());
+    for v in values {
+        write_value(w, v);
+    }
+}
+
+fn read_values(r: &mut Reader<'_>) -> Result<Vec<LoggedValue>, DecodeError> {
+    read_values_at_depth(r, 0)
+}
+
+fn read_values_at_depth(r: &mut Reader<'_>, depth: u32) -> Result<Vec<LoggedValue>, DecodeError> {
+    let len = read_len(r)?;
+    let mut out = Vec::new();
+    for _ in 0..len {
+        out.push(read_value(r, depth)?);
+    }
+    Ok(out)
+}
+
+// Value tags follow the value type tags used by `sig_hash`.
+fn write_value(w: &mut Writer, v: &LoggedValue) {
+    match v {
+        LoggedValue::Unit => w.write_u8(0),
+        LoggedValue::Bool(b) => {
+            w.write_u8(1);
+            w.write_u8(u8::from(*b));
+        }
+        LoggedValue::I64(i) => {
+            w.write_u8(2);
+            w.write_sleb128_i64(*i);
+        }
+        LoggedValue::U64(u) => {
+            w.write_u8(3);
+            w.write_uleb128_u64(*u);
+        }
+        LoggedValue::F64(bits) => {
+            w.write_u8(4);
+            w.write_u64_le(*bits);
+        }
+        LoggedValue::Decimal(d) => {
+            w.write_u8(5);
+            w.write_sleb128_i64(d.mantissa);
+            w.write_u8(d.scale);
+        }
+        LoggedValue::Bytes(b) => {
+            w.write_u8(6);
+            write_len(w, b.len());
+            w.write_bytes(b);
+        }
+        LoggedValue::Str(s) => {
+            w.write_u8(7);
+            write_str(w, s);
+        }
+        LoggedValue::Obj(o) => {
+            w.write_u8(8);
+            w.write_u64_le(o.host_type.0);
+            w.write_uleb128_u64(o.handle.0);
+        }
+        LoggedValue::ObjData { obj, data } => {
+            w.write_u8(12);
+            w.write_u64_le(obj.host_type.0);
+            w.write_uleb128_u64(obj.handle.0);
+            write_len(w, data.len());
+            w.write_bytes(data);
+        }
+        LoggedValue::Agg(agg) => {
+            w.write_u8(9);
+            write_agg(w, agg);
+        }
+        LoggedValue::Func(f) => {
+            w.write_u8(10);
+            w.write_uleb128_u32(f.0);
+        }
+        LoggedValue::Closure { func, env } => {
+            w.write_u8(11);
+            w.write_uleb128_u32(func.0);
+            write_agg(w, env);
+        }
+    }
+}
+
+fn read_value(r: &mut Reader<'_>, depth: u32) -> Result<LoggedValue, DecodeError> {
+    Ok(match r.read_u8()? {
+        0 => LoggedValue::Unit,
+        1 => match r.read_u8()? {
+            0 => LoggedValue::Bool(false),
+            1 => LoggedValue::Bool(true),
+            _ => return Err(DecodeError::OutOfBounds),
+        },
+        2 => LoggedValue::I64(r.read_sleb128_i64()?),
+        3 => LoggedValue::U64(r.read_uleb128_u64()?),
+        4 => LoggedValue::F64(r.read_u64_le()?),
+        5 => {
+            let mantissa = r.read_sleb128_i64()?;
+            let scale = r.read_u8()?;
+            LoggedValue::Decimal(Decimal { mantissa, scale })
+        }
+        6 => {
+            let len = read_len(r)?;
+            LoggedValue::Bytes(r.read_bytes(len)?.to_vec())
+        }
+        7 => LoggedValue::Str(read_str(r)?.into()),
+        8 => {
+            let host_type = HostTypeId(r.read_u64_le()?);
+            let handle = ObjHandle(r.read_uleb128_u64()?);
+            LoggedValue::Obj(Obj { host_type, handle })
+        }
+        9 => LoggedValue::Agg(read_agg(r, depth)?),
+        10 => LoggedValue::Func(FuncId(r.read_uleb128_u32()?)),
+        11 => {
+            let func = FuncId(r.read_uleb128_u32()?);
+            let env = read_agg(r, depth)?;
+            LoggedValue::Closure { func, env }
+        }
+        12 => {
+            let host_type = HostTypeId(r.read_u64_le()?);
+            let handle = ObjHandle(r.read_uleb128_u64()?);
+            let len = read_len(r)?;
+            LoggedValue::ObjData {
+                obj: Obj { host_type, handle },
+                data: r.read_bytes(len)?.to_vec(),
+            }
+        }
+        _ => return Err(DecodeError::OutOfBounds),
+    })
+}
+
+fn write_agg(w: &mut Writer, agg: &LoggedAgg) {
+    w.write_uleb128_u32(agg.handle.0);
+    match agg.agg_type {
+        AggType::Tuple { arity } => {
+            w.write_u8(0);
+            w.write_uleb128_u32(arity);
+        }
+        AggType::Struct { type_id } => {
+            w.write_u8(1);
+            w.write_uleb128_u32(type_id.0);
+        }
+        AggType::Array { elem_type_id } => {
+            w.write_u8(2);
+            w.write_uleb128_u32(elem_type_id.0);
+        }
+    }
+    write_values(w, &agg.elems);
+}
+
+fn read_agg(r: &mut Reader<'_>, depth: u32) -> Result<LoggedAgg, DecodeError> {
+    if depth >= MAX_DECODE_DEPTH {
+        return Err(DecodeError::OutOfBounds);
+    }
+    let handle = AggHandle(r.read_uleb128_u32()?);
+    let agg_type = match r.read_u8()? {
+        0 => AggType::Tuple {
+            arity: r.read_uleb128_u32()?,
+        },
+        1 => AggType::Struct {
+            type_id: TypeId(r.read_uleb128_u32()?),
+        },
+        2 => AggType::Array {
+            elem_type_id: ElemTypeId(r.read_uleb128_u32()?),
+        },
+        _ => return Err(DecodeError::OutOfBounds),
+    };
+    let elems = read_values_at_depth(r, depth + 1)?;
+    Ok(LoggedAgg {
+        handle,
+        agg_type,
+        elems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::HostSig;
+    use crate::program::ValueType;
+    use crate::trace::TraceMask;
+    use crate::verifier::VerifiedProgram;
+    use crate::vm::{ExecutionContext, Limits, Trap, Vm};
+    use alloc::vec;
+
+    /// Sums a tuple argument and records one host-state read.
+    struct SumHost {
+        bias: i64,
+    }
+
+    impl Host for SumHost {
+        fn call(
+            &mut self,
+            symbol: &str,
+            sig_hash: SigHash,
+            args: &[ValueRef<'_>],
+            rets: &mut [Value],
+            mut ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            if symbol != "sum" {
+                return Err(HostError::UnknownSymbol);
+            }
+            let [ValueRef::Agg(tuple)] = args else {
+                return Err(HostError::Failed);
+            };
+            let mut total = self.bias;
+            for i in 0..ctx.tuple_len(*tuple).map_err(|_| HostError::Failed)? {
+                let Ok(Value::I64(v)) = ctx.tuple_get(*tuple, i) else {
+                    return Err(HostError::Failed);
+                };
+                total += v;
+            }
+            ctx.record_read(ResourceKeyRef::HostState {
+                op: sig_hash,
+                key: 1,
+            });
+            rets[0] = Value::I64(total);
+            Ok(2)
+        }
+    }
+
+    #[derive(Default)]
+    struct Reads(Vec<LoggedKey>);
+
+    impl AccessSink for Reads {
+        fn read(&mut self, key: ResourceKeyRef<'_>) {
+            self.0.push(LoggedKey::from_key_ref(key));
+        }
+
+        fn write(&mut self, _key: ResourceKeyRef<'_>) {}
+    }
+
+    fn sum_program() -> VerifiedProgram {
+        let mut pb = ProgramBuilder::new();
+        let host_sig = pb.host_sig_for(
+            "sum",
+            HostSig {
+                args: vec![ValueType::Agg],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let mut a = Asm::new();
+        a.tuple_new(2, &[1, 1]);
+        a.host_call(0, host_sig, 0, &[2], &[3]);
+        a.ret(0, &[3]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::I64],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        pb.build_verified().unwrap()
+    }
+
+    fn run<H: Host>(
+        vm: &mut Vm<H>,
+        p: &VerifiedProgram,
+        arg: i64,
+        reads: &mut Reads,
+    ) -> Result<Vec<Value>, crate::vm::TrapInfo> {
+        let mut ctx = ExecutionContext::new();
+        vm.run_with_ctx(
+            &mut ctx,
+            p,
+            FuncId(0),
+            &[Value::I64(arg)],
+            TraceMask::NONE,
+            None,
+            Some(reads),
+        )
+    }
+
+    #[test]
+    fn recorded_log_round_trips_and_replays() {
+        let p = sum_program();
+        let mut vm = Vm::new(RecordingHost::new(SumHost { bias: 100 }), Limits::default());
+        let mut reads = Reads::default();
+        let out = run(&mut vm, &p, 5, &mut reads).unwrap();
+        assert_eq!(out, vec![Value::I64(110)]);
+        assert_eq!(reads.0.len(), 1, "accesses are forwarded while recording");
+
+        let log = vm.host_mut().take_log();
+        let [record] = log.records.as_slice() else {
+            panic!("expected one recorded call, got {:?}", log.records);
+        };
+        assert_eq!(&*record.symbol, "sum");
+        assert_eq!(record.result, Ok(2));
+        let [LoggedValue::Agg(agg)] = record.args.as_slice() else {
+            panic!("expected an aggregate argument, got {:?}", record.args);
+        };
+        assert_eq!(agg.elems, vec![LoggedValue::I64(5), LoggedValue::I64(5)]);
+
+        let decoded = HostCallLog::decode(&log.encode()).unwrap();
+        assert_eq!(decoded, log);
+
+        // Replaying reproduces the host result (including the bias that only the original host
+        // knew about) and re-emits the recorded access.
+        let mut vm = Vm::new(ReplayHost::new(decoded), Limits::default());
+        let mut replay_reads = Reads::default();
+        let out = run(&mut vm, &p, 5, &mut replay_reads).unwrap();
+        assert_eq!(out, vec![Value::I64(110)]);
+        assert_eq!(replay_reads.0, reads.0);
+        assert_eq!(vm.host().finish(), Ok(()));
+    }
+
+    #[test]
+    fn replay_rejects_divergent_arguments() {
+        let p = sum_program();
+        let mut vm = Vm::new(RecordingHost::new(SumHost { bias: 0 }), Limits::default());
+        run(&mut vm, &p, 5, &mut Reads::default()).unwrap();
+        let log = vm.host_mut().take_log();
+
+        let mut vm = Vm::new(ReplayHost::new(log), Limits::default());
+        let err = run(&mut vm, &p, 6, &mut Reads::default()).unwrap_err();
+        assert!(
+            matches!(err.trap, Trap::HostCallFailed { .. }),
+            "unexpected trap: {err}"
+        );
+        assert_eq!(
+            vm.host().divergence(),
+            Some(&ReplayError::ArgsMismatch { call: 0 })
+        );
+        assert_eq!(
+            vm.host().finish(),
+            Err(ReplayError::ArgsMismatch { call: 0 })
+        );
+    }
+
+    #[test]
+    fn decode_rejects_bad_header() {
+        let mut bytes = HostCallLog::new().encode();
+        assert_eq!(HostCallLog::decode(&bytes), Ok(HostCallLog::new()));
+
+        bytes[8] = 9;
+        assert_eq!(
+            HostCallLog::decode(&bytes),
+            Err(DecodeError::UnsupportedVersion { major: 9, minor: 1 })
+        );
+        bytes[0] = b'X';
+        assert_eq!(HostCallLog::decode(&bytes), Err(DecodeError::BadMagic));
+    }
+
+    #[test]
+    fn host_types_capture_and_recreate_objects() {
+        extern crate std;
+
+        use crate::host_types::HostType;
+        use std::sync::Mutex;
+
+        const CELL: HostTypeId = HostTypeId(1);
+        type Cells = Arc<Mutex<Vec<i64>>>;
+
+        /// `make(i64) -> Obj` stores a cell; `read(Obj) -> i64` loads it.
+        struct CellHost(Cells);
+
+        impl Host for CellHost {
+            fn call(
+                &mut self,
+                symbol: &str,
+                _sig_hash: SigHash,
+                args: &[ValueRef<'_>],
+                rets: &mut [Value],
+                _ctx: HostContext<'_, '_>,
+            ) -> Result<u64, HostError> {
+                let mut cells = self.0.lock().unwrap();
+                rets[0] = match (symbol, args) {
+                    ("make", [ValueRef::I64(v)]) => {
+                        cells.push(*v);
+                        Value::Obj(Obj {
+                            host_type: CELL,
+                            handle: ObjHandle(cells.len() as u64 - 1),
+                        })
+                    }
+                    ("read", [ValueRef::Obj(o)]) => {
+                        Value::I64(cells[usize::try_from(o.handle.0).unwrap()])
+                    }
+                    _ => return Err(HostError::UnknownSymbol),
+                };
+                Ok(0)
+            }
+        }
+
+        fn types(cells: &Cells) -> Arc<HostTypeRegistry> {
+            let (ser, de) = (Arc::clone(cells), Arc::clone(cells));
+            let mut types = HostTypeRegistry::new();
+            types
+                .register(
+                    CELL,
+                    HostType::new("cell")
+                        .with_serialize(move |h, out| {
+                            out.extend_from_slice(
+                                &ser.lock().unwrap()[usize::try_from(h.0).unwrap()].to_le_bytes(),
+                            );
+                            Ok(())
+                        })
+                        .with_deserialize(move |bytes| {
+                            let v = bytes.try_into().map_err(|_| HostError::Failed)?;
+                            let mut cells = de.lock().unwrap();
+                            cells.push(i64::from_le_bytes(v));
+                            Ok(ObjHandle(cells.len() as u64 - 1))
+                        }),
+                )
+                .unwrap();
+            Arc::new(types)
+        }
+
+        let mut pb = ProgramBuilder::new();
+        let make = pb.host_sig_for(
+            "make",
+            HostSig {
+                args: vec![ValueType::I64],
+                rets: vec![ValueType::Obj(CELL)],
+            },
+        );
+        let read = pb.host_sig_for(
+            "read",
+            HostSig {
+                args: vec![ValueType::Obj(CELL)],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let mut a = Asm::new();
+        a.host_call(0, make, 0, &[1], &[2]);
+        a.host_call(0, read, 0, &[2], &[3]);
+        a.ret(0, &[3]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::I64],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        let p = pb.build_verified().unwrap();
+
+        let cells = Cells::default();
+        let mut host = RecordingHost::new(CellHost(Arc::clone(&cells)));
+        host.set_host_types(Some(types(&cells)));
+        let mut vm = Vm::new(host, Limits::default());
+        let out = run(&mut vm, &p, 42, &mut Reads::default()).unwrap();
+        assert_eq!(out, vec![Value::I64(42)]);
+        let log = HostCallLog::decode(&vm.host_mut().take_log().encode()).unwrap();
+        assert_eq!(
+            log.records[0].rets,
+            vec![LoggedValue::ObjData {
+                obj: Obj {
+                    host_type: CELL,
+                    handle: ObjHandle(0),
+                },
+                data: 42_i64.to_le_bytes().to_vec(),
+            }]
+        );
+
+        // The replaying process recreates the object under a different handle; the `read`
+        // argument still matches the recording by contents.
+        let replay_cells: Cells = Arc::new(Mutex::new(vec![7, 8]));
+        let mut host = ReplayHost::new(log);
+        host.set_host_types(Some(types(&replay_cells)));
+        let mut vm = Vm::new(host, Limits::default());
+        let out = run(&mut vm, &p, 42, &mut Reads::default()).unwrap();
+        assert_eq!(out, vec![Value::I64(42)]);
+        assert_eq!(vm.host().finish(), Ok(()));
+        assert_eq!(*replay_cells.lock().unwrap(), vec![7, 8, 42]);
+    }
+
+    #[test]
+    fn uncaptured_arguments_are_served_and_replayed() {
+        use crate::host_types::HostType;
+
+        const OPAQUE: HostTypeId = HostTypeId(1);
+
+        /// `peek(Obj) -> i64` returns the object's handle.
+        struct PeekHost;
+
+        impl Host for PeekHost {
+            fn call(
+                &mut self,
+                _symbol: &str,
+                _sig_hash: SigHash,
+                args: &[ValueRef<'_>],
+                rets: &mut [Value],
+                _ctx: HostContext<'_, '_>,
+            ) -> Result<u64, HostError> {
+                let [ValueRef::Obj(o)] = args else {
+                    return Err(HostError::Failed);
+                };
+                rets[0] = Value::I64(i64::try_from(o.handle.0).unwrap());
+                Ok(0)
+            }
+        }
+
+        let mut types = HostTypeRegistry::new();
+        types
+            .register(
+                OPAQUE,
+                HostType::new("opaque").with_serialize(|_, _| Err(HostError::Failed)),
+            )
+            .unwrap();
+        let types = Arc::new(types);
+
+        let mut pb = ProgramBuilder::new();
+        let peek = pb.host_sig_for(
+            "peek",
+            HostSig {
+                args: vec![ValueType::Obj(OPAQUE)],
+                rets: vec![ValueType::I64],
+            },
+        );
+        let mut a = Asm::new();
+        a.host_call(0, peek, 0, &[1], &[2]);
+        a.ret(0, &[2]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::Obj(OPAQUE)],
+                ret_types: vec![ValueType::I64],
+            },
+        )
+        .unwrap();
+        let p = pb.build_verified().unwrap();
+        let arg = [Value::Obj(Obj {
+            host_type: OPAQUE,
+            handle: ObjHandle(7),
+        })];
+
+        // The wrapped host still serves the call; the record notes that the argument could not
+        // be captured.
+        let mut host = RecordingHost::new(PeekHost);
+        host.set_host_types(Some(Arc::clone(&types)));
+        let mut vm = Vm::new(host, Limits::default());
+        let out = vm.run(&p, FuncId(0), &arg, TraceMask::NONE, None).unwrap();
+        assert_eq!(out, vec![Value::I64(7)]);
+        let log = HostCallLog::decode(&vm.host_mut().take_log().encode()).unwrap();
+        let [record] = log.records.as_slice() else {
+            panic!("expected one recorded call, got {:?}", log.records);
+        };
+        assert!(!record.args_captured);
+        assert!(record.args.is_empty());
+        assert_eq!(record.result, Ok(0));
+
+        let mut host = ReplayHost::new(log.clone());
+        host.set_host_types(Some(types));
+        let mut vm = Vm::new(host, Limits::default());
+        let out = vm.run(&p, FuncId(0), &arg, TraceMask::NONE, None).unwrap();
+        assert_eq!(out, vec![Value::I64(7)]);
+        assert_eq!(vm.host().finish(), Ok(()));
+
+        // Without the registry the argument is captured by handle, which the record cannot match.
+        let mut vm = Vm::new(ReplayHost::new(log), Limits::default());
+        vm.run(&p, FuncId(0), &arg, TraceMask::NONE, None)
+            .unwrap_err();
+        assert_eq!(
+            vm.host().finish(),
+            Err(ReplayError::ArgsMismatch { call: 0 })
+        );
+    }
+}
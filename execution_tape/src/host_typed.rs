@@ -0,0 +1,303 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Typed host functions with automatic argument/return marshalling.
+//!
+//! [`HostArg`] and [`HostRet`] map Rust types to [`ValueType`]s. A closure over such types
+//! implements [`HostFunction`], which derives the host signature from the closure's parameter and
+//! return types and converts between [`ValueRef`]/[`Value`] and Rust values at call time. Typed
+//! functions are registered with [`HostLinker::register`](crate::host_linker::HostLinker::register)
+//! and [`HostLinker::register_with_state`](crate::host_linker::HostLinker::register_with_state).
+//!
+//! | Rust type | [`ValueType`] |
+//! |---|---|
+//! | `bool` | `Bool` |
+//! | `i64` | `I64` |
+//! | `u64` | `U64` |
+//! | `f64` | `F64` |
+//! | [`Decimal`] | `Decimal` |
+//! | `&[u8]` (argument), `Vec<u8>` (return) | `Bytes` |
+//! | `&str` (argument), `String` (return) | `Str` |
+//! | [`AggHandle`] | `Agg` |
+//! | [`FuncId`] | `Func` |
+//! | [`Closure`] | `Closure` |
+//!
+//! A function returning `()` has no return values and one returning a tuple `(A, B, ..)` has one
+//! return value per element. Returning `Result<R, HostError>` makes the call fallible.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::host::{HostError, ValueRef};
+use crate::program::ValueType;
+use crate::value::{AggHandle, Closure, Decimal, FuncId, Value};
+
+/// A Rust type that can be received as a host-call argument.
+///
+/// Borrowing types are implemented for any lifetime, so that `|s: &str| ...` closures accept
+/// arguments borrowed from VM registers for the duration of the call.
+pub trait HostArg {
+    /// The type handed to the host function for arguments borrowed for `'a`.
+    type Item<'a>;
+
+    /// The argument's value type.
+    const VALUE_TYPE: ValueType;
+
+    /// Converts a call argument, returning `None` on a type mismatch.
+    fn from_value_ref(value: ValueRef<'_>) -> Option<Self::Item<'_>>;
+}
+
+/// A Rust type that can be produced as a single host-call return value.
+pub trait HostRet {
+    /// The return value's type.
+    const VALUE_TYPE: ValueType;
+
+    /// Converts into a VM value.
+    fn into_value(self) -> Value;
+}
+
+macro_rules! impl_host_value {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl HostArg for $ty {
+                type Item<'a> = $ty;
+                const VALUE_TYPE: ValueType = ValueType::$variant;
+
+                fn from_value_ref(value: ValueRef<'_>) -> Option<Self> {
+                    match value {
+                        ValueRef::$variant(v) => Some(v),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl HostRet for $ty {
+                const VALUE_TYPE: ValueType = ValueType::$variant;
+
+                fn into_value(self) -> Value {
+                    Value::$variant(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_host_value!(
+    bool => Bool,
+    i64 => I64,
+    u64 => U64,
+    f64 => F64,
+    Decimal => Decimal,
+    AggHandle => Agg,
+    FuncId => Func,
+    Closure => Closure,
+);
+
+impl HostArg for &str {
+    type Item<'a> = &'a str;
+    const VALUE_TYPE: ValueType = ValueType::Str;
+
+    fn from_value_ref(value: ValueRef<'_>) -> Option<&str> {
+        match value {
+            ValueRef::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl HostArg for &[u8] {
+    type Item<'a> = &'a [u8];
+    const VALUE_TYPE: ValueType = ValueType::Bytes;
+
+    fn from_value_ref(value: ValueRef<'_>) -> Option<&[u8]> {
+        match value {
+            ValueRef::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl HostRet for String {
+    const VALUE_TYPE: ValueType = ValueType::Str;
+
+    fn into_value(self) -> Value {
+        Value::Str(self)
+    }
+}
+
+impl HostRet for Vec<u8> {
+    const VALUE_TYPE: ValueType = ValueType::Bytes;
+
+    fn into_value(self) -> Value {
+        Value::Bytes(self)
+    }
+}
+
+/// The full return of a host function: zero or more [`HostRet`] values, optionally fallible.
+pub trait HostRets {
+    /// The return types, in order.
+    fn ret_types() -> Vec<ValueType>;
+
+    /// Writes the return values into `rets`.
+    fn write_rets(self, rets: &mut [Value]) -> Result<(), HostError>;
+}
+
+impl<T: HostRet> HostRets for T {
+    fn ret_types() -> Vec<ValueType> {
+        vec![T::VALUE_TYPE]
+    }
+
+    fn write_rets(self, rets: &mut [Value]) -> Result<(), HostError> {
+        let [slot] = rets else {
+            return Err(HostError::SignatureMismatch);
+        };
+        *slot = self.into_value();
+        Ok(())
+    }
+}
+
+impl<T: HostRets> HostRets for Result<T, HostError> {
+    fn ret_types() -> Vec<ValueType> {
+        T::ret_types()
+    }
+
+    fn write_rets(self, rets: &mut [Value]) -> Result<(), HostError> {
+        self?.write_rets(rets)
+    }
+}
+
+macro_rules! impl_host_rets_tuple {
+    ($n:literal; $($ix:tt $r:ident),*) => {
+        impl<$($r: HostRet),*> HostRets for ($($r,)*) {
+            fn ret_types() -> Vec<ValueType> {
+                vec![$($r::VALUE_TYPE),*]
+            }
+
+            fn write_rets(self, rets: &mut [Value]) -> Result<(), HostError> {
+                if rets.len() != $n {
+                    return Err(HostError::SignatureMismatch);
+                }
+                $(rets[$ix] = self.$ix.into_value();)*
+                Ok(())
+            }
+        }
+    };
+}
+
+impl HostRets for () {
+    fn ret_types() -> Vec<ValueType> {
+        Vec::new()
+    }
+
+    fn write_rets(self, rets: &mut [Value]) -> Result<(), HostError> {
+        if rets.is_empty() {
+            Ok(())
+        } else {
+            Err(HostError::SignatureMismatch)
+        }
+    }
+}
+
+impl_host_rets_tuple!(1; 0 R0);
+impl_host_rets_tuple!(2; 0 R0, 1 R1);
+impl_host_rets_tuple!(3; 0 R0, 1 R1, 2 R2);
+impl_host_rets_tuple!(4; 0 R0, 1 R1, 2 R2, 3 R3);
+
+/// A Rust callable usable as a host function operating on embedder state `S`.
+///
+/// Implemented for closures of up to six [`HostArg`] parameters returning [`HostRets`]. `Marker`
+/// only disambiguates the blanket implementations and is inferred: `fn(A, ..) -> R` for
+/// stateless closures, and [`WithState`] for closures taking `&mut S` first.
+pub trait HostFunction<S, Marker>: 'static {
+    /// The argument types, in order.
+    fn arg_types() -> Vec<ValueType>;
+
+    /// The return types, in order.
+    fn ret_types() -> Vec<ValueType>;
+
+    /// Converts `args`, calls the function and writes its results into `rets`.
+    fn call(
+        &mut self,
+        state: &mut S,
+        args: &[ValueRef<'_>],
+        rets: &mut [Value],
+    ) -> Result<(), HostError>;
+}
+
+/// [`HostFunction`] marker for closures that take `&mut S` as their first parameter.
+#[derive(Debug)]
+pub struct WithState<F>(PhantomData<F>);
+
+macro_rules! impl_host_function {
+    ($($a:ident),*) => {
+        impl<S, F, $($a,)* R> HostFunction<S, fn($($a,)*) -> R> for F
+        where
+            F: FnMut($($a,)*) -> R + for<'a> FnMut($($a::Item<'a>,)*) -> R + 'static,
+            $($a: HostArg,)*
+            R: HostRets,
+        {
+            fn arg_types() -> Vec<ValueType> {
+                vec![$($a::VALUE_TYPE),*]
+            }
+
+            fn ret_types() -> Vec<ValueType> {
+                R::ret_types()
+            }
+
+            #[allow(non_snake_case, reason = "macro-generated bindings reuse the type names")]
+            fn call(
+                &mut self,
+                _state: &mut S,
+                args: &[ValueRef<'_>],
+                rets: &mut [Value],
+            ) -> Result<(), HostError> {
+                let [$($a,)*] = args else {
+                    return Err(HostError::SignatureMismatch);
+                };
+                $(let $a = $a::from_value_ref(*$a).ok_or(HostError::SignatureMismatch)?;)*
+                let out: R = (self)($($a,)*);
+                out.write_rets(rets)
+            }
+        }
+
+        impl<S, F, $($a,)* R> HostFunction<S, WithState<fn($($a,)*) -> R>> for F
+        where
+            F: FnMut(&mut S, $($a,)*) -> R + for<'a> FnMut(&mut S, $($a::Item<'a>,)*) -> R + 'static,
+            $($a: HostArg,)*
+            R: HostRets,
+        {
+            fn arg_types() -> Vec<ValueType> {
+                vec![$($a::VALUE_TYPE),*]
+            }
+
+            fn ret_types() -> Vec<ValueType> {
+                R::ret_types()
+            }
+
+            #[allow(non_snake_case, reason = "macro-generated bindings reuse the type names")]
+            fn call(
+                &mut self,
+                state: &mut S,
+                args: &[ValueRef<'_>],
+                rets: &mut [Value],
+            ) -> Result<(), HostError> {
+                let [$($a,)*] = args else {
+                    return Err(HostError::SignatureMismatch);
+                };
+                $(let $a = $a::from_value_ref(*$a).ok_or(HostError::SignatureMismatch)?;)*
+                let out: R = (self)(state, $($a,)*);
+                out.write_rets(rets)
+            }
+        }
+    };
+}
+
+impl_host_function!();
+impl_host_function!(A0);
+impl_host_function!(A0, A1);
+impl_host_function!(A0, A1, A2);
+impl_host_function!(A0, A1, A2, A3);
+impl_host_function!(A0, A1, A2, A3, A4);
+impl_host_function!(A0, A1, A2, A3, A4, A5);
@@ -0,0 +1,621 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Embedder-provided capabilities for host object types.
+//!
+//! [`Obj`] values are opaque to the VM: it cannot compare, hash or serialize them on its own. A
+//! [`HostTypeRegistry`] lets an embedder register, per [`HostTypeId`], callbacks that do. With a
+//! registry, values (including aggregates containing objects) can be compared and hashed
+//! structurally ([`HostTypeRegistry::value_eq`], [`HostTypeRegistry::value_hash`]), encoded with
+//! their objects ([`encode_value_with_types`](crate::value_codec::encode_value_with_types)), and
+//! [`RecordingHost`](crate::host_replay::RecordingHost) can capture object contents so that logs
+//! replay against freshly created objects.
+//!
+//! Every capability is optional. Using one that was not registered fails with
+//! [`HostTypeError::Unsupported`], matching the v1 behaviour of treating objects as opaque.
+//!
+//! # Example
+//! ```
+//! use execution_tape::host_types::{HostType, HostTypeRegistry};
+//! use execution_tape::program::HostTypeId;
+//! use execution_tape::value::{Obj, ObjHandle};
+//!
+//! // Handles are interned, so equal handles mean equal objects.
+//! let mut types = HostTypeRegistry::new();
+//! types
+//!     .register(
+//!         HostTypeId(1),
+//!         HostType::new("symbol")
+//!             .with_eq(|a, b| a == b)
+//!             .with_hash(|h| h.0),
+//!     )
+//!     .unwrap();
+//!
+//! let obj = |handle| Obj {
+//!     host_type: HostTypeId(1),
+//!     handle: ObjHandle(handle),
+//! };
+//! assert_eq!(types.obj_eq(obj(3), obj(3)), Ok(true));
+//! assert_eq!(types.obj_eq(obj(3), obj(4)), Ok(false));
+//! assert!(types.serialize_obj(obj(3), &mut Vec::new()).is_err());
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::aggregates::{AggError, AggHeap};
+use crate::host::{Fnv1a64, HostError};
+use crate::program::HostTypeId;
+use crate::value::{AggHandle, AggType, Obj, ObjHandle, Value};
+
+type EqFn = Box<dyn Fn(ObjHandle, ObjHandle) -> bool + Send + Sync>;
+type HashFn = Box<dyn Fn(ObjHandle) -> u64 + Send + Sync>;
+type SerializeFn = Box<dyn Fn(ObjHandle, &mut Vec<u8>) -> Result<(), HostError> + Send + Sync>;
+type DeserializeFn = Box<dyn Fn(&[u8]) -> Result<ObjHandle, HostError> + Send + Sync>;
+
+/// The capabilities of one host object type.
+///
+/// Callbacks are shared (`Fn + Send + Sync`) so a registry can be shared across threads;
+/// embedders that allocate objects while deserializing use interior mutability.
+pub struct HostType {
+    name: Box<str>,
+    eq: Option<EqFn>,
+    hash: Option<HashFn>,
+    serialize: Option<SerializeFn>,
+    deserialize: Option<DeserializeFn>,
+}
+
+impl fmt::Debug for HostType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostType")
+            .field("name", &self.name)
+            .field("eq", &self.eq.is_some())
+            .field("hash", &self.hash.is_some())
+            .field("serialize", &self.serialize.is_some())
+            .field("deserialize", &self.deserialize.is_some())
+            .finish()
+    }
+}
+
+impl HostType {
+    /// Creates a type named `name` (used in diagnostics) with no capabilities.
+    #[must_use]
+    pub fn new(name: impl Into<Box<str>>) -> Self {
+        Self {
+            name: name.into(),
+            eq: None,
+            hash: None,
+            serialize: None,
+            deserialize: None,
+        }
+    }
+
+    /// Sets the equality callback.
+    ///
+    /// It is only called for distinct handles; an object always equals itself.
+    #[must_use]
+    pub fn with_eq(
+        mut self,
+        eq: impl Fn(ObjHandle, ObjHandle) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.eq = Some(Box::new(eq));
+        self
+    }
+
+    /// Sets the hash callback. Objects that compare equal must hash equal.
+    #[must_use]
+    pub fn with_hash(mut self, hash: impl Fn(ObjHandle) -> u64 + Send + Sync + 'static) -> Self {
+        self.hash = Some(Box::new(hash));
+        self
+    }
+
+    /// Sets the serialization callback, which appends the object's contents to the buffer.
+    #[must_use]
+    pub fn with_serialize(
+        mut self,
+        serialize: impl Fn(ObjHandle, &mut Vec<u8>) -> Result<(), HostError> + Send + Sync + 'static,
+    ) -> Self {
+        self.serialize = Some(Box::new(serialize));
+        self
+    }
+
+    /// Sets the deserialization callback, which creates an object from serialized contents.
+    #[must_use]
+    pub fn with_deserialize(
+        mut self,
+        deserialize: impl Fn(&[u8]) -> Result<ObjHandle, HostError> + Send + Sync + 'static,
+    ) -> Self {
+        self.deserialize = Some(Box::new(deserialize));
+        self
+    }
+
+    /// Returns the type's name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the type supports `capability`.
+    #[must_use]
+    pub fn supports(&self, capability: HostTypeCapability) -> bool {
+        match capability {
+            HostTypeCapability::Eq => self.eq.is_some(),
+            HostTypeCapability::Hash => self.hash.is_some(),
+            HostTypeCapability::Serialize => self.serialize.is_some(),
+            HostTypeCapability::Deserialize => self.deserialize.is_some(),
+        }
+    }
+}
+
+/// A capability a [`HostType`] may provide.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HostTypeCapability {
+    /// Equality between objects.
+    Eq,
+    /// Hashing consistent with equality.
+    Hash,
+    /// Serializing an object's contents.
+    Serialize,
+    /// Recreating an object from serialized contents.
+    Deserialize,
+}
+
+impl fmt::Display for HostTypeCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eq => write!(f, "equality"),
+            Self::Hash => write!(f, "hashing"),
+            Self::Serialize => write!(f, "serialization"),
+            Self::Deserialize => write!(f, "deserialization"),
+        }
+    }
+}
+
+/// Errors from host-type operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostTypeError {
+    /// A type was registered twice.
+    Duplicate {
+        /// Host type id.
+        host_type: HostTypeId,
+    },
+    /// The host type is not registered.
+    UnknownType {
+        /// Host type id.
+        host_type: HostTypeId,
+    },
+    /// The host type does not provide the capability.
+    Unsupported {
+        /// Host type id.
+        host_type: HostTypeId,
+        /// Missing capability.
+        capability: HostTypeCapability,
+    },
+    /// A serialization callback failed.
+    Host(HostError),
+    /// An aggregate handle did not resolve.
+    Agg(AggError),
+}
+
+impl fmt::Display for HostTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate { host_type } => {
+                write!(f, "host type {} registered twice", host_type.0)
+            }
+            Self::UnknownType { host_type } => {
+                write!(f, "host type {} is not registered", host_type.0)
+            }
+            Self::Unsupported {
+                host_type,
+                capability,
+            } => write!(f, "host type {} does not support {capability}", host_type.0),
+            Self::Host(e) => write!(f, "host type callback failed: {e}"),
+            Self::Agg(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for HostTypeError {}
+
+impl From<AggError> for HostTypeError {
+    fn from(e: AggError) -> Self {
+        Self::Agg(e)
+    }
+}
+
+/// Registered [`HostType`]s, keyed by [`HostTypeId`].
+#[derive(Debug, Default)]
+pub struct HostTypeRegistry {
+    types: BTreeMap<HostTypeId, HostType>,
+}
+
+impl HostTypeRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the capabilities of `host_type`. Each type may be registered once.
+    pub fn register(
+        &mut self,
+        host_type: HostTypeId,
+        ty: HostType,
+    ) -> Result<&mut Self, HostTypeError> {
+        if self.types.contains_key(&host_type) {
+            return Err(HostTypeError::Duplicate { host_type });
+        }
+        self.types.insert(host_type, ty);
+        Ok(self)
+    }
+
+    /// Returns the registered type, if any.
+    #[must_use]
+    pub fn get(&self, host_type: HostTypeId) -> Option<&HostType> {
+        self.types.get(&host_type)
+    }
+
+    /// Returns `true` if `host_type` is registered and supports `capability`.
+    #[must_use]
+    pub fn supports(&self, host_type: HostTypeId, capability: HostTypeCapability) -> bool {
+        self.get(host_type)
+            .is_some_and(|ty| ty.supports(capability))
+    }
+
+    /// Compares two objects.
+    ///
+    /// Objects of different types are never equal, and an object always equals itself; otherwise
+    /// the type's equality callback decides.
+    pub fn obj_eq(&self, a: Obj, b: Obj) -> Result<bool, HostTypeError> {
+        if a.host_type != b.host_type {
+            return Ok(false);
+        }
+        if a.handle == b.handle {
+            return Ok(true);
+        }
+        let eq = self.capability(a.host_type, HostTypeCapability::Eq, |ty| ty.eq.as_ref())?;
+        Ok(eq(a.handle, b.handle))
+    }
+
+    /// Hashes an object, including its type.
+    pub fn obj_hash(&self, obj: Obj) -> Result<u64, HostTypeError> {
+        let mut h = Fnv1a64::new();
+        self.hash_obj(&mut h, obj)?;
+        Ok(h.finish())
+    }
+
+    /// Appends the serialized contents of `obj` to `out`.
+    pub fn serialize_obj(&self, obj: Obj, out: &mut Vec<u8>) -> Result<(), HostTypeError> {
+        let serialize = self.capability(obj.host_type, HostTypeCapability::Serialize, |ty| {
+            ty.serialize.as_ref()
+        })?;
+        serialize(obj.handle, out).map_err(HostTypeError::Host)
+    }
+
+    /// Creates an object of `host_type` from serialized contents.
+    pub fn deserialize_obj(
+        &self,
+        host_type: HostTypeId,
+        bytes: &[u8],
+    ) -> Result<Obj, HostTypeError> {
+        let deserialize = self.capability(host_type, HostTypeCapability::Deserialize, |ty| {
+            ty.deserialize.as_ref()
+        })?;
+        let handle = deserialize(bytes).map_err(HostTypeError::Host)?;
+        Ok(Obj { host_type, handle })
+    }
+
+    /// Compares two values structurally.
+    ///
+    /// Aggregates and closure environments are compared by contents read from `aggregates`,
+    /// floats by their bits, and objects with [`HostTypeRegistry::obj_eq`].
+    pub fn value_eq(
+        &self,
+        aggregates: &AggHeap,
+        a: &Value,
+        b: &Value,
+    ) -> Result<bool, HostTypeError> {
+        Ok(match (a, b) {
+            (Value::F64(a), Value::F64(b)) => a.to_bits() == b.to_bits(),
+            (Value::Obj(a), Value::Obj(b)) => self.obj_eq(*a, *b)?,
+            (Value::Agg(a), Value::Agg(b)) => self.agg_eq(aggregates, *a, *b)?,
+            (Value::Closure(a), Value::Closure(b)) => {
+                a.func == b.func && self.agg_eq(aggregates, a.env, b.env)?
+            }
+            (Value::F64(_) | Value::Obj(_) | Value::Agg(_) | Value::Closure(_), _) => false,
+            (a, b) => a == b,
+        })
+    }
+
+    /// Hashes a value structurally, consistently with [`HostTypeRegistry::value_eq`].
+    pub fn value_hash(&self, aggregates: &AggHeap, value: &Value) -> Result<u64, HostTypeError> {
+        let mut h = Fnv1a64::new();
+        self.hash_value(&mut h, aggregates, value)?;
+        Ok(h.finish())
+    }
+
+    fn capability<'a, T>(
+        &'a self,
+        host_type: HostTypeId,
+        capability: HostTypeCapability,
+        get: impl FnOnce(&'a HostType) -> Option<&'a T>,
+    ) -> Result<&'a T, HostTypeError> {
+        let ty = self
+            .get(host_type)
+            .ok_or(HostTypeError::UnknownType { host_type })?;
+        get(ty).ok_or(HostTypeError::Unsupported {
+            host_type,
+            capability,
+        })
+    }
+
+    fn agg_eq(
+        &self,
+        aggregates: &AggHeap,
+        a: AggHandle,
+        b: AggHandle,
+    ) -> Result<bool, HostTypeError> {
+        if a == b {
+            return Ok(true);
+        }
+        let agg_type = aggregates.agg_type(a)?;
+        if aggregates.agg_type(b)? != agg_type {
+            return Ok(false);
+        }
+        let len = agg_len(aggregates, agg_type, a)?;
+        if agg_len(aggregates, agg_type, b)? != len {
+            return Ok(false);
+        }
+        for index in 0..len {
+            let x = agg_elem(aggregates, agg_type, a, index)?;
+            let y = agg_elem(aggregates, agg_type, b, index)?;
+            if !self.value_eq(aggregates, x, y)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn hash_obj(&self, h: &mut Fnv1a64, obj: Obj) -> Result<(), HostTypeError> {
+        let hash = self.capability(obj.host_type, HostTypeCapability::Hash, |ty| {
+            ty.hash.as_ref()
+        })?;
+        h.update(&obj.host_type.0.to_le_bytes());
+        h.update(&hash(obj.handle).to_le_bytes());
+        Ok(())
+    }
+
+    fn hash_value(
+        &self,
+        h: &mut Fnv1a64,
+        aggregates: &AggHeap,
+        value: &Value,
+    ) -> Result<(), HostTypeError> {
+        match value {
+            Value::Unit => h.update(&[0]),
+            Value::Bool(b) => h.update(&[1, u8::from(*b)]),
+            Value::I64(i) => {
+                h.update(&[2]);
+                h.update(&i.to_le_bytes());
+            }
+            Value::U64(u) => {
+                h.update(&[3]);
+                h.update(&u.to_le_bytes());
+            }
+            Value::F64(f) => {
+                h.update(&[4]);
+                h.update(&f.to_bits().to_le_bytes());
+            }
+            Value::Decimal(d) => {
+                h.update(&[5]);
+                h.update(&d.mantissa.to_le_bytes());
+                h.update(&[d.scale]);
+            }
+            Value::Bytes(b) => {
+                h.update(&[6]);
+                h.update(&(b.len() as u64).to_le_bytes());
+                h.update(b);
+            }
+            Value::Str(s) => {
+                h.update(&[7]);
+                h.update(&(s.len() as u64).to_le_bytes());
+                h.update(s.as_bytes());
+            }
+            Value::Obj(o) => {
+                h.update(&[8]);
+                self.hash_obj(h, *o)?;
+            }
+            Value::Agg(handle) => {
+                h.update(&[9]);
+                self.hash_agg(h, aggregates, *handle)?;
+            }
+            Value::Func(f) => {
+                h.update(&[10]);
+                h.update(&f.0.to_le_bytes());
+            }
+            Value::Closure(c) => {
+                h.update(&[11]);
+                h.update(&c.func.0.to_le_bytes());
+                self.hash_agg(h, aggregates, c.env)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn hash_agg(
+        &self,
+        h: &mut Fnv1a64,
+        aggregates: &AggHeap,
+        handle: AggHandle,
+    ) -> Result<(), HostTypeError> {
+        let agg_type = aggregates.agg_type(handle)?;
+        match agg_type {
+            AggType::Tuple { arity } => {
+                h.update(&[0]);
+                h.update(&arity.to_le_bytes());
+            }
+            AggType::Struct { type_id } => {
+                h.update(&[1]);
+                h.update(&type_id.0.to_le_bytes());
+            }
+            AggType::Array { elem_type_id } => {
+                h.update(&[2]);
+                h.update(&elem_type_id.0.to_le_bytes());
+            }
+        }
+        let len = agg_len(aggregates, agg_type, handle)?;
+        h.update(&(len as u64).to_le_bytes());
+        for index in 0..len {
+            self.hash_value(
+                h,
+                aggregates,
+                agg_elem(aggregates, agg_type, handle, index)?,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn agg_len(aggregates: &AggHeap, agg_type: AggType, handle: AggHandle) -> Result<usize, AggError> {
+    match agg_type {
+        AggType::Tuple { .. } => aggregates.tuple_len(handle),
+        AggType::Struct { .. } => aggregates.struct_field_count(handle),
+        AggType::Array { .. } => aggregates.array_len(handle),
+    }
+}
+
+fn agg_elem(
+    aggregates: &AggHeap,
+    agg_type: AggType,
+    handle: AggHandle,
+    index: usize,
+) -> Result<&Value, AggError> {
+    match agg_type {
+        AggType::Tuple { .. } => aggregates.tuple_get_ref(handle, index),
+        AggType::Struct { .. } => aggregates.struct_get_ref(handle, index),
+        AggType::Array { .. } => aggregates.array_get_ref(handle, index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec;
+    use std::sync::Mutex;
+
+    const POINT: HostTypeId = HostTypeId(7);
+
+    fn obj(handle: u64) -> Obj {
+        Obj {
+            host_type: POINT,
+            handle: ObjHandle(handle),
+        }
+    }
+
+    fn ix(handle: ObjHandle) -> usize {
+        usize::try_from(handle.0).unwrap()
+    }
+
+    /// Objects are points stored in a shared table; handles index the table.
+    fn registry(points: &Arc<Mutex<Vec<(i64, i64)>>>) -> HostTypeRegistry {
+        let eq_points = Arc::clone(points);
+        let hash_points = Arc::clone(points);
+        let ser_points = Arc::clone(points);
+        let de_points = Arc::clone(points);
+        let mut types = HostTypeRegistry::new();
+        types
+            .register(
+                POINT,
+                HostType::new("point")
+                    .with_eq(move |a, b| {
+                        let p = eq_points.lock().unwrap();
+                        p[ix(a)] == p[ix(b)]
+                    })
+                    .with_hash(move |h| {
+                        let (x, y) = hash_points.lock().unwrap()[ix(h)];
+                        x.wrapping_mul(31).wrapping_add(y).cast_unsigned()
+                    })
+                    .with_serialize(move |h, out| {
+                        let (x, y) = *ser_points
+                            .lock()
+                            .unwrap()
+                            .get(ix(h))
+                            .ok_or(HostError::Failed)?;
+                        out.extend_from_slice(&x.to_le_bytes());
+                        out.extend_from_slice(&y.to_le_bytes());
+                        Ok(())
+                    })
+                    .with_deserialize(move |bytes| {
+                        let (x, y) = bytes.split_at_checked(8).ok_or(HostError::Failed)?;
+                        let x = i64::from_le_bytes(x.try_into().map_err(|_| HostError::Failed)?);
+                        let y = i64::from_le_bytes(y.try_into().map_err(|_| HostError::Failed)?);
+                        let mut p = de_points.lock().unwrap();
+                        p.push((x, y));
+                        Ok(ObjHandle(p.len() as u64 - 1))
+                    }),
+            )
+            .unwrap();
+        types
+    }
+
+    #[test]
+    fn structural_eq_and_hash_see_through_objects_in_aggregates() {
+        let points = Arc::new(Mutex::new(vec![(1, 2), (1, 2), (3, 4)]));
+        let types = registry(&points);
+
+        let mut heap = AggHeap::new();
+        let a = heap.tuple_new(vec![Value::Obj(obj(0)), Value::I64(5)]);
+        let b = heap.tuple_new(vec![Value::Obj(obj(1)), Value::I64(5)]);
+        let c = heap.tuple_new(vec![Value::Obj(obj(2)), Value::I64(5)]);
+        let (a, b, c) = (Value::Agg(a), Value::Agg(b), Value::Agg(c));
+
+        assert_eq!(types.value_eq(&heap, &a, &b), Ok(true));
+        assert_eq!(types.value_eq(&heap, &a, &c), Ok(false));
+        assert_eq!(types.value_hash(&heap, &a), types.value_hash(&heap, &b));
+        assert_ne!(types.value_hash(&heap, &a), types.value_hash(&heap, &c));
+
+        let unknown = Value::Obj(Obj {
+            host_type: HostTypeId(99),
+            handle: ObjHandle(0),
+        });
+        assert_eq!(
+            types.value_hash(&heap, &unknown),
+            Err(HostTypeError::UnknownType {
+                host_type: HostTypeId(99)
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_round_trips_and_missing_capabilities_are_reported() {
+        let points = Arc::new(Mutex::new(vec![(3, 4)]));
+        let types = registry(&points);
+
+        let mut bytes = Vec::new();
+        types.serialize_obj(obj(0), &mut bytes).unwrap();
+        let back = types.deserialize_obj(POINT, &bytes).unwrap();
+        assert_eq!(back, obj(1));
+        assert_eq!(types.obj_eq(obj(0), back), Ok(true));
+
+        let mut opaque = HostTypeRegistry::new();
+        opaque.register(POINT, HostType::new("point")).unwrap();
+        assert_eq!(
+            opaque.obj_eq(obj(0), obj(1)),
+            Err(HostTypeError::Unsupported {
+                host_type: POINT,
+                capability: HostTypeCapability::Eq,
+            })
+        );
+        assert_eq!(opaque.obj_eq(obj(0), obj(0)), Ok(true));
+        assert_eq!(
+            opaque.register(POINT, HostType::new("again")).unwrap_err(),
+            HostTypeError::Duplicate { host_type: POINT }
+        );
+    }
+}
@@ -43,8 +43,14 @@ pub(crate) fn visit_func_ids(instr: &Instr, mut f: impl FnMut(FuncId)) {
 
 #[rustfmt::skip]
 pub(crate) fn visit_host_sig_ids(instr: &Instr, mut f: impl FnMut(HostSigId)) {
-    if let Instr::HostCall { host_sig, .. } = instr {
-        f(*host_sig);
+    match instr {
+        Instr::HostCall { host_sig, .. } => {
+            f(*host_sig);
+        }
+        Instr::HostCallPure { host_sig, .. } => {
+            f(*host_sig);
+        }
+        _ => {}
     }
 }
 
@@ -67,22 +67,36 @@
 extern crate alloc;
 
 pub mod aggregates;
-pub(crate) mod analysis;
+pub mod analysis;
 pub(crate) mod arena;
+pub mod artifact;
 pub mod asm;
+pub mod asm_text;
 pub(crate) mod bytecode;
 pub mod codec;
 pub(crate) mod codec_primitives;
+pub mod convert;
 pub mod disasm;
+pub mod entry;
 pub mod format;
 pub mod host;
 pub mod host_catalog;
+pub mod host_linker;
+pub mod host_policy;
+pub mod host_replay;
+pub mod host_typed;
+pub mod host_types;
 pub(crate) mod instr_operands;
+pub mod linker;
 pub mod opcode;
 pub mod program;
 pub mod program_memory;
+pub mod program_ref;
+pub(crate) mod sha256;
+pub mod strip;
 pub mod trace;
 pub(crate) mod typed;
 pub mod value;
+pub mod value_codec;
 pub mod verifier;
 pub mod vm;
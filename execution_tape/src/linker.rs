@@ -0,0 +1,1017 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Linking several programs (modules) into one.
+//!
+//! A [`Program`] is a closed world: `call` targets a [`FuncId`] of the same program. To share
+//! functions between programs, a module exports them by name
+//! ([`ProgramBuilder::export_function`]) and other modules import them
+//! ([`ProgramBuilder::import_function`]). An import is backed by a local placeholder function
+//! with the import's signature, so each module still verifies on its own.
+//!
+//! [`Linker`] merges a set of named modules into a single [`VerifiedProgram`]:
+//! - every import is resolved to the exported function it names (following re-exports), and the
+//!   import's placeholder is dropped,
+//! - [`FuncId`], [`ConstId`], [`SymbolId`], [`TypeId`], [`ElemTypeId`], [`HostSigId`] and
+//!   [`CallSigId`] operands and metadata are remapped into the merged tables,
+//! - identical symbols, constants, struct/array types, host signatures and call signatures are
+//!   deduplicated,
+//! - source maps are merged. [`SpanId`]s are kept as they are, so modules may share a span only if
+//!   they record the same source location for it.
+//!
+//! The merged program has no imports, exports or custom sections. Use
+//! [`LinkedProgram::export`] or [`LinkedProgram::func_id`] to find a module's functions in it.
+//!
+//! # Example
+//! ```
+//! extern crate alloc;
+//!
+//! use alloc::vec;
+//!
+//! use execution_tape::asm::{Asm, BuildError, FunctionSig, ProgramBuilder};
+//! use execution_tape::host::{Host, HostContext, HostError, SigHash, ValueRef};
+//! use execution_tape::linker::Linker;
+//! use execution_tape::program::ValueType;
+//! use execution_tape::trace::TraceMask;
+//! use execution_tape::value::Value;
+//! use execution_tape::vm::{Limits, Vm};
+//!
+//! struct NoHost;
+//!
+//! impl Host for NoHost {
+//!     fn call(
+//!         &mut self,
+//!         _symbol: &str,
+//!         _sig_hash: SigHash,
+//!         _args: &[ValueRef<'_>],
+//!         _rets: &mut [Value],
+//!         _ctx: HostContext<'_, '_>,
+//!     ) -> Result<u64, HostError> {
+//!         Err(HostError::UnknownSymbol)
+//!     }
+//! }
+//!
+//! let sig = FunctionSig {
+//!     arg_types: vec![ValueType::I64],
+//!     ret_types: vec![ValueType::I64],
+//! };
+//!
+//! // lib: `inc(x) = x + 1`, exported as "inc".
+//! let mut lib = ProgramBuilder::new();
+//! let mut a = Asm::new();
+//! a.const_i64(2, 1);
+//! a.i64_add(3, 1, 2);
+//! a.ret(0, &[3]);
+//! let inc = lib.push_function_checked(a, &sig)?;
+//! lib.export_function(inc, "inc")?;
+//!
+//! // app: `main(x) = lib.inc(lib.inc(x))`.
+//! let mut app = ProgramBuilder::new();
+//! let lib_inc = app.import_function("lib", "inc", &sig);
+//! let mut a = Asm::new();
+//! a.call(0, lib_inc, 0, &[1], &[2]);
+//! a.call(0, lib_inc, 0, &[2], &[3]);
+//! a.ret(0, &[3]);
+//! let main = app.push_function_checked(a, &sig)?;
+//! app.export_function(main, "main")?;
+//!
+//! let mut linker = Linker::new();
+//! linker.add_module("lib", lib.build()).unwrap();
+//! linker.add_module("app", app.build()).unwrap();
+//! let linked = linker.link().unwrap();
+//! let main = linked.export("app", "main").unwrap();
+//!
+//! let mut vm = Vm::new(NoHost, Limits::default());
+//! let out = vm.run(linked.program(), main, &[Value::I64(5)], TraceMask::NONE, None);
+//! assert_eq!(out.unwrap(), vec![Value::I64(7)]);
+//! # Ok::<(), BuildError>(())
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::collections::btree_map::Entry;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::num::NonZeroU32;
+
+use crate::asm::{BuildError, ProgramBuilder};
+use crate::codec::{DecodeError, DecodedOperand, rewrite_function};
+use crate::format::DecodeError as FormatDecodeError;
+use crate::host::HostSig;
+use crate::opcode::{Opcode, OperandRole};
+use crate::program::{
+    AggShape, CallSigId, Const, ConstEntry, ConstId, ElemTypeId, FunctionDef, HostSigId, Program,
+    SpanId, SpanInfo, StructTypeDef, SymbolId, TypeId,
+};
+use crate::value::FuncId;
+use crate::verifier::{
+    VerifiedProgram, VerifyConfig, VerifyError, verify_program, verify_program_owned,
+};
+
+/// Trap code raised by the placeholder body of an import that has not been linked.
+///
+/// The value is the ASCII bytes `IMPT` read as a big-endian `u32`.
+pub const UNRESOLVED_IMPORT_TRAP_CODE: u32 = 0x494D_5054;
+
+/// Module linking failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModuleLinkError {
+    /// A module with this name was already added.
+    DuplicateModule {
+        /// Module name.
+        module: String,
+    },
+    /// A module failed verification on its own.
+    InvalidModule {
+        /// Module name.
+        module: String,
+        /// Verifier error for the module.
+        error: VerifyError,
+    },
+    /// An import names a module or export that does not exist.
+    UnresolvedImport {
+        /// Importing module.
+        module: String,
+        /// Module named by the import.
+        import_module: String,
+        /// Export named by the import.
+        name: String,
+    },
+    /// An import's signature differs from the signature of the export it resolves to.
+    ImportSignatureMismatch {
+        /// Importing module.
+        module: String,
+        /// Module named by the import.
+        import_module: String,
+        /// Export named by the import.
+        name: String,
+    },
+    /// An import resolves, through re-exports, back to itself.
+    ImportCycle {
+        /// Importing module.
+        module: String,
+        /// Module named by the import.
+        import_module: String,
+        /// Export named by the import.
+        name: String,
+    },
+    /// A module's bytecode or tables could not be decoded for remapping.
+    Decode {
+        /// Module name.
+        module: String,
+        /// Decode error.
+        error: DecodeError,
+    },
+    /// Two modules record different source locations for the same span.
+    SpanCollision {
+        /// Module whose source map entry conflicts with an earlier module's.
+        module: String,
+        /// The conflicting span.
+        span: SpanId,
+    },
+    /// A module's function metadata could not be carried over into the merged program.
+    Build {
+        /// Module name.
+        module: String,
+        /// Builder error.
+        error: BuildError,
+    },
+    /// The merged program failed verification.
+    Verify(VerifyError),
+}
+
+impl fmt::Display for ModuleLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateModule { module } => write!(f, "duplicate module `{module}`"),
+            Self::InvalidModule { module, error } => write!(f, "module `{module}`: {error}"),
+            Self::UnresolvedImport {
+                module,
+                import_module,
+                name,
+            } => write!(
+                f,
+                "module `{module}` imports `{import_module}.{name}`, which is not exported"
+            ),
+            Self::ImportSignatureMismatch {
+                module,
+                import_module,
+                name,
+            } => write!(
+                f,
+                "module `{module}` imports `{import_module}.{name}` with a different signature"
+            ),
+            Self::ImportCycle {
+                module,
+                import_module,
+                name,
+            } => write!(
+                f,
+                "module `{module}` imports `{import_module}.{name}`, which re-exports itself"
+            ),
+            Self::Decode { module, error } => write!(f, "module `{module}`: {error}"),
+            Self::SpanCollision { module, span } => write!(
+                f,
+                "module `{module}` records a different source location for span {span}"
+            ),
+            Self::Build { module, error } => write!(f, "module `{module}`: {error}"),
+            Self::Verify(e) => write!(f, "linked program failed verification: {e}"),
+        }
+    }
+}
+
+impl core::error::Error for ModuleLinkError {}
+
+#[derive(Clone, Debug)]
+struct Module {
+    name: String,
+    program: Program,
+}
+
+/// Merges named modules into a single program.
+///
+/// See the [module docs](self) for an overview.
+#[derive(Clone, Debug, Default)]
+pub struct Linker {
+    cfg: VerifyConfig,
+    modules: Vec<Module>,
+}
+
+impl Linker {
+    /// Creates an empty linker that verifies with [`VerifyConfig::default`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty linker that verifies modules and the merged program with `cfg`.
+    #[must_use]
+    pub fn with_config(cfg: VerifyConfig) -> Self {
+        Self {
+            cfg,
+            modules: Vec::new(),
+        }
+    }
+
+    /// Adds `program` as module `name`, verifying it on its own.
+    ///
+    /// Modules are laid out in the merged program in the order they are added.
+    pub fn add_module(
+        &mut self,
+        name: &str,
+        program: Program,
+    ) -> Result<&mut Self, ModuleLinkError> {
+        if self.modules.iter().any(|m| m.name == name) {
+            return Err(ModuleLinkError::DuplicateModule {
+                module: name.into(),
+            });
+        }
+        verify_program(&program, &self.cfg).map_err(|error| ModuleLinkError::InvalidModule {
+            module: name.into(),
+            error,
+        })?;
+        self.modules.push(Module {
+            name: name.into(),
+            program,
+        });
+        Ok(self)
+    }
+
+    /// Resolves imports and merges all modules into one verified program.
+    pub fn link(&self) -> Result<LinkedProgram, ModuleLinkError> {
+        let funcs = self.resolve()?;
+
+        let mut pb = ProgramBuilder::new();
+        let mut span_infos: BTreeMap<SpanId, SpanInfo<'_>> = BTreeMap::new();
+        for (m, module) in self.modules.iter().enumerate() {
+            let mut remap = ModuleRemap::new(&module.program);
+            remap
+                .push_functions(&mut pb, &funcs, m)
+                .map_err(|e| match e {
+                    RemapError::Decode(error) => ModuleLinkError::Decode {
+                        module: module.name.clone(),
+                        error,
+                    },
+                    RemapError::Build(error) => ModuleLinkError::Build {
+                        module: module.name.clone(),
+                        error,
+                    },
+                })?;
+
+            let p = &module.program;
+            for e in &p.source_map {
+                let info = p.span_info(e.span).ok_or_else(|| ModuleLinkError::Decode {
+                    module: module.name.clone(),
+                    error: DecodeError::OutOfBounds,
+                })?;
+                match span_infos.entry(e.span) {
+                    Entry::Occupied(prev) if *prev.get() != info => {
+                        return Err(ModuleLinkError::SpanCollision {
+                            module: module.name.clone(),
+                            span: e.span,
+                        });
+                    }
+                    Entry::Occupied(_) => {}
+                    Entry::Vacant(slot) => {
+                        pb.set_span_info(e.span, info.file, info.range, info.node_guid);
+                        slot.insert(info);
+                    }
+                }
+            }
+        }
+
+        let program =
+            verify_program_owned(pb.build(), &self.cfg).map_err(ModuleLinkError::Verify)?;
+
+        let mut modules = Vec::with_capacity(self.modules.len());
+        for (module, funcs) in self.modules.iter().zip(funcs) {
+            let p = &module.program;
+            let exports = p
+                .exports
+                .iter()
+                .filter_map(|e| {
+                    let name = p.symbol_str(e.name).ok()?;
+                    Some((String::from(name), funcs[e.func as usize]))
+                })
+                .collect();
+            modules.push(LinkedModule {
+                name: module.name.clone(),
+                funcs,
+                exports,
+            });
+        }
+        Ok(LinkedProgram { program, modules })
+    }
+
+    /// Assigns merged [`FuncId`]s to every function of every module.
+    ///
+    /// Defined functions are numbered in module order; import placeholders take the id of the
+    /// function they resolve to.
+    fn resolve(&self) -> Result<Vec<Vec<FuncId>>, ModuleLinkError> {
+        let by_name: BTreeMap<&str, usize> = self
+            .modules
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.name.as_str(), i))
+            .collect();
+
+        let mut defined: Vec<Vec<Option<FuncId>>> = Vec::with_capacity(self.modules.len());
+        let mut next = 0_u32;
+        for module in &self.modules {
+            let p = &module.program;
+            let mut ids = vec![None; p.functions.len()];
+            for (f, id) in ids.iter_mut().enumerate() {
+                if !p.imports.iter().any(|e| e.func as usize == f) {
+                    *id = Some(FuncId(next));
+                    next += 1;
+                }
+            }
+            defined.push(ids);
+        }
+
+        let import_count: usize = self.modules.iter().map(|m| m.program.imports.len()).sum();
+        let mut out = defined.clone();
+        for (m, module) in self.modules.iter().enumerate() {
+            for import in &module.program.imports {
+                let (mut at_module, mut at_func) = (m, import.func);
+                let mut hops = 0;
+                let resolved = loop {
+                    if let Some(id) = defined[at_module][at_func as usize] {
+                        break id;
+                    }
+                    let err = self.import_error(m, import.module, import.name);
+                    if hops > import_count {
+                        return Err(err(ImportErrorKind::Cycle));
+                    }
+                    hops += 1;
+                    let here = &self.modules[at_module].program;
+                    let Some(e) = here.imports.iter().find(|e| e.func == at_func) else {
+                        return Err(err(ImportErrorKind::Unresolved));
+                    };
+                    let target_module = here.symbol_str(e.module).unwrap_or_default();
+                    let target_name = here.symbol_str(e.name).unwrap_or_default();
+                    let Some(&target) = by_name.get(target_module) else {
+                        return Err(err(ImportErrorKind::Unresolved));
+                    };
+                    let there = &self.modules[target].program;
+                    let Some(export) = there
+                        .exports
+                        .iter()
+                        .find(|x| there.symbol_str(x.name).ok() == Some(target_name))
+                    else {
+                        return Err(err(ImportErrorKind::Unresolved));
+                    };
+                    if !same_signature(here, at_func, there, export.func) {
+                        return Err(err(ImportErrorKind::SignatureMismatch));
+                    }
+                    (at_module, at_func) = (target, export.func);
+                };
+                out[m][import.func as usize] = Some(resolved);
+            }
+        }
+
+        Ok(out
+            .into_iter()
+            .map(|ids| ids.into_iter().map(|id| id.unwrap_or(FuncId(0))).collect())
+            .collect())
+    }
+
+    fn import_error(
+        &self,
+        m: usize,
+        import_module: SymbolId,
+        name: SymbolId,
+    ) -> impl Fn(ImportErrorKind) -> ModuleLinkError + '_ {
+        move |kind| {
+            let p = &self.modules[m].program;
+            let module = self.modules[m].name.clone();
+            let import_module = String::from(p.symbol_str(import_module).unwrap_or_default());
+            let name = String::from(p.symbol_str(name).unwrap_or_default());
+            match kind {
+                ImportErrorKind::Unresolved => ModuleLinkError::UnresolvedImport {
+                    module,
+                    import_module,
+                    name,
+                },
+                ImportErrorKind::SignatureMismatch => ModuleLinkError::ImportSignatureMismatch {
+                    module,
+                    import_module,
+                    name,
+                },
+                ImportErrorKind::Cycle => ModuleLinkError::ImportCycle {
+                    module,
+                    import_module,
+                    name,
+                },
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum ImportErrorKind {
+    Unresolved,
+    SignatureMismatch,
+    Cycle,
+}
+
+fn same_signature(a: &Program, a_func: u32, b: &Program, b_func: u32) -> bool {
+    let (Some(fa), Some(fb)) = (
+        a.functions.get(a_func as usize),
+        b.functions.get(b_func as usize),
+    ) else {
+        return false;
+    };
+    a.function_arg_types(fa).ok() == b.function_arg_types(fb).ok()
+        && a.function_ret_types(fa).ok() == b.function_ret_types(fb).ok()
+}
+
+#[derive(Clone, Debug)]
+struct LinkedModule {
+    name: String,
+    /// Merged id of every function of the module, by local index.
+    funcs: Vec<FuncId>,
+    exports: Vec<(String, FuncId)>,
+}
+
+/// The result of [`Linker::link`]: the merged program plus a map from module functions to merged
+/// function ids.
+#[derive(Clone, Debug)]
+pub struct LinkedProgram {
+    program: VerifiedProgram,
+    modules: Vec<LinkedModule>,
+}
+
+impl LinkedProgram {
+    /// Returns the merged program.
+    #[must_use]
+    pub fn program(&self) -> &VerifiedProgram {
+        &self.program
+    }
+
+    /// Consumes `self` and returns the merged program.
+    #[must_use]
+    pub fn into_program(self) -> VerifiedProgram {
+        self.program
+    }
+
+    /// Returns the merged id of the function `module` exports as `name`.
+    #[must_use]
+    pub fn export(&self, module: &str, name: &str) -> Option<FuncId> {
+        let module = self.modules.iter().find(|m| m.name == module)?;
+        module
+            .exports
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, id)| id)
+    }
+
+    /// Returns the merged id of function `func` of `module`.
+    ///
+    /// For an import placeholder this is the id of the function the import resolved to.
+    #[must_use]
+    pub fn func_id(&self, module: &str, func: FuncId) -> Option<FuncId> {
+        let module = self.modules.iter().find(|m| m.name == module)?;
+        module.funcs.get(func.0 as usize).copied()
+    }
+}
+
+/// Error while appending one module's functions to the merged program.
+#[derive(Debug)]
+enum RemapError {
+    Decode(DecodeError),
+    Build(BuildError),
+}
+
+impl From<DecodeError> for RemapError {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+impl From<FormatDecodeError> for RemapError {
+    fn from(e: FormatDecodeError) -> Self {
+        Self::Decode(e.into())
+    }
+}
+
+impl From<BuildError> for RemapError {
+    fn from(e: BuildError) -> Self {
+        Self::Build(e)
+    }
+}
+
+/// Lazily interns one module's table entries into the merged [`ProgramBuilder`].
+///
+/// Only entries the module's surviving functions reference are carried over.
+struct ModuleRemap<'p> {
+    program: &'p Program,
+    symbols: Vec<Option<SymbolId>>,
+    consts: Vec<Option<ConstId>>,
+    structs: Vec<Option<TypeId>>,
+    elems: Vec<Option<ElemTypeId>>,
+    host_sigs: Vec<Option<HostSigId>>,
+    call_sigs: Vec<Option<CallSigId>>,
+}
+
+impl<'p> ModuleRemap<'p> {
+    fn new(program: &'p Program) -> Self {
+        Self {
+            program,
+            symbols: vec![None; program.symbols.len()],
+            consts: vec![None; program.const_pool.len()],
+            structs: vec![None; program.types.structs.len()],
+            elems: vec![None; program.types.array_elems.len()],
+            host_sigs: vec![None; program.host_sigs.len()],
+            call_sigs: vec![None; program.call_sigs.len()],
+        }
+    }
+
+    /// Appends the module's defined functions (and their metadata) to `pb`.
+    fn push_functions(
+        &mut self,
+        pb: &mut ProgramBuilder,
+        funcs: &[Vec<FuncId>],
+        m: usize,
+    ) -> Result<(), RemapError> {
+        let p = self.program;
+        for (f, func) in p.functions.iter().enumerate() {
+            let local = u32::try_from(f).map_err(|_| DecodeError::OutOfBounds)?;
+            if p.imports.iter().any(|e| e.func == local) {
+                continue;
+            }
+            let mut rewritten = rewrite_function::<DecodeError>(p, local, |opcode, i, operand| {
+                self.operand(pb, &funcs[m], opcode, i, operand)
+            })?;
+            let id = pb.push_function_def(FunctionDef {
+                arg_types: p.function_arg_types(func)?.to_vec(),
+                ret_types: p.function_ret_types(func)?.to_vec(),
+                reg_count: func.reg_count,
+                bytecode: core::mem::take(&mut rewritten.bytecode),
+                spans: core::mem::take(&mut rewritten.spans),
+            });
+            debug_assert_eq!(
+                id, funcs[m][f],
+                "merged function order must match resolve()"
+            );
+
+            for e in p.function_names.iter().filter(|e| e.func == local) {
+                pb.set_function_name(id, p.symbol_str(e.name)?)?;
+            }
+            for e in p.labels.iter().filter(|e| e.func == local) {
+                pb.set_label_name_pc(id, rewritten.remap_pc(e.pc)?, p.symbol_str(e.name)?)?;
+            }
+            for (arg, &name) in p.function_arg_name_ids(func)?.iter().enumerate() {
+                if let Some(name) = NonZeroU32::new(name) {
+                    let arg = u32::try_from(arg).map_err(|_| DecodeError::OutOfBounds)?;
+                    pb.set_function_input_name(id, arg, p.symbol_str(SymbolId(name))?)?;
+                }
+            }
+            for (ret, &name) in p.function_ret_name_ids(func)?.iter().enumerate() {
+                if let Some(name) = NonZeroU32::new(name) {
+                    let ret = u32::try_from(ret).map_err(|_| DecodeError::OutOfBounds)?;
+                    pb.set_function_output_name(id, ret, p.symbol_str(SymbolId(name))?)?;
+                }
+            }
+            for e in p.function_arg_agg_shapes.iter().filter(|e| e.func == local) {
+                let shape = match &e.shape {
+                    AggShape::Tuple { elems } => AggShape::Tuple {
+                        elems: elems.clone(),
+                    },
+                    AggShape::Struct(id) => AggShape::Struct(self.struct_type(pb, *id)?),
+                    AggShape::Array(id) => AggShape::Array(self.array_elem(pb, *id)?),
+                };
+                pb.set_function_arg_agg_shape(id, e.arg, shape)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn operand(
+        &mut self,
+        pb: &mut ProgramBuilder,
+        funcs: &[FuncId],
+        opcode: Opcode,
+        index: usize,
+        operand: &mut DecodedOperand,
+    ) -> Result<(), DecodeError> {
+        match operand {
+            DecodedOperand::ConstId(id) => *id = self.constant(pb, *id)?,
+            DecodedOperand::FuncId(id) => {
+                *id = *funcs.get(id.0 as usize).ok_or(DecodeError::OutOfBounds)?;
+            }
+            DecodedOperand::HostSigId(id) => *id = self.host_sig(pb, *id)?,
+            DecodedOperand::TypeId(id) => *id = self.struct_type(pb, *id)?,
+            DecodedOperand::ElemTypeId(id) => *id = self.array_elem(pb, *id)?,
+            // Call signature ids are plain immediates; the operand role tells them apart.
+            DecodedOperand::ImmU32(id)
+                if opcode
+                    .operands()
+                    .get(index)
+                    .is_some_and(|o| o.role == OperandRole::CallSig) =>
+            {
+                *id = self.call_sig(pb, CallSigId(*id))?.0;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn symbol(&mut self, pb: &mut ProgramBuilder, id: SymbolId) -> Result<SymbolId, DecodeError> {
+        let slot = self
+            .symbols
+            .get_mut(id.index() as usize)
+            .ok_or(DecodeError::OutOfBounds)?;
+        if let Some(new) = *slot {
+            return Ok(new);
+        }
+        let new = pb.symbol(self.program.symbol_str(id)?);
+        *slot = Some(new);
+        Ok(new)
+    }
+
+    fn constant(&mut self, pb: &mut ProgramBuilder, id: ConstId) -> Result<ConstId, DecodeError> {
+        if let Some(Some(new)) = self.consts.get(id.0 as usize) {
+            return Ok(*new);
+        }
+        let p = self.program;
+        let c = match p.const_pool.get(id.0 as usize) {
+            None => return Err(DecodeError::OutOfBounds),
+            Some(ConstEntry::Unit) => Const::Unit,
+            Some(ConstEntry::Bool(v)) => Const::Bool(*v),
+            Some(ConstEntry::I64(v)) => Const::I64(*v),
+            Some(ConstEntry::U64(v)) => Const::U64(*v),
+            Some(ConstEntry::F64(bits)) => Const::F64(*bits),
+            Some(ConstEntry::Decimal { mantissa, scale }) => Const::Decimal {
+                mantissa: *mantissa,
+                scale: *scale,
+            },
+            Some(ConstEntry::Bytes(_)) => Const::Bytes(p.const_bytes(id)?.to_vec()),
+            Some(ConstEntry::Str(_)) => Const::Str(p.const_str(id)?.into()),
+        };
+        let new = pb.constant(c);
+        self.consts[id.0 as usize] = Some(new);
+        Ok(new)
+    }
+
+    fn struct_type(&mut self, pb: &mut ProgramBuilder, id: TypeId) -> Result<TypeId, DecodeError> {
+        if let Some(Some(new)) = self.structs.get(id.0 as usize) {
+            return Ok(*new);
+        }
+        let types = &self.program.types;
+        let st = types
+            .structs
+            .get(id.0 as usize)
+            .ok_or(DecodeError::OutOfBounds)?;
+        let field_names = types
+            .struct_field_name_ids(st)?
+            .iter()
+            .map(|&n| types.field_name_str(n).map(String::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(DecodeError::OutOfBounds)?;
+        let new = pb.struct_type(StructTypeDef {
+            field_names,
+            field_types: types.struct_field_types(st)?.to_vec(),
+        });
+        self.structs[id.0 as usize] = Some(new);
+        Ok(new)
+    }
+
+    fn array_elem(
+        &mut self,
+        pb: &mut ProgramBuilder,
+        id: ElemTypeId,
+    ) -> Result<ElemTypeId, DecodeError> {
+        if let Some(Some(new)) = self.elems.get(id.0 as usize) {
+            return Ok(*new);
+        }
+        let ty = *self
+            .program
+            .types
+            .array_elems
+            .get(id.0 as usize)
+            .ok_or(DecodeError::OutOfBounds)?;
+        let new = pb.array_elem(ty);
+        self.elems[id.0 as usize] = Some(new);
+        Ok(new)
+    }
+
+    fn host_sig(
+        &mut self,
+        pb: &mut ProgramBuilder,
+        id: HostSigId,
+    ) -> Result<HostSigId, DecodeError> {
+        if let Some(Some(new)) = self.host_sigs.get(id.0 as usize) {
+            return Ok(*new);
+        }
+        let p = self.program;
+        let hs = p.host_sig(id).ok_or(DecodeError::OutOfBounds)?;
+        let symbol = self.symbol(pb, hs.symbol)?;
+        let sig = HostSig {
+            args: p.host_sig_args(hs)?.to_vec(),
+            rets: p.host_sig_rets(hs)?.to_vec(),
+        };
+        let new = if hs.pure {
+            pb.pure_host_sig(symbol, sig)
+        } else {
+            pb.host_sig(symbol, sig)
+        };
+        self.host_sigs[id.0 as usize] = Some(new);
+        Ok(new)
+    }
+
+    fn call_sig(
+        &mut self,
+        pb: &mut ProgramBuilder,
+        id: CallSigId,
+    ) -> Result<CallSigId, DecodeError> {
+        if let Some(Some(new)) = self.call_sigs.get(id.0 as usize) {
+            return Ok(*new);
+        }
+        let p = self.program;
+        let cs = p.call_sig(id).ok_or(DecodeError::OutOfBounds)?;
+        let new = pb.call_sig(p.call_sig_args(cs)?, p.call_sig_rets(cs)?);
+        self.call_sigs[id.0 as usize] = Some(new);
+        Ok(new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig};
+    use crate::host::{Host, HostContext, HostError, SigHash, ValueRef};
+    use crate::program::{SourceRange, ValueType};
+    use crate::trace::TraceMask;
+    use crate::value::Value;
+    use crate::vm::{Limits, Trap, Vm};
+
+    struct NoHost;
+
+    impl Host for NoHost {
+        fn call(
+            &mut self,
+            _symbol: &str,
+            _sig_hash: SigHash,
+            _args: &[ValueRef<'_>],
+            _rets: &mut [Value],
+            _ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            Err(HostError::UnknownSymbol)
+        }
+    }
+
+    fn sig(ret: ValueType) -> FunctionSig {
+        FunctionSig {
+            arg_types: vec![ValueType::I64],
+            ret_types: vec![ret],
+        }
+    }
+
+    fn point() -> StructTypeDef {
+        StructTypeDef {
+            field_names: vec!["x".into()],
+            field_types: vec![ValueType::I64],
+        }
+    }
+
+    /// `r2 = point { x: r1 * 10 }.x`, using the const pool and the type table.
+    fn scale_by_ten(pb: &mut ProgramBuilder, a: &mut Asm) {
+        let ten = pb.constant(Const::I64(10));
+        let point = pb.struct_type(point());
+        a.const_pool(2, ten);
+        a.i64_mul(2, 1, 2);
+        a.struct_new(3, point, &[2]);
+        a.struct_get(2, 3, 0);
+    }
+
+    /// lib: f0 `mul10(x)`, f1 `scale(x) = mul10(x)` exported as "scale".
+    fn lib() -> Program {
+        let mut pb = ProgramBuilder::new();
+        let mut a = Asm::new();
+        scale_by_ten(&mut pb, &mut a);
+        a.ret(0, &[2]);
+        let mul10 = pb.push_function_checked(a, sig(ValueType::I64)).unwrap();
+        let mut a = Asm::new();
+        a.call(0, mul10, 0, &[1], &[2]);
+        a.ret(0, &[2]);
+        let scale = pb.push_function_checked(a, sig(ValueType::I64)).unwrap();
+        pb.set_function_name(scale, "scale").unwrap();
+        pb.export_function(scale, "scale").unwrap();
+        pb.build()
+    }
+
+    /// app: f0 imports `lib.scale`, f1 `main(x) = lib.scale(x) + x * 10`.
+    fn app() -> ProgramBuilder {
+        let mut pb = ProgramBuilder::new();
+        let scale = pb.import_function("lib", "scale", sig(ValueType::I64));
+        let mut a = Asm::new();
+        a.call(0, scale, 0, &[1], &[4]);
+        scale_by_ten(&mut pb, &mut a);
+        a.i64_add(2, 2, 4);
+        a.ret(0, &[2]);
+        let main = pb.push_function_checked(a, sig(ValueType::I64)).unwrap();
+        pb.export_function(main, "main").unwrap();
+        pb
+    }
+
+    #[test]
+    fn unlinked_import_traps() {
+        let p = app().build_verified().unwrap();
+        let mut vm = Vm::new(NoHost, Limits::default());
+        let err = vm
+            .run(&p, FuncId(1), &[Value::I64(3)], TraceMask::NONE, None)
+            .unwrap_err();
+        assert_eq!(err.trap, Trap::TrapCode(UNRESOLVED_IMPORT_TRAP_CODE));
+    }
+
+    #[test]
+    fn link_resolves_imports_and_dedupes_tables() {
+        let mut linker = Linker::new();
+        linker
+            .add_module("lib", lib())
+            .unwrap()
+            .add_module("app", app().build())
+            .unwrap();
+        let linked = linker.link().unwrap();
+
+        let scale = linked.export("lib", "scale").unwrap();
+        let main = linked.export("app", "main").unwrap();
+        assert_eq!((scale, main), (FuncId(1), FuncId(2)));
+        assert_eq!(linked.func_id("app", FuncId(0)), Some(scale));
+        assert_eq!(linked.func_id("app", FuncId(2)), None);
+        assert_eq!(linked.export("app", "scale"), None);
+
+        let p = linked.program().program();
+        assert_eq!(p.functions.len(), 3);
+        assert_eq!(p.const_pool, vec![ConstEntry::I64(10)]);
+        assert_eq!(p.types.structs.len(), 1);
+        assert_eq!(p.function_name(1), Some("scale"));
+        assert!(p.exports.is_empty() && p.imports.is_empty());
+
+        let mut vm = Vm::new(NoHost, Limits::default());
+        let out = vm.run(
+            linked.program(),
+            main,
+            &[Value::I64(3)],
+            TraceMask::NONE,
+            None,
+        );
+        assert_eq!(out.unwrap(), vec![Value::I64(60)]);
+    }
+
+    #[test]
+    fn link_reports_bad_modules_and_imports() {
+        let mut linker = Linker::new();
+        linker.add_module("app", app().build()).unwrap();
+        assert_eq!(
+            linker.add_module("app", app().build()).unwrap_err(),
+            ModuleLinkError::DuplicateModule {
+                module: "app".into()
+            }
+        );
+        let unresolved = ModuleLinkError::UnresolvedImport {
+            module: "app".into(),
+            import_module: "lib".into(),
+            name: "scale".into(),
+        };
+        assert_eq!(linker.link().unwrap_err(), unresolved);
+
+        let mut other = ProgramBuilder::new();
+        let mut a = Asm::new();
+        a.trap(1);
+        let f = other.push_function_checked(a, sig(ValueType::I64)).unwrap();
+        other.export_function(f, "other").unwrap();
+        linker.add_module("lib", other.build()).unwrap();
+        assert_eq!(linker.link().unwrap_err(), unresolved);
+
+        let mut bool_lib = ProgramBuilder::new();
+        let mut a = Asm::new();
+        a.trap(1);
+        let f = bool_lib
+            .push_function_checked(a, sig(ValueType::Bool))
+            .unwrap();
+        bool_lib.export_function(f, "scale").unwrap();
+        let mut linker = Linker::new();
+        linker
+            .add_module("lib", bool_lib.build())
+            .unwrap()
+            .add_module("app", app().build())
+            .unwrap();
+        assert_eq!(
+            linker.link().unwrap_err(),
+            ModuleLinkError::ImportSignatureMismatch {
+                module: "app".into(),
+                import_module: "lib".into(),
+                name: "scale".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn link_merges_source_maps() {
+        let span = SpanId::try_from(7).unwrap();
+        let range = SourceRange {
+            start_line: 1,
+            start_col: 1,
+            end_line: 1,
+            end_col: 9,
+        };
+        let module = |file: &str| {
+            let mut pb = ProgramBuilder::new();
+            let mut a = Asm::new();
+            a.span(span);
+            a.ret(0, &[1]);
+            pb.push_function_checked(a, sig(ValueType::I64)).unwrap();
+            pb.set_span_info(span, file, range, None);
+            pb.build()
+        };
+
+        let mut linker = Linker::new();
+        linker
+            .add_module("a", module("shared.src"))
+            .unwrap()
+            .add_module("b", module("shared.src"))
+            .unwrap();
+        let linked = linker.link().unwrap();
+        let p = linked.program().program();
+        assert_eq!(p.source_map.len(), 1);
+        assert_eq!(p.span_info(span).map(|i| i.file), Some("shared.src"));
+
+        let mut linker = Linker::new();
+        linker
+            .add_module("a", module("a.src"))
+            .unwrap()
+            .add_module("b", module("b.src"))
+            .unwrap();
+        assert_eq!(
+            linker.link().unwrap_err(),
+            ModuleLinkError::SpanCollision {
+                module: "b".into(),
+                span,
+            }
+        );
+    }
+
+    #[test]
+    fn link_rejects_import_cycles() {
+        // `a.y` re-exports `b.x`, which re-exports `a.y`.
+        let module = |import_module: &str, import: &str, export: &str| {
+            let mut pb = ProgramBuilder::new();
+            let f = pb.import_function(import_module, import, sig(ValueType::I64));
+            pb.export_function(f, export).unwrap();
+            pb.build()
+        };
+        let mut linker = Linker::new();
+        linker
+            .add_module("a", module("b", "x", "y"))
+            .unwrap()
+            .add_module("b", module("a", "y", "x"))
+            .unwrap();
+        assert!(matches!(
+            linker.link().unwrap_err(),
+            ModuleLinkError::ImportCycle { .. }
+        ));
+    }
+}
@@ -56,6 +56,7 @@ pub enum OperandRole {
     Const,
     Func,
     HostSig,
+    CallSig,
     Type,
     ElemType,
     EffIn,
@@ -268,7 +269,7 @@ pub const OPERANDS: &[OperandSchema] = &[
     OperandSchema::new(OperandKind::Reg, OperandRole::Dst, OperandEncoding::RegU32Uleb, Some(OperandAccess::Write)),
     OperandSchema::new(OperandKind::FuncId, OperandRole::Func, OperandEncoding::U32Uleb, None),
     OperandSchema::new(OperandKind::Reg, OperandRole::EffOut, OperandEncoding::RegU32Uleb, Some(OperandAccess::Write)),
-    OperandSchema::new(OperandKind::ImmU32, OperandRole::Imm, OperandEncoding::U32Uleb, None),
+    OperandSchema::new(OperandKind::ImmU32, OperandRole::CallSig, OperandEncoding::U32Uleb, None),
     OperandSchema::new(OperandKind::Reg, OperandRole::Src, OperandEncoding::RegU32Uleb, Some(OperandAccess::Read)),
     OperandSchema::new(OperandKind::Reg, OperandRole::EffIn, OperandEncoding::RegU32Uleb, Some(OperandAccess::Read)),
     OperandSchema::new(OperandKind::RegList, OperandRole::Args, OperandEncoding::RegListU32UlebCountThenRegs, Some(OperandAccess::Read)),
@@ -276,6 +277,9 @@ pub const OPERANDS: &[OperandSchema] = &[
     OperandSchema::new(OperandKind::Reg, OperandRole::Dst, OperandEncoding::RegU32Uleb, Some(OperandAccess::Write)),
     OperandSchema::new(OperandKind::Reg, OperandRole::Func, OperandEncoding::RegU32Uleb, Some(OperandAccess::Read)),
     OperandSchema::new(OperandKind::Reg, OperandRole::Src, OperandEncoding::RegU32Uleb, Some(OperandAccess::Read)),
+    OperandSchema::new(OperandKind::HostSigId, OperandRole::HostSig, OperandEncoding::U32Uleb, None),
+    OperandSchema::new(OperandKind::RegList, OperandRole::Args, OperandEncoding::RegListU32UlebCountThenRegs, Some(OperandAccess::Read)),
+    OperandSchema::new(OperandKind::RegList, OperandRole::Rets, OperandEncoding::RegListU32UlebCountThenRegs, Some(OperandAccess::Write)),
     OperandSchema::new(OperandKind::Reg, OperandRole::Dst, OperandEncoding::RegU32Uleb, Some(OperandAccess::Write)),
     OperandSchema::new(OperandKind::RegList, OperandRole::Values, OperandEncoding::RegListU32UlebCountThenRegs, Some(OperandAccess::Read)),
     OperandSchema::new(OperandKind::Reg, OperandRole::Dst, OperandEncoding::RegU32Uleb, Some(OperandAccess::Write)),
@@ -537,7 +541,7 @@ pub const OPCODE_INFO_BY_BYTE: &[OpcodeInfo] = &[
     OpcodeInfo { mnemonic: "const.func", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 139, len: 2 } }, // 0x53 ConstFunc
     OpcodeInfo { mnemonic: "call.indirect", is_terminator: false, flags: OpcodeFlags::CALL_LIKE, operands: OperandLayout { start: 141, len: 6 } }, // 0x54 CallIndirect
     OpcodeInfo { mnemonic: "closure.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 147, len: 3 } }, // 0x55 ClosureNew
-    OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x56
+    OpcodeInfo { mnemonic: "host_call.pure", is_terminator: false, flags: OpcodeFlags::CALL_LIKE, operands: OperandLayout { start: 150, len: 3 } }, // 0x56 HostCallPure
     OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x57
     OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x58
     OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x59
@@ -547,58 +551,58 @@ pub const OPCODE_INFO_BY_BYTE: &[OpcodeInfo] = &[
     OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x5D
     OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x5E
     OpcodeInfo { mnemonic: "<invalid>", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 0, len: 0 } }, // 0x5F
-    OpcodeInfo { mnemonic: "tuple.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 150, len: 2 } }, // 0x60 TupleNew
-    OpcodeInfo { mnemonic: "tuple.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 152, len: 3 } }, // 0x61 TupleGet
-    OpcodeInfo { mnemonic: "struct.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 155, len: 3 } }, // 0x62 StructNew
-    OpcodeInfo { mnemonic: "struct.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 158, len: 3 } }, // 0x63 StructGet
-    OpcodeInfo { mnemonic: "array.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 161, len: 3 } }, // 0x64 ArrayNew
-    OpcodeInfo { mnemonic: "array.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 164, len: 2 } }, // 0x65 ArrayLen
-    OpcodeInfo { mnemonic: "array.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 166, len: 3 } }, // 0x66 ArrayGet
-    OpcodeInfo { mnemonic: "tuple.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 169, len: 2 } }, // 0x67 TupleLen
-    OpcodeInfo { mnemonic: "struct.field_count", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 171, len: 2 } }, // 0x68 StructFieldCount
-    OpcodeInfo { mnemonic: "array.get_imm", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 173, len: 3 } }, // 0x69 ArrayGetImm
-    OpcodeInfo { mnemonic: "bytes.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 176, len: 2 } }, // 0x6A BytesLen
-    OpcodeInfo { mnemonic: "str.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 178, len: 2 } }, // 0x6B StrLen
-    OpcodeInfo { mnemonic: "i64.div", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 180, len: 3 } }, // 0x6C I64Div
-    OpcodeInfo { mnemonic: "i64.rem", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 183, len: 3 } }, // 0x6D I64Rem
-    OpcodeInfo { mnemonic: "u64.div", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 186, len: 3 } }, // 0x6E U64Div
-    OpcodeInfo { mnemonic: "u64.rem", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 189, len: 3 } }, // 0x6F U64Rem
-    OpcodeInfo { mnemonic: "i64.to_f64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 192, len: 2 } }, // 0x70 I64ToF64
-    OpcodeInfo { mnemonic: "u64.to_f64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 194, len: 2 } }, // 0x71 U64ToF64
-    OpcodeInfo { mnemonic: "f64.to_i64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 196, len: 2 } }, // 0x72 F64ToI64
-    OpcodeInfo { mnemonic: "f64.to_u64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 198, len: 2 } }, // 0x73 F64ToU64
-    OpcodeInfo { mnemonic: "dec.to_i64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 200, len: 2 } }, // 0x74 DecToI64
-    OpcodeInfo { mnemonic: "dec.to_u64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 202, len: 2 } }, // 0x75 DecToU64
-    OpcodeInfo { mnemonic: "i64.to_dec", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 204, len: 3 } }, // 0x76 I64ToDec
-    OpcodeInfo { mnemonic: "u64.to_dec", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 207, len: 3 } }, // 0x77 U64ToDec
-    OpcodeInfo { mnemonic: "bytes.eq", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 210, len: 3 } }, // 0x78 BytesEq
-    OpcodeInfo { mnemonic: "str.eq", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 213, len: 3 } }, // 0x79 StrEq
-    OpcodeInfo { mnemonic: "bytes.concat", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 216, len: 3 } }, // 0x7A BytesConcat
-    OpcodeInfo { mnemonic: "str.concat", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 219, len: 3 } }, // 0x7B StrConcat
-    OpcodeInfo { mnemonic: "bytes.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 222, len: 3 } }, // 0x7C BytesGet
-    OpcodeInfo { mnemonic: "bytes.get_imm", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 225, len: 3 } }, // 0x7D BytesGetImm
-    OpcodeInfo { mnemonic: "bytes.slice", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 228, len: 4 } }, // 0x7E BytesSlice
-    OpcodeInfo { mnemonic: "str.slice", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 232, len: 4 } }, // 0x7F StrSlice
-    OpcodeInfo { mnemonic: "str.to_bytes", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 236, len: 2 } }, // 0x80 StrToBytes
-    OpcodeInfo { mnemonic: "bytes.to_str", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 238, len: 2 } }, // 0x81 BytesToStr
-    OpcodeInfo { mnemonic: "f64.div", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 240, len: 3 } }, // 0x82 F64Div
-    OpcodeInfo { mnemonic: "f64.eq", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 243, len: 3 } }, // 0x83 F64Eq
-    OpcodeInfo { mnemonic: "f64.lt", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 246, len: 3 } }, // 0x84 F64Lt
-    OpcodeInfo { mnemonic: "f64.gt", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 249, len: 3 } }, // 0x85 F64Gt
-    OpcodeInfo { mnemonic: "f64.le", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 252, len: 3 } }, // 0x86 F64Le
-    OpcodeInfo { mnemonic: "f64.ge", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 255, len: 3 } }, // 0x87 F64Ge
-    OpcodeInfo { mnemonic: "bool.and", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 258, len: 3 } }, // 0x88 BoolAnd
-    OpcodeInfo { mnemonic: "bool.or", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 261, len: 3 } }, // 0x89 BoolOr
-    OpcodeInfo { mnemonic: "bool.xor", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 264, len: 3 } }, // 0x8A BoolXor
-    OpcodeInfo { mnemonic: "f64.neg", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 267, len: 2 } }, // 0x8B F64Neg
-    OpcodeInfo { mnemonic: "f64.abs", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 269, len: 2 } }, // 0x8C F64Abs
-    OpcodeInfo { mnemonic: "f64.min", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 271, len: 3 } }, // 0x8D F64Min
-    OpcodeInfo { mnemonic: "f64.max", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 274, len: 3 } }, // 0x8E F64Max
-    OpcodeInfo { mnemonic: "f64.min_num", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 277, len: 3 } }, // 0x8F F64MinNum
-    OpcodeInfo { mnemonic: "f64.max_num", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 280, len: 3 } }, // 0x90 F64MaxNum
-    OpcodeInfo { mnemonic: "f64.rem", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 283, len: 3 } }, // 0x91 F64Rem
-    OpcodeInfo { mnemonic: "f64.to_bits", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 286, len: 2 } }, // 0x92 F64ToBits
-    OpcodeInfo { mnemonic: "f64.from_bits", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 288, len: 2 } }, // 0x93 F64FromBits
+    OpcodeInfo { mnemonic: "tuple.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 153, len: 2 } }, // 0x60 TupleNew
+    OpcodeInfo { mnemonic: "tuple.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 155, len: 3 } }, // 0x61 TupleGet
+    OpcodeInfo { mnemonic: "struct.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 158, len: 3 } }, // 0x62 StructNew
+    OpcodeInfo { mnemonic: "struct.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 161, len: 3 } }, // 0x63 StructGet
+    OpcodeInfo { mnemonic: "array.new", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 164, len: 3 } }, // 0x64 ArrayNew
+    OpcodeInfo { mnemonic: "array.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 167, len: 2 } }, // 0x65 ArrayLen
+    OpcodeInfo { mnemonic: "array.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 169, len: 3 } }, // 0x66 ArrayGet
+    OpcodeInfo { mnemonic: "tuple.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 172, len: 2 } }, // 0x67 TupleLen
+    OpcodeInfo { mnemonic: "struct.field_count", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 174, len: 2 } }, // 0x68 StructFieldCount
+    OpcodeInfo { mnemonic: "array.get_imm", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 176, len: 3 } }, // 0x69 ArrayGetImm
+    OpcodeInfo { mnemonic: "bytes.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 179, len: 2 } }, // 0x6A BytesLen
+    OpcodeInfo { mnemonic: "str.len", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 181, len: 2 } }, // 0x6B StrLen
+    OpcodeInfo { mnemonic: "i64.div", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 183, len: 3 } }, // 0x6C I64Div
+    OpcodeInfo { mnemonic: "i64.rem", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 186, len: 3 } }, // 0x6D I64Rem
+    OpcodeInfo { mnemonic: "u64.div", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 189, len: 3 } }, // 0x6E U64Div
+    OpcodeInfo { mnemonic: "u64.rem", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 192, len: 3 } }, // 0x6F U64Rem
+    OpcodeInfo { mnemonic: "i64.to_f64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 195, len: 2 } }, // 0x70 I64ToF64
+    OpcodeInfo { mnemonic: "u64.to_f64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 197, len: 2 } }, // 0x71 U64ToF64
+    OpcodeInfo { mnemonic: "f64.to_i64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 199, len: 2 } }, // 0x72 F64ToI64
+    OpcodeInfo { mnemonic: "f64.to_u64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 201, len: 2 } }, // 0x73 F64ToU64
+    OpcodeInfo { mnemonic: "dec.to_i64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 203, len: 2 } }, // 0x74 DecToI64
+    OpcodeInfo { mnemonic: "dec.to_u64", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 205, len: 2 } }, // 0x75 DecToU64
+    OpcodeInfo { mnemonic: "i64.to_dec", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 207, len: 3 } }, // 0x76 I64ToDec
+    OpcodeInfo { mnemonic: "u64.to_dec", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 210, len: 3 } }, // 0x77 U64ToDec
+    OpcodeInfo { mnemonic: "bytes.eq", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 213, len: 3 } }, // 0x78 BytesEq
+    OpcodeInfo { mnemonic: "str.eq", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 216, len: 3 } }, // 0x79 StrEq
+    OpcodeInfo { mnemonic: "bytes.concat", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 219, len: 3 } }, // 0x7A BytesConcat
+    OpcodeInfo { mnemonic: "str.concat", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 222, len: 3 } }, // 0x7B StrConcat
+    OpcodeInfo { mnemonic: "bytes.get", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 225, len: 3 } }, // 0x7C BytesGet
+    OpcodeInfo { mnemonic: "bytes.get_imm", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 228, len: 3 } }, // 0x7D BytesGetImm
+    OpcodeInfo { mnemonic: "bytes.slice", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 231, len: 4 } }, // 0x7E BytesSlice
+    OpcodeInfo { mnemonic: "str.slice", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 235, len: 4 } }, // 0x7F StrSlice
+    OpcodeInfo { mnemonic: "str.to_bytes", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 239, len: 2 } }, // 0x80 StrToBytes
+    OpcodeInfo { mnemonic: "bytes.to_str", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 241, len: 2 } }, // 0x81 BytesToStr
+    OpcodeInfo { mnemonic: "f64.div", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 243, len: 3 } }, // 0x82 F64Div
+    OpcodeInfo { mnemonic: "f64.eq", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 246, len: 3 } }, // 0x83 F64Eq
+    OpcodeInfo { mnemonic: "f64.lt", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 249, len: 3 } }, // 0x84 F64Lt
+    OpcodeInfo { mnemonic: "f64.gt", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 252, len: 3 } }, // 0x85 F64Gt
+    OpcodeInfo { mnemonic: "f64.le", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 255, len: 3 } }, // 0x86 F64Le
+    OpcodeInfo { mnemonic: "f64.ge", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 258, len: 3 } }, // 0x87 F64Ge
+    OpcodeInfo { mnemonic: "bool.and", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 261, len: 3 } }, // 0x88 BoolAnd
+    OpcodeInfo { mnemonic: "bool.or", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 264, len: 3 } }, // 0x89 BoolOr
+    OpcodeInfo { mnemonic: "bool.xor", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 267, len: 3 } }, // 0x8A BoolXor
+    OpcodeInfo { mnemonic: "f64.neg", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 270, len: 2 } }, // 0x8B F64Neg
+    OpcodeInfo { mnemonic: "f64.abs", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 272, len: 2 } }, // 0x8C F64Abs
+    OpcodeInfo { mnemonic: "f64.min", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 274, len: 3 } }, // 0x8D F64Min
+    OpcodeInfo { mnemonic: "f64.max", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 277, len: 3 } }, // 0x8E F64Max
+    OpcodeInfo { mnemonic: "f64.min_num", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 280, len: 3 } }, // 0x8F F64MinNum
+    OpcodeInfo { mnemonic: "f64.max_num", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 283, len: 3 } }, // 0x90 F64MaxNum
+    OpcodeInfo { mnemonic: "f64.rem", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 286, len: 3 } }, // 0x91 F64Rem
+    OpcodeInfo { mnemonic: "f64.to_bits", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 289, len: 2 } }, // 0x92 F64ToBits
+    OpcodeInfo { mnemonic: "f64.from_bits", is_terminator: false, flags: OpcodeFlags::NONE, operands: OperandLayout { start: 291, len: 2 } }, // 0x93 F64FromBits
 ];
 
 /// Bytecode opcode byte for the v1 instruction set.
@@ -713,6 +717,8 @@ pub enum Opcode {
     CallIndirect = 0x54,
     /// Construct a closure from a function value register and env aggregate register.
     ClosureNew = 0x55,
+    /// Call a pure host function (no effect token).
+    HostCallPure = 0x56,
     /// Allocate a tuple aggregate.
     TupleNew = 0x60,
     /// Read tuple element at an immediate index.
@@ -878,6 +884,7 @@ impl Opcode {
             0x53 => Self::ConstFunc,
             0x54 => Self::CallIndirect,
             0x55 => Self::ClosureNew,
+            0x56 => Self::HostCallPure,
             0x60 => Self::TupleNew,
             0x61 => Self::TupleGet,
             0x62 => Self::StructNew,
@@ -14,15 +14,15 @@ use alloc::vec::Vec;
 use core::num::{NonZeroU32, NonZeroU64};
 
 use crate::format::{DecodeError, Reader, Writer};
-use crate::host::{HostSig, SigHash, sig_hash, sig_hash_slices};
+use crate::host::{SigHash, pure_sig_hash_slices, sig_hash_slices};
 
 #[cfg(doc)]
-use crate::verifier;
+use crate::{linker, verifier};
 
 /// The `execution_tape` binary format version supported by this crate (draft).
 pub const VERSION_MAJOR: u16 = 0;
 /// The `execution_tape` binary format version supported by this crate (draft).
-pub const VERSION_MINOR: u16 = 1;
+pub const VERSION_MINOR: u16 = 2;
 
 const MAGIC: &[u8; 8] = b"EXTAPE\0\0";
 
@@ -103,6 +103,61 @@ impl TryFrom<u64> for SpanId {
     }
 }
 
+/// A line/column range within a source document.
+///
+/// Lines and columns are 1-based by convention; `0` means unknown. The end position is inclusive.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SourceRange {
+    /// First line.
+    pub start_line: u32,
+    /// First column on `start_line`.
+    pub start_col: u32,
+    /// Last line.
+    pub end_line: u32,
+    /// Last column on `end_line`.
+    pub end_col: u32,
+}
+
+/// A source-map entry: where the code tagged with a [`SpanId`] came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// The span being described.
+    pub span: SpanId,
+    /// Symbol id naming the source file or document.
+    pub file: SymbolId,
+    /// Position within `file`.
+    pub range: SourceRange,
+    /// Symbol id of an external node GUID (for graph-authored programs), if any.
+    pub node_guid: Option<SymbolId>,
+}
+
+/// Resolved source information for a [`SpanId`] (see [`Program::span_info`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SpanInfo<'a> {
+    /// The span being described.
+    pub span: SpanId,
+    /// Source file or document name.
+    pub file: &'a str,
+    /// Position within `file`.
+    pub range: SourceRange,
+    /// External node GUID, if any.
+    pub node_guid: Option<&'a str>,
+}
+
+impl core::fmt::Display for SpanInfo<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.file, self.range.start_line, self.range.start_col
+        )?;
+        if let Some(guid) = self.node_guid {
+            write!(f, " (node {guid})")?;
+        }
+        Ok(())
+    }
+}
+
 /// Symbol table identifier (index into [`Program::symbols`]).
 ///
 /// This is a non-zero index: symbol index `0` is reserved (and encoded as an empty symbol) and is
@@ -228,6 +283,8 @@ pub struct HostSigDef {
     pub args: Vec<ValueType>,
     /// Return types.
     pub rets: Vec<ValueType>,
+    /// Whether the host function is pure (called via `host_call.pure`, without an effect token).
+    pub pure: bool,
 }
 
 /// A decoded `execution_tape` program.
@@ -280,6 +337,54 @@ pub struct Program {
     /// Frontends may emit these for debugging, profiling, and diagnostics. These are not required
     /// for execution.
     pub labels: Vec<LabelNameEntry>,
+    /// Functions this program makes available to other modules, by name.
+    ///
+    /// Only meaningful when linking several programs together (see [`linker`]).
+    pub exports: Vec<ExportEntry>,
+    /// Functions this program expects another module to provide.
+    ///
+    /// Each import is backed by a local placeholder function whose signature is the signature of
+    /// the import. Until linked, the placeholder body traps (see [`linker`]).
+    pub imports: Vec<ImportEntry>,
+    /// Embedder-defined named sections, carried through encode/decode unchanged.
+    ///
+    /// The VM and verifier ignore these. Names are unique; [`Program::encode`] writes the sections
+    /// in name order, and decoding yields them in that order.
+    pub custom_sections: Vec<CustomSection>,
+    /// Optional source map from span ids to source locations, sorted by span id.
+    ///
+    /// Frontends may emit this for diagnostics (trap messages, disassembly, profiling). It is not
+    /// required for execution.
+    pub source_map: Vec<SourceMapEntry>,
+}
+
+/// An embedder-defined named section (node GUID maps, build provenance, ...).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomSection {
+    /// Section name.
+    pub name: String,
+    /// Opaque section payload.
+    pub bytes: Vec<u8>,
+}
+
+/// An exported function entry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ExportEntry {
+    /// Function index within the program.
+    pub func: u32,
+    /// Symbol id naming the export.
+    pub name: SymbolId,
+}
+
+/// An imported function entry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImportEntry {
+    /// Index of the local placeholder function that stands in for the import.
+    pub func: u32,
+    /// Symbol id naming the module that provides the function.
+    pub module: SymbolId,
+    /// Symbol id naming the export within that module.
+    pub name: SymbolId,
 }
 
 /// A function-name entry.
@@ -354,7 +459,7 @@ pub struct ElemTypeId(pub u32);
 /// A stable identifier for a host-provided object type.
 ///
 /// The embedder defines the meaning and registry of these ids.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct HostTypeId(pub u64);
 
 /// A value type used in signatures and aggregate layouts.
@@ -422,8 +527,11 @@ impl AggShape {
 pub struct HostSigEntry {
     /// Host symbol id.
     pub symbol: SymbolId,
-    /// Stable signature hash (must match the canonical hash for `args`/`rets`).
+    /// Stable signature hash (must match the canonical hash for `args`/`rets`/`pure`).
     pub sig_hash: SigHash,
+    /// Whether the host function is pure: referentially transparent and called via
+    /// `host_call.pure`, without an effect token.
+    pub pure: bool,
     /// Argument types.
     pub args: ByteRange,
     /// Return types.
@@ -501,6 +609,12 @@ fn intern_field(
     id
 }
 
+impl From<TypeTableDef> for TypeTable {
+    fn from(def: TypeTableDef) -> Self {
+        Self::pack(def)
+    }
+}
+
 impl TypeTable {
     fn pack(def: TypeTableDef) -> Self {
         let mut name_data: Vec<u8> = Vec::new();
@@ -587,6 +701,32 @@ impl TypeTable {
             .get(start..end)
             .ok_or(DecodeError::OutOfBounds)
     }
+
+    /// Returns the id of the first struct type whose field names and types match `def`.
+    #[must_use]
+    pub fn struct_id(&self, def: &StructTypeDef) -> Option<TypeId> {
+        let i = self.structs.iter().position(|st| {
+            let (Ok(names), Ok(types)) =
+                (self.struct_field_name_ids(st), self.struct_field_types(st))
+            else {
+                return false;
+            };
+            types == def.field_types.as_slice()
+                && names.len() == def.field_names.len()
+                && names
+                    .iter()
+                    .zip(&def.field_names)
+                    .all(|(&id, name)| self.field_name_str(id) == Some(name.as_str()))
+        })?;
+        Some(TypeId(u32::try_from(i).ok()?))
+    }
+
+    /// Returns the id of the first array element type equal to `ty`.
+    #[must_use]
+    pub fn array_elem_id(&self, ty: ValueType) -> Option<ElemTypeId> {
+        let i = self.array_elems.iter().position(|&t| t == ty)?;
+        Some(ElemTypeId(u32::try_from(i).ok()?))
+    }
 }
 
 impl Program {
@@ -662,13 +802,15 @@ impl Program {
             value_types.extend_from_slice(&hs.rets);
             let rets_len = u32::try_from(hs.rets.len()).unwrap_or(u32::MAX);
 
-            let sig = HostSig {
-                args: hs.args,
-                rets: hs.rets,
+            let sig_hash = if hs.pure {
+                pure_sig_hash_slices(&hs.args, &hs.rets)
+            } else {
+                sig_hash_slices(&hs.args, &hs.rets)
             };
             packed_host_sigs.push(HostSigEntry {
                 symbol: hs.symbol,
-                sig_hash: sig_hash(&sig),
+                sig_hash,
+                pure: hs.pure,
                 args: ByteRange {
                     offset: args_off,
                     len: args_len,
@@ -739,9 +881,40 @@ impl Program {
             program_name: None,
             function_names: Vec::new(),
             labels: Vec::new(),
+            exports: Vec::new(),
+            imports: Vec::new(),
+            custom_sections: Vec::new(),
+            source_map: Vec::new(),
         }
     }
 
+    /// Returns a borrowed view of the typed tables.
+    pub(crate) fn tables(&self) -> ProgramTables<'_> {
+        ProgramTables {
+            symbols: &self.symbols,
+            const_pool: &self.const_pool,
+            call_sigs: &self.call_sigs,
+            host_sigs: &self.host_sigs,
+            types: &self.types,
+            value_types: &self.value_types,
+            value_name_ids: &self.value_name_ids,
+            spans: &self.spans,
+            functions: &self.functions,
+            function_arg_agg_shapes: &self.function_arg_agg_shapes,
+            exports: &self.exports,
+            imports: &self.imports,
+        }
+    }
+
+    /// Returns the payload of the custom section named `name`, if present.
+    #[must_use]
+    pub fn custom_section(&self, name: &str) -> Option<&[u8]> {
+        self.custom_sections
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.bytes.as_slice())
+    }
+
     /// Returns the program name, if present.
     #[must_use]
     pub fn name(&self) -> Option<&str> {
@@ -766,6 +939,28 @@ impl Program {
             .and_then(|e| self.symbol_str(e.name).ok())
     }
 
+    /// Returns the source location recorded for `span` in the source map, if present.
+    #[must_use]
+    pub fn span_info(&self, span: SpanId) -> Option<SpanInfo<'_>> {
+        let i = self
+            .source_map
+            .binary_search_by_key(&span, |e| e.span)
+            .ok()?;
+        self.resolve_source_map_entry(&self.source_map[i])
+    }
+
+    fn resolve_source_map_entry(&self, e: &SourceMapEntry) -> Option<SpanInfo<'_>> {
+        Some(SpanInfo {
+            span: e.span,
+            file: self.symbol_str(e.file).ok()?,
+            range: e.range,
+            node_guid: match e.node_guid {
+                Some(id) => Some(self.symbol_str(id).ok()?),
+                None => None,
+            },
+        })
+    }
+
     /// Returns aggregate shape metadata for function argument `arg`, if present.
     ///
     /// This metadata only refines arguments whose declared type is [`ValueType::Agg`].
@@ -813,22 +1008,14 @@ impl Program {
     ///
     /// Entries are symbol table indices (so `0` means “no name”).
     pub fn function_arg_name_ids(&self, func: &Function) -> Result<&[u32], DecodeError> {
-        let start = func.arg_name_ids.offset as usize;
-        let end = func.arg_name_ids.end()? as usize;
-        self.value_name_ids
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().function_arg_name_ids(func)
     }
 
     /// Returns a slice of return name ids for `func`.
     ///
     /// Entries are symbol table indices (so `0` means “no name”).
     pub fn function_ret_name_ids(&self, func: &Function) -> Result<&[u32], DecodeError> {
-        let start = func.ret_name_ids.offset as usize;
-        let end = func.ret_name_ids.end()? as usize;
-        self.value_name_ids
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().function_ret_name_ids(func)
     }
 
     /// Returns a host-call symbol string for `id`.
@@ -870,20 +1057,12 @@ impl Program {
 
     /// Returns a slice of argument types for `func`.
     pub fn function_arg_types(&self, func: &Function) -> Result<&[ValueType], DecodeError> {
-        let start = func.arg_types.offset as usize;
-        let end = func.arg_types.end()? as usize;
-        self.value_types
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().function_arg_types(func)
     }
 
     /// Returns a slice of return types for `func`.
     pub fn function_ret_types(&self, func: &Function) -> Result<&[ValueType], DecodeError> {
-        let start = func.ret_types.offset as usize;
-        let end = func.ret_types.end()? as usize;
-        self.value_types
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().function_ret_types(func)
     }
 
     /// Returns the host signature entry for `id`.
@@ -898,20 +1077,12 @@ impl Program {
 
     /// Returns the call signature argument types for `entry`.
     pub fn call_sig_args(&self, entry: &CallSigEntry) -> Result<&[ValueType], DecodeError> {
-        let start = entry.args.offset as usize;
-        let end = entry.args.end()? as usize;
-        self.value_types
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().call_sig_args(entry)
     }
 
     /// Returns the call signature return types for `entry`.
     pub fn call_sig_rets(&self, entry: &CallSigEntry) -> Result<&[ValueType], DecodeError> {
-        let start = entry.rets.offset as usize;
-        let end = entry.rets.end()? as usize;
-        self.value_types
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().call_sig_rets(entry)
     }
 
     /// Computes the canonical signature hash for `entry`.
@@ -923,20 +1094,12 @@ impl Program {
 
     /// Returns the host signature argument types for `entry`.
     pub fn host_sig_args(&self, entry: &HostSigEntry) -> Result<&[ValueType], DecodeError> {
-        let start = entry.args.offset as usize;
-        let end = entry.args.end()? as usize;
-        self.value_types
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().host_sig_args(entry)
     }
 
     /// Returns the host signature return types for `entry`.
     pub fn host_sig_rets(&self, entry: &HostSigEntry) -> Result<&[ValueType], DecodeError> {
-        let start = entry.rets.offset as usize;
-        let end = entry.rets.end()? as usize;
-        self.value_types
-            .get(start..end)
-            .ok_or(DecodeError::OutOfBounds)
+        self.tables().host_sig_rets(entry)
     }
 
     /// Returns the raw bytecode slice for `func`.
@@ -950,9 +1113,7 @@ impl Program {
 
     /// Returns the span entries for `func`.
     pub fn function_spans(&self, func: &Function) -> Result<&[SpanEntry], DecodeError> {
-        let start = usize::try_from(func.spans.offset).map_err(|_| DecodeError::OutOfBounds)?;
-        let end = usize::try_from(func.spans.end()?).map_err(|_| DecodeError::OutOfBounds)?;
-        self.spans.get(start..end).ok_or(DecodeError::OutOfBounds)
+        self.tables().function_spans(func)
     }
 
     /// Encodes this program into the draft v1 container format.
@@ -979,6 +1140,9 @@ impl Program {
         // 8 = host_sigs
         // 10 = call_sigs (optional)
         // 11 = function_arg_agg_shapes (optional)
+        // 12 = linkage (optional)
+        // 13 = source_map (optional)
+        // 0x80..=0xFF = custom named sections (optional; written last, in name order)
         let mut w = Writer::new();
         w.write_bytes(MAGIC);
         w.write_u16_le(VERSION_MAJOR);
@@ -1102,6 +1266,7 @@ impl Program {
             for hs in &self.host_sigs {
                 payload.write_uleb128_u32(hs.symbol.index());
                 payload.write_u64_le(hs.sig_hash.0);
+                payload.write_u8(u8::from(hs.pure));
                 let args = self.host_sig_args(hs).unwrap_or(&[]);
                 payload.write_uleb128_u64(args.len() as u64);
                 for &t in args {
@@ -1188,27 +1353,61 @@ impl Program {
             write_section(&mut w, SectionTag::Names, payload.as_slice());
         }
 
-        w.into_vec()
-    }
-
-    /// Decodes a draft v1 container-format program from `bytes`.
-    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let mut r = Reader::new(bytes);
-        let magic = r.read_bytes(MAGIC.len())?;
-        if magic != MAGIC {
-            return Err(DecodeError::BadMagic);
+        // module linkage section (optional; omitted when there are no exports or imports)
+        if !self.exports.is_empty() || !self.imports.is_empty() {
+            let mut payload = Writer::new();
+            payload.write_uleb128_u64(self.exports.len() as u64);
+            for e in &self.exports {
+                payload.write_uleb128_u32(e.func);
+                payload.write_uleb128_u32(e.name.index());
+            }
+            payload.write_uleb128_u64(self.imports.len() as u64);
+            for e in &self.imports {
+                payload.write_uleb128_u32(e.func);
+                payload.write_uleb128_u32(e.module.index());
+                payload.write_uleb128_u32(e.name.index());
+            }
+            write_section(&mut w, SectionTag::Linkage, payload.as_slice());
         }
 
-        let major = r.read_u16_le()?;
-        let minor = r.read_u16_le()?;
-        if major != VERSION_MAJOR {
-            return Err(DecodeError::UnsupportedVersion { major, minor });
+        // source map section (optional; omitted when empty)
+        if !self.source_map.is_empty() {
+            let mut entries: Vec<&SourceMapEntry> = self.source_map.iter().collect();
+            entries.sort_by_key(|e| e.span);
+            let mut payload = Writer::new();
+            payload.write_uleb128_u64(entries.len() as u64);
+            for e in entries {
+                payload.write_uleb128_u64(e.span.get());
+                payload.write_uleb128_u32(e.file.index());
+                payload.write_uleb128_u32(e.range.start_line);
+                payload.write_uleb128_u32(e.range.start_col);
+                payload.write_uleb128_u32(e.range.end_line);
+                payload.write_uleb128_u32(e.range.end_col);
+                payload.write_uleb128_u32(e.node_guid.map_or(0, SymbolId::index));
+            }
+            write_section(&mut w, SectionTag::SourceMap, payload.as_slice());
         }
-        if minor != VERSION_MINOR {
-            return Err(DecodeError::UnsupportedVersion { major, minor });
+
+        // custom sections (optional; name-sorted so the encoding is canonical)
+        let mut custom: Vec<&CustomSection> = self.custom_sections.iter().collect();
+        custom.sort_by(|a, b| a.name.cmp(&b.name));
+        for section in custom {
+            let mut payload = Writer::new();
+            payload.write_uleb128_u64(section.name.len() as u64);
+            payload.write_bytes(section.name.as_bytes());
+            payload.write_bytes(&section.bytes);
+            w.write_u8(CUSTOM_SECTION_TAG);
+            w.write_uleb128_u64(payload.as_slice().len() as u64);
+            w.write_bytes(payload.as_slice());
         }
 
-        decode_current(bytes, r)
+        w.into_vec()
+    }
+
+    /// Decodes a draft v1 container-format program from `bytes`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let sections = Sections::read(bytes)?;
+        DecodedTables::decode(&sections)?.into_program(&sections)
     }
 }
 
@@ -1225,9 +1424,19 @@ enum SectionTag {
     Names = 9,
     CallSigs = 10,
     FunctionArgAggShapes = 11,
+    Linkage = 12,
+    SourceMap = 13,
 }
 
+/// First tag of the range reserved for custom named sections (`0x80..=0xFF`).
+///
+/// Encoders always write this tag; decoders accept any tag in the range.
+const CUSTOM_SECTION_TAG: u8 = 0x80;
+
 impl SectionTag {
+    /// One past the largest known tag, for tables indexed by tag.
+    const COUNT: usize = Self::SourceMap as usize + 1;
+
     fn from_u8_opt(v: u8) -> Option<Self> {
         match v {
             1 => Some(Self::Symbols),
@@ -1241,11 +1450,36 @@ impl SectionTag {
             9 => Some(Self::Names),
             10 => Some(Self::CallSigs),
             11 => Some(Self::FunctionArgAggShapes),
+            12 => Some(Self::Linkage),
+            13 => Some(Self::SourceMap),
             _ => None,
         }
     }
 }
 
+/// Returns a human-readable name for a container section tag.
+pub(crate) fn section_name(tag: u8) -> &'static str {
+    if tag >= CUSTOM_SECTION_TAG {
+        return "custom";
+    }
+    match SectionTag::from_u8_opt(tag) {
+        Some(SectionTag::Symbols) => "symbols",
+        Some(SectionTag::ConstPool) => "const_pool",
+        Some(SectionTag::Types) => "types",
+        Some(SectionTag::FunctionTable) => "function_table",
+        Some(SectionTag::BytecodeBlobs) => "bytecode_blobs",
+        Some(SectionTag::SpanTables) => "span_tables",
+        Some(SectionTag::FunctionSigs) => "function_sigs",
+        Some(SectionTag::HostSigs) => "host_sigs",
+        Some(SectionTag::Names) => "names",
+        Some(SectionTag::CallSigs) => "call_sigs",
+        Some(SectionTag::FunctionArgAggShapes) => "function_arg_agg_shapes",
+        Some(SectionTag::Linkage) => "linkage",
+        Some(SectionTag::SourceMap) => "source_map",
+        None => "unknown",
+    }
+}
+
 fn write_section(w: &mut Writer, tag: SectionTag, payload: &[u8]) {
     w.write_u8(tag as u8);
     w.write_uleb128_u64(payload.len() as u64);
@@ -1305,400 +1539,712 @@ fn decode_names(payload: &[u8]) -> Result<NamesDef, DecodeError> {
     })
 }
 
-fn decode_symbols(payload: &[u8]) -> Result<(Vec<SymbolEntry>, String), DecodeError> {
+#[derive(Clone, Debug, Default)]
+struct LinkageDef {
+    exports: Vec<ExportEntry>,
+    imports: Vec<ImportEntry>,
+}
+
+fn decode_source_map(payload: &[u8]) -> Result<Vec<SourceMapEntry>, DecodeError> {
+    let mut r = Reader::new(payload);
+    let n = read_usize(&mut r)?;
+    let mut out: Vec<SourceMapEntry> = Vec::with_capacity(n.min(payload.len()));
+    for _ in 0..n {
+        let span = SpanId::try_from(r.read_uleb128_u64()?)?;
+        // Entries are sorted by span id (and so unique) to allow binary search.
+        if out.last().is_some_and(|prev| prev.span >= span) {
+            return Err(DecodeError::OutOfBounds);
+        }
+        let file = SymbolId(r.read_uleb128_u32_nz()?);
+        let range = SourceRange {
+            start_line: r.read_uleb128_u32()?,
+            start_col: r.read_uleb128_u32()?,
+            end_line: r.read_uleb128_u32()?,
+            end_col: r.read_uleb128_u32()?,
+        };
+        let node_guid = NonZeroU32::new(r.read_uleb128_u32()?).map(SymbolId);
+        out.push(SourceMapEntry {
+            span,
+            file,
+            range,
+            node_guid,
+        });
+    }
+
+    if r.offset() != payload.len() {
+        return Err(DecodeError::OutOfBounds);
+    }
+    Ok(out)
+}
+
+fn decode_linkage(payload: &[u8]) -> Result<LinkageDef, DecodeError> {
+    let mut r = Reader::new(payload);
+    let n_exports = read_usize(&mut r)?;
+    let mut exports = Vec::with_capacity(n_exports);
+    for _ in 0..n_exports {
+        let func = r.read_uleb128_u32()?;
+        let name = r.read_uleb128_u32_nz()?;
+        exports.push(ExportEntry {
+            func,
+            name: SymbolId(name),
+        });
+    }
+
+    let n_imports = read_usize(&mut r)?;
+    let mut imports = Vec::with_capacity(n_imports);
+    for _ in 0..n_imports {
+        let func = r.read_uleb128_u32()?;
+        let module = r.read_uleb128_u32_nz()?;
+        let name = r.read_uleb128_u32_nz()?;
+        imports.push(ImportEntry {
+            func,
+            module: SymbolId(module),
+            name: SymbolId(name),
+        });
+    }
+
+    if r.offset() != payload.len() {
+        return Err(DecodeError::OutOfBounds);
+    }
+
+    Ok(LinkageDef { exports, imports })
+}
+
+/// Decodes the symbol table as ranges into `payload`.
+fn decode_symbol_ranges(payload: &[u8]) -> Result<Vec<ByteRange>, DecodeError> {
     let mut r = Reader::new(payload);
     let n = read_usize(&mut r)?;
-    let mut symbols: Vec<SymbolEntry> = Vec::with_capacity(n);
-    let mut symbol_data: String = String::new();
+    let mut out: Vec<ByteRange> = Vec::with_capacity(n);
     for _ in 0..n {
         let len = read_usize(&mut r)?;
+        let offset = u32::try_from(r.offset()).map_err(|_| DecodeError::OutOfBounds)?;
         let s = r.read_str(len)?;
-        let offset = u32::try_from(symbol_data.len()).map_err(|_| DecodeError::OutOfBounds)?;
         let len = u32::try_from(s.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        symbol_data.push_str(s);
-        symbols.push(SymbolEntry {
-            bytes: ByteRange { offset, len },
-        });
+        out.push(ByteRange { offset, len });
     }
     // Symbol index 0 is reserved so `SymbolId` can be non-zero. We encode it as an empty string so
     // it cannot accidentally collide with a real symbol.
-    if symbols.is_empty() || symbols[0].bytes.len != 0 {
+    if out.is_empty() || out[0].len != 0 {
         return Err(DecodeError::OutOfBounds);
     }
-    Ok((symbols, symbol_data))
-}
-
-fn decode_current(bytes: &[u8], mut r: Reader<'_>) -> Result<Program, DecodeError> {
-    let mut symbols: Vec<SymbolEntry> = Vec::new();
-    let mut symbol_data: String = String::new();
-    let mut const_pool: Vec<ConstEntry> = Vec::new();
-    let mut const_bytes_data: Vec<u8> = Vec::new();
-    let mut const_str_data: String = String::new();
-    let mut call_sig_defs: Vec<DecodedCallSig> = Vec::new();
-    let mut host_sig_defs: Vec<(SymbolId, SigHash, Vec<ValueType>, Vec<ValueType>)> = Vec::new();
-    let mut types: TypeTableDef = TypeTableDef::default();
-    let mut function_table: Vec<FunctionTableEntry> = Vec::new();
-    let mut function_sig_defs: Vec<DecodedFunctionSig> = Vec::new();
-    let mut bytecode_blobs: Vec<Vec<u8>> = Vec::new();
-    let mut span_tables: Vec<Vec<SpanEntry>> = Vec::new();
-    let mut function_arg_agg_shapes: Vec<FunctionArgAggShapeEntry> = Vec::new();
-    let mut names: NamesDef = NamesDef::default();
-
-    let mut saw_symbols = false;
-    let mut saw_const_pool = false;
-    let mut saw_call_sigs = false;
-    let mut saw_host_sigs = false;
-    let mut saw_types = false;
-    let mut saw_function_table = false;
-    let mut saw_function_sigs = false;
-    let mut saw_bytecode_blobs = false;
-    let mut saw_span_tables = false;
-    let mut saw_function_arg_agg_shapes = false;
-    let mut saw_names = false;
-
-    while r.offset() < bytes.len() {
-        let tag = SectionTag::from_u8_opt(r.read_u8()?);
-        let len = read_usize(&mut r)?;
-        let payload = r.read_bytes(len)?;
-        match tag {
-            Some(SectionTag::Symbols) => {
-                if saw_symbols {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_symbols = true;
-                (symbols, symbol_data) = decode_symbols(payload)?;
-            }
-            Some(SectionTag::ConstPool) => {
-                if saw_const_pool {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_const_pool = true;
-                const_pool =
-                    decode_const_pool(payload, &mut const_bytes_data, &mut const_str_data)?;
-            }
-            Some(SectionTag::Types) => {
-                if saw_types {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_types = true;
-                types = decode_types(payload)?;
-            }
-            Some(SectionTag::FunctionSigs) => {
-                if saw_function_sigs {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_function_sigs = true;
-                function_sig_defs = decode_function_sigs(payload)?;
-            }
-            Some(SectionTag::FunctionTable) => {
-                if saw_function_table {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_function_table = true;
-                function_table = decode_function_table(payload)?;
-            }
-            Some(SectionTag::BytecodeBlobs) => {
-                if saw_bytecode_blobs {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_bytecode_blobs = true;
-                bytecode_blobs = decode_blob_list(payload)?;
-            }
-            Some(SectionTag::SpanTables) => {
-                if saw_span_tables {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_span_tables = true;
-                span_tables = decode_span_tables(payload)?;
-            }
-            Some(SectionTag::HostSigs) => {
-                if saw_host_sigs {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_host_sigs = true;
-                host_sig_defs = decode_host_sigs(payload)?;
-            }
-            Some(SectionTag::CallSigs) => {
-                if saw_call_sigs {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_call_sigs = true;
-                call_sig_defs = decode_call_sigs(payload)?;
-            }
-            Some(SectionTag::FunctionArgAggShapes) => {
-                if saw_function_arg_agg_shapes {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_function_arg_agg_shapes = true;
-                function_arg_agg_shapes = decode_function_arg_agg_shapes(payload)?;
-            }
-            Some(SectionTag::Names) => {
-                if saw_names {
-                    return Err(DecodeError::DuplicateSection);
-                }
-                saw_names = true;
-                names = decode_names(payload)?;
+    Ok(out)
+}
+
+/// Resolves `range` within `data`.
+pub(crate) fn range_bytes(data: &[u8], range: ByteRange) -> Result<&[u8], DecodeError> {
+    let start = usize::try_from(range.offset).map_err(|_| DecodeError::OutOfBounds)?;
+    let end = usize::try_from(range.end()?).map_err(|_| DecodeError::OutOfBounds)?;
+    data.get(start..end).ok_or(DecodeError::OutOfBounds)
+}
+
+/// Resolves `range` within `data` as UTF-8.
+pub(crate) fn range_str(data: &[u8], range: ByteRange) -> Result<&str, DecodeError> {
+    core::str::from_utf8(range_bytes(data, range)?).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+/// Section payloads of an encoded program, located without decoding them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Sections<'a> {
+    pub(crate) symbols: &'a [u8],
+    pub(crate) const_pool: &'a [u8],
+    pub(crate) types: &'a [u8],
+    pub(crate) function_sigs: &'a [u8],
+    pub(crate) function_table: &'a [u8],
+    pub(crate) bytecode_blobs: &'a [u8],
+    pub(crate) span_tables: &'a [u8],
+    pub(crate) host_sigs: &'a [u8],
+    pub(crate) call_sigs: Option<&'a [u8]>,
+    pub(crate) function_arg_agg_shapes: Option<&'a [u8]>,
+    pub(crate) names: Option<&'a [u8]>,
+    pub(crate) linkage: Option<&'a [u8]>,
+    pub(crate) source_map: Option<&'a [u8]>,
+    /// Custom sections as `(name, payload)`, sorted by name.
+    pub(crate) custom: Vec<(&'a str, &'a [u8])>,
+}
+
+impl<'a> Sections<'a> {
+    /// Checks the container header and locates every known section payload.
+    pub(crate) fn read(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader::new(bytes);
+        let magic = r.read_bytes(MAGIC.len())?;
+        if magic != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+
+        let major = r.read_u16_le()?;
+        let minor = r.read_u16_le()?;
+        if major != VERSION_MAJOR {
+            return Err(DecodeError::UnsupportedVersion { major, minor });
+        }
+        if minor != VERSION_MINOR {
+            return Err(DecodeError::UnsupportedVersion { major, minor });
+        }
+
+        let mut found: [Option<&'a [u8]>; SectionTag::COUNT] = [None; SectionTag::COUNT];
+        let mut custom: Vec<(&'a str, &'a [u8])> = Vec::new();
+        while r.offset() < bytes.len() {
+            let raw_tag = r.read_u8()?;
+            let len = read_usize(&mut r)?;
+            let payload = r.read_bytes(len)?;
+            if raw_tag >= CUSTOM_SECTION_TAG {
+                let mut pr = Reader::new(payload);
+                let name_len = read_usize(&mut pr)?;
+                let name = pr.read_str(name_len)?;
+                custom.push((name, &payload[pr.offset()..]));
+                continue;
             }
-            None => {
-                // Forward-compat: skip unknown section tags.
+            let tag = SectionTag::from_u8_opt(raw_tag);
+            // Forward-compat: skip unknown section tags.
+            let Some(tag) = tag else { continue };
+            let slot = &mut found[tag as usize];
+            if slot.is_some() {
+                return Err(DecodeError::DuplicateSection);
             }
+            *slot = Some(payload);
+        }
+
+        custom.sort_by(|a, b| a.0.cmp(b.0));
+        if custom.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(DecodeError::DuplicateSection);
         }
+
+        let required = |tag: SectionTag| {
+            found[tag as usize].ok_or(DecodeError::MissingSection { tag: tag as u8 })
+        };
+        Ok(Self {
+            symbols: required(SectionTag::Symbols)?,
+            const_pool: required(SectionTag::ConstPool)?,
+            types: required(SectionTag::Types)?,
+            function_sigs: required(SectionTag::FunctionSigs)?,
+            function_table: required(SectionTag::FunctionTable)?,
+            bytecode_blobs: required(SectionTag::BytecodeBlobs)?,
+            span_tables: required(SectionTag::SpanTables)?,
+            host_sigs: required(SectionTag::HostSigs)?,
+            call_sigs: found[SectionTag::CallSigs as usize],
+            function_arg_agg_shapes: found[SectionTag::FunctionArgAggShapes as usize],
+            names: found[SectionTag::Names as usize],
+            linkage: found[SectionTag::Linkage as usize],
+            source_map: found[SectionTag::SourceMap as usize],
+            custom,
+        })
     }
+}
 
-    if !saw_symbols {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::Symbols as u8,
-        });
+/// The typed tables of a program, borrowed from a [`Program`] or a [`DecodedTables`].
+///
+/// Symbol, bytes/string constant and bytecode ranges index byte arenas that are not part of this
+/// view; its owner resolves them. The verifier reads programs through this view so owned and
+/// borrowed programs share one implementation.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ProgramTables<'a> {
+    pub(crate) symbols: &'a [SymbolEntry],
+    pub(crate) const_pool: &'a [ConstEntry],
+    pub(crate) call_sigs: &'a [CallSigEntry],
+    pub(crate) host_sigs: &'a [HostSigEntry],
+    pub(crate) types: &'a TypeTable,
+    pub(crate) value_types: &'a [ValueType],
+    pub(crate) value_name_ids: &'a [u32],
+    pub(crate) spans: &'a [SpanEntry],
+    pub(crate) functions: &'a [Function],
+    pub(crate) function_arg_agg_shapes: &'a [FunctionArgAggShapeEntry],
+    pub(crate) exports: &'a [ExportEntry],
+    pub(crate) imports: &'a [ImportEntry],
+}
+
+impl<'a> ProgramTables<'a> {
+    /// See [`Program::function_arg_name_ids`].
+    pub(crate) fn function_arg_name_ids(&self, func: &Function) -> Result<&'a [u32], DecodeError> {
+        range_slice(self.value_name_ids, func.arg_name_ids)
     }
-    if !saw_const_pool {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::ConstPool as u8,
-        });
+
+    /// See [`Program::function_ret_name_ids`].
+    pub(crate) fn function_ret_name_ids(&self, func: &Function) -> Result<&'a [u32], DecodeError> {
+        range_slice(self.value_name_ids, func.ret_name_ids)
     }
-    if !saw_types {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::Types as u8,
-        });
+
+    /// See [`Program::function_arg_types`].
+    pub(crate) fn function_arg_types(
+        &self,
+        func: &Function,
+    ) -> Result<&'a [ValueType], DecodeError> {
+        range_slice(self.value_types, func.arg_types)
     }
-    if !saw_function_sigs {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::FunctionSigs as u8,
-        });
+
+    /// See [`Program::function_ret_types`].
+    pub(crate) fn function_ret_types(
+        &self,
+        func: &Function,
+    ) -> Result<&'a [ValueType], DecodeError> {
+        range_slice(self.value_types, func.ret_types)
     }
-    if !saw_function_table {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::FunctionTable as u8,
-        });
+
+    /// See [`Program::function_spans`].
+    pub(crate) fn function_spans(&self, func: &Function) -> Result<&'a [SpanEntry], DecodeError> {
+        range_slice(self.spans, func.spans)
     }
-    if !saw_bytecode_blobs {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::BytecodeBlobs as u8,
-        });
+
+    /// See [`Program::host_sig`].
+    pub(crate) fn host_sig(&self, id: HostSigId) -> Option<&'a HostSigEntry> {
+        self.host_sigs.get(id.0 as usize)
     }
-    if !saw_span_tables {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::SpanTables as u8,
-        });
+
+    /// See [`Program::call_sig`].
+    pub(crate) fn call_sig(&self, id: CallSigId) -> Option<&'a CallSigEntry> {
+        self.call_sigs.get(id.0 as usize)
     }
-    if !saw_host_sigs {
-        return Err(DecodeError::MissingSection {
-            tag: SectionTag::HostSigs as u8,
-        });
+
+    /// See [`Program::call_sig_args`].
+    pub(crate) fn call_sig_args(
+        &self,
+        entry: &CallSigEntry,
+    ) -> Result<&'a [ValueType], DecodeError> {
+        range_slice(self.value_types, entry.args)
     }
 
-    // Pack arenas.
-    let mut bytecode_data: Vec<u8> = Vec::new();
-    let mut bytecode_ranges: Vec<ByteRange> = Vec::with_capacity(bytecode_blobs.len());
-    for b in bytecode_blobs {
-        let offset = u32::try_from(bytecode_data.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        bytecode_data.extend_from_slice(&b);
-        let len = u32::try_from(b.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        bytecode_ranges.push(ByteRange { offset, len });
+    /// See [`Program::call_sig_rets`].
+    pub(crate) fn call_sig_rets(
+        &self,
+        entry: &CallSigEntry,
+    ) -> Result<&'a [ValueType], DecodeError> {
+        range_slice(self.value_types, entry.rets)
     }
 
-    let mut spans: Vec<SpanEntry> = Vec::new();
-    let mut span_ranges: Vec<ByteRange> = Vec::with_capacity(span_tables.len());
-    for s in span_tables {
-        let offset = u32::try_from(spans.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        spans.extend_from_slice(&s);
-        let len = u32::try_from(s.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        span_ranges.push(ByteRange { offset, len });
-    }
-
-    // Pack value types in builder order — host sigs, then functions, then call
-    // sigs (appended after the function loop) — so `decode(p.encode()) == p`
-    // holds for builder-produced programs. See `ProgramBuilder::build`.
-    let mut value_types: Vec<ValueType> = Vec::new();
-    let mut value_name_ids: Vec<u32> = Vec::new();
-    let mut host_sigs: Vec<HostSigEntry> = Vec::with_capacity(host_sig_defs.len());
-    for (symbol, sig_hash, args, rets) in host_sig_defs {
-        let args_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        value_types.extend_from_slice(&args);
-        let args_len = u32::try_from(args.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        let rets_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        value_types.extend_from_slice(&rets);
-        let rets_len = u32::try_from(rets.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        host_sigs.push(HostSigEntry {
-            symbol,
-            sig_hash,
-            args: ByteRange {
-                offset: args_off,
-                len: args_len,
-            },
-            rets: ByteRange {
-                offset: rets_off,
-                len: rets_len,
-            },
-        });
+    /// See [`Program::host_sig_args`].
+    pub(crate) fn host_sig_args(
+        &self,
+        entry: &HostSigEntry,
+    ) -> Result<&'a [ValueType], DecodeError> {
+        range_slice(self.value_types, entry.args)
     }
 
-    let mut functions = Vec::with_capacity(function_table.len());
-    if function_sig_defs.len() != function_table.len() {
-        return Err(DecodeError::OutOfBounds);
+    /// See [`Program::host_sig_rets`].
+    pub(crate) fn host_sig_rets(
+        &self,
+        entry: &HostSigEntry,
+    ) -> Result<&'a [ValueType], DecodeError> {
+        range_slice(self.value_types, entry.rets)
     }
-    for (entry, (arg_types, ret_types, arg_names, ret_names)) in
-        function_table.into_iter().zip(function_sig_defs)
-    {
-        let bc = *bytecode_ranges
-            .get(usize::try_from(entry.bytecode_index).map_err(|_| DecodeError::OutOfBounds)?)
-            .ok_or(DecodeError::OutOfBounds)?;
-        let sp = *span_ranges
-            .get(usize::try_from(entry.span_index).map_err(|_| DecodeError::OutOfBounds)?)
-            .ok_or(DecodeError::OutOfBounds)?;
+}
 
-        let arg_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        value_types.extend_from_slice(&arg_types);
-        let arg_len = u32::try_from(arg_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        let ret_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        value_types.extend_from_slice(&ret_types);
-        let ret_len = u32::try_from(ret_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+fn range_slice<T>(data: &[T], range: ByteRange) -> Result<&[T], DecodeError> {
+    let start = usize::try_from(range.offset).map_err(|_| DecodeError::OutOfBounds)?;
+    let end = usize::try_from(range.end()?).map_err(|_| DecodeError::OutOfBounds)?;
+    data.get(start..end).ok_or(DecodeError::OutOfBounds)
+}
 
-        let arg_name_range = if arg_names.is_empty() {
-            ByteRange { offset: 0, len: 0 }
-        } else {
-            if arg_names.len() != arg_types.len() {
-                return Err(DecodeError::OutOfBounds);
-            }
-            let offset =
-                u32::try_from(value_name_ids.len()).map_err(|_| DecodeError::OutOfBounds)?;
-            value_name_ids.extend_from_slice(&arg_names);
-            let len = u32::try_from(arg_names.len()).map_err(|_| DecodeError::OutOfBounds)?;
-            ByteRange { offset, len }
-        };
+/// The typed tables of an encoded program, decoded without copying its byte payloads.
+///
+/// Symbol, bytes/string constant and bytecode ranges index the [`Sections`] payloads they were
+/// read from. [`DecodedTables::into_program`] copies those payloads into a [`Program`]'s arenas.
+#[derive(Clone, Debug)]
+pub(crate) struct DecodedTables {
+    pub(crate) symbols: Vec<SymbolEntry>,
+    pub(crate) const_pool: Vec<ConstEntry>,
+    pub(crate) call_sigs: Vec<CallSigEntry>,
+    pub(crate) host_sigs: Vec<HostSigEntry>,
+    pub(crate) types: TypeTable,
+    pub(crate) value_types: Vec<ValueType>,
+    pub(crate) value_name_ids: Vec<u32>,
+    pub(crate) spans: Vec<SpanEntry>,
+    pub(crate) functions: Vec<Function>,
+    pub(crate) function_arg_agg_shapes: Vec<FunctionArgAggShapeEntry>,
+    pub(crate) program_name: Option<SymbolId>,
+    pub(crate) function_names: Vec<FunctionNameEntry>,
+    pub(crate) labels: Vec<LabelNameEntry>,
+    pub(crate) exports: Vec<ExportEntry>,
+    pub(crate) imports: Vec<ImportEntry>,
+    pub(crate) source_map: Vec<SourceMapEntry>,
+    /// Bytecode blob ranges, in section order.
+    blobs: Vec<ByteRange>,
+    /// Index into `blobs` of each function's bytecode.
+    function_blobs: Vec<usize>,
+}
 
-        let ret_name_range = if ret_names.is_empty() {
-            ByteRange { offset: 0, len: 0 }
-        } else {
-            if ret_names.len() != ret_types.len() {
-                return Err(DecodeError::OutOfBounds);
+impl DecodedTables {
+    /// Returns a borrowed view of the tables.
+    pub(crate) fn tables(&self) -> ProgramTables<'_> {
+        ProgramTables {
+            symbols: &self.symbols,
+            const_pool: &self.const_pool,
+            call_sigs: &self.call_sigs,
+            host_sigs: &self.host_sigs,
+            types: &self.types,
+            value_types: &self.value_types,
+            value_name_ids: &self.value_name_ids,
+            spans: &self.spans,
+            functions: &self.functions,
+            function_arg_agg_shapes: &self.function_arg_agg_shapes,
+            exports: &self.exports,
+            imports: &self.imports,
+        }
+    }
+
+    /// Builds a [`Program`], copying symbol, constant and bytecode bytes out of `sections`.
+    ///
+    /// `sections` must be the sections these tables were decoded from.
+    pub(crate) fn into_program(mut self, sections: &Sections<'_>) -> Result<Program, DecodeError> {
+        let mut symbol_data = String::new();
+        for e in &mut self.symbols {
+            let s = range_str(sections.symbols, e.bytes)?;
+            e.bytes.offset =
+                u32::try_from(symbol_data.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            symbol_data.push_str(s);
+        }
+
+        let mut const_bytes_data: Vec<u8> = Vec::new();
+        let mut const_str_data = String::new();
+        for c in &mut self.const_pool {
+            match c {
+                ConstEntry::Bytes(r) => {
+                    let b = range_bytes(sections.const_pool, *r)?;
+                    r.offset = u32::try_from(const_bytes_data.len())
+                        .map_err(|_| DecodeError::OutOfBounds)?;
+                    const_bytes_data.extend_from_slice(b);
+                }
+                ConstEntry::Str(r) => {
+                    let s = range_str(sections.const_pool, *r)?;
+                    r.offset = u32::try_from(const_str_data.len())
+                        .map_err(|_| DecodeError::OutOfBounds)?;
+                    const_str_data.push_str(s);
+                }
+                _ => {}
             }
+        }
+
+        let mut bytecode_data: Vec<u8> = Vec::new();
+        let mut blobs: Vec<ByteRange> = Vec::with_capacity(self.blobs.len());
+        for range in &self.blobs {
+            let b = range_bytes(sections.bytecode_blobs, *range)?;
             let offset =
-                u32::try_from(value_name_ids.len()).map_err(|_| DecodeError::OutOfBounds)?;
-            value_name_ids.extend_from_slice(&ret_names);
-            let len = u32::try_from(ret_names.len()).map_err(|_| DecodeError::OutOfBounds)?;
-            ByteRange { offset, len }
-        };
+                u32::try_from(bytecode_data.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            bytecode_data.extend_from_slice(b);
+            blobs.push(ByteRange {
+                offset,
+                len: range.len,
+            });
+        }
+        for (f, &blob) in self.functions.iter_mut().zip(&self.function_blobs) {
+            f.bytecode = blobs[blob];
+        }
 
-        functions.push(Function {
-            arg_count: entry.arg_count,
-            ret_count: entry.ret_count,
-            reg_count: entry.reg_count,
-            bytecode: bc,
-            spans: sp,
-            arg_types: ByteRange {
-                offset: arg_off,
-                len: arg_len,
-            },
-            ret_types: ByteRange {
-                offset: ret_off,
-                len: ret_len,
-            },
-            arg_name_ids: arg_name_range,
-            ret_name_ids: ret_name_range,
-        });
-    }
+        Ok(Program {
+            symbols: self.symbols,
+            symbol_data,
+            const_pool: self.const_pool,
+            const_bytes_data,
+            const_str_data,
+            call_sigs: self.call_sigs,
+            host_sigs: self.host_sigs,
+            types: self.types,
+            value_types: self.value_types,
+            value_name_ids: self.value_name_ids,
+            bytecode_data,
+            spans: self.spans,
+            functions: self.functions,
+            function_arg_agg_shapes: self.function_arg_agg_shapes,
+            program_name: self.program_name,
+            function_names: self.function_names,
+            labels: self.labels,
+            exports: self.exports,
+            imports: self.imports,
+            custom_sections: sections
+                .custom
+                .iter()
+                .map(|&(name, bytes)| CustomSection {
+                    name: name.into(),
+                    bytes: bytes.to_vec(),
+                })
+                .collect(),
+            source_map: self.source_map,
+        })
+    }
+
+    /// Decodes the typed tables of `sections`.
+    pub(crate) fn decode(sections: &Sections<'_>) -> Result<Self, DecodeError> {
+        let symbols: Vec<SymbolEntry> = decode_symbol_ranges(sections.symbols)?
+            .into_iter()
+            .map(|bytes| SymbolEntry { bytes })
+            .collect();
+        let const_pool = decode_const_pool(sections.const_pool)?;
+        let types = decode_types(sections.types)?;
+        let function_sig_defs = decode_function_sigs(sections.function_sigs)?;
+        let function_table = decode_function_table(sections.function_table)?;
+        let blob_ranges = decode_blob_ranges(sections.bytecode_blobs)?;
+        let span_tables = decode_span_tables(sections.span_tables)?;
+        let host_sig_defs = decode_host_sigs(sections.host_sigs)?;
+        let call_sig_defs = sections
+            .call_sigs
+            .map(decode_call_sigs)
+            .transpose()?
+            .unwrap_or_default();
+        let function_arg_agg_shapes = sections
+            .function_arg_agg_shapes
+            .map(decode_function_arg_agg_shapes)
+            .transpose()?
+            .unwrap_or_default();
+        let names = sections
+            .names
+            .map(decode_names)
+            .transpose()?
+            .unwrap_or_default();
+        let linkage = sections
+            .linkage
+            .map(decode_linkage)
+            .transpose()?
+            .unwrap_or_default();
+        let source_map = sections
+            .source_map
+            .map(decode_source_map)
+            .transpose()?
+            .unwrap_or_default();
 
-    // Call sigs packed last, matching the builder (see the note above).
-    let mut call_sigs: Vec<CallSigEntry> = Vec::with_capacity(call_sig_defs.len());
-    for (args, rets) in call_sig_defs {
-        let args_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        value_types.extend_from_slice(&args);
-        let args_len = u32::try_from(args.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        let rets_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        value_types.extend_from_slice(&rets);
-        let rets_len = u32::try_from(rets.len()).map_err(|_| DecodeError::OutOfBounds)?;
-        call_sigs.push(CallSigEntry {
-            args: ByteRange {
-                offset: args_off,
-                len: args_len,
-            },
-            rets: ByteRange {
-                offset: rets_off,
-                len: rets_len,
-            },
-        });
-    }
+        let mut spans: Vec<SpanEntry> = Vec::new();
+        let mut span_ranges: Vec<ByteRange> = Vec::with_capacity(span_tables.len());
+        for s in span_tables {
+            let offset = u32::try_from(spans.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            spans.extend_from_slice(&s);
+            let len = u32::try_from(s.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            span_ranges.push(ByteRange { offset, len });
+        }
 
-    // Validate optional name metadata.
-    if let Some(name) = names.program_name
-        && symbols.get(name.index() as usize).is_none()
-    {
-        return Err(DecodeError::OutOfBounds);
-    }
-    for e in &names.function_names {
-        if usize::try_from(e.func)
-            .ok()
-            .and_then(|i| functions.get(i))
-            .is_none()
-        {
-            return Err(DecodeError::OutOfBounds);
+        // Pack value types in builder order — host sigs, then functions, then call
+        // sigs (appended after the function loop) — so `decode(p.encode()) == p`
+        // holds for builder-produced programs. See `ProgramBuilder::build`.
+        let mut value_types: Vec<ValueType> = Vec::new();
+        let mut value_name_ids: Vec<u32> = Vec::new();
+        let mut host_sigs: Vec<HostSigEntry> = Vec::with_capacity(host_sig_defs.len());
+        for (symbol, sig_hash, pure, args, rets) in host_sig_defs {
+            let args_off =
+                u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            value_types.extend_from_slice(&args);
+            let args_len = u32::try_from(args.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            let rets_off =
+                u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            value_types.extend_from_slice(&rets);
+            let rets_len = u32::try_from(rets.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            host_sigs.push(HostSigEntry {
+                symbol,
+                sig_hash,
+                pure,
+                args: ByteRange {
+                    offset: args_off,
+                    len: args_len,
+                },
+                rets: ByteRange {
+                    offset: rets_off,
+                    len: rets_len,
+                },
+            });
         }
-        if symbols.get(e.name.index() as usize).is_none() {
+
+        let mut functions = Vec::with_capacity(function_table.len());
+        let mut function_blobs = Vec::with_capacity(function_table.len());
+        if function_sig_defs.len() != function_table.len() {
             return Err(DecodeError::OutOfBounds);
         }
-    }
-    for e in &names.labels {
-        let Some(func) = usize::try_from(e.func).ok().and_then(|i| functions.get(i)) else {
-            return Err(DecodeError::OutOfBounds);
-        };
-        if e.pc > func.bytecode.len {
-            return Err(DecodeError::OutOfBounds);
+        for (entry, (arg_types, ret_types, arg_names, ret_names)) in
+            function_table.into_iter().zip(function_sig_defs)
+        {
+            let blob =
+                usize::try_from(entry.bytecode_index).map_err(|_| DecodeError::OutOfBounds)?;
+            let bc = *blob_ranges.get(blob).ok_or(DecodeError::OutOfBounds)?;
+            function_blobs.push(blob);
+            let sp = *span_ranges
+                .get(usize::try_from(entry.span_index).map_err(|_| DecodeError::OutOfBounds)?)
+                .ok_or(DecodeError::OutOfBounds)?;
+
+            let arg_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            value_types.extend_from_slice(&arg_types);
+            let arg_len = u32::try_from(arg_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            let ret_off = u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            value_types.extend_from_slice(&ret_types);
+            let ret_len = u32::try_from(ret_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+
+            let arg_name_range = if arg_names.is_empty() {
+                ByteRange { offset: 0, len: 0 }
+            } else {
+                if arg_names.len() != arg_types.len() {
+                    return Err(DecodeError::OutOfBounds);
+                }
+                let offset =
+                    u32::try_from(value_name_ids.len()).map_err(|_| DecodeError::OutOfBounds)?;
+                value_name_ids.extend_from_slice(&arg_names);
+                let len = u32::try_from(arg_names.len()).map_err(|_| DecodeError::OutOfBounds)?;
+                ByteRange { offset, len }
+            };
+
+            let ret_name_range = if ret_names.is_empty() {
+                ByteRange { offset: 0, len: 0 }
+            } else {
+                if ret_names.len() != ret_types.len() {
+                    return Err(DecodeError::OutOfBounds);
+                }
+                let offset =
+                    u32::try_from(value_name_ids.len()).map_err(|_| DecodeError::OutOfBounds)?;
+                value_name_ids.extend_from_slice(&ret_names);
+                let len = u32::try_from(ret_names.len()).map_err(|_| DecodeError::OutOfBounds)?;
+                ByteRange { offset, len }
+            };
+
+            functions.push(Function {
+                arg_count: entry.arg_count,
+                ret_count: entry.ret_count,
+                reg_count: entry.reg_count,
+                bytecode: bc,
+                spans: sp,
+                arg_types: ByteRange {
+                    offset: arg_off,
+                    len: arg_len,
+                },
+                ret_types: ByteRange {
+                    offset: ret_off,
+                    len: ret_len,
+                },
+                arg_name_ids: arg_name_range,
+                ret_name_ids: ret_name_range,
+            });
         }
-        if symbols.get(e.name.index() as usize).is_none() {
+
+        // Call sigs packed last, matching the builder (see the note above).
+        let mut call_sigs: Vec<CallSigEntry> = Vec::with_capacity(call_sig_defs.len());
+        for (args, rets) in call_sig_defs {
+            let args_off =
+                u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            value_types.extend_from_slice(&args);
+            let args_len = u32::try_from(args.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            let rets_off =
+                u32::try_from(value_types.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            value_types.extend_from_slice(&rets);
+            let rets_len = u32::try_from(rets.len()).map_err(|_| DecodeError::OutOfBounds)?;
+            call_sigs.push(CallSigEntry {
+                args: ByteRange {
+                    offset: args_off,
+                    len: args_len,
+                },
+                rets: ByteRange {
+                    offset: rets_off,
+                    len: rets_len,
+                },
+            });
+        }
+
+        // Validate optional name metadata.
+        if let Some(name) = names.program_name
+            && symbols.get(name.index() as usize).is_none()
+        {
             return Err(DecodeError::OutOfBounds);
         }
-    }
+        for e in &names.function_names {
+            if usize::try_from(e.func)
+                .ok()
+                .and_then(|i| functions.get(i))
+                .is_none()
+            {
+                return Err(DecodeError::OutOfBounds);
+            }
+            if symbols.get(e.name.index() as usize).is_none() {
+                return Err(DecodeError::OutOfBounds);
+            }
+        }
+        for e in &names.labels {
+            let Some(func) = usize::try_from(e.func).ok().and_then(|i| functions.get(i)) else {
+                return Err(DecodeError::OutOfBounds);
+            };
+            if e.pc > func.bytecode.len {
+                return Err(DecodeError::OutOfBounds);
+            }
+            if symbols.get(e.name.index() as usize).is_none() {
+                return Err(DecodeError::OutOfBounds);
+            }
+        }
 
-    for f in &functions {
-        for range in [f.arg_name_ids, f.ret_name_ids] {
-            if range.len == 0 {
-                continue;
+        for e in &linkage.exports {
+            if usize::try_from(e.func)
+                .ok()
+                .and_then(|i| functions.get(i))
+                .is_none()
+            {
+                return Err(DecodeError::OutOfBounds);
             }
-            let start = range.offset as usize;
-            let end = range.end()? as usize;
-            let slice = value_name_ids
-                .get(start..end)
-                .ok_or(DecodeError::OutOfBounds)?;
-            for &v in slice {
-                if v == 0 {
-                    continue;
+            if symbols.get(e.name.index() as usize).is_none() {
+                return Err(DecodeError::OutOfBounds);
+            }
+        }
+        for e in &linkage.imports {
+            if usize::try_from(e.func)
+                .ok()
+                .and_then(|i| functions.get(i))
+                .is_none()
+            {
+                return Err(DecodeError::OutOfBounds);
+            }
+            for sym in [e.module, e.name] {
+                if symbols.get(sym.index() as usize).is_none() {
+                    return Err(DecodeError::OutOfBounds);
                 }
-                let sym_ix = usize::try_from(v).map_err(|_| DecodeError::OutOfBounds)?;
-                if symbols.get(sym_ix).is_none() {
+            }
+        }
+
+        for e in &source_map {
+            for sym in core::iter::once(e.file).chain(e.node_guid) {
+                if symbols.get(sym.index() as usize).is_none() {
                     return Err(DecodeError::OutOfBounds);
                 }
             }
         }
-    }
 
-    for e in &function_arg_agg_shapes {
-        let Some(func) = usize::try_from(e.func).ok().and_then(|i| functions.get(i)) else {
-            return Err(DecodeError::OutOfBounds);
-        };
-        if e.arg >= func.arg_count {
-            return Err(DecodeError::OutOfBounds);
+        for f in &functions {
+            for range in [f.arg_name_ids, f.ret_name_ids] {
+                if range.len == 0 {
+                    continue;
+                }
+                let start = range.offset as usize;
+                let end = range.end()? as usize;
+                let slice = value_name_ids
+                    .get(start..end)
+                    .ok_or(DecodeError::OutOfBounds)?;
+                for &v in slice {
+                    if v == 0 {
+                        continue;
+                    }
+                    let sym_ix = usize::try_from(v).map_err(|_| DecodeError::OutOfBounds)?;
+                    if symbols.get(sym_ix).is_none() {
+                        return Err(DecodeError::OutOfBounds);
+                    }
+                }
+            }
         }
-    }
 
-    Ok(Program {
-        symbols,
-        symbol_data,
-        const_pool,
-        const_bytes_data,
-        const_str_data,
-        call_sigs,
-        host_sigs,
-        types: TypeTable::pack(types),
-        value_types,
-        value_name_ids,
-        bytecode_data,
-        spans,
-        functions,
-        function_arg_agg_shapes,
-        program_name: names.program_name,
-        function_names: names.function_names,
-        labels: names.labels,
-    })
+        for e in &function_arg_agg_shapes {
+            let Some(func) = usize::try_from(e.func).ok().and_then(|i| functions.get(i)) else {
+                return Err(DecodeError::OutOfBounds);
+            };
+            if e.arg >= func.arg_count {
+                return Err(DecodeError::OutOfBounds);
+            }
+        }
+
+        Ok(Self {
+            symbols,
+            const_pool,
+            call_sigs,
+            host_sigs,
+            types: TypeTable::pack(types),
+            value_types,
+            value_name_ids,
+            spans,
+            functions,
+            function_arg_agg_shapes,
+            program_name: names.program_name,
+            function_names: names.function_names,
+            labels: names.labels,
+            exports: linkage.exports,
+            imports: linkage.imports,
+            source_map,
+            blobs: blob_ranges,
+            function_blobs,
+        })
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -1773,11 +2319,8 @@ fn encode_const(w: &mut Writer, c: &ConstEntry, const_bytes: &[u8], const_str: &
     }
 }
 
-fn decode_const_pool(
-    payload: &[u8],
-    const_bytes: &mut Vec<u8>,
-    const_str: &mut String,
-) -> Result<Vec<ConstEntry>, DecodeError> {
+/// Decodes the constant pool; bytes and string ranges index `payload`.
+fn decode_const_pool(payload: &[u8]) -> Result<Vec<ConstEntry>, DecodeError> {
     let mut r = Reader::new(payload);
     let n = read_usize(&mut r)?;
     let mut out = Vec::with_capacity(n);
@@ -1795,20 +2338,18 @@ fn decode_const_pool(
             },
             ConstTag::Bytes => {
                 let len = read_usize(&mut r)?;
+                let offset = r.offset();
                 let bytes = r.read_bytes(len)?;
-                let offset =
-                    u32::try_from(const_bytes.len()).map_err(|_| DecodeError::OutOfBounds)?;
-                const_bytes.extend_from_slice(bytes);
+                let offset = u32::try_from(offset).map_err(|_| DecodeError::OutOfBounds)?;
                 let len = u32::try_from(bytes.len()).map_err(|_| DecodeError::OutOfBounds)?;
                 ConstEntry::Bytes(ByteRange { offset, len })
             }
             ConstTag::Str => {
                 let len = read_usize(&mut r)?;
+                let offset = r.offset();
                 let s = r.read_str(len)?;
-                let offset =
-                    u32::try_from(const_str.len()).map_err(|_| DecodeError::OutOfBounds)?;
+                let offset = u32::try_from(offset).map_err(|_| DecodeError::OutOfBounds)?;
                 let len = u32::try_from(s.len()).map_err(|_| DecodeError::OutOfBounds)?;
-                const_str.push_str(s);
                 ConstEntry::Str(ByteRange { offset, len })
             }
         };
@@ -2028,7 +2569,7 @@ fn decode_types(payload: &[u8]) -> Result<TypeTableDef, DecodeError> {
 }
 
 type DecodedFunctionSig = (Vec<ValueType>, Vec<ValueType>, Vec<u32>, Vec<u32>);
-type DecodedHostSig = (SymbolId, SigHash, Vec<ValueType>, Vec<ValueType>);
+type DecodedHostSig = (SymbolId, SigHash, bool, Vec<ValueType>, Vec<ValueType>);
 type DecodedCallSig = (Vec<ValueType>, Vec<ValueType>);
 
 fn decode_function_sigs(payload: &[u8]) -> Result<Vec<DecodedFunctionSig>, DecodeError> {
@@ -2082,6 +2623,12 @@ fn decode_host_sigs(payload: &[u8]) -> Result<Vec<DecodedHostSig>, DecodeError>
     for _ in 0..n {
         let symbol = SymbolId(r.read_uleb128_u32_nz()?);
         let sig_hash = SigHash(r.read_u64_le()?);
+        // Flags byte: bit 0 marks a pure signature; other bits are reserved.
+        let pure = match r.read_u8()? {
+            0 => false,
+            1 => true,
+            _ => return Err(DecodeError::OutOfBounds),
+        };
         let argc = read_usize(&mut r)?;
         let mut args = Vec::with_capacity(argc);
         for _ in 0..argc {
@@ -2092,7 +2639,7 @@ fn decode_host_sigs(payload: &[u8]) -> Result<Vec<DecodedHostSig>, DecodeError>
         for _ in 0..retc {
             rets.push(decode_value_type(&mut r)?);
         }
-        out.push((symbol, sig_hash, args, rets));
+        out.push((symbol, sig_hash, pure, args, rets));
     }
     Ok(out)
 }
@@ -2166,13 +2713,20 @@ fn decode_function_table(payload: &[u8]) -> Result<Vec<FunctionTableEntry>, Deco
     Ok(out)
 }
 
-fn decode_blob_list(payload: &[u8]) -> Result<Vec<Vec<u8>>, DecodeError> {
+/// Decodes a length-prefixed blob list as ranges into `payload`.
+fn decode_blob_ranges(payload: &[u8]) -> Result<Vec<ByteRange>, DecodeError> {
     let mut r = Reader::new(payload);
     let n = read_usize(&mut r)?;
     let mut out = Vec::with_capacity(n);
     for _ in 0..n {
         let len = read_usize(&mut r)?;
-        out.push(r.read_bytes(len)?.to_vec());
+        let offset = u32::try_from(r.offset()).map_err(|_| DecodeError::OutOfBounds)?;
+        let len_u32 = u32::try_from(len).map_err(|_| DecodeError::OutOfBounds)?;
+        r.read_bytes(len)?;
+        out.push(ByteRange {
+            offset,
+            len: len_u32,
+        });
     }
     Ok(out)
 }
@@ -2203,6 +2757,7 @@ fn read_usize(r: &mut Reader<'_>) -> Result<usize, DecodeError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
     use alloc::vec;
 
     fn sid(v: u64) -> SpanId {
@@ -2353,6 +2908,150 @@ mod tests {
         );
     }
 
+    #[test]
+    fn program_roundtrips_linkage() {
+        let sym = |i: u32| SymbolId(NonZeroU32::new(i).unwrap());
+        let func = || FunctionDef {
+            arg_types: vec![ValueType::I64],
+            ret_types: vec![ValueType::I64],
+            reg_count: 2,
+            bytecode: vec![],
+            spans: vec![],
+        };
+        let mut p = Program::new(
+            vec![
+                HostSymbol {
+                    symbol: "main".into(),
+                },
+                HostSymbol {
+                    symbol: "lib".into(),
+                },
+                HostSymbol {
+                    symbol: "inc".into(),
+                },
+            ],
+            vec![],
+            vec![],
+            TypeTableDef::default(),
+            vec![func(), func()],
+        );
+        assert!(!section_tags(&p.encode()).contains(&(SectionTag::Linkage as u8)));
+
+        p.exports = vec![ExportEntry {
+            func: 1,
+            name: sym(1),
+        }];
+        p.imports = vec![ImportEntry {
+            func: 0,
+            module: sym(2),
+            name: sym(3),
+        }];
+        let bytes = p.encode();
+        assert!(section_tags(&bytes).contains(&(SectionTag::Linkage as u8)));
+        assert_eq!(Program::decode(&bytes).unwrap(), p);
+
+        p.imports[0].func = 2;
+        assert_eq!(Program::decode(&p.encode()), Err(DecodeError::OutOfBounds));
+    }
+
+    #[test]
+    fn program_roundtrips_source_map() {
+        let sym = |i: u32| SymbolId(NonZeroU32::new(i).unwrap());
+        let mut p = Program::new(
+            vec![
+                HostSymbol {
+                    symbol: "main.graph".into(),
+                },
+                HostSymbol {
+                    symbol: "node-7".into(),
+                },
+            ],
+            vec![],
+            vec![],
+            TypeTableDef::default(),
+            vec![],
+        );
+        assert!(!section_tags(&p.encode()).contains(&(SectionTag::SourceMap as u8)));
+
+        let range = SourceRange {
+            start_line: 3,
+            start_col: 5,
+            end_line: 4,
+            end_col: 1,
+        };
+        p.source_map = vec![
+            SourceMapEntry {
+                span: sid(7),
+                file: sym(1),
+                range,
+                node_guid: Some(sym(2)),
+            },
+            SourceMapEntry {
+                span: sid(9),
+                file: sym(1),
+                range: SourceRange::default(),
+                node_guid: None,
+            },
+        ];
+        let bytes = p.encode();
+        assert!(section_tags(&bytes).contains(&(SectionTag::SourceMap as u8)));
+        assert_eq!(Program::decode(&bytes).unwrap(), p);
+
+        let info = p.span_info(sid(7)).unwrap();
+        assert_eq!(
+            (info.file, info.range, info.node_guid),
+            ("main.graph", range, Some("node-7"))
+        );
+        assert_eq!(info.to_string(), "main.graph:3:5 (node node-7)");
+        assert_eq!(p.span_info(sid(9)).unwrap().node_guid, None);
+        assert_eq!(p.span_info(sid(8)), None);
+
+        p.source_map[1].node_guid = Some(sym(3));
+        assert_eq!(Program::decode(&p.encode()), Err(DecodeError::OutOfBounds));
+    }
+
+    #[test]
+    fn program_roundtrips_custom_sections() {
+        let mut p = Program::new(
+            vec![HostSymbol { symbol: "x".into() }],
+            vec![],
+            vec![],
+            TypeTableDef::default(),
+            vec![],
+        );
+        p.custom_sections = vec![
+            CustomSection {
+                name: "provenance".into(),
+                bytes: b"editor 1.2".to_vec(),
+            },
+            CustomSection {
+                name: "guids".into(),
+                bytes: vec![],
+            },
+        ];
+        let bytes = p.encode();
+        let tags = section_tags(&bytes);
+        assert_eq!(tags[tags.len() - 2..], [CUSTOM_SECTION_TAG; 2]);
+
+        // Decoding yields the canonical (name-sorted) order.
+        let back = Program::decode(&bytes).unwrap();
+        assert_eq!(back.custom_section("guids"), Some(&[][..]));
+        assert_eq!(back.custom_section("provenance"), Some(&b"editor 1.2"[..]));
+        assert_eq!(back.custom_section("missing"), None);
+        p.custom_sections.reverse();
+        assert_eq!(back, p);
+        assert_eq!(back.encode(), bytes);
+
+        // Any tag in the reserved range is a custom section; names must be unique.
+        let mut bytes = bytes;
+        bytes.extend_from_slice(&[0xC3, 6, 5, b'g', b'u', b'i', b'd', b's']);
+        assert_eq!(Program::decode(&bytes), Err(DecodeError::DuplicateSection));
+        let at = bytes.len() - 2;
+        bytes[at] = b'x';
+        let back = Program::decode(&bytes).unwrap();
+        assert_eq!(back.custom_section("guixs"), Some(&[][..]));
+    }
+
     #[test]
     fn program_roundtrips_with_function_input_and_output_names() {
         let mut p = Program::new(
@@ -2463,6 +3162,33 @@ mod tests {
         assert!(!tags.contains(&(SectionTag::CallSigs as u8)));
     }
 
+    #[test]
+    fn program_roundtrips_host_sig_purity() {
+        let mut pb = crate::asm::ProgramBuilder::new();
+        let sig = || crate::host::HostSig {
+            args: vec![ValueType::I64],
+            rets: vec![ValueType::I64],
+        };
+        let effectful = pb.host_sig_for("h", sig());
+        let pure = pb.pure_host_sig_for("h", sig());
+        let p = pb.build();
+
+        let back = Program::decode(&p.encode()).unwrap();
+        assert_eq!(back, p);
+        let effectful = back.host_sig(effectful).unwrap();
+        let pure = back.host_sig(pure).unwrap();
+        assert!(!effectful.pure);
+        assert!(pure.pure);
+        assert_eq!(
+            effectful.sig_hash,
+            sig_hash_slices(&[ValueType::I64], &[ValueType::I64])
+        );
+        assert_eq!(
+            pure.sig_hash,
+            pure_sig_hash_slices(&[ValueType::I64], &[ValueType::I64])
+        );
+    }
+
     #[test]
     fn program_roundtrips_with_call_sigs() {
         let mut p = Program::new(
@@ -2598,8 +3324,8 @@ mod tests {
         );
         let mut bytes = p.encode();
 
-        // Append an unknown section tag 0xFE with a 3-byte payload.
-        bytes.push(0xFE);
+        // Append an unknown (non-custom) section tag 0x7F with a 3-byte payload.
+        bytes.push(0x7F);
         bytes.push(3); // uleb128(3)
         bytes.extend_from_slice(&[9, 9, 9]);
 
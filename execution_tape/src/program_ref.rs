@@ -0,0 +1,344 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Borrowed, zero-copy view of an encoded program.
+//!
+//! [`Program::decode`] copies symbol strings, constant payloads and bytecode into owned arenas.
+//! A [`ProgramRef`] instead keeps borrowing the encoded bytes (for example a memory-mapped file):
+//! constructing one decodes the typed tables (function table, signatures, type table, ...) but
+//! leaves the byte payloads in place. Byte accessors return slices of the original buffer.
+//!
+//! [`verifier::verify_program_ref`] verifies a [`ProgramRef`] without copying it, producing a
+//! [`VerifiedProgram<ProgramRef>`](verifier::VerifiedProgram) that owns only the verifier's typed
+//! artifacts.
+//!
+//! # Limitations
+//!
+//! The VM only runs owned programs: [`Vm::run`](crate::vm::Vm::run) takes a
+//! [`VerifiedProgram<Program>`](verifier::VerifiedProgram). Running a verified [`ProgramRef`]
+//! goes through [`VerifiedProgram::into_owned`](verifier::VerifiedProgram::into_owned), which
+//! skips re-verification and reuses the decoded tables but still copies the symbol strings,
+//! constants and bytecode. A [`ProgramRef`] therefore saves copies for inspection and
+//! verification (tooling, caching verdicts, rejecting bad inputs), not for execution.
+
+use crate::format::DecodeError;
+use crate::program::{
+    ConstEntry, ConstId, DecodedTables, Function, Program, ProgramTables, Sections, SpanEntry,
+    SpanId, SpanInfo, SymbolId, ValueType, range_bytes, range_str,
+};
+
+#[cfg(doc)]
+use crate::verifier;
+
+/// A borrowed view of an encoded program.
+///
+/// Accessors mirror [`Program`]. Strings and byte slices borrow from the encoded buffer for `'a`;
+/// typed tables are decoded when the view is constructed.
+#[derive(Clone, Debug)]
+pub struct ProgramRef<'a> {
+    bytes: &'a [u8],
+    sections: Sections<'a>,
+    /// Typed tables whose symbol, constant and bytecode ranges index `sections` in place.
+    tables: DecodedTables,
+}
+
+impl<'a> ProgramRef<'a> {
+    /// Checks the container header of `bytes` and decodes its typed tables.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let sections = Sections::read(bytes)?;
+        let tables = DecodedTables::decode(&sections)?;
+        Ok(Self {
+            bytes,
+            sections,
+            tables,
+        })
+    }
+
+    /// Returns the encoded bytes this view borrows.
+    #[must_use]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Builds an owned [`Program`], copying the byte payloads out of the borrowed buffer.
+    pub fn to_program(&self) -> Result<Program, DecodeError> {
+        self.tables.clone().into_program(&self.sections)
+    }
+
+    /// Like [`ProgramRef::to_program`], but reuses this view's decoded tables.
+    pub(crate) fn into_program(self) -> Result<Program, DecodeError> {
+        self.tables.into_program(&self.sections)
+    }
+
+    /// Returns a borrowed view of the typed tables.
+    pub(crate) fn tables(&self) -> ProgramTables<'_> {
+        self.tables.tables()
+    }
+
+    /// Returns the function table.
+    #[must_use]
+    pub fn functions(&self) -> &[Function] {
+        &self.tables.functions
+    }
+
+    /// Returns the constant pool.
+    ///
+    /// [`ConstEntry::Bytes`] and [`ConstEntry::Str`] ranges index the encoded buffer; resolve them
+    /// with [`ProgramRef::const_bytes`] and [`ProgramRef::const_str`].
+    #[must_use]
+    pub fn const_pool(&self) -> &[ConstEntry] {
+        &self.tables.const_pool
+    }
+
+    /// Returns the program name, if present.
+    #[must_use]
+    pub fn name(&self) -> Option<&'a str> {
+        self.symbol_str(self.tables.program_name?).ok()
+    }
+
+    /// Returns the function name for `func`, if present.
+    #[must_use]
+    pub fn function_name(&self, func: u32) -> Option<&'a str> {
+        let e = self.tables.function_names.iter().find(|e| e.func == func)?;
+        self.symbol_str(e.name).ok()
+    }
+
+    /// Returns the payload of the custom section named `name`, if present.
+    #[must_use]
+    pub fn custom_section(&self, name: &str) -> Option<&'a [u8]> {
+        let custom = &self.sections.custom;
+        let i = custom.binary_search_by(|(n, _)| (*n).cmp(name)).ok()?;
+        Some(custom[i].1)
+    }
+
+    /// Returns the source location recorded for `span` in the source map, if present.
+    #[must_use]
+    pub fn span_info(&self, span: SpanId) -> Option<SpanInfo<'a>> {
+        let source_map = &self.tables.source_map;
+        let i = source_map.binary_search_by_key(&span, |e| e.span).ok()?;
+        let e = source_map[i];
+        Some(SpanInfo {
+            span,
+            file: self.symbol_str(e.file).ok()?,
+            range: e.range,
+            node_guid: match e.node_guid {
+                Some(id) => Some(self.symbol_str(id).ok()?),
+                None => None,
+            },
+        })
+    }
+
+    /// Returns a host-call symbol string for `id`.
+    pub fn symbol_str(&self, id: SymbolId) -> Result<&'a str, DecodeError> {
+        let e = self
+            .tables
+            .symbols
+            .get(id.index() as usize)
+            .ok_or(DecodeError::OutOfBounds)?;
+        range_str(self.sections.symbols, e.bytes)
+    }
+
+    /// Returns a byte slice for a [`ConstEntry::Bytes`] constant.
+    pub fn const_bytes(&self, id: ConstId) -> Result<&'a [u8], DecodeError> {
+        let Some(ConstEntry::Bytes(r)) = self.tables.const_pool.get(id.0 as usize) else {
+            return Err(DecodeError::OutOfBounds);
+        };
+        range_bytes(self.sections.const_pool, *r)
+    }
+
+    /// Returns a UTF-8 string slice for a [`ConstEntry::Str`] constant.
+    pub fn const_str(&self, id: ConstId) -> Result<&'a str, DecodeError> {
+        let Some(ConstEntry::Str(r)) = self.tables.const_pool.get(id.0 as usize) else {
+            return Err(DecodeError::OutOfBounds);
+        };
+        range_str(self.sections.const_pool, *r)
+    }
+
+    /// Returns the raw bytecode slice for `func`.
+    pub fn function_bytecode(&self, func: &Function) -> Result<&'a [u8], DecodeError> {
+        range_bytes(self.sections.bytecode_blobs, func.bytecode)
+    }
+
+    /// Returns the span entries for `func`.
+    pub fn function_spans(&self, func: &Function) -> Result<&[SpanEntry], DecodeError> {
+        self.tables().function_spans(func)
+    }
+
+    /// Returns a slice of argument types for `func`.
+    pub fn function_arg_types(&self, func: &Function) -> Result<&[ValueType], DecodeError> {
+        self.tables().function_arg_types(func)
+    }
+
+    /// Returns a slice of return types for `func`.
+    pub fn function_ret_types(&self, func: &Function) -> Result<&[ValueType], DecodeError> {
+        self.tables().function_ret_types(func)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::{Host, HostContext, HostError, SigHash, ValueRef};
+    use crate::program::{Const, FunctionDef, SourceRange};
+    use crate::trace::TraceMask;
+    use crate::value::Value;
+    use crate::verifier::{VerifyConfig, VerifyError, verify_program_ref};
+    use crate::vm::{Limits, Vm};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    struct NoHost;
+
+    impl Host for NoHost {
+        fn call(
+            &mut self,
+            _symbol: &str,
+            _sig_hash: SigHash,
+            _args: &[ValueRef<'_>],
+            _rets: &mut [Value],
+            _ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            Err(HostError::UnknownSymbol)
+        }
+    }
+
+    fn sample() -> Program {
+        let mut pb = ProgramBuilder::new();
+        pb.set_program_name("sample");
+        pb.add_custom_section("build", [0]);
+        pb.add_custom_section("build", [1, 2]);
+        pb.set_span_info(
+            SpanId::try_from(7).unwrap(),
+            "sample.graph",
+            SourceRange {
+                start_line: 2,
+                start_col: 1,
+                end_line: 2,
+                end_col: 9,
+            },
+            Some("node-1"),
+        );
+        let greeting = pb.const_str("hello");
+        let blob = pb.constant(Const::Bytes(vec![1, 2, 3]));
+        let mut a = Asm::new();
+        a.span(SpanId::try_from(7).unwrap());
+        a.const_pool(1, greeting);
+        a.str_len(2, 1);
+        a.const_pool(3, blob);
+        a.bytes_len(4, 3);
+        a.u64_add(5, 2, 4);
+        a.ret(0, &[5]);
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![],
+                    ret_types: vec![ValueType::U64],
+                },
+            )
+            .unwrap();
+        pb.set_function_name(f, "main").unwrap();
+        pb.build_checked().unwrap()
+    }
+
+    #[test]
+    fn accessors_match_owned_program() {
+        let program = sample();
+        let bytes = program.encode();
+        let view = ProgramRef::new(&bytes).unwrap();
+
+        assert_eq!(view.name(), Some("sample"));
+        assert_eq!(view.function_name(0), Some("main"));
+        assert_eq!(view.const_str(ConstId(0)).unwrap(), "hello");
+        assert_eq!(view.const_bytes(ConstId(1)).unwrap(), &[1, 2, 3]);
+        assert!(view.const_str(ConstId(1)).is_err());
+        assert_eq!(view.custom_section("build"), Some(&[1, 2][..]));
+        assert_eq!(view.custom_section("other"), None);
+        let span = SpanId::try_from(7).unwrap();
+        assert_eq!(view.span_info(span), program.span_info(span));
+        assert_eq!(view.span_info(span).unwrap().node_guid, Some("node-1"));
+
+        let funcs = view.functions();
+        assert_eq!(funcs.len(), program.functions.len());
+        let (owned, borrowed) = (&program.functions[0], &funcs[0]);
+        assert_eq!(
+            view.function_bytecode(borrowed).unwrap(),
+            program.function_bytecode(owned).unwrap()
+        );
+        assert_eq!(
+            view.function_spans(borrowed).unwrap(),
+            program.function_spans(owned).unwrap()
+        );
+        assert_eq!(
+            view.function_ret_types(borrowed).unwrap(),
+            &[ValueType::U64]
+        );
+
+        // Byte accessors borrow the encoded buffer rather than a decoded copy.
+        let range = bytes.as_ptr_range();
+        assert!(range.contains(&view.function_bytecode(borrowed).unwrap().as_ptr()));
+        assert!(range.contains(&view.const_str(ConstId(0)).unwrap().as_ptr()));
+        assert!(
+            range.contains(
+                &view
+                    .symbol_str(program.program_name.unwrap())
+                    .unwrap()
+                    .as_ptr()
+            )
+        );
+
+        assert_eq!(view.to_program().unwrap(), program);
+    }
+
+    #[test]
+    fn view_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ProgramRef<'_>>();
+    }
+
+    #[test]
+    fn verified_view_runs_after_into_owned() {
+        let bytes = sample().encode();
+        let verified =
+            verify_program_ref(ProgramRef::new(&bytes).unwrap(), &VerifyConfig::default()).unwrap();
+        assert_eq!(verified.program().function_name(0), Some("main"));
+
+        let owned = verified.into_owned().unwrap();
+        let mut vm = Vm::new(NoHost, Limits::default());
+        let out = vm
+            .run(&owned, crate::value::FuncId(0), &[], TraceMask::NONE, None)
+            .unwrap();
+        assert_eq!(out, vec![Value::U64(8)]);
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert_eq!(
+            ProgramRef::new(b"nope").unwrap_err(),
+            DecodeError::UnexpectedEof
+        );
+
+        // A truncated section payload is rejected up front.
+        let mut bytes: Vec<u8> = sample().encode();
+        let at = bytes.len() - 1;
+        bytes.truncate(at);
+        assert!(ProgramRef::new(&bytes).is_err());
+
+        // Verification rejects a function that returns an uninitialized register.
+        let mut a = Asm::new();
+        a.ret(0, &[1]);
+        let mut pb = ProgramBuilder::new();
+        pb.push_function_def(FunctionDef {
+            arg_types: vec![],
+            ret_types: vec![ValueType::I64],
+            reg_count: 2,
+            bytecode: a.finish().unwrap(),
+            spans: vec![],
+        });
+        let bytes = pb.build().encode();
+        let err = verify_program_ref(ProgramRef::new(&bytes).unwrap(), &VerifyConfig::default())
+            .unwrap_err();
+        assert!(!matches!(err, VerifyError::Decode(_)), "{err:?}");
+    }
+}
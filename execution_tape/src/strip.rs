@@ -0,0 +1,471 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Stripping debug metadata from programs for release.
+//!
+//! [`strip`] returns a copy of a program with the requested metadata removed (program and function
+//! names, label names, span tables, the source map, argument/return names), bytecode re-encoded
+//! canonically (see [`canonicalize_program`]) and symbols that are no longer referenced dropped
+//! from the symbol table. None of this affects execution: the stripped program verifies and runs
+//! exactly like the original, but traces and disassembly lose the removed names and spans.
+//!
+//! The accompanying [`StripReport`] lists the encoded size of each container section before and
+//! after stripping.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+use crate::codec::{DecodeError, canonicalize_program};
+use crate::format::Reader;
+use crate::program::{ByteRange, Program, SymbolEntry, SymbolId, range_str, section_name};
+
+/// What [`strip`] removes. The default removes everything.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StripOptions {
+    /// Remove the program name and function names.
+    pub names: bool,
+    /// Remove label names.
+    pub labels: bool,
+    /// Remove span tables.
+    pub spans: bool,
+    /// Remove the source map.
+    pub source_map: bool,
+    /// Remove argument and return names from function signatures.
+    pub value_names: bool,
+    /// Re-encode bytecode with canonical operands, recomputing branch targets.
+    pub canonicalize_bytecode: bool,
+    /// Drop symbols that nothing references any more.
+    pub unused_symbols: bool,
+}
+
+impl Default for StripOptions {
+    fn default() -> Self {
+        Self {
+            names: true,
+            labels: true,
+            spans: true,
+            source_map: true,
+            value_names: true,
+            canonicalize_bytecode: true,
+            unused_symbols: true,
+        }
+    }
+}
+
+/// Encoded size of one container section before and after stripping.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SectionSavings {
+    /// Section tag.
+    pub tag: u8,
+    /// Human-readable section name (`"symbols"`, `"bytecode_blobs"`, ...).
+    pub name: &'static str,
+    /// Bytes (tag, length prefix and payload) before stripping; `0` if the section was absent.
+    pub before: usize,
+    /// Bytes after stripping; `0` if the section was omitted.
+    pub after: usize,
+}
+
+impl SectionSavings {
+    /// Returns the number of bytes saved in this section.
+    #[must_use]
+    pub fn saved(&self) -> usize {
+        self.before.saturating_sub(self.after)
+    }
+}
+
+/// Encoded-size comparison produced by [`strip`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StripReport {
+    /// Encoded size of the original program.
+    pub before: usize,
+    /// Encoded size of the stripped program.
+    pub after: usize,
+    /// Per-section sizes, in container order (sections only present after stripping come last).
+    pub sections: Vec<SectionSavings>,
+}
+
+impl StripReport {
+    /// Returns the total number of bytes saved.
+    #[must_use]
+    pub fn saved(&self) -> usize {
+        self.before.saturating_sub(self.after)
+    }
+}
+
+/// Returns a copy of `program` with the metadata selected by `opts` removed, plus a size report.
+///
+/// Fails if bytecode canonicalization cannot decode a function body, or if the program references
+/// a symbol outside its symbol table.
+pub fn strip(
+    program: &Program,
+    opts: &StripOptions,
+) -> Result<(Program, StripReport), DecodeError> {
+    let mut out = program.clone();
+    if opts.names {
+        out.program_name = None;
+        out.function_names.clear();
+    }
+    if opts.labels {
+        out.labels.clear();
+    }
+    if opts.spans {
+        out.spans.clear();
+        for f in &mut out.functions {
+            f.spans = ByteRange { offset: 0, len: 0 };
+        }
+    }
+    if opts.source_map {
+        out.source_map.clear();
+    }
+    if opts.value_names {
+        out.value_name_ids.clear();
+        for f in &mut out.functions {
+            f.arg_name_ids = ByteRange { offset: 0, len: 0 };
+            f.ret_name_ids = ByteRange { offset: 0, len: 0 };
+        }
+    }
+    if opts.canonicalize_bytecode {
+        out = canonicalize_program(&out)?;
+    }
+    if opts.unused_symbols {
+        drop_unused_symbols(&mut out)?;
+    }
+
+    let report = compare(&program.encode(), &out.encode());
+    Ok((out, report))
+}
+
+/// Calls `f` on every symbol reference in `p`.
+fn for_each_symbol(p: &mut Program, mut f: impl FnMut(&mut SymbolId)) {
+    for e in &mut p.host_sigs {
+        f(&mut e.symbol);
+    }
+    if let Some(id) = &mut p.program_name {
+        f(id);
+    }
+    for e in &mut p.function_names {
+        f(&mut e.name);
+    }
+    for e in &mut p.labels {
+        f(&mut e.name);
+    }
+    for e in &mut p.source_map {
+        f(&mut e.file);
+        if let Some(guid) = &mut e.node_guid {
+            f(guid);
+        }
+    }
+    for e in &mut p.exports {
+        f(&mut e.name);
+    }
+    for e in &mut p.imports {
+        f(&mut e.module);
+        f(&mut e.name);
+    }
+    for v in &mut p.value_name_ids {
+        if let Some(nz) = NonZeroU32::new(*v) {
+            let mut id = SymbolId(nz);
+            f(&mut id);
+            *v = id.index();
+        }
+    }
+}
+
+/// Rebuilds the symbol table with only referenced symbols, in their original order.
+fn drop_unused_symbols(p: &mut Program) -> Result<(), DecodeError> {
+    let mut used = vec![false; p.symbols.len()];
+    let mut in_range = true;
+    for_each_symbol(p, |id| match used.get_mut(id.index() as usize) {
+        Some(u) => *u = true,
+        None => in_range = false,
+    });
+    if !in_range {
+        return Err(DecodeError::OutOfBounds);
+    }
+
+    // Index 0 is the reserved empty symbol and always stays.
+    let mut remap = vec![0_u32; p.symbols.len()];
+    let mut symbol_data = String::new();
+    let mut symbols = Vec::new();
+    for (i, e) in p.symbols.iter().enumerate() {
+        if i != 0 && !used[i] {
+            continue;
+        }
+        let s = range_str(p.symbol_data.as_bytes(), e.bytes)?;
+        remap[i] = u32::try_from(symbols.len()).map_err(|_| DecodeError::OutOfBounds)?;
+        let offset = u32::try_from(symbol_data.len()).map_err(|_| DecodeError::OutOfBounds)?;
+        symbol_data.push_str(s);
+        symbols.push(SymbolEntry {
+            bytes: ByteRange {
+                offset,
+                len: e.bytes.len,
+            },
+        });
+    }
+
+    for_each_symbol(p, |id| {
+        // Referenced symbols are never index 0, so neither are their new indices.
+        if let Some(nz) = NonZeroU32::new(remap[id.index() as usize]) {
+            *id = SymbolId(nz);
+        }
+    });
+    p.symbols = symbols;
+    p.symbol_data = symbol_data;
+    Ok(())
+}
+
+/// Sums the encoded size of each section of a container, keyed by tag in first-seen order.
+fn section_sizes(bytes: &[u8]) -> Vec<(u8, usize)> {
+    let body = bytes.get(12..).unwrap_or(&[]);
+    let mut r = Reader::new(body);
+    let mut out: Vec<(u8, usize)> = Vec::new();
+    while r.offset() < body.len() {
+        let start = r.offset();
+        let Ok(tag) = r.read_u8() else { break };
+        let Ok(len) = r.read_uleb128_u64() else { break };
+        let Ok(len) = usize::try_from(len) else { break };
+        if r.read_bytes(len).is_err() {
+            break;
+        }
+        let size = r.offset() - start;
+        match out.iter_mut().find(|(t, _)| *t == tag) {
+            Some((_, total)) => *total += size,
+            None => out.push((tag, size)),
+        }
+    }
+    out
+}
+
+fn compare(before: &[u8], after: &[u8]) -> StripReport {
+    let after_sizes = section_sizes(after);
+    let mut sections: Vec<SectionSavings> = section_sizes(before)
+        .into_iter()
+        .map(|(tag, before)| SectionSavings {
+            tag,
+            name: section_name(tag),
+            before,
+            after: 0,
+        })
+        .collect();
+    for (tag, size) in after_sizes {
+        match sections.iter_mut().find(|s| s.tag == tag) {
+            Some(s) => s.after = size,
+            None => sections.push(SectionSavings {
+                tag,
+                name: section_name(tag),
+                before: 0,
+                after: size,
+            }),
+        }
+    }
+    StripReport {
+        before: before.len(),
+        after: after.len(),
+        sections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::host::{Host, HostContext, HostError, HostSig, SigHash, ValueRef};
+    use crate::program::{SourceRange, SpanId, ValueType};
+    use crate::trace::TraceMask;
+    use crate::value::{FuncId, Value};
+    use crate::verifier::{VerifyConfig, verify_program_owned};
+    use crate::vm::{Limits, Vm};
+
+    /// Triples its argument and records each call.
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<(String, i64)>,
+    }
+
+    impl Host for Recorder {
+        fn call(
+            &mut self,
+            symbol: &str,
+            _sig_hash: SigHash,
+            args: &[ValueRef<'_>],
+            rets: &mut [Value],
+            _ctx: HostContext<'_, '_>,
+        ) -> Result<u64, HostError> {
+            let [ValueRef::I64(x)] = args else {
+                return Err(HostError::UnknownSymbol);
+            };
+            self.calls.push((symbol.into(), *x));
+            rets[0] = Value::I64(x * 3);
+            Ok(0)
+        }
+    }
+
+    /// `f1 main(n)`: sums `add(sum, scale(i))` for `i` in `0..n`, with names, labels and spans.
+    fn sample() -> Program {
+        let mut pb = ProgramBuilder::new();
+        pb.set_program_name("sample");
+        let scale = pb.host_sig_for(
+            "math.scale",
+            HostSig {
+                args: vec![ValueType::I64],
+                rets: vec![ValueType::I64],
+            },
+        );
+
+        let mut a = Asm::new();
+        a.i64_add(3, 1, 2);
+        a.ret(0, &[3]);
+        let add = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::I64, ValueType::I64],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_name(add, "add").unwrap();
+
+        let mut a = Asm::new();
+        let l_loop = a.label_named("loop");
+        let l_body = a.label_named("body");
+        let l_done = a.label_named("done");
+        a.const_i64(2, 0);
+        a.const_i64(3, 0);
+        a.const_i64(4, 1);
+        a.jmp(l_loop);
+        a.place(l_loop).unwrap();
+        a.span(SpanId::try_from(11).unwrap());
+        a.i64_lt(5, 3, 1);
+        a.br(5, l_body, l_done);
+        a.place(l_body).unwrap();
+        a.span(SpanId::try_from(12).unwrap());
+        a.host_call(0, scale, 0, &[3], &[6]);
+        a.call(0, add, 0, &[2, 6], &[2]);
+        a.i64_add(3, 3, 4);
+        a.jmp(l_loop);
+        a.place(l_done).unwrap();
+        a.ret(0, &[2]);
+        let main = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::I64],
+                    ret_types: vec![ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_name(main, "main").unwrap();
+        pb.set_function_input_name(main, 0, "n").unwrap();
+        pb.set_span_info(
+            SpanId::try_from(12).unwrap(),
+            "sample.graph",
+            SourceRange::default(),
+            Some("node-12"),
+        );
+        pb.set_function_output_name(main, 0, "total").unwrap();
+        pb.build_checked().unwrap()
+    }
+
+    fn run(program: &Program, n: i64) -> (Vec<Value>, Vec<(String, i64)>) {
+        let verified = verify_program_owned(program.clone(), &VerifyConfig::default()).unwrap();
+        let mut vm = Vm::new(Recorder::default(), Limits::default());
+        let out = vm
+            .run(
+                &verified,
+                FuncId(1),
+                &[Value::I64(n)],
+                TraceMask::NONE,
+                None,
+            )
+            .unwrap();
+        (out, vm.host().calls.clone())
+    }
+
+    #[test]
+    fn stripped_program_behaves_identically() {
+        let p = sample();
+        let (s, report) = strip(&p, &StripOptions::default()).unwrap();
+
+        for n in [0, 1, 2, 5] {
+            assert_eq!(run(&s, n), run(&p, n), "n = {n}");
+        }
+        assert_eq!(run(&s, 3).0, vec![Value::I64(9)]);
+
+        assert_eq!(s.name(), None);
+        assert_eq!(s.function_name(1), None);
+        assert!(s.labels.is_empty() && s.spans.is_empty() && s.value_name_ids.is_empty());
+        // Only the host symbol (plus the reserved empty symbol) survives.
+        assert_eq!(s.symbols.len(), 2);
+        assert_eq!(s.symbol_str(s.host_sigs[0].symbol), Ok("math.scale"));
+        assert_eq!(Program::decode(&s.encode()).unwrap(), s);
+
+        assert_eq!(report.before, p.encode().len());
+        assert_eq!(report.after, s.encode().len());
+        assert!(report.saved() > 0);
+        let sum = |f: fn(&SectionSavings) -> usize| report.sections.iter().map(f).sum::<usize>();
+        assert_eq!(sum(|s| s.before) + 12, report.before);
+        assert_eq!(sum(|s| s.after) + 12, report.after);
+        let section = |name| report.sections.iter().find(|s| s.name == name).unwrap();
+        assert!(
+            section("bytecode_blobs").saved() > 0,
+            "wide branch targets shrink"
+        );
+        assert!(section("symbols").saved() > 0);
+        assert_eq!(section("names").after, 0);
+        assert_eq!(section("host_sigs").saved(), 0);
+
+        // Stripping is idempotent.
+        let (again, report) = strip(&s, &StripOptions::default()).unwrap();
+        assert_eq!(again, s);
+        assert_eq!(report.saved(), 0);
+    }
+
+    #[test]
+    fn options_select_what_is_removed() {
+        let p = sample();
+        let keep_all = StripOptions {
+            names: false,
+            labels: false,
+            spans: false,
+            source_map: false,
+            value_names: false,
+            canonicalize_bytecode: false,
+            unused_symbols: false,
+        };
+        let (s, report) = strip(&p, &keep_all).unwrap();
+        assert_eq!(s, p);
+        assert!(report.sections.iter().all(|s| s.saved() == 0));
+
+        let (s, _) = strip(
+            &p,
+            &StripOptions {
+                spans: true,
+                ..keep_all
+            },
+        )
+        .unwrap();
+        assert!(s.spans.is_empty());
+        assert_eq!(s.function_name(1), Some("main"));
+        assert_eq!(s.labels, p.labels);
+
+        // Names kept while unused symbols are dropped: name references are remapped.
+        let (s, _) = strip(
+            &p,
+            &StripOptions {
+                names: false,
+                source_map: false,
+                ..StripOptions::default()
+            },
+        )
+        .unwrap();
+        let span = SpanId::try_from(12).unwrap();
+        assert_eq!(s.span_info(span), p.span_info(span));
+        assert_eq!(s.name(), Some("sample"));
+        assert_eq!(s.function_name(0), Some("add"));
+        assert!(s.labels.is_empty());
+        assert_eq!(run(&s, 4), run(&p, 4));
+    }
+}
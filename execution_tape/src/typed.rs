@@ -92,6 +92,25 @@ pub(crate) enum VReg {
     Closure(ClosureReg),
 }
 
+impl VReg {
+    pub(crate) fn class(self) -> RegClass {
+        match self {
+            Self::Unit(_) => RegClass::Unit,
+            Self::Bool(_) => RegClass::Bool,
+            Self::I64(_) => RegClass::I64,
+            Self::U64(_) => RegClass::U64,
+            Self::F64(_) => RegClass::F64,
+            Self::Decimal(_) => RegClass::Decimal,
+            Self::Bytes(_) => RegClass::Bytes,
+            Self::Str(_) => RegClass::Str,
+            Self::Obj(_) => RegClass::Obj,
+            Self::Agg(_) => RegClass::Agg,
+            Self::Func(_) => RegClass::Func,
+            Self::Closure(_) => RegClass::Closure,
+        }
+    }
+}
+
 /// A slice of typed registers stored in a per-function operand pool.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct VRegSlice {
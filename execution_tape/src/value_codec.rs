@@ -0,0 +1,838 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Portable serialization of runtime values.
+//!
+//! [`encode_value`] turns a [`Value`] into bytes that can be persisted or sent to another process,
+//! and [`decode_value`] rebuilds it, allocating any aggregates in a caller-provided [`AggHeap`].
+//! Aggregates (tuples, structs, arrays and closure environments) are written inline as a tree, so
+//! the encoding does not depend on heap handles: structurally equal values encode to identical
+//! bytes, and a tree cannot express a cycle. Aggregates shared within a value are written once per
+//! reference and decode as separate (structurally equal) aggregates. Because sharing can make the
+//! tree exponentially larger than the heap, encoding gives up with
+//! [`ValueCodecError::TooLarge`] after [`MAX_ENCODED_VALUES`] values or [`MAX_ENCODED_LEN`] bytes.
+//!
+//! Host objects have no portable representation of their own: encountering an [`Obj`](Value::Obj)
+//! fails with [`Trap::SerializationEncounteredObj`]. [`encode_value_with_types`] and
+//! [`decode_value_with_types`] instead serialize and recreate objects through a
+//! [`HostTypeRegistry`]. Function ids and aggregate type ids are only meaningful
+//! relative to a [`Program`], so both directions take the program and check the ids (and struct
+//! and array element types) against its tables.
+//!
+//! ## Format
+//!
+//! - magic `EXTVAL\0\0` (8 bytes)
+//! - value format version `u16le` ([`VALUE_FORMAT_VERSION`])
+//! - one encoded value, with nothing after it
+//!
+//! Each value is a tag byte followed by its payload:
+//!
+//! | tag | value       | payload                                                  |
+//! |-----|-------------|----------------------------------------------------------|
+//! | 0   | `Unit`      | none                                                     |
+//! | 1   | `Bool`      | `u8` (0 or 1)                                            |
+//! | 2   | `I64`       | sleb                                                     |
+//! | 3   | `U64`       | uleb                                                     |
+//! | 4   | `F64`       | IEEE 754 bits, `u64le`                                   |
+//! | 5   | `Decimal`   | mantissa (sleb), scale (`u8`)                            |
+//! | 6   | `Bytes`     | length (uleb), bytes                                     |
+//! | 7   | `Str`       | length (uleb), UTF-8 bytes                               |
+//! | 8   | `Func`      | function id (uleb)                                       |
+//! | 9   | `Closure`   | function id (uleb), then the environment aggregate       |
+//! | 10  | tuple       | element count (uleb), elements                           |
+//! | 11  | struct      | type id (uleb), field count (uleb), fields               |
+//! | 12  | array       | element type id (uleb), element count (uleb), elements   |
+//! | 13  | `Obj`       | host type id (`u64le`), length (uleb), serialized bytes  |
+//!
+//! Aggregate values ([`Value::Agg`]) use tags 10 to 12 directly. Nesting is limited to
+//! [`MAX_NESTING`] aggregate levels.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::aggregates::{AggError, AggHeap};
+use crate::format::{DecodeError, Reader, Writer};
+use crate::host_types::{HostTypeError, HostTypeRegistry};
+use crate::program::{ElemTypeId, HostTypeId, Program, TypeId, ValueType};
+use crate::value::{AggHandle, AggType, Closure, Decimal, FuncId, Value};
+use crate::vm::Trap;
+
+/// The value format version written by [`encode_value`].
+pub const VALUE_FORMAT_VERSION: u16 = 1;
+
+/// Maximum aggregate nesting depth accepted by [`encode_value`] and [`decode_value`].
+pub const MAX_NESTING: usize = 128;
+
+/// Maximum number of values (scalars and aggregates, counted per reference) [`encode_value`] writes.
+pub const MAX_ENCODED_VALUES: usize = 1 << 20;
+
+/// Maximum encoded size in bytes [`encode_value`] produces.
+pub const MAX_ENCODED_LEN: usize = 1 << 28;
+
+const MAGIC: &[u8; 8] = b"EXTVAL\0\0";
+
+const TAG_UNIT: u8 = 0;
+const TAG_BOOL: u8 = 1;
+const TAG_I64: u8 = 2;
+const TAG_U64: u8 = 3;
+const TAG_F64: u8 = 4;
+const TAG_DECIMAL: u8 = 5;
+const TAG_BYTES: u8 = 6;
+const TAG_STR: u8 = 7;
+const TAG_FUNC: u8 = 8;
+const TAG_CLOSURE: u8 = 9;
+const TAG_TUPLE: u8 = 10;
+const TAG_STRUCT: u8 = 11;
+const TAG_ARRAY: u8 = 12;
+const TAG_OBJ: u8 = 13;
+
+/// A value serialization error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueCodecError {
+    /// Serialization trapped: the value contains a host object
+    /// ([`Trap::SerializationEncounteredObj`]) or an invalid aggregate handle
+    /// ([`Trap::AggError`]).
+    Trap(Trap),
+    /// The encoded value is truncated or structurally invalid.
+    Malformed(DecodeError),
+    /// The encoded value was written by an incompatible format version.
+    UnsupportedVersion(u16),
+    /// Unknown value tag.
+    UnknownTag(u8),
+    /// Bytes remain after the encoded value.
+    TrailingBytes,
+    /// Aggregates are nested deeper than [`MAX_NESTING`].
+    NestingTooDeep,
+    /// The encoding would exceed [`MAX_ENCODED_VALUES`] values or [`MAX_ENCODED_LEN`] bytes.
+    TooLarge,
+    /// A function id is not in the program's function table.
+    BadFuncId(u32),
+    /// A struct type id is not in the program's type table.
+    BadTypeId(u32),
+    /// An array element type id is not in the program's type table.
+    BadElemTypeId(u32),
+    /// A struct has a different number of fields than its type declares.
+    BadArity {
+        /// Struct type id.
+        type_id: u32,
+        /// Declared field count.
+        expected: usize,
+        /// Actual field count.
+        actual: usize,
+    },
+    /// A struct field or array element does not have its declared type.
+    TypeMismatch {
+        /// Declared type.
+        expected: ValueType,
+        /// Actual type.
+        actual: ValueType,
+    },
+    /// A host object could not be serialized or recreated through the [`HostTypeRegistry`].
+    HostType(HostTypeError),
+}
+
+impl fmt::Display for ValueCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trap(trap) => write!(f, "serialization trapped: {trap}"),
+            Self::Malformed(e) => write!(f, "malformed value: {e}"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported value format version {v}"),
+            Self::UnknownTag(tag) => write!(f, "unknown value tag {tag}"),
+            Self::TrailingBytes => write!(f, "trailing bytes after value"),
+            Self::NestingTooDeep => write!(f, "aggregates nested deeper than {MAX_NESTING}"),
+            Self::TooLarge => write!(
+                f,
+                "encoded value exceeds {MAX_ENCODED_VALUES} values or {MAX_ENCODED_LEN} bytes"
+            ),
+            Self::BadFuncId(id) => write!(f, "function id {id} out of bounds"),
+            Self::BadTypeId(id) => write!(f, "type id {id} out of bounds"),
+            Self::BadElemTypeId(id) => write!(f, "elem type id {id} out of bounds"),
+            Self::BadArity {
+                type_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "struct type {type_id} has {expected} fields, got {actual}"
+            ),
+            Self::TypeMismatch { expected, actual } => {
+                write!(f, "type mismatch (expected {expected:?}, got {actual:?})")
+            }
+            Self::HostType(e) => write!(f, "host object: {e}"),
+        }
+    }
+}
+
+impl core::error::Error for ValueCodecError {}
+
+impl From<DecodeError> for ValueCodecError {
+    fn from(e: DecodeError) -> Self {
+        Self::Malformed(e)
+    }
+}
+
+impl From<HostTypeError> for ValueCodecError {
+    fn from(e: HostTypeError) -> Self {
+        Self::HostType(e)
+    }
+}
+
+impl From<AggError> for ValueCodecError {
+    fn from(e: AggError) -> Self {
+        Self::Trap(Trap::AggError(e))
+    }
+}
+
+/// Serializes `value`, reading any aggregates it references from `aggregates`.
+///
+/// Function ids and aggregate types are checked against `program`, the program that produced the
+/// value.
+pub fn encode_value(
+    value: &Value,
+    aggregates: &AggHeap,
+    program: &Program,
+) -> Result<Vec<u8>, ValueCodecError> {
+    encode(value, aggregates, program, None)
+}
+
+/// Like [`encode_value`], serializing host objects with `host_types`.
+///
+/// Objects whose type is not registered or cannot be serialized fail with
+/// [`ValueCodecError::HostType`].
+pub fn encode_value_with_types(
+    value: &Value,
+    aggregates: &AggHeap,
+    program: &Program,
+    host_types: &HostTypeRegistry,
+) -> Result<Vec<u8>, ValueCodecError> {
+    encode(value, aggregates, program, Some(host_types))
+}
+
+fn encode(
+    value: &Value,
+    aggregates: &AggHeap,
+    program: &Program,
+    host_types: Option<&HostTypeRegistry>,
+) -> Result<Vec<u8>, ValueCodecError> {
+    let mut w = Writer::new();
+    w.write_bytes(MAGIC);
+    w.write_u16_le(VALUE_FORMAT_VERSION);
+    Encoder {
+        w: &mut w,
+        aggregates,
+        program,
+        host_types,
+        values: 0,
+    }
+    .value(value, 0)?;
+    Ok(w.into_vec())
+}
+
+/// Deserializes a value written by [`encode_value`], allocating its aggregates in `aggregates`.
+///
+/// `program` must be the program the value was encoded against. On error, aggregates decoded
+/// before the failure remain in `aggregates` but are not referenced by any returned value.
+pub fn decode_value(
+    bytes: &[u8],
+    aggregates: &mut AggHeap,
+    program: &Program,
+) -> Result<Value, ValueCodecError> {
+    decode(bytes, aggregates, program, None)
+}
+
+/// Like [`decode_value`], recreating host objects with `host_types`.
+///
+/// Objects whose type is not registered or cannot be deserialized fail with
+/// [`ValueCodecError::HostType`].
+pub fn decode_value_with_types(
+    bytes: &[u8],
+    aggregates: &mut AggHeap,
+    program: &Program,
+    host_types: &HostTypeRegistry,
+) -> Result<Value, ValueCodecError> {
+    decode(bytes, aggregates, program, Some(host_types))
+}
+
+fn decode(
+    bytes: &[u8],
+    aggregates: &mut AggHeap,
+    program: &Program,
+    host_types: Option<&HostTypeRegistry>,
+) -> Result<Value, ValueCodecError> {
+    let mut r = Reader::new(bytes);
+    if r.read_bytes(MAGIC.len())? != MAGIC {
+        return Err(DecodeError::BadMagic.into());
+    }
+    let version = r.read_u16_le()?;
+    if version != VALUE_FORMAT_VERSION {
+        return Err(ValueCodecError::UnsupportedVersion(version));
+    }
+    let value = Decoder {
+        r: &mut r,
+        input_len: bytes.len(),
+        aggregates,
+        program,
+        host_types,
+    }
+    .value(0)?;
+    if r.offset() != bytes.len() {
+        return Err(ValueCodecError::TrailingBytes);
+    }
+    Ok(value)
+}
+
+fn check_func(program: &Program, func: u32) -> Result<(), ValueCodecError> {
+    if func as usize >= program.functions.len() {
+        return Err(ValueCodecError::BadFuncId(func));
+    }
+    Ok(())
+}
+
+fn struct_field_types(program: &Program, type_id: u32) -> Result<&[ValueType], ValueCodecError> {
+    let st = program
+        .types
+        .structs
+        .get(type_id as usize)
+        .ok_or(ValueCodecError::BadTypeId(type_id))?;
+    Ok(program.types.struct_field_types(st)?)
+}
+
+fn elem_type(program: &Program, elem_type_id: u32) -> Result<ValueType, ValueCodecError> {
+    program
+        .types
+        .array_elems
+        .get(elem_type_id as usize)
+        .copied()
+        .ok_or(ValueCodecError::BadElemTypeId(elem_type_id))
+}
+
+fn check_type(expected: ValueType, value: &Value) -> Result<(), ValueCodecError> {
+    let actual = value.value_type();
+    if actual != expected {
+        return Err(ValueCodecError::TypeMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+struct Encoder<'a> {
+    w: &'a mut Writer,
+    aggregates: &'a AggHeap,
+    program: &'a Program,
+    host_types: Option<&'a HostTypeRegistry>,
+    /// Values written so far, counting shared aggregates once per reference.
+    values: usize,
+}
+
+impl Encoder<'_> {
+    fn value(&mut self, value: &Value, depth: usize) -> Result<(), ValueCodecError> {
+        self.values += 1;
+        let payload = match value {
+            Value::Bytes(b) => b.len(),
+            Value::Str(s) => s.len(),
+            _ => 0,
+        };
+        if self.values > MAX_ENCODED_VALUES
+            || self.w.as_slice().len().saturating_add(payload) > MAX_ENCODED_LEN
+        {
+            return Err(ValueCodecError::TooLarge);
+        }
+        match value {
+            Value::Unit => self.w.write_u8(TAG_UNIT),
+            Value::Bool(b) => {
+                self.w.write_u8(TAG_BOOL);
+                self.w.write_u8(u8::from(*b));
+            }
+            Value::I64(i) => {
+                self.w.write_u8(TAG_I64);
+                self.w.write_sleb128_i64(*i);
+            }
+            Value::U64(u) => {
+                self.w.write_u8(TAG_U64);
+                self.w.write_uleb128_u64(*u);
+            }
+            Value::F64(x) => {
+                self.w.write_u8(TAG_F64);
+                self.w.write_u64_le(x.to_bits());
+            }
+            Value::Decimal(d) => {
+                self.w.write_u8(TAG_DECIMAL);
+                self.w.write_sleb128_i64(d.mantissa);
+                self.w.write_u8(d.scale);
+            }
+            Value::Bytes(b) => {
+                self.w.write_u8(TAG_BYTES);
+                self.w.write_uleb128_u64(b.len() as u64);
+                self.w.write_bytes(b);
+            }
+            Value::Str(s) => {
+                self.w.write_u8(TAG_STR);
+                self.w.write_uleb128_u64(s.len() as u64);
+                self.w.write_bytes(s.as_bytes());
+            }
+            Value::Obj(o) => {
+                let Some(host_types) = self.host_types else {
+                    return Err(ValueCodecError::Trap(Trap::SerializationEncounteredObj {
+                        host_type: o.host_type,
+                    }));
+                };
+                let mut data = Vec::new();
+                host_types.serialize_obj(*o, &mut data)?;
+                if self.w.as_slice().len().saturating_add(data.len()) > MAX_ENCODED_LEN {
+                    return Err(ValueCodecError::TooLarge);
+                }
+                self.w.write_u8(TAG_OBJ);
+                self.w.write_u64_le(o.host_type.0);
+                self.w.write_uleb128_u64(data.len() as u64);
+                self.w.write_bytes(&data);
+            }
+            Value::Agg(handle) => self.agg(*handle, depth)?,
+            Value::Func(f) => {
+                check_func(self.program, f.0)?;
+                self.w.write_u8(TAG_FUNC);
+                self.w.write_uleb128_u32(f.0);
+            }
+            Value::Closure(c) => {
+                check_func(self.program, c.func.0)?;
+                self.w.write_u8(TAG_CLOSURE);
+                self.w.write_uleb128_u32(c.func.0);
+                self.agg(c.env, depth)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn agg(&mut self, handle: AggHandle, depth: usize) -> Result<(), ValueCodecError> {
+        if depth >= MAX_NESTING {
+            return Err(ValueCodecError::NestingTooDeep);
+        }
+        let heap = self.aggregates;
+        match heap.agg_type(handle)? {
+            AggType::Tuple { .. } => {
+                let len = heap.tuple_len(handle)?;
+                self.w.write_u8(TAG_TUPLE);
+                self.w.write_uleb128_u64(len as u64);
+                for i in 0..len {
+                    self.value(heap.tuple_get_ref(handle, i)?, depth + 1)?;
+                }
+            }
+            AggType::Struct { type_id } => {
+                let types = struct_field_types(self.program, type_id.0)?;
+                let len = heap.struct_field_count(handle)?;
+                if len != types.len() {
+                    return Err(ValueCodecError::BadArity {
+                        type_id: type_id.0,
+                        expected: types.len(),
+                        actual: len,
+                    });
+                }
+                self.w.write_u8(TAG_STRUCT);
+                self.w.write_uleb128_u32(type_id.0);
+                self.w.write_uleb128_u64(len as u64);
+                for (i, ty) in types.iter().enumerate() {
+                    let field = heap.struct_get_ref(handle, i)?;
+                    self.value(field, depth + 1)?;
+                    check_type(*ty, field)?;
+                }
+            }
+            AggType::Array { elem_type_id } => {
+                let ty = elem_type(self.program, elem_type_id.0)?;
+                let len = heap.array_len(handle)?;
+                self.w.write_u8(TAG_ARRAY);
+                self.w.write_uleb128_u32(elem_type_id.0);
+                self.w.write_uleb128_u64(len as u64);
+                for i in 0..len {
+                    let elem = heap.array_get_ref(handle, i)?;
+                    self.value(elem, depth + 1)?;
+                    check_type(ty, elem)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Decoder<'r, 'a> {
+    r: &'r mut Reader<'a>,
+    input_len: usize,
+    aggregates: &'r mut AggHeap,
+    program: &'r Program,
+    host_types: Option<&'r HostTypeRegistry>,
+}
+
+impl Decoder<'_, '_> {
+    fn len(&mut self) -> Result<usize, ValueCodecError> {
+        let len = self.r.read_uleb128_u64()?;
+        usize::try_from(len).map_err(|_| DecodeError::OutOfBounds.into())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, ValueCodecError> {
+        let tag = self.r.read_u8()?;
+        Ok(match tag {
+            TAG_UNIT => Value::Unit,
+            TAG_BOOL => match self.r.read_u8()? {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => return Err(DecodeError::OutOfBounds.into()),
+            },
+            TAG_I64 => Value::I64(self.r.read_sleb128_i64()?),
+            TAG_U64 => Value::U64(self.r.read_uleb128_u64()?),
+            TAG_F64 => Value::F64(f64::from_bits(self.r.read_u64_le()?)),
+            TAG_DECIMAL => {
+                let mantissa = self.r.read_sleb128_i64()?;
+                let scale = self.r.read_u8()?;
+                Value::Decimal(Decimal { mantissa, scale })
+            }
+            TAG_BYTES => {
+                let len = self.len()?;
+                Value::Bytes(self.r.read_bytes(len)?.to_vec())
+            }
+            TAG_STR => {
+                let len = self.len()?;
+                Value::Str(self.r.read_str(len)?.into())
+            }
+            TAG_FUNC => {
+                let func = self.r.read_uleb128_u32()?;
+                check_func(self.program, func)?;
+                Value::Func(FuncId(func))
+            }
+            TAG_CLOSURE => {
+                let func = self.r.read_uleb128_u32()?;
+                check_func(self.program, func)?;
+                let tag = self.r.read_u8()?;
+                let env = self.agg(tag, depth)?;
+                Value::Closure(Closure {
+                    func: FuncId(func),
+                    env,
+                })
+            }
+            TAG_OBJ => {
+                let host_type = HostTypeId(self.r.read_u64_le()?);
+                let len = self.len()?;
+                let data = self.r.read_bytes(len)?;
+                let Some(host_types) = self.host_types else {
+                    return Err(ValueCodecError::Trap(Trap::SerializationEncounteredObj {
+                        host_type,
+                    }));
+                };
+                Value::Obj(host_types.deserialize_obj(host_type, data)?)
+            }
+            _ => Value::Agg(self.agg(tag, depth)?),
+        })
+    }
+
+    fn agg(&mut self, tag: u8, depth: usize) -> Result<AggHandle, ValueCodecError> {
+        if !matches!(tag, TAG_TUPLE | TAG_STRUCT | TAG_ARRAY) {
+            return Err(ValueCodecError::UnknownTag(tag));
+        }
+        if depth >= MAX_NESTING {
+            return Err(ValueCodecError::NestingTooDeep);
+        }
+        Ok(match tag {
+            TAG_TUPLE => {
+                let len = self.len()?;
+                let values = self.values(len, depth, |_| None)?;
+                self.aggregates.tuple_new(values)
+            }
+            TAG_STRUCT => {
+                let type_id = self.r.read_uleb128_u32()?;
+                let program = self.program;
+                let types = struct_field_types(program, type_id)?;
+                let len = self.len()?;
+                if len != types.len() {
+                    return Err(ValueCodecError::BadArity {
+                        type_id,
+                        expected: types.len(),
+                        actual: len,
+                    });
+                }
+                let values = self.values(len, depth, |i| Some(types[i]))?;
+                self.aggregates.struct_new(TypeId(type_id), values)
+            }
+            _ => {
+                let elem_type_id = self.r.read_uleb128_u32()?;
+                let ty = elem_type(self.program, elem_type_id)?;
+                let len = self.len()?;
+                let values = self.values(len, depth, |_| Some(ty))?;
+                self.aggregates.array_new(ElemTypeId(elem_type_id), values)
+            }
+        })
+    }
+
+    fn values(
+        &mut self,
+        len: usize,
+        depth: usize,
+        expected: impl Fn(usize) -> Option<ValueType>,
+    ) -> Result<Vec<Value>, ValueCodecError> {
+        // Every element takes at least one byte, which bounds the allocation by the input size.
+        let mut values = Vec::with_capacity(len.min(self.input_len - self.r.offset()));
+        for i in 0..len {
+            let value = self.value(depth + 1)?;
+            if let Some(ty) = expected(i) {
+                check_type(ty, &value)?;
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{Asm, FunctionSig, ProgramBuilder};
+    use crate::program::{HostTypeId, StructTypeDef};
+    use crate::value::{Obj, ObjHandle};
+    use alloc::vec;
+
+    fn sample_program() -> Program {
+        let mut pb = ProgramBuilder::new();
+        pb.struct_type(StructTypeDef {
+            field_names: vec!["name".into(), "items".into()],
+            field_types: vec![ValueType::Str, ValueType::Agg],
+        });
+        pb.array_elem(ValueType::Decimal);
+        let mut a = Asm::new();
+        a.ret(0, &[]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![],
+                ret_types: vec![],
+            },
+        )
+        .unwrap();
+        pb.build()
+    }
+
+    fn sample_value(heap: &mut AggHeap) -> Value {
+        let prices = heap.array_new(
+            ElemTypeId(0),
+            vec![
+                Value::Decimal(Decimal {
+                    mantissa: -1250,
+                    scale: 2,
+                }),
+                Value::Decimal(Decimal {
+                    mantissa: 7,
+                    scale: 0,
+                }),
+            ],
+        );
+        let order = heap.struct_new(
+            TypeId(0),
+            vec![Value::Str("order-1".into()), Value::Agg(prices)],
+        );
+        let env = heap.tuple_new(vec![Value::Bytes(vec![0, 1, 255]), Value::Agg(order)]);
+        Value::Agg(heap.tuple_new(vec![
+            Value::Unit,
+            Value::Bool(true),
+            Value::I64(i64::MIN),
+            Value::U64(u64::MAX),
+            Value::F64(-0.5),
+            Value::Func(FuncId(0)),
+            Value::Closure(Closure {
+                func: FuncId(0),
+                env,
+            }),
+            Value::Agg(order),
+        ]))
+    }
+
+    #[test]
+    fn nested_values_roundtrip_canonically() {
+        let program = sample_program();
+        let mut heap = AggHeap::new();
+        let value = sample_value(&mut heap);
+        let bytes = encode_value(&value, &heap, &program).unwrap();
+        assert_eq!(&bytes[..8], MAGIC);
+
+        let mut other = AggHeap::new();
+        other.tuple_new(vec![]);
+        let decoded = decode_value(&bytes, &mut other, &program).unwrap();
+        assert_ne!(decoded, value, "handles differ between heaps");
+        assert_eq!(encode_value(&decoded, &other, &program).unwrap(), bytes);
+
+        for scalar in [
+            Value::Str("héllo".into()),
+            Value::F64(f64::NAN),
+            Value::Decimal(Decimal {
+                mantissa: 1,
+                scale: 255,
+            }),
+        ] {
+            let bytes = encode_value(&scalar, &heap, &program).unwrap();
+            let decoded = decode_value(&bytes, &mut other, &program).unwrap();
+            assert_eq!(encode_value(&decoded, &other, &program).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn obj_traps() {
+        let program = sample_program();
+        let mut heap = AggHeap::new();
+        let obj = Value::Obj(Obj {
+            host_type: HostTypeId(3),
+            handle: ObjHandle(9),
+        });
+        let inner = heap.tuple_new(vec![Value::I64(1), obj.clone()]);
+        let outer = heap.tuple_new(vec![Value::Agg(inner)]);
+        for value in [obj, Value::Agg(outer)] {
+            assert_eq!(
+                encode_value(&value, &heap, &program),
+                Err(ValueCodecError::Trap(Trap::SerializationEncounteredObj {
+                    host_type: HostTypeId(3),
+                }))
+            );
+        }
+    }
+
+    #[test]
+    fn objs_roundtrip_through_host_types() {
+        use crate::host::HostError;
+        use crate::host_types::HostType;
+
+        let mut types = HostTypeRegistry::new();
+        types
+            .register(
+                HostTypeId(3),
+                HostType::new("counter")
+                    .with_serialize(|h, out| {
+                        out.extend_from_slice(&h.0.to_le_bytes());
+                        Ok(())
+                    })
+                    .with_deserialize(|bytes| {
+                        let bytes = bytes.try_into().map_err(|_| HostError::Failed)?;
+                        Ok(ObjHandle(u64::from_le_bytes(bytes) + 100))
+                    }),
+            )
+            .unwrap();
+
+        let program = sample_program();
+        let mut heap = AggHeap::new();
+        let obj = |host_type, handle| {
+            Value::Obj(Obj {
+                host_type: HostTypeId(host_type),
+                handle: ObjHandle(handle),
+            })
+        };
+        let value = Value::Agg(heap.tuple_new(vec![Value::I64(1), obj(3, 9)]));
+        let bytes = encode_value_with_types(&value, &heap, &program, &types).unwrap();
+
+        let mut other = AggHeap::new();
+        let decoded = decode_value_with_types(&bytes, &mut other, &program, &types).unwrap();
+        let Value::Agg(tuple) = decoded else {
+            panic!("expected a tuple, got {decoded:?}");
+        };
+        assert_eq!(other.tuple_get_ref(tuple, 1), Ok(&obj(3, 109)));
+
+        assert_eq!(
+            decode_value(&bytes, &mut other, &program),
+            Err(ValueCodecError::Trap(Trap::SerializationEncounteredObj {
+                host_type: HostTypeId(3),
+            }))
+        );
+        assert_eq!(
+            encode_value_with_types(&obj(4, 0), &heap, &program, &types),
+            Err(ValueCodecError::HostType(HostTypeError::UnknownType {
+                host_type: HostTypeId(4),
+            }))
+        );
+    }
+
+    #[test]
+    fn shared_aggregates_are_bounded() {
+        let program = sample_program();
+        let mut heap = AggHeap::new();
+        // `t0 = (x, x)`, `t{n+1} = (tn, tn)`: 60 heap tuples, 2^61 values as a tree.
+        let mut t = heap.tuple_new(vec![Value::I64(1), Value::I64(1)]);
+        for _ in 0..60 {
+            t = heap.tuple_new(vec![Value::Agg(t), Value::Agg(t)]);
+        }
+        assert_eq!(
+            encode_value(&Value::Agg(t), &heap, &program),
+            Err(ValueCodecError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        let program = sample_program();
+        let mut heap = AggHeap::new();
+
+        assert_eq!(
+            encode_value(&Value::Agg(AggHandle(0)), &heap, &program),
+            Err(ValueCodecError::Trap(Trap::AggError(AggError::BadHandle)))
+        );
+        assert_eq!(
+            encode_value(&Value::Func(FuncId(1)), &heap, &program),
+            Err(ValueCodecError::BadFuncId(1))
+        );
+        let arr = heap.array_new(ElemTypeId(0), vec![Value::I64(1)]);
+        assert_eq!(
+            encode_value(&Value::Agg(arr), &heap, &program),
+            Err(ValueCodecError::TypeMismatch {
+                expected: ValueType::Decimal,
+                actual: ValueType::I64,
+            })
+        );
+
+        let header = |body: &[u8]| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend_from_slice(&VALUE_FORMAT_VERSION.to_le_bytes());
+            bytes.extend_from_slice(body);
+            bytes
+        };
+        let mut decode = |bytes: &[u8]| decode_value(bytes, &mut heap, &program);
+        assert_eq!(
+            decode(b"EXTVAL\0\0\x02\0\0"),
+            Err(ValueCodecError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            decode(b"EXTVAM\0\0\x01\0\0"),
+            Err(ValueCodecError::Malformed(DecodeError::BadMagic))
+        );
+        assert_eq!(
+            decode(&header(&[TAG_UNIT, 0])),
+            Err(ValueCodecError::TrailingBytes)
+        );
+        assert_eq!(decode(&header(&[42])), Err(ValueCodecError::UnknownTag(42)));
+        assert_eq!(
+            decode(&header(&[TAG_BOOL, 2])),
+            Err(ValueCodecError::Malformed(DecodeError::OutOfBounds))
+        );
+        assert_eq!(
+            decode(&header(&[TAG_STR, 1, 0xFF])),
+            Err(ValueCodecError::Malformed(DecodeError::InvalidUtf8))
+        );
+        // Closure environments must be aggregates.
+        assert_eq!(
+            decode(&header(&[TAG_CLOSURE, 0, TAG_UNIT])),
+            Err(ValueCodecError::UnknownTag(TAG_UNIT))
+        );
+        assert_eq!(
+            decode(&header(&[TAG_STRUCT, 0, 1, TAG_STR, 0])),
+            Err(ValueCodecError::BadArity {
+                type_id: 0,
+                expected: 2,
+                actual: 1,
+            })
+        );
+        assert_eq!(
+            decode(&header(&[TAG_STRUCT, 1, 0])),
+            Err(ValueCodecError::BadTypeId(1))
+        );
+        // A huge element count fails on the missing elements rather than on allocation.
+        assert_eq!(
+            decode(&header(&[TAG_TUPLE, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F])),
+            Err(ValueCodecError::Malformed(DecodeError::UnexpectedEof))
+        );
+
+        let mut deep = Vec::new();
+        for _ in 0..=MAX_NESTING {
+            deep.extend_from_slice(&[TAG_TUPLE, 1]);
+        }
+        deep.push(TAG_UNIT);
+        assert_eq!(decode(&header(&deep)), Err(ValueCodecError::NestingTooDeep));
+    }
+}
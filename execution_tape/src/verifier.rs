@@ -17,13 +17,14 @@ use crate::analysis::dataflow;
 use crate::analysis::liveness;
 use crate::bytecode::{DecodedInstr, Instr, decode_instructions};
 use crate::format::DecodeError;
-use crate::host::{SigHash, sig_hash_slices};
+use crate::host::{SigHash, pure_sig_hash_slices, sig_hash_slices};
 use crate::instr_operands;
 use crate::opcode::Opcode;
 use crate::program::{
-    AggShape, CallSigId, ConstEntry, ElemTypeId, Function, Program, SpanEntry, SpanId, SymbolId,
-    TypeId, ValueType,
+    AggShape, CallSigId, ConstEntry, ElemTypeId, Function, HostSigId, Program, ProgramTables,
+    SpanEntry, SpanId, SymbolId, TypeId, ValueType,
 };
+use crate::program_ref::ProgramRef;
 use crate::typed::{
     AggReg, BoolReg, BytesReg, ClosureReg, DecimalReg, ExecDecoded, ExecFunc, ExecInstr, F64Reg,
     FuncReg, I64Reg, ObjReg, RegClass, RegCounts, RegLayout, StrReg, U64Reg, UnitReg, VReg,
@@ -44,9 +45,13 @@ use crate::vm::Vm;
 ///
 /// Internally, a [`VerifiedProgram`] also carries a decoded instruction stream so the VM does not
 /// need to decode bytecode at runtime.
+///
+/// `P` is the program storage: an owned [`Program`] (the default, and what [`Vm::run`] accepts),
+/// or a borrowed [`ProgramRef`] from [`verify_program_ref`], which keeps only the typed verifier
+/// artifacts and borrows everything else from the encoded bytes.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct VerifiedProgram {
-    program: Program,
+pub struct VerifiedProgram<P = Program> {
+    program: P,
     verified_functions: Vec<ExecFunc>,
     signature_cache: SignatureCache,
 }
@@ -119,10 +124,38 @@ pub enum VerifyLint {
     },
 }
 
-impl VerifiedProgram {
+impl fmt::Display for VerifyLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnreachableBlock { func, pc } => {
+                write!(f, "function {func} pc={pc}: unreachable block")
+            }
+            Self::DeadStore { func, pc, reg } => {
+                write!(
+                    f,
+                    "function {func} pc={pc}: r{reg} is written but never read"
+                )
+            }
+            Self::RedundantMove { func, pc, dst, src } => {
+                write!(
+                    f,
+                    "function {func} pc={pc}: redundant move r{dst} <- r{src}"
+                )
+            }
+            Self::UnusedCallReturn { func, pc, reg } => {
+                write!(
+                    f,
+                    "function {func} pc={pc}: call return r{reg} is never read"
+                )
+            }
+        }
+    }
+}
+
+impl<P> VerifiedProgram<P> {
     /// Returns the underlying program.
     #[must_use]
-    pub fn program(&self) -> &Program {
+    pub fn program(&self) -> &P {
         &self.program
     }
 
@@ -157,11 +190,86 @@ impl VerifiedProgram {
 
     /// Consumes `self` and returns the underlying program.
     #[must_use]
-    pub fn into_program(self) -> Program {
+    pub fn into_program(self) -> P {
         self.program
     }
 }
 
+impl VerifiedProgram {
+    /// Returns each function's register-class assignment, in register order.
+    pub(crate) fn reg_classes(&self) -> impl Iterator<Item = impl Iterator<Item = RegClass>> {
+        self.verified_functions
+            .iter()
+            .map(|f| f.reg_layout.reg_map.iter().map(|r| r.class()))
+    }
+
+    /// Assembles a verified program from artifacts built by [`lower_function_with_classes`].
+    pub(crate) fn from_trusted_parts(
+        program: Program,
+        verified_functions: Vec<ExecFunc>,
+    ) -> Result<Self, VerifyError> {
+        let signature_cache = build_signature_cache(&program.tables())?;
+        Ok(Self {
+            program,
+            verified_functions,
+            signature_cache,
+        })
+    }
+}
+
+impl VerifiedProgram<ProgramRef<'_>> {
+    /// Copies the program's byte payloads into an owned [`Program`], keeping the decoded tables
+    /// and the verified artifacts.
+    ///
+    /// The result can be run by [`Vm::run`] without verifying again. The VM only runs owned
+    /// programs, so this is the one copy a borrowed program pays before execution.
+    pub fn into_owned(self) -> Result<VerifiedProgram, DecodeError> {
+        Ok(VerifiedProgram {
+            program: self.program.into_program()?,
+            verified_functions: self.verified_functions,
+            signature_cache: self.signature_cache,
+        })
+    }
+}
+
+/// Read access to a program's typed tables and the byte arenas they index.
+///
+/// Owned programs keep their arenas inline; a [`ProgramRef`] resolves them against the encoded
+/// bytes.
+pub(crate) trait ProgramSource {
+    fn tables(&self) -> ProgramTables<'_>;
+    fn symbol_str(&self, id: SymbolId) -> Result<&str, DecodeError>;
+    fn function_bytecode(&self, func: &Function) -> Result<&[u8], DecodeError>;
+}
+
+impl ProgramSource for Program {
+    fn tables(&self) -> ProgramTables<'_> {
+        Self::tables(self)
+    }
+
+    fn symbol_str(&self, id: SymbolId) -> Result<&str, DecodeError> {
+        Self::symbol_str(self, id)
+    }
+
+    fn function_bytecode(&self, func: &Function) -> Result<&[u8], DecodeError> {
+        Self::function_bytecode(self, func)
+    }
+}
+
+impl ProgramSource for ProgramRef<'_> {
+    fn tables(&self) -> ProgramTables<'_> {
+        Self::tables(self)
+    }
+
+    fn symbol_str(&self, id: SymbolId) -> Result<&str, DecodeError> {
+        Self::symbol_str(self, id)
+    }
+
+    fn function_bytecode(&self, func: &Function) -> Result<&[u8], DecodeError> {
+        Self::function_bytecode(self, func)
+    }
+}
+
 /// A verification error.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum VerifyError {
@@ -322,6 +430,36 @@ pub enum VerifyError {
         /// Return index within the function signature.
         ret: u32,
     },
+    /// An export entry references an unknown function.
+    ExportFuncOutOfBounds {
+        /// Function index referenced by the export entry.
+        func: u32,
+    },
+    /// An export name is empty.
+    ExportNameEmpty {
+        /// Function index within the program.
+        func: u32,
+    },
+    /// Two export entries share the same name.
+    ExportNameDuplicate {
+        /// Symbol id naming the duplicated export.
+        name: SymbolId,
+    },
+    /// An import entry references an unknown placeholder function.
+    ImportFuncOutOfBounds {
+        /// Function index referenced by the import entry.
+        func: u32,
+    },
+    /// An import module or function name is empty.
+    ImportNameEmpty {
+        /// Placeholder function index within the program.
+        func: u32,
+    },
+    /// Two import entries share the same placeholder function.
+    ImportFuncDuplicate {
+        /// Placeholder function index within the program.
+        func: u32,
+    },
     /// A function span table has a bad `pc_delta` sequence.
     ///
     /// v1 requires `pc_delta` values to be non-zero after the first entry (to ensure progress)
@@ -535,6 +673,15 @@ pub enum VerifyError {
         /// Host signature id.
         host_sig: u32,
     },
+    /// A `host_call.pure` referenced a host signature that is not pure.
+    HostCallNotPure {
+        /// Function index within the program.
+        func: u32,
+        /// Byte offset of the instruction.
+        pc: u32,
+        /// Host signature id.
+        host_sig: u32,
+    },
     /// A `call.indirect` referenced a call signature, but that entry was malformed.
     CallIndirectSigMalformed {
         /// Function index within the program.
@@ -767,6 +914,24 @@ impl fmt::Display for VerifyError {
                     "function {func} output name ret index is duplicated: {ret}"
                 )
             }
+            Self::ExportFuncOutOfBounds { func } => {
+                write!(f, "export references unknown function {func}")
+            }
+            Self::ExportNameEmpty { func } => {
+                write!(f, "export of function {func} has an empty name")
+            }
+            Self::ExportNameDuplicate { name } => {
+                write!(f, "export name is duplicated (symbol_id={})", name.0)
+            }
+            Self::ImportFuncOutOfBounds { func } => {
+                write!(f, "import references unknown placeholder function {func}")
+            }
+            Self::ImportNameEmpty { func } => {
+                write!(f, "import for function {func} has an empty module or name")
+            }
+            Self::ImportFuncDuplicate { func } => {
+                write!(f, "function {func} backs more than one import")
+            }
             Self::BadSpanDeltas { func } => {
                 write!(f, "function {func} span table has bad pc_deltas")
             }
@@ -885,6 +1050,10 @@ impl fmt::Display for VerifyError {
                 f,
                 "function {func} pc={pc} host_sig {host_sig} malformed (via host_call)"
             ),
+            Self::HostCallNotPure { func, pc, host_sig } => write!(
+                f,
+                "function {func} pc={pc} host_sig {host_sig} is not pure (via host_call.pure)"
+            ),
             Self::CallIndirectSigMalformed { func, pc, call_sig } => write!(
                 f,
                 "function {func} pc={pc} call_sig {call_sig} malformed (via call.indirect)"
@@ -998,12 +1167,21 @@ impl Default for VerifyConfig {
     }
 }
 
-/// Verifies `program` according to v1 container-level rules.
-pub fn verify_program(program: &Program, cfg: &VerifyConfig) -> Result<(), VerifyError> {
+/// Runs the program-level table checks that do not depend on per-function analyses.
+///
+/// These are linear in the size of the tables and run before any function is lowered.
+pub(crate) fn verify_program_tables(src: &impl ProgramSource) -> Result<(), VerifyError> {
+    let program = &src.tables();
     verify_host_sigs(program)?;
     verify_call_sigs(program)?;
-    verify_function_value_names(program)?;
+    verify_function_value_names(src)?;
     verify_function_arg_agg_shapes(program)?;
+    verify_linkage(src)
+}
+
+/// Verifies `program` according to v1 container-level rules.
+pub fn verify_program(program: &Program, cfg: &VerifyConfig) -> Result<(), VerifyError> {
+    verify_program_tables(program)?;
 
     for (i, func) in program.functions.iter().enumerate() {
         let func_id = u32::try_from(i).unwrap_or(u32::MAX);
@@ -1017,10 +1195,7 @@ pub fn verify_program_with_lints(
     program: &Program,
     cfg: &VerifyConfig,
 ) -> Result<Vec<VerifyLint>, VerifyError> {
-    verify_host_sigs(program)?;
-    verify_call_sigs(program)?;
-    verify_function_value_names(program)?;
-    verify_function_arg_agg_shapes(program)?;
+    verify_program_tables(program)?;
 
     let mut lints: Vec<VerifyLint> = Vec::new();
     for (i, func) in program.functions.iter().enumerate() {
@@ -1036,11 +1211,8 @@ pub fn verify_program_owned(
     program: Program,
     cfg: &VerifyConfig,
 ) -> Result<VerifiedProgram, VerifyError> {
-    verify_host_sigs(&program)?;
-    verify_call_sigs(&program)?;
-    verify_function_value_names(&program)?;
-    verify_function_arg_agg_shapes(&program)?;
-    let signature_cache = build_signature_cache(&program)?;
+    verify_program_tables(&program)?;
+    let signature_cache = build_signature_cache(&program.tables())?;
 
     let mut verified_functions: Vec<ExecFunc> = Vec::with_capacity(program.functions.len());
     for (i, func) in program.functions.iter().enumerate() {
@@ -1060,11 +1232,8 @@ pub fn verify_program_owned_with_lints(
     program: Program,
     cfg: &VerifyConfig,
 ) -> Result<(VerifiedProgram, Vec<VerifyLint>), VerifyError> {
-    verify_host_sigs(&program)?;
-    verify_call_sigs(&program)?;
-    verify_function_value_names(&program)?;
-    verify_function_arg_agg_shapes(&program)?;
-    let signature_cache = build_signature_cache(&program)?;
+    verify_program_tables(&program)?;
+    let signature_cache = build_signature_cache(&program.tables())?;
 
     let mut verified_functions: Vec<ExecFunc> = Vec::with_capacity(program.functions.len());
     let mut lints: Vec<VerifyLint> = Vec::new();
@@ -1085,7 +1254,31 @@ pub fn verify_program_owned_with_lints(
     ))
 }
 
-fn build_signature_cache(program: &Program) -> Result<SignatureCache, VerifyError> {
+/// Verifies a borrowed `program` without copying its bytecode, symbols or constants.
+///
+/// The returned [`VerifiedProgram`] owns the decoded typed tables and verifier artifacts and keeps
+/// borrowing the encoded bytes. Use [`VerifiedProgram::into_owned`] to run it on a [`Vm`].
+pub fn verify_program_ref<'a>(
+    program: ProgramRef<'a>,
+    cfg: &VerifyConfig,
+) -> Result<VerifiedProgram<ProgramRef<'a>>, VerifyError> {
+    verify_program_tables(&program)?;
+    let signature_cache = build_signature_cache(&program.tables())?;
+
+    let mut verified_functions: Vec<ExecFunc> = Vec::with_capacity(program.functions().len());
+    for (i, func) in program.functions().iter().enumerate() {
+        let func_id = u32::try_from(i).unwrap_or(u32::MAX);
+        verified_functions.push(verify_function_container(&program, func_id, func, cfg)?.exec);
+    }
+
+    Ok(VerifiedProgram {
+        program,
+        verified_functions,
+        signature_cache,
+    })
+}
+
+fn build_signature_cache(program: &ProgramTables<'_>) -> Result<SignatureCache, VerifyError> {
     let mut canonical: Vec<CanonicalSignature> = Vec::new();
     let mut call_sigs: Vec<SigFingerprint> = Vec::with_capacity(program.call_sigs.len());
     for (i, entry) in program.call_sigs.iter().enumerate() {
@@ -1164,91 +1357,80 @@ struct ExecFuncContainer {
     lints: Vec<VerifyLint>,
 }
 
-fn verify_function_value_names(program: &Program) -> Result<(), VerifyError> {
+fn verify_function_value_names(src: &impl ProgramSource) -> Result<(), VerifyError> {
+    let program = src.tables();
     for (func_id, func) in program.functions.iter().enumerate() {
         let func_id = u32::try_from(func_id).unwrap_or(u32::MAX);
 
         let arg_names = program
             .function_arg_name_ids(func)
             .map_err(VerifyError::Decode)?;
+        let mut seen: Vec<&str> = Vec::with_capacity(arg_names.len());
         for (arg, &name_id) in arg_names.iter().enumerate() {
             let arg = u32::try_from(arg).unwrap_or(u32::MAX);
-            if name_id == 0 {
+            let Some((sym, name)) = value_name(src, name_id)? else {
                 continue;
-            }
-            let sym = SymbolId(
-                core::num::NonZeroU32::new(name_id)
-                    .ok_or(VerifyError::Decode(DecodeError::OutOfBounds))?,
-            );
-            let name = program.symbol_str(sym).map_err(VerifyError::Decode)?;
+            };
             if name.is_empty() {
                 return Err(VerifyError::FunctionInputNameEmpty { func: func_id, arg });
             }
-        }
-        for (i, &a) in arg_names.iter().enumerate() {
-            if a == 0 {
-                continue;
-            }
-            for &b in &arg_names[..i] {
-                if b == a {
-                    return Err(VerifyError::FunctionInputNameDuplicate {
-                        func: func_id,
-                        name: SymbolId(
-                            core::num::NonZeroU32::new(a)
-                                .ok_or(VerifyError::Decode(DecodeError::OutOfBounds))?,
-                        ),
-                    });
-                }
+            // Distinct symbols may hold the same string, so compare names rather than ids.
+            if seen.contains(&name) {
+                return Err(VerifyError::FunctionInputNameDuplicate {
+                    func: func_id,
+                    name: sym,
+                });
             }
+            seen.push(name);
         }
 
         let ret_names = program
             .function_ret_name_ids(func)
             .map_err(VerifyError::Decode)?;
+        seen.clear();
         for (ret, &name_id) in ret_names.iter().enumerate() {
             let ret = u32::try_from(ret).unwrap_or(u32::MAX);
-            if name_id == 0 {
+            let Some((sym, name)) = value_name(src, name_id)? else {
                 continue;
-            }
-            let sym = SymbolId(
-                core::num::NonZeroU32::new(name_id)
-                    .ok_or(VerifyError::Decode(DecodeError::OutOfBounds))?,
-            );
-            let name = program.symbol_str(sym).map_err(VerifyError::Decode)?;
+            };
             if name.is_empty() {
                 return Err(VerifyError::FunctionOutputNameEmpty { func: func_id, ret });
             }
-        }
-        for (i, &a) in ret_names.iter().enumerate() {
-            if a == 0 {
-                continue;
-            }
-            for &b in &ret_names[..i] {
-                if b == a {
-                    return Err(VerifyError::FunctionOutputNameDuplicate {
-                        func: func_id,
-                        name: SymbolId(
-                            core::num::NonZeroU32::new(a)
-                                .ok_or(VerifyError::Decode(DecodeError::OutOfBounds))?,
-                        ),
-                    });
-                }
+            if seen.contains(&name) {
+                return Err(VerifyError::FunctionOutputNameDuplicate {
+                    func: func_id,
+                    name: sym,
+                });
             }
+            seen.push(name);
         }
     }
 
     Ok(())
 }
 
-fn verify_function_arg_agg_shapes(program: &Program) -> Result<(), VerifyError> {
+/// Resolves an argument or return name id; `0` marks an unnamed value.
+fn value_name(
+    src: &impl ProgramSource,
+    name_id: u32,
+) -> Result<Option<(SymbolId, &str)>, VerifyError> {
+    let Some(id) = core::num::NonZeroU32::new(name_id) else {
+        return Ok(None);
+    };
+    let sym = SymbolId(id);
+    let name = src.symbol_str(sym).map_err(VerifyError::Decode)?;
+    Ok(Some((sym, name)))
+}
+
+fn verify_function_arg_agg_shapes(program: &ProgramTables<'_>) -> Result<(), VerifyError> {
     let mut seen: Vec<(u32, u32)> = Vec::new();
 
-    for e in &program.function_arg_agg_shapes {
+    for e in program.function_arg_agg_shapes {
         let Some(func) = program.functions.get(e.func as usize) else {
             return Err(VerifyError::FunctionArgAggShapeFuncOutOfBounds { func: e.func });
         };
-        let arg_types = func
-            .arg_types(program)
+        let arg_types = program
+            .function_arg_types(func)
             .map_err(|_| VerifyError::FunctionArgTypesOutOfBounds { func: e.func })?;
         let Some(actual) = arg_types.get(e.arg as usize).copied() else {
             return Err(VerifyError::FunctionArgAggShapeArgOutOfBounds {
@@ -1305,7 +1487,42 @@ fn verify_function_arg_agg_shapes(program: &Program) -> Result<(), VerifyError>
     Ok(())
 }
 
-fn verify_host_sigs(program: &Program) -> Result<(), VerifyError> {
+fn verify_linkage(src: &impl ProgramSource) -> Result<(), VerifyError> {
+    let program = src.tables();
+    for (i, e) in program.exports.iter().enumerate() {
+        if program.functions.get(e.func as usize).is_none() {
+            return Err(VerifyError::ExportFuncOutOfBounds { func: e.func });
+        }
+        let name = src.symbol_str(e.name).map_err(VerifyError::Decode)?;
+        if name.is_empty() {
+            return Err(VerifyError::ExportNameEmpty { func: e.func });
+        }
+        if program.exports[..i]
+            .iter()
+            .any(|prev| src.symbol_str(prev.name).ok() == Some(name))
+        {
+            return Err(VerifyError::ExportNameDuplicate { name: e.name });
+        }
+    }
+
+    for (i, e) in program.imports.iter().enumerate() {
+        if program.functions.get(e.func as usize).is_none() {
+            return Err(VerifyError::ImportFuncOutOfBounds { func: e.func });
+        }
+        for sym in [e.module, e.name] {
+            if src.symbol_str(sym).map_err(VerifyError::Decode)?.is_empty() {
+                return Err(VerifyError::ImportNameEmpty { func: e.func });
+            }
+        }
+        if program.imports[..i].iter().any(|prev| prev.func == e.func) {
+            return Err(VerifyError::ImportFuncDuplicate { func: e.func });
+        }
+    }
+
+    Ok(())
+}
+
+fn verify_host_sigs(program: &ProgramTables<'_>) -> Result<(), VerifyError> {
     for (i, hs) in program.host_sigs.iter().enumerate() {
         let host_sig = u32::try_from(i).unwrap_or(u32::MAX);
         if (hs.symbol.index() as usize) >= program.symbols.len() {
@@ -1317,14 +1534,19 @@ fn verify_host_sigs(program: &Program) -> Result<(), VerifyError> {
         let rets = program
             .host_sig_rets(hs)
             .map_err(|_| VerifyError::HostSigMalformed { host_sig })?;
-        if hs.sig_hash != sig_hash_slices(args, rets) {
+        let expected = if hs.pure {
+            pure_sig_hash_slices(args, rets)
+        } else {
+            sig_hash_slices(args, rets)
+        };
+        if hs.sig_hash != expected {
             return Err(VerifyError::HostSigHashMismatch { host_sig });
         }
     }
     Ok(())
 }
 
-fn verify_call_sigs(program: &Program) -> Result<(), VerifyError> {
+fn verify_call_sigs(program: &ProgramTables<'_>) -> Result<(), VerifyError> {
     for (i, cs) in program.call_sigs.iter().enumerate() {
         let call_sig = u32::try_from(i).unwrap_or(u32::MAX);
         let _ = program
@@ -1337,12 +1559,67 @@ fn verify_call_sigs(program: &Program) -> Result<(), VerifyError> {
     Ok(())
 }
 
+/// A function's container data, bounds-checked and decoded, ready for bytecode verification.
+struct FunctionParts<'p> {
+    bytecode: &'p [u8],
+    spans: &'p [SpanEntry],
+    arg_types: &'p [ValueType],
+    ret_types: &'p [ValueType],
+    decoded: Vec<DecodedInstr>,
+}
+
 fn verify_function_container(
-    program: &Program,
+    src: &impl ProgramSource,
     func_id: u32,
     func: &Function,
     cfg: &VerifyConfig,
 ) -> Result<ExecFuncContainer, VerifyError> {
+    let parts = decode_function_parts(src, func_id, func, cfg)?;
+    verify_function_bytecode(
+        &src.tables(),
+        func_id,
+        func,
+        parts.bytecode,
+        parts.spans,
+        parts.arg_types,
+        parts.ret_types,
+        &parts.decoded,
+    )
+}
+
+/// Rebuilds a function's execution artifacts from a cached register-class assignment.
+///
+/// This runs the container-level checks and instruction lowering but none of the CFG, must-init or
+/// type analyses; `classes` must come from an earlier successful verification of the same
+/// program.
+pub(crate) fn lower_function_with_classes(
+    program: &Program,
+    func_id: u32,
+    func: &Function,
+    classes: &[RegClass],
+    cfg: &VerifyConfig,
+) -> Result<ExecFunc, VerifyError> {
+    let parts = decode_function_parts(program, func_id, func, cfg)?;
+    let byte_len = u32::try_from(parts.bytecode.len())
+        .map_err(|_| VerifyError::BytecodeDecode { func: func_id })?;
+    let reg_layout = build_reg_layout(classes.iter().copied(), parts.arg_types.len());
+    lower_function(
+        &program.tables(),
+        func_id,
+        byte_len,
+        &parts.decoded,
+        parts.spans,
+        reg_layout,
+    )
+}
+
+fn decode_function_parts<'p, S: ProgramSource>(
+    src: &'p S,
+    func_id: u32,
+    func: &Function,
+    cfg: &VerifyConfig,
+) -> Result<FunctionParts<'p>, VerifyError> {
+    let program = src.tables();
     if func.reg_count > cfg.max_regs_per_function {
         return Err(VerifyError::RegCountTooLarge {
             func: func_id,
@@ -1350,17 +1627,17 @@ fn verify_function_container(
         });
     }
 
-    let bytecode = func
-        .bytecode(program)
+    let bytecode = src
+        .function_bytecode(func)
         .map_err(|_| VerifyError::FunctionBytecodeOutOfBounds { func: func_id })?;
-    let spans = func
-        .spans(program)
+    let spans = program
+        .function_spans(func)
         .map_err(|_| VerifyError::FunctionSpansOutOfBounds { func: func_id })?;
-    let arg_types = func
-        .arg_types(program)
+    let arg_types = program
+        .function_arg_types(func)
         .map_err(|_| VerifyError::FunctionArgTypesOutOfBounds { func: func_id })?;
-    let ret_types = func
-        .ret_types(program)
+    let ret_types = program
+        .function_ret_types(func)
         .map_err(|_| VerifyError::FunctionRetTypesOutOfBounds { func: func_id })?;
 
     if u32::try_from(arg_types.len()).ok() != Some(func.arg_count)
@@ -1398,14 +1675,18 @@ fn verify_function_container(
             });
         }
     }
-    verify_id_operands_in_bounds(program, func_id, &decoded)?;
-    verify_function_bytecode(
-        program, func_id, func, bytecode, spans, arg_types, ret_types, &decoded,
-    )
+    verify_id_operands_in_bounds(&program, func_id, &decoded)?;
+    Ok(FunctionParts {
+        bytecode,
+        spans,
+        arg_types,
+        ret_types,
+        decoded,
+    })
 }
 
 fn verify_id_operands_in_bounds(
-    program: &Program,
+    program: &ProgramTables<'_>,
     func: u32,
     decoded: &[DecodedInstr],
 ) -> Result<(), VerifyError> {
@@ -1535,7 +1816,7 @@ fn build_span_by_instr_ix(decoded: &[DecodedInstr], spans: &[SpanEntry]) -> Vec<
 }
 
 fn verify_function_bytecode(
-    program: &Program,
+    program: &ProgramTables<'_>,
     func_id: u32,
     func: &Function,
     bytecode: &[u8],
@@ -1722,13 +2003,22 @@ fn verify_function_bytecode(
         }
     }
 
-    // Build a per-function register layout: each virtual register maps to exactly one class-local
-    // index.
+    let classes = reg_types
+        .iter()
+        .map(|t| RegClass::of(t.unwrap_or(ValueType::Unit)));
+    let reg_layout = build_reg_layout(classes, arg_types.len());
+    let exec = lower_function(program, func_id, byte_len, decoded, spans, reg_layout)?;
+    Ok(ExecFuncContainer { exec, lints })
+}
+
+/// Builds a register layout: each virtual register maps to exactly one class-local index.
+fn build_reg_layout(
+    classes: impl ExactSizeIterator<Item = RegClass>,
+    arg_count: usize,
+) -> RegLayout {
     let mut counts = RegCounts::default();
-    let mut reg_map: Vec<VReg> = Vec::with_capacity(reg_count);
-    for t in reg_types.iter().copied() {
-        let t = t.unwrap_or(ValueType::Unit);
-        let class = RegClass::of(t);
+    let mut reg_map: Vec<VReg> = Vec::with_capacity(classes.len());
+    for class in classes {
         let idx_u32 = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
         let v = match class {
             RegClass::Unit => {
@@ -1795,20 +2085,30 @@ fn verify_function_bytecode(
         reg_map.push(v);
     }
 
-    let mut arg_regs: Vec<VReg> = Vec::with_capacity(arg_types.len());
-    for i in 0..arg_types.len() {
+    let mut arg_regs: Vec<VReg> = Vec::with_capacity(arg_count);
+    for i in 0..arg_count {
         let r = reg_map
             .get(1 + i)
             .copied()
             .unwrap_or(VReg::Unit(UnitReg(0)));
         arg_regs.push(r);
     }
-    let reg_layout = RegLayout {
+    RegLayout {
         reg_map,
         counts,
         arg_regs,
-    };
+    }
+}
 
+/// Lowers decoded instructions to the typed instruction stream for `reg_layout`.
+fn lower_function(
+    program: &ProgramTables<'_>,
+    func_id: u32,
+    byte_len: u32,
+    decoded: &[DecodedInstr],
+    spans: &[SpanEntry],
+    reg_layout: RegLayout,
+) -> Result<ExecFunc, VerifyError> {
     let map_pc_to_ix = |src_pc: u32, target_pc: u32| -> Result<u32, VerifyError> {
         let ix = decoded
             .binary_search_by_key(&target_pc, |d| d.offset)
@@ -2419,6 +2719,19 @@ fn verify_function_bytecode(
                 args: push_vregs(args)?,
                 rets: push_vregs(rets)?,
             },
+            // Pure calls run as host calls that pass the (always initialized) `r0` effect token
+            // through unchanged.
+            Instr::HostCallPure {
+                host_sig,
+                args,
+                rets,
+            } => ExecInstr::HostCall {
+                eff_out: map_unit(0)?,
+                host_sig: *host_sig,
+                eff_in: map_unit(0)?,
+                args: push_vregs(args)?,
+                rets: push_vregs(rets)?,
+            },
             Instr::CallIndirect {
                 eff_out,
                 call_sig,
@@ -2654,15 +2967,12 @@ fn verify_function_bytecode(
     }
     let span_by_instr_ix = build_span_by_instr_ix(decoded, spans);
 
-    Ok(ExecFuncContainer {
-        exec: ExecFunc {
-            byte_len,
-            reg_layout,
-            operands,
-            instrs: execution_instrs,
-            span_by_instr_ix,
-        },
-        lints,
+    Ok(ExecFunc {
+        byte_len,
+        reg_layout,
+        operands,
+        instrs: execution_instrs,
+        span_by_instr_ix,
     })
 }
 
@@ -2716,7 +3026,9 @@ fn lint_function_bytecode(
             let mut call_rets: Option<&[u32]> = None;
             if let Instr::Call { rets, .. } = &di.instr {
                 call_rets = Some(rets.as_slice());
-            } else if let Instr::HostCall { rets, .. } = &di.instr {
+            } else if let Instr::HostCall { rets, .. } | Instr::HostCallPure { rets, .. } =
+                &di.instr
+            {
                 call_rets = Some(rets.as_slice());
             }
 
@@ -2758,7 +3070,7 @@ fn lint_function_bytecode(
 }
 
 fn validate_instr_reads_writes(
-    program: &Program,
+    program: &ProgramTables<'_>,
     func_id: u32,
     pc: u32,
     instr: &Instr,
@@ -2850,29 +3162,24 @@ fn validate_instr_reads_writes(
         } => {
             require_eff_in_r0(*eff_in)?;
             require_eff_out_r0(*eff_out)?;
-
+            check_host_call_arity(program, func_id, pc, *host_sig, args, rets)?;
+        }
+        Instr::HostCallPure {
+            host_sig,
+            args,
+            rets,
+        } => {
             let hs = program
                 .host_sig(*host_sig)
                 .expect("validated by verify_id_operands_in_bounds");
-            let hs_args =
-                program
-                    .host_sig_args(hs)
-                    .map_err(|_| VerifyError::HostCallSigMalformed {
-                        func: func_id,
-                        pc,
-                        host_sig: host_sig.0,
-                    })?;
-            let hs_rets =
-                program
-                    .host_sig_rets(hs)
-                    .map_err(|_| VerifyError::HostCallSigMalformed {
-                        func: func_id,
-                        pc,
-                        host_sig: host_sig.0,
-                    })?;
-            if args.len() != hs_args.len() || rets.len() != hs_rets.len() {
-                return Err(VerifyError::HostCallArityMismatch { func: func_id, pc });
+            if !hs.pure {
+                return Err(VerifyError::HostCallNotPure {
+                    func: func_id,
+                    pc,
+                    host_sig: host_sig.0,
+                });
             }
+            check_host_call_arity(program, func_id, pc, *host_sig, args, rets)?;
         }
         Instr::CallIndirect {
             eff_out,
@@ -2922,6 +3229,30 @@ fn validate_instr_reads_writes(
     Ok(())
 }
 
+fn check_host_call_arity(
+    program: &ProgramTables<'_>,
+    func_id: u32,
+    pc: u32,
+    host_sig: HostSigId,
+    args: &[u32],
+    rets: &[u32],
+) -> Result<(), VerifyError> {
+    let hs = program
+        .host_sig(host_sig)
+        .expect("validated by verify_id_operands_in_bounds");
+    let malformed = |_| VerifyError::HostCallSigMalformed {
+        func: func_id,
+        pc,
+        host_sig: host_sig.0,
+    };
+    let hs_args = program.host_sig_args(hs).map_err(malformed)?;
+    let hs_rets = program.host_sig_rets(hs).map_err(malformed)?;
+    if args.len() != hs_args.len() || rets.len() != hs_rets.len() {
+        return Err(VerifyError::HostCallArityMismatch { func: func_id, pc });
+    }
+    Ok(())
+}
+
 fn initial_init(reg_count: usize, arg_count: usize) -> BitSet {
     let mut s = BitSet::new_empty(reg_count);
     if reg_count != 0 {
@@ -2991,7 +3322,7 @@ fn initial_types(
 }
 
 fn initial_arg_agg_metas(
-    program: &Program,
+    program: &ProgramTables<'_>,
     func_id: u32,
     arg_types: &[ValueType],
 ) -> Vec<Option<AggMeta>> {
@@ -3045,7 +3376,7 @@ fn const_value_type(c: &ConstEntry) -> ValueType {
     }
 }
 
-fn transfer_types(program: &Program, instr: &Instr, state: &mut TypeState) {
+fn transfer_types(program: &ProgramTables<'_>, instr: &Instr, state: &mut TypeState) {
     fn clear_agg(state: &mut TypeState, reg: u32) {
         if let Some(slot) = state.aggs.get_mut(reg as usize) {
             *slot = None;
@@ -3224,7 +3555,7 @@ fn transfer_types(program: &Program, instr: &Instr, state: &mut TypeState) {
         } => {
             set_value(state, *eff_out, ValueType::Unit);
             if let Some(callee) = program.functions.get(func_id.0 as usize)
-                && let Ok(types) = callee.ret_types(program)
+                && let Ok(types) = program.function_ret_types(callee)
             {
                 for (dst, t) in rets.iter().zip(types.iter().copied()) {
                     set_value(state, *dst, t);
@@ -3235,13 +3566,10 @@ fn transfer_types(program: &Program, instr: &Instr, state: &mut TypeState) {
                 set_ambiguous(state, *dst);
             }
         }
-        Instr::HostCall {
-            eff_out,
-            host_sig,
-            rets,
-            ..
-        } => {
-            set_value(state, *eff_out, ValueType::Unit);
+        Instr::HostCall { host_sig, rets, .. } | Instr::HostCallPure { host_sig, rets, .. } => {
+            if let Instr::HostCall { eff_out, .. } = instr {
+                set_value(state, *eff_out, ValueType::Unit);
+            }
             let Some(hs) = program.host_sig(*host_sig) else {
                 for dst in rets {
                     set_ambiguous(state, *dst);
@@ -3363,7 +3691,7 @@ fn transfer_types(program: &Program, instr: &Instr, state: &mut TypeState) {
 }
 
 fn compute_must_types(
-    program: &Program,
+    program: &ProgramTables<'_>,
     blocks: &[BasicBlock],
     reachable: &[bool],
     reg_count: usize,
@@ -3473,7 +3801,7 @@ fn check_assignable(
 }
 
 fn validate_instr_types(
-    program: &Program,
+    program: &ProgramTables<'_>,
     func_id: u32,
     pc: u32,
     instr: &Instr,
@@ -3715,14 +4043,14 @@ fn validate_instr_types(
                 .functions
                 .get(callee.0 as usize)
                 .ok_or(VerifyError::CallArityMismatch { func: func_id, pc })?;
-            let callee_args = callee_fn.arg_types(program).map_err(|_| {
+            let callee_args = program.function_arg_types(callee_fn).map_err(|_| {
                 VerifyError::CallCalleeArgTypesOutOfBounds {
                     func: func_id,
                     pc,
                     callee: callee.0,
                 }
             })?;
-            let callee_rets = callee_fn.ret_types(program).map_err(|_| {
+            let callee_rets = program.function_ret_types(callee_fn).map_err(|_| {
                 VerifyError::CallCalleeRetTypesOutOfBounds {
                     func: func_id,
                     pc,
@@ -3752,6 +4080,11 @@ fn validate_instr_types(
             args,
             rets,
             ..
+        }
+        | Instr::HostCallPure {
+            host_sig,
+            args,
+            rets,
         } => {
             let hs = program
                 .host_sig(*host_sig)
@@ -4603,6 +4936,45 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn verifier_checks_host_call_pure_purity() {
+        let build = |pure: bool| {
+            let mut pb = ProgramBuilder::new();
+            let sig = crate::host::HostSig {
+                args: vec![ValueType::F64],
+                rets: vec![ValueType::F64],
+            };
+            let host_sig = if pure {
+                pb.pure_host_sig_for("f64.sqrt", sig)
+            } else {
+                pb.host_sig_for("f64.sqrt", sig)
+            };
+            let mut a = Asm::new();
+            a.host_call_pure(host_sig, &[1], &[2]);
+            a.ret(0, &[2]);
+            pb.push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::F64],
+                    ret_types: vec![ValueType::F64],
+                },
+            )
+            .unwrap();
+            pb.build_verified()
+        };
+
+        build(true).unwrap();
+        let err = build(false).unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Verify(VerifyError::HostCallNotPure {
+                func: 0,
+                host_sig: 0,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn verifier_rejects_duplicate_function_output_names() {
         let mut pb = ProgramBuilder::new();
@@ -4657,6 +5029,51 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn verifier_rejects_duplicate_value_names_held_by_distinct_symbols() {
+        let mut pb = ProgramBuilder::new();
+        let mut a = Asm::new();
+        a.ret(0, &[1, 2]);
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![ValueType::I64, ValueType::I64],
+                    ret_types: vec![ValueType::I64, ValueType::I64],
+                },
+            )
+            .unwrap();
+        pb.set_function_input_name(f, 0, "x").unwrap();
+        pb.set_function_input_name(f, 1, "y").unwrap();
+        pb.set_function_output_name(f, 0, "x").unwrap();
+        pb.set_function_output_name(f, 1, "y").unwrap();
+        let mut p = pb.build();
+        assert_eq!(verify_program(&p, &VerifyConfig::default()), Ok(()));
+
+        // Point the symbol for "y" at the bytes of "x": two symbol ids, one string.
+        let [x, y] = *p.function_arg_name_ids(&p.functions[0]).unwrap() else {
+            panic!("expected two input names");
+        };
+        assert_ne!(x, y);
+        p.symbols[y as usize].bytes = p.symbols[x as usize].bytes;
+        assert_eq!(p.function_input_name(0, 1), Some("x"));
+        assert_eq!(
+            verify_program(&p, &VerifyConfig::default()),
+            Err(VerifyError::FunctionInputNameDuplicate {
+                func: 0,
+                name: SymbolId(core::num::NonZeroU32::new(y).unwrap()),
+            })
+        );
+
+        // Outputs are checked the same way once the inputs are unnamed.
+        let start = p.functions[0].arg_name_ids.offset as usize;
+        p.value_name_ids[start..start + 2].fill(0);
+        assert!(matches!(
+            verify_program(&p, &VerifyConfig::default()),
+            Err(VerifyError::FunctionOutputNameDuplicate { func: 0, .. })
+        ));
+    }
+
     #[test]
     fn verifier_rejects_missing_terminator() {
         let p = Program::new(
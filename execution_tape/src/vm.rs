@@ -8,16 +8,19 @@
 //! The VM executes [`VerifiedProgram`]s only.
 
 use alloc::boxed::Box;
-use alloc::string::String;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use crate::aggregates::{AggError, AggHeap};
 use crate::arena::{BytesHandle, StrHandle, ValueArena};
 use crate::host::{AccessSink, Host, HostContext, HostError, ValueRef};
+use crate::opcode::Opcode;
 use crate::program::ValueType;
-use crate::program::{ConstEntry, Function, Program};
+use crate::program::{ConstEntry, Function, HostTypeId, Program, SpanId};
 use crate::trace::{ScopeKind, TraceMask, TraceOutcome, TraceSink};
 use crate::typed::{
     AggReg, BoolReg, BytesReg, ClosureReg, DecimalReg, ExecFunc, ExecInstr, F64Reg, FuncReg,
@@ -31,20 +34,142 @@ use crate::verifier::VerifiedProgram;
 pub struct Limits {
     /// Instruction budget. Each instruction costs 1 by default; host calls may charge extra.
     pub fuel: u64,
+    /// Optional fuel cost model replacing the flat cost of 1 per instruction.
+    ///
+    /// `None` (the default) keeps the flat model on a dedicated interpreter path with no
+    /// per-instruction cost lookups.
+    pub cost_model: Option<Arc<CostModel>>,
     /// Maximum call depth (frames).
     pub max_call_depth: usize,
     /// Maximum host calls.
     pub max_host_calls: u64,
+    /// Maximum number of live registers across all frames, summed over every register class.
+    pub max_regs_total: usize,
+    /// Maximum bytes allocated for byte strings and strings during a run.
+    pub max_arena_bytes: usize,
+    /// Maximum aggregates (tuples, structs, arrays) allocated during a run.
+    pub max_agg_nodes: usize,
+    /// Maximum aggregate element values allocated during a run, summed over all aggregates.
+    pub max_agg_values: usize,
+    /// Capture a full call-stack backtrace into [`TrapInfo::backtrace`] when a run traps.
+    ///
+    /// Off by default: capture walks the frame stack and resolves function names, which is only
+    /// worth paying for when the trap is going to be reported.
+    pub capture_backtrace: bool,
 }
 
 impl Default for Limits {
     fn default() -> Self {
         Self {
             fuel: 1_000_000,
+            cost_model: None,
             max_call_depth: 256,
             max_host_calls: 1_000_000,
+            max_regs_total: 1 << 22,
+            max_arena_bytes: 256 << 20,
+            max_agg_nodes: 1 << 22,
+            max_agg_values: 1 << 24,
+            capture_backtrace: false,
+        }
+    }
+}
+
+/// Fuel cost model: a per-opcode base cost plus scaling for data-proportional work.
+///
+/// Base costs are at least 1, so every executed instruction consumes fuel.
+///
+/// Costs are charged from [`Limits::fuel`]. Scaled costs are charged in addition to the base cost
+/// of the instruction that incurs them:
+/// - `per_byte` for every byte produced by bytes/string instructions (constants, concatenation,
+///   slicing and conversions),
+/// - `per_agg_value` for every element of a newly allocated tuple, struct or array,
+/// - `per_arg` for every argument passed by `call`, `call_indirect` and `host_call`.
+///
+/// [`CostModel::new`] matches the default flat model: every opcode costs 1 and nothing scales.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostModel {
+    base: [u64; 256],
+    /// Fuel per byte produced by bytes/string instructions.
+    pub per_byte: u64,
+    /// Fuel per element of a newly allocated aggregate.
+    pub per_agg_value: u64,
+    /// Fuel per argument passed to a call or host call.
+    pub per_arg: u64,
+}
+
+impl CostModel {
+    /// The flat model, used as a placeholder on the default interpreter path.
+    const FLAT: Self = Self::new();
+
+    /// Creates a model where every opcode costs 1 and no scaling is applied.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            base: [1; 256],
+            per_byte: 0,
+            per_agg_value: 0,
+            per_arg: 0,
         }
     }
+
+    /// Returns the base cost of `op`.
+    #[must_use]
+    pub fn base_cost(&self, op: Opcode) -> u64 {
+        self.base[usize::from(op.byte())]
+    }
+
+    /// Sets the base cost of `op`.
+    ///
+    /// Every instruction costs at least 1, so a `cost` of 0 is stored as 1. This keeps the fuel
+    /// limit a bound on the number of executed instructions.
+    pub fn set_base_cost(&mut self, op: Opcode, cost: u64) -> &mut Self {
+        self.base[usize::from(op.byte())] = cost.max(1);
+        self
+    }
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cooperative cancellation flag for a running [`Vm`].
+///
+/// Handles are cheap to clone and can be shared across threads. Once
+/// [`interrupt`](Self::interrupt) is called, the interpreter traps with [`Trap::Interrupted`] at
+/// the next poll point (run entry, backward jumps, and calls). The flag stays set until
+/// [`reset`](Self::reset) is called, so every subsequent run is interrupted as well.
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle {
+    flag: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    /// Creates a new, un-interrupted handle.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests that any run polling this handle stops.
+    #[inline]
+    pub fn interrupt(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Clears a pending interrupt request.
+    #[inline]
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if an interrupt has been requested.
+    #[inline]
+    #[must_use]
+    pub fn is_interrupted(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
 }
 
 /// A runtime trap.
@@ -56,6 +181,16 @@ pub enum Trap {
     CallDepthExceeded,
     /// Host call count limit exceeded.
     HostCallLimitExceeded,
+    /// The run was stopped through an [`InterruptHandle`].
+    Interrupted,
+    /// Register file limit ([`Limits::max_regs_total`]) exceeded.
+    RegLimitExceeded,
+    /// Bytes/string arena limit ([`Limits::max_arena_bytes`]) exceeded.
+    ArenaLimitExceeded,
+    /// Aggregate allocation limit ([`Limits::max_agg_nodes`]) exceeded.
+    AggNodeLimitExceeded,
+    /// Aggregate element limit ([`Limits::max_agg_values`]) exceeded.
+    AggValueLimitExceeded,
     /// Attempted to access an invalid `pc` / instruction boundary.
     InvalidPc,
     /// A register was out of bounds.
@@ -111,6 +246,12 @@ pub enum Trap {
     StrNotCharBoundary,
     /// Invalid UTF-8 (runtime conversion).
     InvalidUtf8,
+    /// A host object was encountered while serializing a value
+    /// (see [`value_codec`](crate::value_codec)).
+    SerializationEncounteredObj {
+        /// Host type of the object.
+        host_type: HostTypeId,
+    },
     /// Explicit trap instruction.
     TrapCode(u32),
 }
@@ -121,6 +262,11 @@ impl fmt::Display for Trap {
             Self::FuelExceeded => write!(f, "fuel limit exceeded"),
             Self::CallDepthExceeded => write!(f, "call depth limit exceeded"),
             Self::HostCallLimitExceeded => write!(f, "host call limit exceeded"),
+            Self::Interrupted => write!(f, "interrupted"),
+            Self::RegLimitExceeded => write!(f, "register limit exceeded"),
+            Self::ArenaLimitExceeded => write!(f, "arena byte limit exceeded"),
+            Self::AggNodeLimitExceeded => write!(f, "aggregate node limit exceeded"),
+            Self::AggValueLimitExceeded => write!(f, "aggregate value limit exceeded"),
             Self::InvalidPc => write!(f, "invalid pc"),
             Self::RegOutOfBounds => write!(f, "register out of bounds"),
             Self::ConstOutOfBounds => write!(f, "constant out of bounds"),
@@ -155,6 +301,13 @@ impl fmt::Display for Trap {
             Self::IndexOutOfBounds => write!(f, "index out of bounds"),
             Self::StrNotCharBoundary => write!(f, "string slice not on char boundary"),
             Self::InvalidUtf8 => write!(f, "invalid utf-8"),
+            Self::SerializationEncounteredObj { host_type } => {
+                write!(
+                    f,
+                    "serialization encountered host object (type {})",
+                    host_type.0
+                )
+            }
             Self::TrapCode(code) => write!(f, "trap({code})"),
         }
     }
@@ -171,23 +324,72 @@ pub struct TrapInfo {
     pub pc: u32,
     /// Best-effort span id for tracing/source mapping.
     pub span_id: Option<u64>,
+    /// Source location of `span_id` from the program's source map, if present.
+    pub source_location: Option<Box<str>>,
     /// Trap kind.
     pub trap: Trap,
+    /// Call stack at the time of the trap, innermost frame first.
+    ///
+    /// Only populated when [`Limits::capture_backtrace`] is enabled.
+    pub backtrace: Option<Vec<FrameInfo>>,
 }
 
 impl fmt::Display for TrapInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.span_id {
-            Some(span) => write!(
-                f,
-                "trap at f{} pc={} span={span}: {}",
-                self.func.0, self.pc, self.trap
-            ),
-            None => write!(f, "trap at f{} pc={}: {}", self.func.0, self.pc, self.trap),
+        write!(f, "trap at f{} pc={}", self.func.0, self.pc)?;
+        if let Some(span) = self.span_id {
+            write!(f, " span={span}")?;
+        }
+        if let Some(source) = &self.source_location {
+            write!(f, " ({source})")?;
+        }
+        write!(f, ": {}", self.trap)?;
+        if let Some(backtrace) = &self.backtrace {
+            for (i, frame) in backtrace.iter().enumerate() {
+                write!(f, "\n  #{i} {frame}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One frame of a captured call-stack backtrace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Function id.
+    pub func: FuncId,
+    /// Byte offset pc: the trapping instruction for the innermost frame, the call site otherwise.
+    pub pc: u32,
+    /// Best-effort span id for tracing/source mapping.
+    pub span_id: Option<u64>,
+    /// Function name from the program's name table, if present.
+    pub name: Option<Box<str>>,
+    /// Source location of `span_id` from the program's source map, if present.
+    pub source_location: Option<Box<str>>,
+}
+
+impl fmt::Display for FrameInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name} (f{}) pc={}", self.func.0, self.pc)?,
+            None => write!(f, "f{} pc={}", self.func.0, self.pc)?,
+        }
+        if let Some(span) = self.span_id {
+            write!(f, " span={span}")?;
         }
+        if let Some(source) = &self.source_location {
+            write!(f, " ({source})")?;
+        }
+        Ok(())
     }
 }
 
+/// Formats the source-map location of `span_id` in `program`, if it has one.
+fn span_source(program: &Program, span_id: Option<u64>) -> Option<Box<str>> {
+    let span = SpanId::try_from(span_id?).ok()?;
+    Some(program.span_info(span)?.to_string().into_boxed_str())
+}
+
 impl core::error::Error for TrapInfo {
     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         Some(&self.trap)
@@ -261,6 +463,14 @@ pub struct ExecutionContext {
     fuel: u64,
     /// Number of host calls performed so far in the current run.
     host_calls: u64,
+    /// Maximum live registers for the current run.
+    max_regs_total: usize,
+    /// Remaining arena byte budget for the current run.
+    arena_bytes_left: usize,
+    /// Remaining aggregate node budget for the current run.
+    agg_nodes_left: usize,
+    /// Remaining aggregate element budget for the current run.
+    agg_values_left: usize,
 
     /// Arena backing bytes/strings and other out-of-line temporaries.
     arena: ValueArena,
@@ -305,7 +515,13 @@ impl ExecutionContext {
         Self::default()
     }
 
-    fn reset(&mut self, fuel: u64) {
+    /// Returns the fuel left over by the most recent run using this context.
+    #[must_use]
+    pub fn fuel_remaining(&self) -> u64 {
+        self.fuel
+    }
+
+    fn reset(&mut self, limits: &Limits) {
         self.frames.clear();
         self.units.clear();
         self.bools.clear();
@@ -321,8 +537,12 @@ impl ExecutionContext {
         self.closures.clear();
         self.arena.clear();
 
-        self.fuel = fuel;
+        self.fuel = limits.fuel;
         self.host_calls = 0;
+        self.max_regs_total = limits.max_regs_total;
+        self.arena_bytes_left = limits.max_arena_bytes;
+        self.agg_nodes_left = limits.max_agg_nodes;
+        self.agg_values_left = limits.max_agg_values;
     }
 }
 
@@ -330,6 +550,7 @@ impl ExecutionContext {
 pub struct Vm<H: Host> {
     host: H,
     limits: Limits,
+    interrupt: InterruptHandle,
 
     /// Aggregate heap storage. This is VM-owned so embedders can inspect aggregates after a run.
     agg: AggHeap,
@@ -706,6 +927,36 @@ impl TracePolicy for WithTracePolicy {
     }
 }
 
+/// Compile-time selection between the flat fuel model and a [`CostModel`].
+///
+/// Like [`TracePolicy`], this keeps the default path free of per-instruction cost lookups.
+trait CostPolicy {
+    /// Whether instruction costs come from a [`CostModel`] rather than the flat 1-per-instruction.
+    const MODELED: bool;
+
+    /// Charges `per_unit * units` fuel for data-proportional work.
+    #[inline(always)]
+    fn charge(ctx: &mut ExecutionContext, per_unit: u64, units: usize) -> Result<(), Trap> {
+        if !Self::MODELED || per_unit == 0 {
+            return Ok(());
+        }
+        let units = u64::try_from(units).unwrap_or(u64::MAX);
+        ctx.charge_fuel(per_unit.saturating_mul(units))
+    }
+}
+
+struct FlatCost;
+
+impl CostPolicy for FlatCost {
+    const MODELED: bool = false;
+}
+
+struct ModelCost;
+
+impl CostPolicy for ModelCost {
+    const MODELED: bool = true;
+}
+
 impl<H: Host> Vm<H> {
     /// Creates a new VM with `host` and `limits`.
     #[must_use]
@@ -713,10 +964,33 @@ impl<H: Host> Vm<H> {
         Self {
             host,
             limits,
+            interrupt: InterruptHandle::new(),
             agg: AggHeap::new(),
         }
     }
 
+    /// Returns the host.
+    #[must_use]
+    pub fn host(&self) -> &H {
+        &self.host
+    }
+
+    /// Returns the host mutably, e.g. to collect state a host gathered during a run.
+    pub fn host_mut(&mut self) -> &mut H {
+        &mut self.host
+    }
+
+    /// Returns a handle that can interrupt runs of this VM, possibly from another thread.
+    #[must_use]
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Replaces the handle polled by this VM, e.g. to share one handle across several VMs.
+    pub fn set_interrupt_handle(&mut self, handle: InterruptHandle) {
+        self.interrupt = handle;
+    }
+
     /// Executes `program` starting at `entry` with `args` as value arguments.
     ///
     /// Convention:
@@ -752,6 +1026,34 @@ impl<H: Host> Vm<H> {
         trace: Option<&mut dyn TraceSink>,
         access: Option<&mut dyn AccessSink>,
     ) -> Result<Vec<Value>, TrapInfo> {
+        let ret_count = program
+            .program()
+            .functions
+            .get(entry.0 as usize)
+            .map_or(0, |f| f.ret_count as usize);
+        let mut rets = vec![Value::Unit; ret_count];
+        self.run_into(
+            ctx, program, entry, args, &mut rets, trace_mask, trace, access,
+        )?;
+        Ok(rets)
+    }
+
+    /// Like [`Vm::run_with_ctx`], but writes the entry function's return values into `rets`
+    /// instead of allocating them.
+    ///
+    /// `rets.len()` must equal the entry function's return count; otherwise the final return
+    /// traps with [`Trap::InvalidPc`].
+    pub(crate) fn run_into(
+        &mut self,
+        ctx: &mut ExecutionContext,
+        program: &VerifiedProgram,
+        entry: FuncId,
+        args: &[Value],
+        rets: &mut [Value],
+        trace_mask: TraceMask,
+        trace: Option<&mut dyn TraceSink>,
+        access: Option<&mut dyn AccessSink>,
+    ) -> Result<(), TrapInfo> {
         let program_ref = program.program();
         let mut trace = TraceCtx::new(trace_mask, trace);
         trace.run_start(program_ref, entry, args.len());
@@ -762,12 +1064,44 @@ impl<H: Host> Vm<H> {
             || trace.enabled(TraceMask::CALL)
             || trace.enabled(TraceMask::HOST);
 
-        let result = if trace_active {
-            self.run_body::<WithTracePolicy>(ctx, program, entry, args, &mut trace, access)
-        } else {
-            self.run_body::<NoTracePolicy>(ctx, program, entry, args, &mut trace, access)
+        let cost_model = self.limits.cost_model.clone();
+        let result = match (trace_active, cost_model.as_deref()) {
+            (true, None) => self.run_body::<WithTracePolicy, FlatCost>(
+                ctx,
+                program,
+                entry,
+                args,
+                rets,
+                &mut trace,
+                access,
+                &CostModel::FLAT,
+            ),
+            (false, None) => self.run_body::<NoTracePolicy, FlatCost>(
+                ctx,
+                program,
+                entry,
+                args,
+                rets,
+                &mut trace,
+                access,
+                &CostModel::FLAT,
+            ),
+            (true, Some(model)) => self.run_body::<WithTracePolicy, ModelCost>(
+                ctx, program, entry, args, rets, &mut trace, access, model,
+            ),
+            (false, Some(model)) => self.run_body::<NoTracePolicy, ModelCost>(
+                ctx, program, entry, args, rets, &mut trace, access, model,
+            ),
         };
 
+        let result = result.map_err(|mut info| {
+            info.source_location = span_source(program_ref, info.span_id);
+            if self.limits.capture_backtrace {
+                info.backtrace = Some(ctx.backtrace(program, &info));
+            }
+            info
+        });
+
         let outcome = match &result {
             Ok(_) => TraceOutcome::Ok,
             Err(e) => TraceOutcome::Trap(e),
@@ -777,19 +1111,26 @@ impl<H: Host> Vm<H> {
         result
     }
 
-    fn run_body<P: TracePolicy>(
+    fn run_body<P: TracePolicy, C: CostPolicy>(
         &mut self,
         ctx: &mut ExecutionContext,
         program: &VerifiedProgram,
         entry: FuncId,
         args: &[Value],
+        out: &mut [Value],
         trace: &mut TraceCtx<'_>,
         mut access: Option<&mut dyn AccessSink>,
-    ) -> Result<Vec<Value>, TrapInfo> {
+        cost_model: &CostModel,
+    ) -> Result<(), TrapInfo> {
         let program_ref = program.program();
         let max_call_depth = self.limits.max_call_depth;
         let max_host_calls = self.limits.max_host_calls;
-        ctx.reset(self.limits.fuel);
+        let interrupt = self.interrupt.clone();
+        ctx.reset(&self.limits);
+
+        if interrupt.is_interrupted() {
+            return Err(ctx.trap(entry, 0, None, Trap::Interrupted));
+        }
 
         let entry_fn = program_ref
             .functions
@@ -804,7 +1145,9 @@ impl<H: Host> Vm<H> {
         let entry_vf = program
             .verified(entry)
             .ok_or_else(|| ctx.trap(entry, 0, None, Trap::InvalidPc))?;
-        let entry_base = ctx.alloc_frame(entry_vf);
+        let entry_base = ctx
+            .alloc_frame(entry_vf)
+            .map_err(|t| ctx.trap(entry, 0, None, t))?;
         ctx.init_args(entry_base, entry_vf, args)
             .map_err(|t| ctx.trap(entry, 0, None, t))?;
         ctx.frames.push(Frame {
@@ -826,15 +1169,17 @@ impl<H: Host> Vm<H> {
         );
 
         loop {
-            if ctx.fuel == 0 {
-                return Err(ctx.trap(
-                    ctx.cur_func(),
-                    ctx.cur_pc(),
-                    ctx.cur_span(program_ref),
-                    Trap::FuelExceeded,
-                ));
+            if !C::MODELED {
+                if ctx.fuel == 0 {
+                    return Err(ctx.trap(
+                        ctx.cur_func(),
+                        ctx.cur_pc(),
+                        ctx.cur_span(program_ref),
+                        Trap::FuelExceeded,
+                    ));
+                }
+                ctx.fuel -= 1;
             }
-            ctx.fuel -= 1;
 
             let frame_index = ctx
                 .frames
@@ -865,6 +1210,11 @@ impl<H: Host> Vm<H> {
                 "frame pc must match verified instruction offset"
             );
             let next_instr_ix = instr_ix.saturating_add(1);
+            if C::MODELED {
+                let cost = cost_model.base[usize::from(opcode)];
+                ctx.charge_fuel(cost)
+                    .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+            }
 
             P::instr(trace, program_ref, func_id, pc, next_pc, span_id, opcode);
 
@@ -1087,9 +1437,12 @@ impl<H: Host> Vm<H> {
                     let bytes = program_ref
                         .const_bytes_data
                         .get(start..end)
-                        .ok_or_else(|| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?
-                        .to_vec();
-                    let h = ctx.arena.alloc_bytes(bytes);
+                        .ok_or_else(|| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?;
+                    C::charge(ctx, cost_model.per_byte, bytes.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    let h = ctx
+                        .alloc_bytes_from_slice(bytes)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     ctx.write_bytes_handle(base, *dst, h);
                     ctx.frames[frame_index].pc = next_pc;
                 }
@@ -1110,7 +1463,11 @@ impl<H: Host> Vm<H> {
                         .const_str_data
                         .get(start..end)
                         .ok_or_else(|| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?;
-                    let h = ctx.arena.alloc_str_from_str(s);
+                    C::charge(ctx, cost_model.per_byte, s.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    let h = ctx
+                        .alloc_str_from_str(s)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     ctx.write_str_handle(base, *dst, h);
                     ctx.frames[frame_index].pc = next_pc;
                 }
@@ -1548,6 +1905,9 @@ impl<H: Host> Vm<H> {
                     };
                     let target_ix = usize::try_from(target_ix_u32)
                         .map_err(|_| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?;
+                    if target_ix <= instr_ix && interrupt.is_interrupted() {
+                        return Err(ctx.trap(func_id, pc, span_id, Trap::Interrupted));
+                    }
                     let target_pc = vf
                         .pc_at_ix(target_ix)
                         .ok_or_else(|| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?;
@@ -1557,6 +1917,9 @@ impl<H: Host> Vm<H> {
                 ExecInstr::Jmp { target_ix } => {
                     let target_ix = usize::try_from(*target_ix)
                         .map_err(|_| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?;
+                    if target_ix <= instr_ix && interrupt.is_interrupted() {
+                        return Err(ctx.trap(func_id, pc, span_id, Trap::Interrupted));
+                    }
                     let target_pc = vf
                         .pc_at_ix(target_ix)
                         .ok_or_else(|| ctx.trap(func_id, pc, span_id, Trap::InvalidPc))?;
@@ -1571,6 +1934,9 @@ impl<H: Host> Vm<H> {
                     args,
                     rets: dst_rets,
                 } => {
+                    if interrupt.is_interrupted() {
+                        return Err(ctx.trap(func_id, pc, span_id, Trap::Interrupted));
+                    }
                     if ctx.frames.len() >= max_call_depth {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::CallDepthExceeded));
                     }
@@ -1586,8 +1952,12 @@ impl<H: Host> Vm<H> {
                     // v1: effect token is `Unit` (stored as 0).
                     ctx.write_unit(base, *eff_out, 0);
 
-                    let callee_base = ctx.alloc_frame(callee_vf);
+                    let callee_base = ctx
+                        .alloc_frame(callee_vf)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let args = vf.vregs(*args);
+                    C::charge(ctx, cost_model.per_arg, args.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     if args.len() != callee_vf.reg_layout.arg_regs.len() {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::InvalidPc));
                     }
@@ -1631,14 +2001,15 @@ impl<H: Host> Vm<H> {
 
                     if ctx.frames.len() == 1 {
                         let rets = vf.vregs(*rets);
-                        let mut out: Vec<Value> = Vec::with_capacity(rets.len());
-                        for &r in rets {
-                            out.push(
-                                ctx.materialize_vreg(base, r)
-                                    .map_err(|t| ctx.trap(func_id, pc, span_id, t))?,
-                            );
+                        if rets.len() != out.len() {
+                            return Err(ctx.trap(func_id, pc, span_id, Trap::InvalidPc));
+                        }
+                        for (slot, &r) in out.iter_mut().zip(rets) {
+                            *slot = ctx
+                                .materialize_vreg(base, r)
+                                .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                         }
-                        return Ok(out);
+                        return Ok(());
                     }
 
                     let finished = ctx
@@ -1698,6 +2069,8 @@ impl<H: Host> Vm<H> {
                         .map_err(|_| ctx.trap(func_id, pc, span_id, Trap::ConstOutOfBounds))?;
 
                     let args = vf.vregs(*args);
+                    C::charge(ctx, cost_model.per_arg, args.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
 
                     // Stack buffer for the common case (0–8 args); Vec fallback for the rest.
                     // 8 × 24 bytes = 192 bytes on the stack — every host call in the codebase
@@ -1769,7 +2142,7 @@ impl<H: Host> Vm<H> {
                     let host_ctx = HostContext::new(program_ref, &self.agg, access_for_call);
                     let extra_fuel = self
                         .host
-                        .call(sym, hs.sig_hash, call_args, ret_slots, host_ctx)
+                        .call_with_id(*host_sig, sym, hs.sig_hash, call_args, ret_slots, host_ctx)
                         .map_err(|error| {
                             ctx.trap(
                                 func_id,
@@ -1829,6 +2202,9 @@ impl<H: Host> Vm<H> {
                     args,
                     rets: dst_rets,
                 } => {
+                    if interrupt.is_interrupted() {
+                        return Err(ctx.trap(func_id, pc, span_id, Trap::Interrupted));
+                    }
                     if ctx.frames.len() >= max_call_depth {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::CallDepthExceeded));
                     }
@@ -1854,8 +2230,12 @@ impl<H: Host> Vm<H> {
                     // v1: effect token is `Unit` (stored as 0).
                     ctx.write_unit(base, *eff_out, 0);
 
-                    let callee_base = ctx.alloc_frame(callee_vf);
+                    let callee_base = ctx
+                        .alloc_frame(callee_vf)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let args = vf.vregs(*args);
+                    C::charge(ctx, cost_model.per_arg, args.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     if args.len() != callee_vf.reg_layout.arg_regs.len() {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::InvalidPc));
                     }
@@ -1901,6 +2281,9 @@ impl<H: Host> Vm<H> {
                     args,
                     rets: dst_rets,
                 } => {
+                    if interrupt.is_interrupted() {
+                        return Err(ctx.trap(func_id, pc, span_id, Trap::Interrupted));
+                    }
                     if ctx.frames.len() >= max_call_depth {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::CallDepthExceeded));
                     }
@@ -1926,8 +2309,12 @@ impl<H: Host> Vm<H> {
                     // v1: effect token is `Unit` (stored as 0).
                     ctx.write_unit(base, *eff_out, 0);
 
-                    let callee_base = ctx.alloc_frame(callee_vf);
+                    let callee_base = ctx
+                        .alloc_frame(callee_vf)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let args = vf.vregs(*args);
+                    C::charge(ctx, cost_model.per_arg, args.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let Some((&env_dst, user_arg_dsts)) =
                         callee_vf.reg_layout.arg_regs.split_first()
                     else {
@@ -1982,6 +2369,10 @@ impl<H: Host> Vm<H> {
 
                 ExecInstr::TupleNew { dst, values } => {
                     let values = vf.vregs(*values);
+                    ctx.charge_agg(values.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    C::charge(ctx, cost_model.per_agg_value, values.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let mut vals = Vec::with_capacity(values.len());
                     for &r in values {
                         vals.push(
@@ -2023,6 +2414,10 @@ impl<H: Host> Vm<H> {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::ArityMismatch));
                     }
 
+                    ctx.charge_agg(values.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    C::charge(ctx, cost_model.per_agg_value, values.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let mut vals = Vec::with_capacity(values.len());
                     for &r in values {
                         vals.push(
@@ -2068,6 +2463,10 @@ impl<H: Host> Vm<H> {
                         return Err(ctx.trap(func_id, pc, span_id, Trap::ArityMismatch));
                     }
 
+                    ctx.charge_agg(values.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    C::charge(ctx, cost_model.per_agg_value, values.len())
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let mut vals = Vec::with_capacity(values.len());
                     for &r in values {
                         vals.push(
@@ -2317,6 +2716,20 @@ impl<H: Host> Vm<H> {
                     ctx.frames[frame_index].pc = next_pc;
                 }
                 ExecInstr::BytesConcat { dst, a, b } => {
+                    // Charge fuel and the arena budget before allocating the result.
+                    let len = {
+                        let a = ctx
+                            .read_bytes(a, base)
+                            .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                        let b = ctx
+                            .read_bytes(b, base)
+                            .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                        a.len().saturating_add(b.len())
+                    };
+                    C::charge(ctx, cost_model.per_byte, len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    ctx.charge_arena_bytes(len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let out = {
                         let a = ctx
                             .read_bytes(a, base)
@@ -2324,7 +2737,7 @@ impl<H: Host> Vm<H> {
                         let b = ctx
                             .read_bytes(b, base)
                             .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
-                        let mut out = Vec::with_capacity(a.len() + b.len());
+                        let mut out = Vec::with_capacity(len);
                         out.extend_from_slice(a);
                         out.extend_from_slice(b);
                         out
@@ -2334,6 +2747,19 @@ impl<H: Host> Vm<H> {
                     ctx.frames[frame_index].pc = next_pc;
                 }
                 ExecInstr::StrConcat { dst, a, b } => {
+                    let len = {
+                        let a = ctx
+                            .read_str(a, base)
+                            .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                        let b = ctx
+                            .read_str(b, base)
+                            .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                        a.len().saturating_add(b.len())
+                    };
+                    C::charge(ctx, cost_model.per_byte, len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    ctx.charge_arena_bytes(len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                     let out = {
                         let a = ctx
                             .read_str(a, base)
@@ -2341,7 +2767,7 @@ impl<H: Host> Vm<H> {
                         let b = ctx
                             .read_str(b, base)
                             .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
-                        let mut out = String::with_capacity(a.len() + b.len());
+                        let mut out = String::with_capacity(len);
                         out.push_str(a);
                         out.push_str(b);
                         out
@@ -2378,24 +2804,32 @@ impl<H: Host> Vm<H> {
                     start,
                     end,
                 } => {
-                    let out = {
+                    let (start, end) = {
                         let bytes = ctx
                             .read_bytes(bytes, base)
                             .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
                         let start =
                             usize::try_from(ctx.read_u64(base, *start)).unwrap_or(usize::MAX);
                         let end = usize::try_from(ctx.read_u64(base, *end)).unwrap_or(usize::MAX);
-                        bytes
-                            .get(start..end)
-                            .ok_or_else(|| ctx.trap(func_id, pc, span_id, Trap::IndexOutOfBounds))?
-                            .to_vec()
+                        if start > end || end > bytes.len() {
+                            return Err(ctx.trap(func_id, pc, span_id, Trap::IndexOutOfBounds));
+                        }
+                        (start, end)
                     };
+                    C::charge(ctx, cost_model.per_byte, end - start)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    ctx.charge_arena_bytes(end - start)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    let out = ctx
+                        .read_bytes(bytes, base)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?[start..end]
+                        .to_vec();
                     let h = ctx.arena.alloc_bytes(out);
                     ctx.write_bytes_handle(base, *dst, h);
                     ctx.frames[frame_index].pc = next_pc;
                 }
                 ExecInstr::StrSlice { dst, s, start, end } => {
-                    let out = {
+                    let (start, end) = {
                         let s = ctx
                             .read_str(s, base)
                             .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
@@ -2408,31 +2842,58 @@ impl<H: Host> Vm<H> {
                         if !s.is_char_boundary(start) || !s.is_char_boundary(end) {
                             return Err(ctx.trap(func_id, pc, span_id, Trap::StrNotCharBoundary));
                         }
-                        String::from(&s[start..end])
+                        (start, end)
                     };
+                    C::charge(ctx, cost_model.per_byte, end - start)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    ctx.charge_arena_bytes(end - start)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    let out = String::from(
+                        &ctx.read_str(s, base)
+                            .map_err(|t| ctx.trap(func_id, pc, span_id, t))?
+                            [start..end],
+                    );
                     let h = ctx.arena.alloc_str(out);
                     ctx.write_str_handle(base, *dst, h);
                     ctx.frames[frame_index].pc = next_pc;
                 }
                 ExecInstr::StrToBytes { dst, s } => {
-                    let out = {
-                        let s = ctx
-                            .read_str(s, base)
-                            .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
-                        s.as_bytes().to_vec()
-                    };
+                    let len = ctx
+                        .read_str(s, base)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?
+                        .len();
+                    C::charge(ctx, cost_model.per_byte, len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    ctx.charge_arena_bytes(len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    let out = ctx
+                        .read_str(s, base)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?
+                        .as_bytes()
+                        .to_vec();
                     let h = ctx.arena.alloc_bytes(out);
                     ctx.write_bytes_handle(base, *dst, h);
                     ctx.frames[frame_index].pc = next_pc;
                 }
                 ExecInstr::BytesToStr { dst, bytes } => {
-                    let out = {
+                    let len = {
                         let bytes = ctx
                             .read_bytes(bytes, base)
                             .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
-                        String::from_utf8(bytes.to_vec())
+                        core::str::from_utf8(bytes)
                             .map_err(|_| ctx.trap(func_id, pc, span_id, Trap::InvalidUtf8))?
+                            .len()
                     };
+                    C::charge(ctx, cost_model.per_byte, len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    ctx.charge_arena_bytes(len)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?;
+                    let bytes = ctx
+                        .read_bytes(bytes, base)
+                        .map_err(|t| ctx.trap(func_id, pc, span_id, t))?
+                        .to_vec();
+                    let out = String::from_utf8(bytes)
+                        .map_err(|_| ctx.trap(func_id, pc, span_id, Trap::InvalidUtf8))?;
                     let h = ctx.arena.alloc_str(out);
                     ctx.write_str_handle(base, *dst, h);
                     ctx.frames[frame_index].pc = next_pc;
@@ -2453,7 +2914,7 @@ impl<H: Host> Vm<H> {
 }
 
 impl ExecutionContext {
-    fn alloc_frame(&mut self, vf: &ExecFunc) -> RegBase {
+    fn alloc_frame(&mut self, vf: &ExecFunc) -> Result<RegBase, Trap> {
         let counts = vf.reg_layout.counts;
         let base = RegBase {
             unit: self.units.len(),
@@ -2470,6 +2931,26 @@ impl ExecutionContext {
             closures: self.closures.len(),
         };
 
+        let live = [
+            base.unit + counts.unit,
+            base.bools + counts.bools,
+            base.i64s + counts.i64s,
+            base.u64s + counts.u64s,
+            base.f64s + counts.f64s,
+            base.decimals + counts.decimals,
+            base.bytes + counts.bytes,
+            base.strs + counts.strs,
+            base.objs + counts.objs,
+            base.aggs + counts.aggs,
+            base.funcs + counts.funcs,
+            base.closures + counts.closures,
+        ]
+        .into_iter()
+        .fold(0_usize, usize::saturating_add);
+        if live > self.max_regs_total {
+            return Err(Trap::RegLimitExceeded);
+        }
+
         self.units.resize(base.unit + counts.unit, 0);
         self.bools.resize(base.bools + counts.bools, false);
         self.i64s.resize(base.i64s + counts.i64s, 0);
@@ -2487,7 +2968,7 @@ impl ExecutionContext {
         self.objs.resize(
             base.objs + counts.objs,
             Obj {
-                host_type: crate::program::HostTypeId(0),
+                host_type: HostTypeId(0),
                 handle: ObjHandle(0),
             },
         );
@@ -2501,7 +2982,52 @@ impl ExecutionContext {
             },
         );
 
-        base
+        Ok(base)
+    }
+
+    #[inline]
+    fn charge_fuel(&mut self, cost: u64) -> Result<(), Trap> {
+        self.fuel = self.fuel.checked_sub(cost).ok_or(Trap::FuelExceeded)?;
+        Ok(())
+    }
+
+    /// Charges `len` bytes against the arena budget.
+    ///
+    /// Instructions that build a result call this before allocating it, so an over-budget result
+    /// is never allocated.
+    #[inline]
+    fn charge_arena_bytes(&mut self, len: usize) -> Result<(), Trap> {
+        self.arena_bytes_left = self
+            .arena_bytes_left
+            .checked_sub(len)
+            .ok_or(Trap::ArenaLimitExceeded)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn alloc_bytes_from_slice(&mut self, bytes: &[u8]) -> Result<BytesHandle, Trap> {
+        self.charge_arena_bytes(bytes.len())?;
+        Ok(self.arena.alloc_bytes_from_slice(bytes))
+    }
+
+    #[inline]
+    fn alloc_str_from_str(&mut self, s: &str) -> Result<StrHandle, Trap> {
+        self.charge_arena_bytes(s.len())?;
+        Ok(self.arena.alloc_str_from_str(s))
+    }
+
+    /// Charges one aggregate node holding `values` elements against the run's budgets.
+    #[inline]
+    fn charge_agg(&mut self, values: usize) -> Result<(), Trap> {
+        self.agg_nodes_left = self
+            .agg_nodes_left
+            .checked_sub(1)
+            .ok_or(Trap::AggNodeLimitExceeded)?;
+        self.agg_values_left = self
+            .agg_values_left
+            .checked_sub(values)
+            .ok_or(Trap::AggValueLimitExceeded)?;
+        Ok(())
     }
 
     fn truncate_to(&mut self, base: RegBase) {
@@ -2556,12 +3082,12 @@ impl ExecutionContext {
                 Ok(())
             }
             (VReg::Bytes(r), Value::Bytes(b)) => {
-                let h = self.arena.alloc_bytes_from_slice(b);
+                let h = self.alloc_bytes_from_slice(b)?;
                 self.write_bytes_handle(base, r, h);
                 Ok(())
             }
             (VReg::Str(r), Value::Str(s)) => {
-                let h = self.arena.alloc_str_from_str(s.as_str());
+                let h = self.alloc_str_from_str(s.as_str())?;
                 self.write_str_handle(base, r, h);
                 Ok(())
             }
@@ -2857,8 +3383,45 @@ impl ExecutionContext {
             func,
             pc,
             span_id,
+            source_location: None,
             trap,
+            backtrace: None,
+        }
+    }
+
+    /// Captures the call stack for `info`, innermost frame first.
+    ///
+    /// The innermost frame is taken from `info` itself. Caller frames have already advanced past
+    /// their `call`, so their pc is recovered from the previous instruction index.
+    fn backtrace(&self, program: &VerifiedProgram, info: &TrapInfo) -> Vec<FrameInfo> {
+        let program_ref = program.program();
+        let frame_info = |func: FuncId, pc: u32, span_id: Option<u64>| FrameInfo {
+            func,
+            pc,
+            span_id,
+            name: program_ref.function_name(func.0).map(Box::from),
+            source_location: span_source(program_ref, span_id),
+        };
+
+        let mut out = Vec::with_capacity(self.frames.len().max(1));
+        out.push(frame_info(info.func, info.pc, info.span_id));
+
+        let callers = match self.frames.split_last() {
+            Some((top, rest)) if top.func == info.func => rest,
+            _ => &self.frames[..],
+        };
+        for frame in callers.iter().rev() {
+            let call_ix = frame.instr_ix.saturating_sub(1);
+            let (pc, span_id) = match program.verified(frame.func) {
+                Some(vf) => (
+                    vf.pc_at_ix(call_ix).unwrap_or(frame.pc),
+                    vf.span_at_ix(call_ix).map(|id| id.get()),
+                ),
+                None => (frame.pc, None),
+            };
+            out.push(frame_info(frame.func, pc, span_id));
         }
+        out
     }
 }
 
@@ -3465,6 +4028,295 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vm_interrupt_stops_loop_at_backward_jump() {
+        struct StopHost(InterruptHandle);
+
+        impl Host for StopHost {
+            fn call(
+                &mut self,
+                _symbol: &str,
+                _sig_hash: SigHash,
+                _args: &[ValueRef<'_>],
+                _rets: &mut [Value],
+                _ctx: HostContext<'_, '_>,
+            ) -> Result<u64, HostError> {
+                self.0.interrupt();
+                Ok(0)
+            }
+        }
+
+        let mut pb = ProgramBuilder::new();
+        let host_sig = pb.host_sig_for(
+            "stop",
+            HostSig {
+                args: vec![],
+                rets: vec![],
+            },
+        );
+        let mut a = Asm::new();
+        let l_loop = a.label();
+        a.host_call(0, host_sig, 0, &[], &[]);
+        a.jmp(l_loop);
+        a.place(l_loop).unwrap();
+        a.jmp(l_loop);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![],
+                ret_types: vec![],
+            },
+        )
+        .unwrap();
+        let p = pb.build_verified().unwrap();
+
+        let handle = InterruptHandle::new();
+        let mut vm = Vm::new(StopHost(handle.clone()), Limits::default());
+        vm.set_interrupt_handle(handle.clone());
+        let err = vm
+            .run(&p, FuncId(0), &[], TraceMask::NONE, None)
+            .unwrap_err();
+        assert_eq!(err.trap, Trap::Interrupted);
+        assert_ne!(err.pc, 0, "trap should be raised at the loop, not at entry");
+
+        // The flag is sticky until reset: the next run stops at entry.
+        let err = vm
+            .run(&p, FuncId(0), &[], TraceMask::NONE, None)
+            .unwrap_err();
+        assert_eq!((err.trap, err.pc), (Trap::Interrupted, 0));
+
+        handle.reset();
+        let mut vm = Vm::new(
+            StopHost(InterruptHandle::new()),
+            Limits {
+                fuel: 100,
+                ..Limits::default()
+            },
+        );
+        let err = vm
+            .run(&p, FuncId(0), &[], TraceMask::NONE, None)
+            .unwrap_err();
+        assert_eq!(err.trap, Trap::FuelExceeded);
+    }
+
+    #[test]
+    fn vm_memory_limits_trap_at_allocation_points() {
+        let run = |p: &VerifiedProgram, limits: Limits| {
+            Vm::new(TestHost, limits).run(p, FuncId(0), &[], TraceMask::NONE, None)
+        };
+
+        // Arena bytes: 4 const bytes + 8 concatenated bytes.
+        let mut pb = ProgramBuilder::new();
+        let c = pb.const_str("abcd");
+        let mut a = Asm::new();
+        a.const_pool(1, c);
+        a.str_concat(2, 1, 1);
+        a.ret(0, &[2]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![],
+                ret_types: vec![ValueType::Str],
+            },
+        )
+        .unwrap();
+        let p = pb.build_verified().unwrap();
+        let out = run(
+            &p,
+            Limits {
+                max_arena_bytes: 12,
+                ..Limits::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(out, vec![Value::Str("abcdabcd".into())]);
+        let err = run(
+            &p,
+            Limits {
+                max_arena_bytes: 11,
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.trap, Trap::ArenaLimitExceeded);
+
+        // An over-budget result is rejected before it is allocated: only the constant remains.
+        let mut ctx = ExecutionContext::new();
+        let err = Vm::new(
+            TestHost,
+            Limits {
+                max_arena_bytes: 11,
+                ..Limits::default()
+            },
+        )
+        .run_with_ctx(&mut ctx, &p, FuncId(0), &[], TraceMask::NONE, None, None)
+        .unwrap_err();
+        assert_eq!(err.trap, Trap::ArenaLimitExceeded);
+        assert_eq!(ctx.arena.len(), 1);
+
+        let mut model = CostModel::new();
+        model.per_byte = 100;
+        let mut ctx = ExecutionContext::new();
+        let err = Vm::new(
+            TestHost,
+            Limits {
+                fuel: 500,
+                cost_model: Some(Arc::new(model)),
+                ..Limits::default()
+            },
+        )
+        .run_with_ctx(&mut ctx, &p, FuncId(0), &[], TraceMask::NONE, None, None)
+        .unwrap_err();
+        assert_eq!(err.trap, Trap::FuelExceeded);
+        assert_eq!(ctx.arena.len(), 1);
+
+        // Aggregates: two tuples of two values each.
+        let mut pb = ProgramBuilder::new();
+        let mut a = Asm::new();
+        a.const_i64(1, 1);
+        a.tuple_new(2, &[1, 1]);
+        a.tuple_new(3, &[1, 1]);
+        a.ret(0, &[3]);
+        pb.push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![],
+                ret_types: vec![ValueType::Agg],
+            },
+        )
+        .unwrap();
+        let p = pb.build_verified().unwrap();
+        let err = run(
+            &p,
+            Limits {
+                max_agg_nodes: 1,
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.trap, Trap::AggNodeLimitExceeded);
+        let err = run(
+            &p,
+            Limits {
+                max_agg_values: 3,
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.trap, Trap::AggValueLimitExceeded);
+        assert!(
+            run(
+                &p,
+                Limits {
+                    max_agg_nodes: 2,
+                    max_agg_values: 4,
+                    ..Limits::default()
+                },
+            )
+            .is_ok(),
+            "exact budgets should be enough"
+        );
+
+        // Registers: the entry frame alone exceeds a one-register budget.
+        let err = run(
+            &p,
+            Limits {
+                max_regs_total: 1,
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!((err.trap, err.pc), (Trap::RegLimitExceeded, 0));
+    }
+
+    #[test]
+    fn vm_cost_model_fuel_accounting_is_deterministic() {
+        let mut pb = ProgramBuilder::new();
+        let c = pb.const_str("abcd");
+        let sig = FunctionSig {
+            arg_types: vec![ValueType::I64],
+            ret_types: vec![ValueType::I64],
+        };
+        let f_main = pb.declare_function(FunctionSig {
+            arg_types: vec![],
+            ret_types: vec![ValueType::I64],
+        });
+        let f_id = pb.declare_function(sig);
+
+        let mut a = Asm::new();
+        a.const_pool(1, c);
+        a.str_concat(2, 1, 1);
+        a.const_i64(3, 5);
+        a.tuple_new(4, &[3, 3]);
+        a.call(0, f_id, 0, &[3], &[5]);
+        a.ret(0, &[5]);
+        pb.define_function(f_main, a).unwrap();
+
+        let mut a = Asm::new();
+        a.ret(0, &[1]);
+        pb.define_function(f_id, a).unwrap();
+        let p = pb.build_verified().unwrap();
+
+        let mut model = CostModel::new();
+        model.set_base_cost(Opcode::Nop, 0);
+        assert_eq!(model.base_cost(Opcode::Nop), 1);
+        model.set_base_cost(Opcode::StrConcat, 10);
+        model.per_byte = 2;
+        model.per_agg_value = 3;
+        model.per_arg = 4;
+        let model = Arc::new(model);
+
+        // const_pool: 1 + 4*2, str_concat: 10 + 8*2, const_i64: 1, tuple_new: 1 + 2*3,
+        // call: 1 + 1*4, ret (callee): 1, ret (entry): 1.
+        let expected_cost = 9 + 26 + 1 + 7 + 5 + 1 + 1;
+
+        let mut ctx = ExecutionContext::new();
+        let mut vm = Vm::new(
+            TestHost,
+            Limits {
+                fuel: 1_000,
+                cost_model: Some(model.clone()),
+                ..Limits::default()
+            },
+        );
+        for _ in 0..3 {
+            let out = vm
+                .run_with_ctx(&mut ctx, &p, f_main, &[], TraceMask::NONE, None, None)
+                .unwrap();
+            assert_eq!(out, vec![Value::I64(5)]);
+            assert_eq!(ctx.fuel_remaining(), 1_000 - expected_cost);
+        }
+
+        let run_with_fuel = |fuel: u64| {
+            let mut vm = Vm::new(
+                TestHost,
+                Limits {
+                    fuel,
+                    cost_model: Some(model.clone()),
+                    ..Limits::default()
+                },
+            );
+            vm.run(&p, f_main, &[], TraceMask::NONE, None)
+        };
+        assert!(run_with_fuel(expected_cost).is_ok());
+        assert_eq!(
+            run_with_fuel(expected_cost - 1).unwrap_err().trap,
+            Trap::FuelExceeded
+        );
+
+        // The flat default charges one unit per executed instruction.
+        let mut vm = Vm::new(
+            TestHost,
+            Limits {
+                fuel: 1_000,
+                ..Limits::default()
+            },
+        );
+        vm.run_with_ctx(&mut ctx, &p, f_main, &[], TraceMask::NONE, None, None)
+            .unwrap();
+        assert_eq!(ctx.fuel_remaining(), 1_000 - 7);
+    }
+
     #[derive(Debug, Default)]
     struct CountingAccessSink {
         reads: usize,
@@ -4342,6 +5194,123 @@ mod tests {
         assert_eq!(err.trap, Trap::CallDepthExceeded);
     }
 
+    #[test]
+    fn vm_trap_backtrace_is_opt_in_and_lists_callers() {
+        let mut pb = ProgramBuilder::new();
+        let sig = FunctionSig {
+            arg_types: vec![],
+            ret_types: vec![],
+        };
+        let f_main = pb.declare_function(sig.clone());
+        let f_mid = pb.declare_function(sig.clone());
+        let f_leaf = pb.declare_function(sig);
+
+        let mut a = Asm::new();
+        a.nop();
+        a.call(0, f_mid, 0, &[], &[]);
+        a.ret(0, &[]);
+        pb.define_function(f_main, a).unwrap();
+
+        let mut a = Asm::new();
+        a.call(0, f_leaf, 0, &[], &[]);
+        a.ret(0, &[]);
+        pb.define_function(f_mid, a).unwrap();
+
+        let mut a = Asm::new();
+        a.nop();
+        a.trap(7);
+        pb.define_function(f_leaf, a).unwrap();
+
+        pb.set_function_name(f_main, "main").unwrap();
+        pb.set_function_name(f_leaf, "leaf").unwrap();
+        let p = pb.build_verified().unwrap();
+
+        let mut vm = Vm::new(TestHost, Limits::default());
+        let err = vm.run(&p, f_main, &[], TraceMask::NONE, None).unwrap_err();
+        assert_eq!(err.trap, Trap::TrapCode(7));
+        assert_eq!(err.backtrace, None, "backtraces are off by default");
+
+        let mut vm = Vm::new(
+            TestHost,
+            Limits {
+                capture_backtrace: true,
+                ..Limits::default()
+            },
+        );
+        let err = vm.run(&p, f_main, &[], TraceMask::NONE, None).unwrap_err();
+        let frames: Vec<(FuncId, u32, Option<&str>)> = err
+            .backtrace
+            .as_deref()
+            .expect("backtrace captured")
+            .iter()
+            .map(|fr| (fr.func, fr.pc, fr.name.as_deref()))
+            .collect();
+        assert_eq!(
+            frames,
+            vec![
+                (f_leaf, 1, Some("leaf")),
+                (f_mid, 0, None),
+                (f_main, 1, Some("main")),
+            ]
+        );
+        let rendered = format!("{err}");
+        assert!(
+            rendered.contains("#0 leaf (f2) pc=1") && rendered.contains("#2 main (f0) pc=1"),
+            "unexpected rendering: {rendered}"
+        );
+    }
+
+    #[test]
+    fn vm_trap_reports_source_map_location() {
+        use crate::program::{SourceRange, SpanId};
+
+        let span = SpanId::try_from(9).unwrap();
+        let mut a = Asm::new();
+        a.span(span);
+        a.trap(3);
+        let mut pb = ProgramBuilder::new();
+        let f = pb
+            .push_function_checked(
+                a,
+                FunctionSig {
+                    arg_types: vec![],
+                    ret_types: vec![],
+                },
+            )
+            .unwrap();
+        pb.set_span_info(
+            span,
+            "main.graph",
+            SourceRange {
+                start_line: 12,
+                start_col: 4,
+                end_line: 12,
+                end_col: 30,
+            },
+            Some("node-9"),
+        );
+        let p = pb.build_verified().unwrap();
+
+        let mut vm = Vm::new(
+            TestHost,
+            Limits {
+                capture_backtrace: true,
+                ..Limits::default()
+            },
+        );
+        let err = vm.run(&p, f, &[], TraceMask::NONE, None).unwrap_err();
+        assert_eq!(
+            err.source_location.as_deref(),
+            Some("main.graph:12:4 (node node-9)")
+        );
+        let rendered = format!("{err}");
+        assert!(
+            rendered.starts_with("trap at f0 pc=0 span=9 (main.graph:12:4 (node node-9)): ")
+                && rendered.contains("#0 f0 pc=0 span=9 (main.graph:12:4"),
+            "unexpected rendering: {rendered}"
+        );
+    }
+
     #[test]
     fn vm_multiple_closures_same_func_different_envs() {
         // callee (func1): sig (Agg) -> Agg — returns its env tuple directly
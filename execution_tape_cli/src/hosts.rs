@@ -0,0 +1,193 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Hosts for `tape run`.
+//!
+//! [`StubHost`] rejects every host call. [`ScriptedHost`] serves calls from a script with one
+//! expected call per line, in order:
+//!
+//! ```text
+//! # comment
+//! math.add -> 3
+//! log ->
+//! pair -> 1, "two"
+//! ```
+//!
+//! Return values are parsed against the return types of the symbol's host signature in the
+//! program being run, using the command-line value syntax.
+
+use std::collections::VecDeque;
+
+use execution_tape::host::{Host, HostContext, HostError, SigHash, ValueRef};
+use execution_tape::program::Program;
+use execution_tape::value::Value;
+
+use crate::values;
+
+/// A host that rejects every call with [`HostError::UnknownSymbol`].
+#[derive(Debug)]
+pub(crate) struct StubHost;
+
+impl Host for StubHost {
+    fn call(
+        &mut self,
+        _symbol: &str,
+        _sig_hash: SigHash,
+        _args: &[ValueRef<'_>],
+        _rets: &mut [Value],
+        _ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        Err(HostError::UnknownSymbol)
+    }
+}
+
+#[derive(Debug)]
+struct ScriptedCall {
+    line: usize,
+    symbol: String,
+    sig_hash: SigHash,
+    rets: Vec<Value>,
+}
+
+/// A host that replays scripted return values and fails on the first unexpected call.
+#[derive(Debug)]
+pub(crate) struct ScriptedHost {
+    calls: VecDeque<ScriptedCall>,
+    divergence: Option<String>,
+}
+
+impl ScriptedHost {
+    /// Parses a host script, typing return values against `program`'s host signatures.
+    ///
+    /// Errors are formatted as `<line>: <message>`.
+    pub(crate) fn parse(program: &Program, script: &str) -> Result<Self, String> {
+        let mut calls = VecDeque::new();
+        for (ix, line) in script.lines().enumerate() {
+            let line_no = ix + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (symbol, rets) = line
+                .split_once("->")
+                .ok_or_else(|| format!("{line_no}: expected `<symbol> -> <values>`"))?;
+            let symbol = symbol.trim();
+            let mut sigs = program
+                .host_sigs
+                .iter()
+                .filter(|s| program.symbol_str(s.symbol).ok() == Some(symbol));
+            let sig = sigs
+                .next()
+                .ok_or_else(|| format!("{line_no}: program has no host signature `{symbol}`"))?;
+            // Signatures with the same hash are interchangeable; different ones would leave the
+            // return types to guess.
+            if sigs.any(|s| s.sig_hash != sig.sig_hash) {
+                return Err(format!(
+                    "{line_no}: program has several host signatures for `{symbol}`"
+                ));
+            }
+            let ret_types = program
+                .host_sig_rets(sig)
+                .map_err(|e| format!("{line_no}: {e}"))?;
+            let texts = split_values(rets);
+            if texts.len() != ret_types.len() {
+                return Err(format!(
+                    "{line_no}: `{symbol}` returns {} values, script gives {}",
+                    ret_types.len(),
+                    texts.len()
+                ));
+            }
+            let rets = texts
+                .iter()
+                .zip(ret_types)
+                .map(|(text, ty)| values::parse(text, *ty))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("{line_no}: {e}"))?;
+            calls.push_back(ScriptedCall {
+                line: line_no,
+                symbol: symbol.to_owned(),
+                sig_hash: sig.sig_hash,
+                rets,
+            });
+        }
+        Ok(Self {
+            calls,
+            divergence: None,
+        })
+    }
+
+    /// Returns why the run diverged from the script, if it did.
+    pub(crate) fn divergence(&self) -> Option<&str> {
+        self.divergence.as_deref()
+    }
+
+    /// Returns the number of scripted calls not yet served.
+    pub(crate) fn remaining(&self) -> usize {
+        self.calls.len()
+    }
+}
+
+impl Host for ScriptedHost {
+    fn call(
+        &mut self,
+        symbol: &str,
+        sig_hash: SigHash,
+        _args: &[ValueRef<'_>],
+        rets: &mut [Value],
+        _ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        let Some(call) = self.calls.pop_front() else {
+            self.divergence = Some(format!("unexpected call to `{symbol}` after end of script"));
+            return Err(HostError::Failed);
+        };
+        if call.symbol != symbol || call.sig_hash != sig_hash {
+            self.divergence = Some(format!(
+                "line {}: expected call to `{}`, got `{symbol}`",
+                call.line, call.symbol
+            ));
+            return Err(HostError::Failed);
+        }
+        for (slot, v) in rets.iter_mut().zip(call.rets) {
+            *slot = v;
+        }
+        Ok(0)
+    }
+}
+
+/// Splits a comma-separated value list, keeping commas inside double quotes.
+fn split_values(text: &str) -> Vec<&str> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let mut out = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                out.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(text[start..].trim());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_values_respects_quotes() {
+        assert_eq!(split_values(""), Vec::<&str>::new());
+        assert_eq!(
+            split_values(r#" 1, "a, \"b\"", 0x00 "#),
+            vec!["1", r#""a, \"b\"""#, "0x00"]
+        );
+    }
+}
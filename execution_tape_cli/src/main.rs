@@ -0,0 +1,289 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `tape`: inspect, verify and run `execution_tape` programs.
+//!
+//! Input files may be either the binary container produced by `Program::encode` or `.tape`
+//! assembly text (see `execution_tape::asm_text`).
+
+mod hosts;
+mod stats;
+mod values;
+
+use std::fs;
+use std::io::{self, Write as _};
+use std::process::ExitCode;
+
+use execution_tape::asm_text;
+use execution_tape::codec::canonicalize_program;
+use execution_tape::disasm::disassemble;
+use execution_tape::entry::functions_named;
+use execution_tape::program::Program;
+use execution_tape::trace::TraceMask;
+use execution_tape::value::FuncId;
+use execution_tape::verifier::{VerifyConfig, verify_program_owned, verify_program_with_lints};
+use execution_tape::vm::{Limits, Vm};
+
+use crate::hosts::{ScriptedHost, StubHost};
+
+const USAGE: &str = "\
+usage: tape <command> [options] <file>
+
+commands:
+  disasm <file>                       print the disassembly (.tape text)
+  verify <file>                       verify and print errors and lints
+  run <file> <entry> [args...]        run function <entry> (a name or f<N>)
+      --host-script <script>          serve host calls from a script instead of rejecting them
+      --fuel <n>                      instruction budget
+  stats <file>                        print section sizes and table counts
+  canonicalize <file> [-o <out>]      re-encode with canonical bytecode (binary output)
+
+<file> is a binary tape container or .tape assembly text.";
+
+/// Leading bytes of the binary container (`Program::encode`).
+const CONTAINER_MAGIC: &[u8] = b"EXTAPE\0\0";
+
+/// A command failure: the message to print and the process exit code.
+#[derive(Debug)]
+struct Failure {
+    code: u8,
+    message: String,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: 2,
+            message: message.into(),
+        }
+    }
+
+    fn failed(message: impl Into<String>) -> Self {
+        Self {
+            code: 1,
+            message: message.into(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run_command(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(f) => {
+            eprintln!("error: {}", f.message);
+            if f.code == 2 {
+                eprintln!("\n{USAGE}");
+            }
+            ExitCode::from(f.code)
+        }
+    }
+}
+
+fn run_command(args: &[String]) -> Result<(), Failure> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(Failure::usage("missing command"));
+    };
+    match command.as_str() {
+        "disasm" => {
+            let [path] = rest else {
+                return Err(Failure::usage("disasm takes exactly one file"));
+            };
+            let program = load(path)?;
+            print!("{}", disassemble(&program));
+            Ok(())
+        }
+        "verify" => {
+            let [path] = rest else {
+                return Err(Failure::usage("verify takes exactly one file"));
+            };
+            verify(&load(path)?)
+        }
+        "run" => run(rest),
+        "stats" => {
+            let [path] = rest else {
+                return Err(Failure::usage("stats takes exactly one file"));
+            };
+            let program = load(path)?;
+            print!("{}", stats::Stats::new(&program));
+            Ok(())
+        }
+        "canonicalize" => canonicalize(rest),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        other => Err(Failure::usage(format!("unknown command `{other}`"))),
+    }
+}
+
+/// Reads a program from `path`, accepting either the binary container or assembly text.
+fn load(path: &str) -> Result<Program, Failure> {
+    let bytes = fs::read(path).map_err(|e| Failure::failed(format!("{path}: {e}")))?;
+    if bytes.starts_with(CONTAINER_MAGIC) {
+        return Program::decode(&bytes).map_err(|e| Failure::failed(format!("{path}: {e}")));
+    }
+    let text = String::from_utf8(bytes).map_err(|_| {
+        Failure::failed(format!(
+            "{path}: neither a tape container nor UTF-8 assembly text"
+        ))
+    })?;
+    asm_text::parse(&text).map_err(|e| Failure::failed(format!("{path}:{e}")))
+}
+
+fn verify(program: &Program) -> Result<(), Failure> {
+    let lints = verify_program_with_lints(program, &VerifyConfig::default())
+        .map_err(|e| Failure::failed(format!("verification failed: {e}")))?;
+    for lint in &lints {
+        println!("warning: {lint}");
+    }
+    println!(
+        "ok ({} functions, {} lints)",
+        program.functions.len(),
+        lints.len()
+    );
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), Failure> {
+    let mut positional = Vec::new();
+    let mut script = None;
+    let mut limits = Limits::default();
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--host-script" => {
+                let path = it
+                    .next()
+                    .ok_or_else(|| Failure::usage("--host-script needs a file"))?;
+                script = Some(path);
+            }
+            "--fuel" => {
+                let n = it
+                    .next()
+                    .ok_or_else(|| Failure::usage("--fuel needs a value"))?;
+                limits.fuel = n
+                    .parse()
+                    .map_err(|_| Failure::usage(format!("invalid fuel `{n}`")))?;
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let [path, entry, call_args @ ..] = positional.as_slice() else {
+        return Err(Failure::usage("run needs a file and an entry function"));
+    };
+
+    let program = load(path)?;
+    let entry = resolve_entry(&program, entry)?;
+    let func = &program.functions[entry.0 as usize];
+    let arg_types = program
+        .function_arg_types(func)
+        .map_err(|e| Failure::failed(e.to_string()))?;
+    let ret_types = program
+        .function_ret_types(func)
+        .map_err(|e| Failure::failed(e.to_string()))?
+        .to_vec();
+    if call_args.len() != arg_types.len() {
+        return Err(Failure::usage(format!(
+            "f{} takes {} arguments, got {}",
+            entry.0,
+            arg_types.len(),
+            call_args.len()
+        )));
+    }
+    let mut values = Vec::with_capacity(call_args.len());
+    for (i, (arg, ty)) in call_args.iter().zip(arg_types).enumerate() {
+        values.push(
+            values::parse(arg, *ty)
+                .map_err(|e| Failure::usage(format!("argument {i} ({arg}): {e}")))?,
+        );
+    }
+    let script = match script {
+        Some(script_path) => {
+            let text = fs::read_to_string(script_path)
+                .map_err(|e| Failure::failed(format!("{script_path}: {e}")))?;
+            Some(
+                ScriptedHost::parse(&program, &text)
+                    .map_err(|e| Failure::failed(format!("{script_path}:{e}")))?,
+            )
+        }
+        None => None,
+    };
+
+    let verified = verify_program_owned(program, &VerifyConfig::default())
+        .map_err(|e| Failure::failed(format!("verification failed: {e}")))?;
+    let result = match script {
+        Some(host) => {
+            let mut vm = Vm::new(host, limits);
+            let result = vm.run(&verified, entry, &values, TraceMask::NONE, None);
+            if let Some(message) = vm.host().divergence() {
+                return Err(Failure::failed(format!("host script: {message}")));
+            }
+            if result.is_ok() && vm.host().remaining() != 0 {
+                eprintln!(
+                    "warning: host script has {} unused entries",
+                    vm.host().remaining()
+                );
+            }
+            result
+        }
+        None => Vm::new(StubHost, limits).run(&verified, entry, &values, TraceMask::NONE, None),
+    };
+    let rets = result.map_err(|trap| Failure::failed(trap.to_string()))?;
+
+    let mut out = io::stdout().lock();
+    for (v, ty) in rets.iter().zip(&ret_types) {
+        writeln!(out, "{}", values::Display(v, *ty)).map_err(|e| Failure::failed(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Resolves `entry` as an export or function name (rejecting ambiguous names), falling back to
+/// `f<N>`.
+fn resolve_entry(program: &Program, entry: &str) -> Result<FuncId, Failure> {
+    let count = u32::try_from(program.functions.len()).unwrap_or(u32::MAX);
+    match functions_named(program, entry).as_slice() {
+        [] => {}
+        [func] => return Ok(*func),
+        funcs => {
+            let funcs: Vec<String> = funcs.iter().map(|f| format!("f{}", f.0)).collect();
+            return Err(Failure::failed(format!(
+                "`{entry}` is ambiguous: it names {}",
+                funcs.join(", ")
+            )));
+        }
+    }
+    entry
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u32>().ok())
+        .filter(|&n| n < count)
+        .map(FuncId)
+        .ok_or_else(|| Failure::failed(format!("no function named `{entry}`")))
+}
+
+fn canonicalize(args: &[String]) -> Result<(), Failure> {
+    let (path, out_path) = match args {
+        [path] => (path, None),
+        [path, flag, out] | [flag, out, path] if flag == "-o" => (path, Some(out)),
+        _ => {
+            return Err(Failure::usage(
+                "canonicalize takes a file and an optional -o <out>",
+            ));
+        }
+    };
+    let program = load(path)?;
+    let canonical =
+        canonicalize_program(&program).map_err(|e| Failure::failed(format!("{path}: {e}")))?;
+    let bytes = canonical.encode();
+    match out_path {
+        Some(out) => {
+            fs::write(out, &bytes).map_err(|e| Failure::failed(format!("{out}: {e}")))?;
+            eprintln!("wrote {} bytes to {out}", bytes.len());
+        }
+        None => io::stdout()
+            .lock()
+            .write_all(&bytes)
+            .map_err(|e| Failure::failed(e.to_string()))?,
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `tape stats`: container section sizes and table counts.
+
+use std::fmt;
+
+use execution_tape::codec::decode_all;
+use execution_tape::format::Reader;
+use execution_tape::program::Program;
+
+/// Size summary of a program's binary encoding.
+#[derive(Debug)]
+pub(crate) struct Stats<'a> {
+    program: &'a Program,
+    encoded_len: usize,
+    /// `(tag, payload length)` per section, in container order.
+    sections: Vec<(u8, u64)>,
+}
+
+impl<'a> Stats<'a> {
+    pub(crate) fn new(program: &'a Program) -> Self {
+        let bytes = program.encode();
+        Self {
+            program,
+            encoded_len: bytes.len(),
+            sections: sections(&bytes),
+        }
+    }
+}
+
+/// Walks the tagged sections after the container header (8-byte magic plus two `u16` versions).
+fn sections(bytes: &[u8]) -> Vec<(u8, u64)> {
+    let mut r = Reader::new(bytes.get(12..).unwrap_or(&[]));
+    let mut out = Vec::new();
+    while let Ok(tag) = r.read_u8() {
+        let Ok(len) = r.read_uleb128_u64() else { break };
+        let Ok(len_usize) = usize::try_from(len) else {
+            break;
+        };
+        if r.read_bytes(len_usize).is_err() {
+            break;
+        }
+        out.push((tag, len));
+    }
+    out
+}
+
+fn section_name(tag: u8) -> &'static str {
+    match tag {
+        1 => "symbols",
+        2 => "const_pool",
+        3 => "types",
+        4 => "function_table",
+        5 => "bytecode_blobs",
+        6 => "span_tables",
+        7 => "function_sigs",
+        8 => "host_sigs",
+        9 => "names",
+        10 => "call_sigs",
+        11 => "function_arg_agg_shapes",
+        12 => "linkage",
+        13 => "source_map",
+        0x80..=0xFF => "custom",
+        _ => "unknown",
+    }
+}
+
+impl fmt::Display for Stats<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let p = self.program;
+        if let Some(name) = p.name() {
+            writeln!(f, "program: {name:?}")?;
+        }
+        writeln!(f, "encoded size: {} bytes", self.encoded_len)?;
+        writeln!(f, "sections:")?;
+        for &(tag, len) in &self.sections {
+            writeln!(
+                f,
+                "  {:<24} {len:>8}",
+                format!("{}({tag})", section_name(tag))
+            )?;
+        }
+        writeln!(f, "tables:")?;
+        writeln!(f, "  symbols            {:>8}", p.symbols.len())?;
+        writeln!(f, "  consts             {:>8}", p.const_pool.len())?;
+        writeln!(f, "  struct types       {:>8}", p.types.structs.len())?;
+        writeln!(f, "  array elem types   {:>8}", p.types.array_elems.len())?;
+        writeln!(f, "  host sigs          {:>8}", p.host_sigs.len())?;
+        writeln!(f, "  call sigs          {:>8}", p.call_sigs.len())?;
+        writeln!(f, "  functions          {:>8}", p.functions.len())?;
+        writeln!(f, "functions:")?;
+        for (i, func) in p.functions.iter().enumerate() {
+            let bytecode = p.function_bytecode(func).unwrap_or(&[]);
+            let instrs =
+                decode_all(bytecode).map_or_else(|_| "?".to_owned(), |d| d.len().to_string());
+            let spans = p.function_spans(func).map_or(0, <[_]>::len);
+            write!(
+                f,
+                "  f{i:<4} args={} rets={} regs={} bytecode={} instrs={instrs} spans={spans}",
+                func.arg_count,
+                func.ret_count,
+                func.reg_count,
+                bytecode.len()
+            )?;
+            let func_id = u32::try_from(i).unwrap_or(u32::MAX);
+            if let Some(name) = p.function_name(func_id) {
+                write!(f, " name={name:?}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
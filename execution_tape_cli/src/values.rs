@@ -0,0 +1,173 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Command-line value syntax.
+//!
+//! Values are parsed against a known [`ValueType`]: `()` for unit, `true`/`false`, integers in
+//! decimal or `0x` hex, floats, decimals as `12.34` (the written fractional digits become the
+//! scale), bytes as `0x` hex, strings raw or double-quoted, and functions as `f<N>`.
+
+use std::fmt;
+
+use execution_tape::program::ValueType;
+use execution_tape::value::{Decimal, FuncId, Value};
+
+/// Parses `text` as a value of type `ty`.
+pub(crate) fn parse(text: &str, ty: ValueType) -> Result<Value, String> {
+    let text = text.trim();
+    let invalid = || format!("expected {ty:?}, got `{text}`");
+    let value = match ty {
+        ValueType::Unit => match text {
+            "()" | "unit" => Value::Unit,
+            _ => return Err(invalid()),
+        },
+        ValueType::Bool => Value::Bool(text.parse().map_err(|_| invalid())?),
+        ValueType::I64 => Value::I64(parse_i64(text).ok_or_else(invalid)?),
+        ValueType::U64 => Value::U64(parse_u64(text).ok_or_else(invalid)?),
+        ValueType::F64 => Value::F64(text.parse().map_err(|_| invalid())?),
+        ValueType::Decimal => Value::Decimal(parse_decimal(text).ok_or_else(invalid)?),
+        ValueType::Bytes => Value::Bytes(parse_hex(text).ok_or_else(invalid)?),
+        ValueType::Str => Value::Str(unquote(text).unwrap_or_else(|| text.to_owned())),
+        ValueType::Func => Value::Func(FuncId(
+            text.strip_prefix('f')
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(invalid)?,
+        )),
+        ValueType::Obj(_) | ValueType::Agg | ValueType::Closure => {
+            return Err(format!(
+                "{ty:?} values cannot be written on the command line"
+            ));
+        }
+    };
+    Ok(value)
+}
+
+/// Formats a value in the syntax accepted by [`parse`] (where one exists).
+pub(crate) struct Display<'a>(pub(crate) &'a Value, pub(crate) ValueType);
+
+impl fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::Unit => write!(f, "()"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::I64(v) => write!(f, "{v}"),
+            Value::U64(v) => write!(f, "{v}"),
+            Value::F64(v) => write!(f, "{v:?}"),
+            Value::Decimal(d) => fmt_decimal(f, *d),
+            Value::Bytes(b) => {
+                write!(f, "0x")?;
+                for byte in b {
+                    write!(f, "{byte:02X}")?;
+                }
+                Ok(())
+            }
+            Value::Str(s) => write!(f, "{s:?}"),
+            Value::Func(func) => write!(f, "f{}", func.0),
+            other => write!(f, "<{:?}> {other:?}", self.1),
+        }
+    }
+}
+
+fn fmt_decimal(f: &mut fmt::Formatter<'_>, d: Decimal) -> fmt::Result {
+    let sign = if d.mantissa < 0 { "-" } else { "" };
+    let digits = d.mantissa.unsigned_abs().to_string();
+    let scale = usize::from(d.scale);
+    if scale == 0 {
+        return write!(f, "{sign}{digits}");
+    }
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    write!(f, "{sign}{int}.{frac}")
+}
+
+fn parse_u64(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn parse_i64(text: &str) -> Option<i64> {
+    match text.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn parse_decimal(text: &str) -> Option<Decimal> {
+    let (int, frac) = text.split_once('.').unwrap_or((text, ""));
+    if !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let scale = u8::try_from(frac.len()).ok()?;
+    let mantissa = format!("{int}{frac}").parse().ok()?;
+    Some(Decimal { mantissa, scale })
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let hex = text.strip_prefix("0x")?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Strips surrounding double quotes, decoding `\"`, `\\`, `\n` and `\t` escapes.
+fn unquote(text: &str) -> Option<String> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            'n' => '\n',
+            't' => '\t',
+            other => other,
+        });
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_roundtrip_through_display() {
+        let cases = [
+            ("()", ValueType::Unit),
+            ("true", ValueType::Bool),
+            ("-42", ValueType::I64),
+            ("18446744073709551615", ValueType::U64),
+            ("1.5", ValueType::F64),
+            ("-0.05", ValueType::Decimal),
+            ("12", ValueType::Decimal),
+            ("0x00FF", ValueType::Bytes),
+            ("\"a \\\"b\\\"\"", ValueType::Str),
+            ("f3", ValueType::Func),
+        ];
+        for (text, ty) in cases {
+            let v = parse(text, ty).unwrap();
+            assert_eq!(Display(&v, ty).to_string(), text, "{ty:?}");
+        }
+        assert_eq!(
+            parse("-0.05", ValueType::Decimal).unwrap(),
+            Value::Decimal(Decimal {
+                mantissa: -5,
+                scale: 2
+            })
+        );
+        assert_eq!(
+            parse("hi", ValueType::Str).unwrap(),
+            Value::Str("hi".into())
+        );
+        assert!(parse("1.5", ValueType::I64).is_err());
+        assert!(parse("()", ValueType::Agg).is_err());
+    }
+}
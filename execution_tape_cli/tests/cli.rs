@@ -0,0 +1,178 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! End-to-end tests for the `tape` binary.
+//!
+//! Run with:
+//! `cargo test -p execution_tape_cli`
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use execution_tape::program::Program;
+
+const PROGRAM: &str = r#"
+.host_sig 0 "math.double" [I64] -> [I64]
+
+func f0:
+  .name "main"
+  .sig [x: I64, twice: Bool] -> [out: I64]
+  br r2, @twice, @once
+  @twice:
+  host_call eff_out=r0, host_sig#0, eff_in=r0, args=[r1], rets=[r3]
+  ret eff=r0, rets=[r3]
+  @once:
+  ret eff=r0, rets=[r1]
+"#;
+
+fn write_temp(name: &str, contents: &[u8]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn tape(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tape"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn stderr(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stderr).into_owned()
+}
+
+#[test]
+fn disasm_verify_and_stats_accept_text_and_binary() {
+    let text = write_temp("cli_inspect.tape", PROGRAM.as_bytes());
+    let program = execution_tape::asm_text::parse(PROGRAM).unwrap();
+    let binary = write_temp("cli_inspect.bin", &program.encode());
+
+    for path in [&text, &binary] {
+        let path = path.to_str().unwrap();
+        let out = tape(&["disasm", path]);
+        assert!(out.status.success(), "{}", stderr(&out));
+        assert!(stdout(&out).contains("func f0: ; name=\"main\""));
+
+        let out = tape(&["verify", path]);
+        assert!(out.status.success(), "{}", stderr(&out));
+        assert!(stdout(&out).starts_with("ok (1 functions"));
+
+        let out = tape(&["stats", path]);
+        assert!(out.status.success(), "{}", stderr(&out));
+        let stats = stdout(&out);
+        assert!(stats.contains("bytecode_blobs(5)"), "{stats}");
+        assert!(stats.contains("name=\"main\""), "{stats}");
+    }
+}
+
+#[test]
+fn run_uses_stub_or_scripted_host() {
+    let path = write_temp("cli_run.tape", PROGRAM.as_bytes());
+    let path = path.to_str().unwrap();
+
+    let out = tape(&["run", path, "main", "21", "false"]);
+    assert!(out.status.success(), "{}", stderr(&out));
+    assert_eq!(stdout(&out), "21\n");
+
+    let out = tape(&["run", path, "main", "21", "true"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stderr(&out).contains("trap at f0"), "{}", stderr(&out));
+
+    let script = write_temp("cli_run.script", b"# doubled\nmath.double -> 42\n");
+    let out = tape(&[
+        "run",
+        path,
+        "f0",
+        "21",
+        "true",
+        "--host-script",
+        script.to_str().unwrap(),
+    ]);
+    assert!(out.status.success(), "{}", stderr(&out));
+    assert_eq!(stdout(&out), "42\n");
+
+    let out = tape(&["run", path, "main", "x", "true"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(stderr(&out).contains("expected I64"), "{}", stderr(&out));
+}
+
+#[test]
+fn canonicalize_shrinks_wide_branch_targets() {
+    let wide = PROGRAM.replace(
+        ".sig [x: I64, twice: Bool] -> [out: I64]",
+        ".sig [x: I64, twice: Bool] -> [out: I64]\n  .pcs wide",
+    );
+    let path = write_temp("cli_wide.tape", wide.as_bytes());
+    let out_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli_canonical.bin");
+
+    let out = tape(&[
+        "canonicalize",
+        path.to_str().unwrap(),
+        "-o",
+        out_path.to_str().unwrap(),
+    ]);
+    assert!(out.status.success(), "{}", stderr(&out));
+
+    let original = execution_tape::asm_text::parse(&wide).unwrap();
+    let canonical = Program::decode(&std::fs::read(&out_path).unwrap()).unwrap();
+    assert!(canonical.bytecode_data.len() < original.bytecode_data.len());
+    assert_eq!(
+        canonical.encode(),
+        execution_tape::asm_text::parse(PROGRAM).unwrap().encode()
+    );
+}
+
+#[test]
+fn reports_parse_errors_with_positions() {
+    let path = write_temp("cli_bad.tape", b"func f0:\n  bogus r1\n");
+    let out = tape(&["verify", path.to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        stderr(&out).contains("cli_bad.tape:2:3: unknown instruction mnemonic"),
+        "{}",
+        stderr(&out)
+    );
+}
+
+#[test]
+fn run_rejects_ambiguous_entries_and_host_symbols() {
+    let program = r#"
+.host_sig 0 "pick" [I64] -> [I64]
+.host_sig 1 "pick" [] -> [Bool]
+.export f1 "main"
+
+func f0:
+  .name "main"
+  .sig [] -> [I64]
+  const.i64 r1, imm=1
+  ret eff=r0, rets=[r1]
+
+func f1:
+  .sig [] -> [I64]
+  const.i64 r1, imm=2
+  ret eff=r0, rets=[r1]
+"#;
+    let path = write_temp("cli_ambiguous.tape", program.as_bytes());
+    let path = path.to_str().unwrap();
+
+    let out = tape(&["run", path, "main"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        stderr(&out).contains("`main` is ambiguous: it names f0, f1"),
+        "{}",
+        stderr(&out)
+    );
+
+    let script = write_temp("cli_ambiguous.script", b"pick -> 1\n");
+    let out = tape(&["run", path, "f0", "--host-script", script.to_str().unwrap()]);
+    assert!(
+        stderr(&out).contains("several host signatures for `pick`"),
+        "{}",
+        stderr(&out)
+    );
+}
@@ -257,6 +257,7 @@ fn generate(spec: Spec, src: &Path) -> Result<String> {
     out.push_str("    Const,\n");
     out.push_str("    Func,\n");
     out.push_str("    HostSig,\n");
+    out.push_str("    CallSig,\n");
     out.push_str("    Type,\n");
     out.push_str("    ElemType,\n");
     out.push_str("    EffIn,\n");
@@ -1394,12 +1395,6 @@ fn generate_reads_writes(spec: Spec, src: &Path) -> Result<String> {
                 scalar_writes.len()
             );
         }
-        if list_write.is_some() && scalar_writes.is_empty() {
-            bail!(
-                "opcode {} has reg_list writes but no scalar write (not supported)",
-                op.name
-            );
-        }
 
         if op.operands.is_empty() {
             out.push_str(&format!("            Self::{} => ", op.name));
@@ -1431,6 +1426,7 @@ fn generate_reads_writes(spec: Spec, src: &Path) -> Result<String> {
         match (scalar_writes.as_slice(), list_write) {
             ([], None) => out.push_str("WritesIter::none(),\n"),
             ([a], None) => out.push_str(&format!("WritesIter::one(*{a}),\n")),
+            ([], Some(_)) => out.push_str("WritesIter::slice(rest.as_slice()),\n"),
             ([a], Some(_)) => out.push_str(&format!(
                 "WritesIter::one_plus_slice(*{a}, rest.as_slice()),\n"
             )),
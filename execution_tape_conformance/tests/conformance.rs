@@ -71,7 +71,7 @@ fn verify_owned(program: Program) -> execution_tape::verifier::VerifiedProgram {
 }
 
 #[test]
-fn golden_program_bytes_v0_0_1() {
+fn golden_program_bytes_v0_0_2() {
     let mut p = Program::new(
         vec![],
         vec![],
@@ -115,10 +115,10 @@ fn golden_program_bytes_v0_0_1() {
         0x45,
         0x00,
         0x00,
-        // version_major=0, version_minor=1
+        // version_major=0, version_minor=2
         0x00,
         0x00,
-        0x01,
+        0x02,
         0x00, // symbols: tag=1, len=2, payload=[count=1, sym0_len=0]
         0x01,
         0x02,
@@ -927,7 +927,7 @@ fn vm_traps_call_depth_on_unbounded_recursion() {
     let limits = Limits {
         fuel: 1_000_000,
         max_call_depth: 8,
-        max_host_calls: 1_000_000,
+        ..Limits::default()
     };
     let mut vm = Vm::new(TestHost, limits);
     let err = vm.run(&p, f, &[], TraceMask::NONE, None).unwrap_err();
@@ -0,0 +1,222 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Derive macros for `execution_tape`.
+//!
+//! `#[derive(TapeStruct)]` maps a Rust struct with named fields to a tape struct type. It
+//! implements `execution_tape::convert::{TapeType, IntoValue, FromValue, TapeStruct}`:
+//!
+//! - `TapeStruct::struct_type_def` lists the fields in declaration order, with the
+//!   `TapeType::VALUE_TYPE` of each field type.
+//! - `TapeType::register_types` registers the types of the fields, then the struct type itself,
+//!   with a `ProgramBuilder`.
+//! - `IntoValue` and `FromValue` convert field by field, so fields may themselves be derived
+//!   structs, `Vec<T>` (arrays) or tuples. The struct type is looked up in the `TypeTable` once
+//!   per value, or once per array for a `Vec` of derived structs.
+//!
+//! Generic structs are rejected: a tape struct type has fixed field types.
+//!
+//! ```compile_fail
+//! use execution_tape_derive::TapeStruct;
+//!
+//! #[derive(TapeStruct)]
+//! struct Pair<T> {
+//!     a: T,
+//!     b: T,
+//! }
+//! ```
+//!
+//! ## Example
+//! ```
+//! use execution_tape::aggregates::AggHeap;
+//! use execution_tape::asm::ProgramBuilder;
+//! use execution_tape::convert::{FromValue, IntoValue, TapeType};
+//! use execution_tape_derive::TapeStruct;
+//!
+//! #[derive(TapeStruct, Clone, Debug, PartialEq)]
+//! struct Line {
+//!     sku: String,
+//!     qty: i64,
+//! }
+//!
+//! #[derive(TapeStruct, Clone, Debug, PartialEq)]
+//! struct Order {
+//!     id: u64,
+//!     lines: Vec<Line>,
+//! }
+//!
+//! let mut pb = ProgramBuilder::new();
+//! Order::register_types(&mut pb);
+//! let types = pb.build().types;
+//!
+//! let order = Order {
+//!     id: 7,
+//!     lines: vec![Line { sku: "a-1".into(), qty: 2 }],
+//! };
+//! let mut heap = AggHeap::new();
+//! let value = order.clone().into_value(&types, &mut heap)?;
+//! assert_eq!(Order::from_value(&value, &types, &heap)?, order);
+//! # Ok::<(), execution_tape::convert::ConvertError>(())
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
+
+/// Derives `TapeType`, `IntoValue`, `FromValue` and `TapeStruct` for a struct with named fields.
+///
+/// See the [crate documentation](crate).
+#[proc_macro_derive(TapeStruct)]
+pub fn derive_tape_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "TapeStruct requires a struct with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "TapeStruct can only be derived for structs",
+            ));
+        }
+    };
+
+    if !input.generics.params.is_empty() || input.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "TapeStruct cannot be derived for generic structs",
+        ));
+    }
+
+    let name = &input.ident;
+    let idents: Vec<_> = fields.iter().filter_map(|f| f.ident.as_ref()).collect();
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let names: Vec<_> = idents
+        .iter()
+        .map(|ident| ident.to_string().trim_start_matches("r#").to_owned())
+        .collect();
+    let indices = 0..idents.len();
+    let count = idents.len();
+
+    let tape = quote!(::execution_tape);
+    let convert = quote!(#tape::convert);
+    let vec = quote!(#convert::__derive::Vec);
+    Ok(quote! {
+        impl #convert::TapeType for #name {
+            const VALUE_TYPE: #tape::program::ValueType = #tape::program::ValueType::Agg;
+
+            fn register_types(pb: &mut #tape::asm::ProgramBuilder) {
+                #(<#types as #convert::TapeType>::register_types(pb);)*
+                pb.struct_type(<Self as #convert::TapeStruct>::struct_type_def());
+            }
+        }
+
+        impl #convert::TapeStruct for #name {
+            fn struct_type_def() -> #tape::program::StructTypeDef {
+                let names: [&str; #count] = [#(#names),*];
+                let types: [#tape::program::ValueType; #count] =
+                    [#(<#types as #convert::TapeType>::VALUE_TYPE),*];
+                #tape::program::StructTypeDef {
+                    field_names: names.iter().map(|&n| ::core::convert::Into::into(n)).collect(),
+                    field_types: ::core::iter::IntoIterator::into_iter(types).collect(),
+                }
+            }
+
+            fn into_struct(
+                self,
+                type_id: #tape::program::TypeId,
+                types: &#tape::program::TypeTable,
+                aggregates: &mut #tape::aggregates::AggHeap,
+            ) -> ::core::result::Result<#tape::value::Value, #convert::ConvertError> {
+                let values: [#tape::value::Value; #count] = [
+                    #(#convert::IntoValue::into_value(self.#idents, types, aggregates)?),*
+                ];
+                let values = ::core::iter::IntoIterator::into_iter(values).collect();
+                ::core::result::Result::Ok(#tape::value::Value::Agg(
+                    aggregates.struct_new(type_id, values),
+                ))
+            }
+
+            fn from_struct(
+                value: &#tape::value::Value,
+                type_id: #tape::program::TypeId,
+                types: &#tape::program::TypeTable,
+                aggregates: &#tape::aggregates::AggHeap,
+            ) -> ::core::result::Result<Self, #convert::ConvertError> {
+                let handle = #convert::struct_handle(value, type_id, #count, aggregates)?;
+                ::core::result::Result::Ok(Self {
+                    #(#idents: <#types as #convert::FromValue>::from_value(
+                        aggregates.struct_get_ref(handle, #indices)?,
+                        types,
+                        aggregates,
+                    )?,)*
+                })
+            }
+        }
+
+        impl #convert::IntoValue for #name {
+            fn into_value(
+                self,
+                types: &#tape::program::TypeTable,
+                aggregates: &mut #tape::aggregates::AggHeap,
+            ) -> ::core::result::Result<#tape::value::Value, #convert::ConvertError> {
+                let type_id = #convert::struct_type_id::<Self>(types)?;
+                #convert::TapeStruct::into_struct(self, type_id, types, aggregates)
+            }
+
+            fn into_values(
+                items: #vec<Self>,
+                types: &#tape::program::TypeTable,
+                aggregates: &mut #tape::aggregates::AggHeap,
+            ) -> ::core::result::Result<#vec<#tape::value::Value>, #convert::ConvertError> {
+                let type_id = #convert::struct_type_id::<Self>(types)?;
+                items
+                    .into_iter()
+                    .map(|item| #convert::TapeStruct::into_struct(item, type_id, types, aggregates))
+                    .collect()
+            }
+        }
+
+        impl #convert::FromValue for #name {
+            fn from_value(
+                value: &#tape::value::Value,
+                types: &#tape::program::TypeTable,
+                aggregates: &#tape::aggregates::AggHeap,
+            ) -> ::core::result::Result<Self, #convert::ConvertError> {
+                let type_id = #convert::struct_type_id::<Self>(types)?;
+                <Self as #convert::TapeStruct>::from_struct(value, type_id, types, aggregates)
+            }
+
+            fn from_elements(
+                array: #tape::value::AggHandle,
+                types: &#tape::program::TypeTable,
+                aggregates: &#tape::aggregates::AggHeap,
+            ) -> ::core::result::Result<#vec<Self>, #convert::ConvertError> {
+                let type_id = #convert::struct_type_id::<Self>(types)?;
+                (0..aggregates.array_len(array)?)
+                    .map(|i| {
+                        <Self as #convert::TapeStruct>::from_struct(
+                            aggregates.array_get_ref(array, i)?,
+                            type_id,
+                            types,
+                            aggregates,
+                        )
+                    })
+                    .collect()
+            }
+        }
+    })
+}
@@ -0,0 +1,183 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Integration tests for `#[derive(TapeStruct)]`.
+
+use execution_tape::aggregates::{AggError, AggHeap};
+use execution_tape::asm::{Asm, FunctionSig, ProgramBuilder};
+use execution_tape::convert::{ConvertError, FromValue, IntoValue, TapeStruct, TapeType};
+use execution_tape::host::{Host, HostContext, HostError, SigHash, ValueRef};
+use execution_tape::program::{AggShape, StructTypeDef, ValueType};
+use execution_tape::trace::TraceMask;
+use execution_tape::value::{Decimal, FuncId, Value};
+use execution_tape::vm::{Limits, Vm};
+use execution_tape_derive::TapeStruct;
+
+#[derive(TapeStruct, Clone, Debug, PartialEq)]
+struct Line {
+    sku: String,
+    qty: i64,
+}
+
+#[derive(TapeStruct, Clone, Debug, PartialEq)]
+struct Order {
+    id: u64,
+    customer: (String, bool),
+    lines: Vec<Line>,
+    total: Decimal,
+    r#type: String,
+}
+
+fn sample() -> Order {
+    Order {
+        id: 42,
+        customer: ("ada".into(), true),
+        lines: vec![
+            Line {
+                sku: "a-1".into(),
+                qty: 2,
+            },
+            Line {
+                sku: "b-7".into(),
+                qty: -1,
+            },
+        ],
+        total: Decimal {
+            mantissa: 1999,
+            scale: 2,
+        },
+        r#type: "retail".into(),
+    }
+}
+
+struct NoHost;
+
+impl Host for NoHost {
+    fn call(
+        &mut self,
+        _symbol: &str,
+        _sig_hash: SigHash,
+        _args: &[ValueRef<'_>],
+        _rets: &mut [Value],
+        _ctx: HostContext<'_, '_>,
+    ) -> Result<u64, HostError> {
+        Err(HostError::UnknownSymbol)
+    }
+}
+
+#[test]
+fn struct_type_def_lists_fields_in_order() {
+    assert_eq!(
+        Order::struct_type_def(),
+        StructTypeDef {
+            field_names: ["id", "customer", "lines", "total", "type"]
+                .map(String::from)
+                .to_vec(),
+            field_types: vec![
+                ValueType::U64,
+                ValueType::Agg,
+                ValueType::Agg,
+                ValueType::Decimal,
+                ValueType::Str,
+            ],
+        }
+    );
+}
+
+#[test]
+fn derived_structs_cross_the_vm_boundary() {
+    let mut pb = ProgramBuilder::new();
+    Order::register_types(&mut pb);
+    let order_type = pb.struct_type(Order::struct_type_def());
+
+    // `fn(order) -> (order.id, order.lines)`
+    let mut a = Asm::new();
+    a.struct_get(2, 1, 0);
+    a.struct_get(3, 1, 2);
+    a.ret(0, &[2, 3]);
+    let entry = pb
+        .push_function_checked(
+            a,
+            FunctionSig {
+                arg_types: vec![ValueType::Agg],
+                ret_types: vec![ValueType::U64, ValueType::Agg],
+            }
+            .with_arg_agg_shape(0, AggShape::Struct(order_type)),
+        )
+        .unwrap();
+    let program = pb.build_verified().unwrap();
+    let types = &program.program().types;
+
+    let mut vm = Vm::new(NoHost, Limits::default());
+    let order = sample();
+    let arg = order
+        .clone()
+        .into_value(types, vm.aggregates_mut())
+        .unwrap();
+    let out = vm
+        .run(&program, entry, &[arg], TraceMask::NONE, None)
+        .unwrap();
+
+    assert_eq!(
+        u64::from_value(&out[0], types, vm.aggregates()),
+        Ok(order.id)
+    );
+    assert_eq!(
+        Vec::<Line>::from_value(&out[1], types, vm.aggregates()),
+        Ok(order.lines)
+    );
+}
+
+#[test]
+fn from_value_rejects_other_types() {
+    let mut pb = ProgramBuilder::new();
+    Order::register_types(&mut pb);
+    let types = pb.build().types;
+    let mut heap = AggHeap::new();
+
+    let line = Line {
+        sku: "x".into(),
+        qty: 1,
+    }
+    .into_value(&types, &mut heap)
+    .unwrap();
+    assert!(matches!(
+        Order::from_value(&line, &types, &heap),
+        Err(ConvertError::StructTypeMismatch { .. })
+    ));
+
+    // Arrays of structs resolve the element struct type once and check every element against it.
+    let lines = sample().lines.into_value(&types, &mut heap).unwrap();
+    assert_eq!(
+        Vec::<Line>::from_value(&lines, &types, &heap),
+        Ok(sample().lines)
+    );
+    assert!(matches!(
+        Vec::<Order>::from_value(&lines, &types, &heap),
+        Err(ConvertError::StructTypeMismatch { .. })
+    ));
+
+    let tuple = (1_i64,).into_value(&types, &mut heap).unwrap();
+    assert_eq!(
+        Line::from_value(&tuple, &types, &heap),
+        Err(ConvertError::Agg(AggError::WrongKind))
+    );
+
+    // Without registration, the type table has no matching struct type.
+    let empty = ProgramBuilder::new().build().types;
+    assert_eq!(
+        Line {
+            sku: "x".into(),
+            qty: 1,
+        }
+        .into_value(&empty, &mut heap),
+        Err(ConvertError::MissingStructType)
+    );
+    assert_eq!(
+        Line::from_value(&Value::Func(FuncId(0)), &types, &heap),
+        Err(ConvertError::TypeMismatch {
+            expected: ValueType::Agg,
+            actual: ValueType::Func,
+        })
+    );
+}
@@ -0,0 +1,723 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use execution_tape::aggregates::{AggError, AggHeap};
+use execution_tape::program::{AggShape, ElemTypeId, StructType, TypeId, TypeTable, ValueType};
+use execution_tape::value::{AggHandle, AggType, Decimal, Value};
+use serde_json::{Map, Number, Value as Json};
+
+use crate::error::{JsonError, JsonErrorKind};
+
+/// The expected type of a value converted from JSON.
+///
+/// Struct and array shapes name entries of the [`TypeTable`]; their fields and elements take the
+/// table's [`ValueType`]s. A field or element of type [`ValueType::Agg`] does not say which
+/// aggregate it holds, so its shape comes from [`JsonConverter::with_field_shape`] or
+/// [`JsonConverter::with_elem_shape`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// A non-aggregate value type.
+    ///
+    /// [`ValueType::Agg`] behaves like [`Shape::Unknown`].
+    Value(ValueType),
+    /// A tuple with the given element shapes.
+    Tuple(Vec<Self>),
+    /// A struct of the given type.
+    Struct(TypeId),
+    /// An array of the given element type.
+    Array(ElemTypeId),
+    /// A value of unknown type. Converting to JSON follows the value; converting from JSON fails
+    /// with [`JsonErrorKind::UnknownShape`].
+    Unknown,
+}
+
+impl From<ValueType> for Shape {
+    fn from(ty: ValueType) -> Self {
+        match ty {
+            ValueType::Agg => Self::Unknown,
+            ty => Self::Value(ty),
+        }
+    }
+}
+
+impl From<AggShape> for Shape {
+    fn from(shape: AggShape) -> Self {
+        match shape {
+            AggShape::Tuple { elems } => Self::Tuple(
+                elems
+                    .into_iter()
+                    .map(|e| e.map_or(Self::Unknown, Self::from))
+                    .collect(),
+            ),
+            AggShape::Struct(type_id) => Self::Struct(type_id),
+            AggShape::Array(elem_type_id) => Self::Array(elem_type_id),
+        }
+    }
+}
+
+/// Converts between JSON and [`Value`]s of a program's types.
+///
+/// See the [crate documentation](crate) for the JSON representation of each value type.
+#[derive(Clone, Debug)]
+pub struct JsonConverter<'t> {
+    types: &'t TypeTable,
+    field_shapes: BTreeMap<(u32, String), Shape>,
+    elem_shapes: BTreeMap<u32, Shape>,
+}
+
+impl<'t> JsonConverter<'t> {
+    /// Creates a converter for values of the types in `types`.
+    #[must_use]
+    pub fn new(types: &'t TypeTable) -> Self {
+        Self {
+            types,
+            field_shapes: BTreeMap::new(),
+            elem_shapes: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the shape of the aggregate held by field `field` of struct type `type_id`.
+    #[must_use]
+    pub fn with_field_shape(mut self, type_id: TypeId, field: &str, shape: Shape) -> Self {
+        self.field_shapes.insert((type_id.0, field.into()), shape);
+        self
+    }
+
+    /// Sets the shape of the aggregates held by arrays of element type `elem_type_id`.
+    #[must_use]
+    pub fn with_elem_shape(mut self, elem_type_id: ElemTypeId, shape: Shape) -> Self {
+        self.elem_shapes.insert(elem_type_id.0, shape);
+        self
+    }
+
+    /// Converts `json` into a value of shape `shape`, allocating aggregates in `aggregates`.
+    ///
+    /// On error, aggregates built before the failure remain in `aggregates` but are not
+    /// referenced by any returned value.
+    pub fn from_json(
+        &self,
+        shape: &Shape,
+        json: &Json,
+        aggregates: &mut AggHeap,
+    ) -> Result<Value, JsonError> {
+        let mut path = String::from("$");
+        self.read(shape, json, aggregates, &mut path)
+    }
+
+    /// Converts `value` into JSON, reading any aggregates it references from `aggregates`.
+    pub fn to_json(&self, value: &Value, aggregates: &AggHeap) -> Result<Json, JsonError> {
+        let mut path = String::from("$");
+        self.write(value, aggregates, &mut path)
+    }
+
+    fn struct_type(&self, type_id: u32, path: &str) -> Result<&StructType, JsonError> {
+        self.types
+            .structs
+            .get(type_id as usize)
+            .ok_or_else(|| error(path, JsonErrorKind::BadTypeId(type_id)))
+    }
+
+    /// Returns the names and shapes of the fields of struct type `type_id`.
+    fn fields(&self, type_id: u32, path: &str) -> Result<Vec<(&str, Shape)>, JsonError> {
+        let st = self.struct_type(type_id, path)?;
+        let bad = || error(path, JsonErrorKind::BadTypeId(type_id));
+        let names = self.types.struct_field_name_ids(st).map_err(|_| bad())?;
+        let types = self.types.struct_field_types(st).map_err(|_| bad())?;
+        names
+            .iter()
+            .zip(types)
+            .map(|(&id, &ty)| {
+                let name = self.types.field_name_str(id).ok_or_else(bad)?;
+                let shape = match ty {
+                    ValueType::Agg => self
+                        .field_shapes
+                        .get(&(type_id, name.into()))
+                        .cloned()
+                        .unwrap_or(Shape::Unknown),
+                    ty => Shape::Value(ty),
+                };
+                Ok((name, shape))
+            })
+            .collect()
+    }
+
+    fn elem_shape(&self, elem_type_id: u32, path: &str) -> Result<Shape, JsonError> {
+        match self.types.array_elems.get(elem_type_id as usize) {
+            Some(ValueType::Agg) => Ok(self
+                .elem_shapes
+                .get(&elem_type_id)
+                .cloned()
+                .unwrap_or(Shape::Unknown)),
+            Some(&ty) => Ok(Shape::Value(ty)),
+            None => Err(error(path, JsonErrorKind::BadElemTypeId(elem_type_id))),
+        }
+    }
+
+    fn read(
+        &self,
+        shape: &Shape,
+        json: &Json,
+        aggregates: &mut AggHeap,
+        path: &mut String,
+    ) -> Result<Value, JsonError> {
+        let mismatch = |expected| {
+            error(
+                path,
+                JsonErrorKind::TypeMismatch {
+                    expected,
+                    found: json_kind(json),
+                },
+            )
+        };
+        let ty = match shape {
+            Shape::Value(ValueType::Agg) | Shape::Unknown => {
+                return Err(error(path, JsonErrorKind::UnknownShape));
+            }
+            Shape::Value(ty) => *ty,
+            Shape::Tuple(elems) => {
+                let items = json.as_array().ok_or_else(|| mismatch("array"))?;
+                if items.len() != elems.len() {
+                    return Err(error(
+                        path,
+                        JsonErrorKind::LengthMismatch {
+                            expected: elems.len(),
+                            actual: items.len(),
+                        },
+                    ));
+                }
+                let mut values = Vec::with_capacity(items.len());
+                for (i, (shape, item)) in elems.iter().zip(items).enumerate() {
+                    values.push(self.read_at(shape, item, aggregates, path, Index(i))?);
+                }
+                return Ok(Value::Agg(aggregates.tuple_new(values)));
+            }
+            Shape::Struct(type_id) => {
+                let object = json.as_object().ok_or_else(|| mismatch("object"))?;
+                let fields = self.fields(type_id.0, path)?;
+                if let Some(key) = object
+                    .keys()
+                    .find(|k| !fields.iter().any(|(name, _)| name == k))
+                {
+                    return Err(error(path, JsonErrorKind::UnknownField(key.clone())));
+                }
+                let mut values = Vec::with_capacity(fields.len());
+                for (name, shape) in &fields {
+                    let item = object
+                        .get(*name)
+                        .ok_or_else(|| error(path, JsonErrorKind::MissingField((*name).into())))?;
+                    values.push(self.read_at(shape, item, aggregates, path, Field(name))?);
+                }
+                return Ok(Value::Agg(aggregates.struct_new(*type_id, values)));
+            }
+            Shape::Array(elem_type_id) => {
+                let items = json.as_array().ok_or_else(|| mismatch("array"))?;
+                let shape = self.elem_shape(elem_type_id.0, path)?;
+                let mut values = Vec::with_capacity(items.len());
+                for (i, item) in items.iter().enumerate() {
+                    values.push(self.read_at(&shape, item, aggregates, path, Index(i))?);
+                }
+                return Ok(Value::Agg(aggregates.array_new(*elem_type_id, values)));
+            }
+        };
+        Ok(match ty {
+            ValueType::Unit => match json {
+                Json::Null => Value::Unit,
+                _ => return Err(mismatch("null")),
+            },
+            ValueType::Bool => Value::Bool(json.as_bool().ok_or_else(|| mismatch("boolean"))?),
+            ValueType::I64 => Value::I64(
+                json_int(json, Number::as_i64).map_err(mismatch_or(path, json, "integer"))?,
+            ),
+            ValueType::U64 => Value::U64(json_int(json, Number::as_u64).map_err(mismatch_or(
+                path,
+                json,
+                "unsigned integer",
+            ))?),
+            ValueType::F64 => Value::F64(json.as_f64().ok_or_else(|| mismatch("number"))?),
+            ValueType::Decimal => {
+                let s = json.as_str().ok_or_else(|| mismatch("decimal string"))?;
+                Value::Decimal(
+                    parse_decimal(s).ok_or_else(|| error(path, JsonErrorKind::InvalidDecimal))?,
+                )
+            }
+            ValueType::Bytes => {
+                let s = json.as_str().ok_or_else(|| mismatch("hex string"))?;
+                Value::Bytes(parse_hex(s).ok_or_else(|| error(path, JsonErrorKind::InvalidBytes))?)
+            }
+            ValueType::Str => Value::Str(json.as_str().ok_or_else(|| mismatch("string"))?.into()),
+            ty @ (ValueType::Obj(_) | ValueType::Func | ValueType::Closure | ValueType::Agg) => {
+                return Err(error(path, JsonErrorKind::Unsupported(ty)));
+            }
+        })
+    }
+
+    fn read_at(
+        &self,
+        shape: &Shape,
+        json: &Json,
+        aggregates: &mut AggHeap,
+        path: &mut String,
+        segment: impl PathSegment,
+    ) -> Result<Value, JsonError> {
+        let len = path.len();
+        segment.push_to(path);
+        let value = self.read(shape, json, aggregates, path)?;
+        path.truncate(len);
+        Ok(value)
+    }
+
+    fn write(
+        &self,
+        value: &Value,
+        aggregates: &AggHeap,
+        path: &mut String,
+    ) -> Result<Json, JsonError> {
+        Ok(match value {
+            Value::Unit => Json::Null,
+            Value::Bool(b) => Json::Bool(*b),
+            Value::I64(i) => Json::from(*i),
+            Value::U64(u) => Json::from(*u),
+            Value::F64(x) => Json::Number(
+                Number::from_f64(*x).ok_or_else(|| error(path, JsonErrorKind::NonFiniteFloat))?,
+            ),
+            Value::Decimal(d) => Json::String(format_decimal(*d)),
+            Value::Bytes(b) => Json::String(format_hex(b)),
+            Value::Str(s) => Json::String(s.clone()),
+            Value::Agg(handle) => self.write_agg(*handle, aggregates, path)?,
+            Value::Obj(_) | Value::Func(_) | Value::Closure(_) => {
+                return Err(error(path, JsonErrorKind::Unsupported(value.value_type())));
+            }
+        })
+    }
+
+    fn write_agg(
+        &self,
+        handle: AggHandle,
+        aggregates: &AggHeap,
+        path: &mut String,
+    ) -> Result<Json, JsonError> {
+        match aggregates
+            .agg_type(handle)
+            .map_err(|e| agg_error(path, e))?
+        {
+            AggType::Tuple { .. } => {
+                let len = aggregates
+                    .tuple_len(handle)
+                    .map_err(|e| agg_error(path, e))?;
+                let mut items = Vec::with_capacity(len);
+                for i in 0..len {
+                    let item = aggregates
+                        .tuple_get_ref(handle, i)
+                        .map_err(|e| agg_error(path, e))?;
+                    items.push(self.write_at(item, aggregates, path, Index(i))?);
+                }
+                Ok(Json::Array(items))
+            }
+            AggType::Struct { type_id } => {
+                let fields = self.fields(type_id.0, path)?;
+                let count = aggregates
+                    .struct_field_count(handle)
+                    .map_err(|e| agg_error(path, e))?;
+                if count != fields.len() {
+                    return Err(agg_error(path, AggError::BadArity));
+                }
+                let mut object = Map::new();
+                for (i, (name, _)) in fields.into_iter().enumerate() {
+                    let item = aggregates
+                        .struct_get_ref(handle, i)
+                        .map_err(|e| agg_error(path, e))?;
+                    let json = self.write_at(item, aggregates, path, Field(name))?;
+                    object.insert(name.into(), json);
+                }
+                Ok(Json::Object(object))
+            }
+            AggType::Array { .. } => {
+                let len = aggregates
+                    .array_len(handle)
+                    .map_err(|e| agg_error(path, e))?;
+                let mut items = Vec::with_capacity(len);
+                for i in 0..len {
+                    let item = aggregates
+                        .array_get_ref(handle, i)
+                        .map_err(|e| agg_error(path, e))?;
+                    items.push(self.write_at(item, aggregates, path, Index(i))?);
+                }
+                Ok(Json::Array(items))
+            }
+        }
+    }
+
+    fn write_at(
+        &self,
+        value: &Value,
+        aggregates: &AggHeap,
+        path: &mut String,
+        segment: impl PathSegment,
+    ) -> Result<Json, JsonError> {
+        let len = path.len();
+        segment.push_to(path);
+        let json = self.write(value, aggregates, path)?;
+        path.truncate(len);
+        Ok(json)
+    }
+}
+
+trait PathSegment {
+    fn push_to(self, path: &mut String);
+}
+
+struct Index(usize);
+
+impl PathSegment for Index {
+    fn push_to(self, path: &mut String) {
+        let _ = write!(path, "[{}]", self.0);
+    }
+}
+
+struct Field<'a>(&'a str);
+
+impl PathSegment for Field<'_> {
+    fn push_to(self, path: &mut String) {
+        let plain = self
+            .0
+            .starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && self
+                .0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if plain {
+            path.push('.');
+            path.push_str(self.0);
+        } else {
+            let _ = write!(path, "[{}]", Json::from(self.0));
+        }
+    }
+}
+
+fn error(path: &str, kind: JsonErrorKind) -> JsonError {
+    JsonError {
+        path: path.into(),
+        kind,
+    }
+}
+
+fn agg_error(path: &str, e: AggError) -> JsonError {
+    error(path, JsonErrorKind::Agg(e))
+}
+
+fn json_kind(json: &Json) -> &'static str {
+    match json {
+        Json::Null => "null",
+        Json::Bool(_) => "boolean",
+        Json::Number(n) if n.is_f64() => "number",
+        Json::Number(_) => "integer",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
+
+/// Reads an integer, distinguishing a non-integer (`Err(false)`) from one out of range
+/// (`Err(true)`).
+fn json_int<T>(json: &Json, get: impl FnOnce(&Number) -> Option<T>) -> Result<T, bool> {
+    match json {
+        Json::Number(n) if !n.is_f64() => get(n).ok_or(true),
+        _ => Err(false),
+    }
+}
+
+fn mismatch_or<'a>(
+    path: &'a str,
+    json: &'a Json,
+    expected: &'static str,
+) -> impl FnOnce(bool) -> JsonError + 'a {
+    move |out_of_range| {
+        let kind = if out_of_range {
+            JsonErrorKind::IntOutOfRange
+        } else {
+            JsonErrorKind::TypeMismatch {
+                expected,
+                found: json_kind(json),
+            }
+        };
+        error(path, kind)
+    }
+}
+
+/// Formats a decimal with exactly `scale` fractional digits, e.g. `-0.050` for mantissa `-50` and
+/// scale 3.
+fn format_decimal(d: Decimal) -> String {
+    let digits = d.mantissa.unsigned_abs().to_string();
+    let scale = usize::from(d.scale);
+    let mut out = String::with_capacity(digits.len() + scale + 2);
+    if d.mantissa < 0 {
+        out.push('-');
+    }
+    if scale == 0 {
+        out.push_str(&digits);
+        return out;
+    }
+    if digits.len() <= scale {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', scale - digits.len()));
+        out.push_str(&digits);
+    } else {
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        out.push_str(int);
+        out.push('.');
+        out.push_str(frac);
+    }
+    out
+}
+
+/// Parses `-?digits(.digits)?`; the scale is the number of fractional digits.
+fn parse_decimal(s: &str) -> Option<Decimal> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (int, frac) = match s.split_once('.') {
+        Some((int, frac)) => (int, frac),
+        None => (s, ""),
+    };
+    if int.is_empty() || (s.contains('.') && frac.is_empty()) {
+        return None;
+    }
+    let scale = u8::try_from(frac.len()).ok()?;
+    let mut magnitude: u64 = 0;
+    for c in int.bytes().chain(frac.bytes()) {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        magnitude = magnitude
+            .checked_mul(10)?
+            .checked_add(u64::from(c - b'0'))?;
+    }
+    let mantissa = if negative {
+        0_i64.checked_sub_unsigned(magnitude)?
+    } else {
+        i64::try_from(magnitude).ok()?
+    };
+    Some(Decimal { mantissa, scale })
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{b:02x}");
+    }
+    out
+}
+
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    s.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            let hi = char::from(pair[0]).to_digit(16)?;
+            let lo = char::from(pair[1]).to_digit(16)?;
+            u8::try_from(hi << 4 | lo).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use execution_tape::program::{StructTypeDef, TypeTableDef};
+    use serde_json::json;
+
+    /// `Order { id: u64, total: Decimal, lines: [Line] }`, `Line { sku: str, qty: i64 }`.
+    fn types() -> TypeTable {
+        TypeTable::from(TypeTableDef {
+            structs: vec![
+                StructTypeDef {
+                    field_names: vec!["id".into(), "total".into(), "lines".into()],
+                    field_types: vec![ValueType::U64, ValueType::Decimal, ValueType::Agg],
+                },
+                StructTypeDef {
+                    field_names: vec!["sku".into(), "qty".into()],
+                    field_types: vec![ValueType::Str, ValueType::I64],
+                },
+            ],
+            array_elems: vec![ValueType::Agg, ValueType::Bytes],
+        })
+    }
+
+    fn converter(types: &TypeTable) -> JsonConverter<'_> {
+        JsonConverter::new(types)
+            .with_field_shape(TypeId(0), "lines", Shape::Array(ElemTypeId(0)))
+            .with_elem_shape(ElemTypeId(0), Shape::Struct(TypeId(1)))
+    }
+
+    #[test]
+    fn nested_values_roundtrip() {
+        let types = types();
+        let conv = converter(&types);
+        let shape = Shape::Tuple(vec![
+            Shape::Struct(TypeId(0)),
+            Shape::Array(ElemTypeId(1)),
+            Shape::Value(ValueType::F64),
+            Shape::Value(ValueType::Bool),
+            Shape::Value(ValueType::Unit),
+            Shape::Value(ValueType::I64),
+        ]);
+        let json = json!([
+            {
+                "id": 18446744073709551615_u64,
+                "total": "-1234.50",
+                "lines": [{ "sku": "a-1", "qty": 2 }, { "sku": "b", "qty": -1 }],
+            },
+            ["00ff", ""],
+            0.25,
+            true,
+            null,
+            -9223372036854775808_i64,
+        ]);
+
+        let mut heap = AggHeap::new();
+        let value = conv.from_json(&shape, &json, &mut heap).unwrap();
+        let Value::Agg(tuple) = value else {
+            panic!("expected a tuple, got {value:?}");
+        };
+        let Value::Agg(order) = heap.tuple_get(tuple, 0).unwrap() else {
+            panic!("expected a struct");
+        };
+        assert_eq!(
+            heap.struct_get(order, 1).unwrap(),
+            Value::Decimal(Decimal {
+                mantissa: -123450,
+                scale: 2,
+            })
+        );
+        assert_eq!(conv.to_json(&Value::Agg(tuple), &heap).unwrap(), json);
+    }
+
+    #[test]
+    fn decimals_are_lossless() {
+        for s in [
+            "0",
+            "-7",
+            "0.05",
+            "-0.050",
+            "12.340",
+            "-9223372036854775.808",
+        ] {
+            let d = parse_decimal(s).unwrap();
+            assert_eq!(format_decimal(d), s);
+        }
+        assert_eq!(
+            parse_decimal("9223372036854775807"),
+            Some(Decimal {
+                mantissa: i64::MAX,
+                scale: 0,
+            })
+        );
+        for s in [
+            "",
+            "-",
+            "1.",
+            ".5",
+            "+1",
+            "1e3",
+            "1.2.3",
+            "9223372036854775808",
+        ] {
+            assert_eq!(parse_decimal(s), None, "{s:?}");
+        }
+    }
+
+    #[test]
+    fn errors_carry_json_paths() {
+        let types = types();
+        let conv = converter(&types);
+        let mut heap = AggHeap::new();
+        let order = Shape::Struct(TypeId(0));
+        let mut read = |shape: &Shape, json: Json| conv.from_json(shape, &json, &mut heap);
+
+        let err = read(
+            &order,
+            json!({ "id": 1, "total": "1", "lines": [{ "sku": "a", "qty": 1 }, { "sku": "b", "qty": 1.5 }] }),
+        )
+        .unwrap_err();
+        assert_eq!(err.path, "$.lines[1].qty");
+        assert_eq!(
+            err.to_string(),
+            "$.lines[1].qty: expected integer, found number"
+        );
+
+        let err = read(&order, json!({ "id": -1, "total": "1", "lines": [] })).unwrap_err();
+        assert_eq!(
+            (err.path.as_str(), err.kind),
+            ("$.id", JsonErrorKind::IntOutOfRange)
+        );
+
+        let err = read(&order, json!({ "id": 1, "total": 1.5, "lines": [] })).unwrap_err();
+        assert_eq!(err.path, "$.total");
+        assert!(matches!(err.kind, JsonErrorKind::TypeMismatch { .. }));
+
+        let err = read(&order, json!({ "id": 1, "lines": [] })).unwrap_err();
+        assert_eq!(err.kind, JsonErrorKind::MissingField("total".into()));
+
+        let err = read(
+            &order,
+            json!({ "id": 1, "total": "1", "lines": [], "extra field": 0 }),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind, JsonErrorKind::UnknownField("extra field".into()));
+
+        let err = read(&Shape::Array(ElemTypeId(1)), json!(["0g"])).unwrap_err();
+        assert_eq!(
+            (err.path.as_str(), err.kind),
+            ("$[0]", JsonErrorKind::InvalidBytes)
+        );
+
+        // Without a registered shape, an `Agg` field cannot be read.
+        let bare = JsonConverter::new(&types);
+        let err = bare
+            .from_json(
+                &order,
+                &json!({ "id": 1, "total": "1", "lines": [] }),
+                &mut AggHeap::new(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            (err.path.as_str(), err.kind),
+            ("$.lines", JsonErrorKind::UnknownShape)
+        );
+
+        let err = conv
+            .to_json(&Value::F64(f64::NAN), &AggHeap::new())
+            .unwrap_err();
+        assert_eq!(err.kind, JsonErrorKind::NonFiniteFloat);
+    }
+
+    #[test]
+    fn agg_shape_converts_to_shape() {
+        assert_eq!(
+            Shape::from(AggShape::tuple([
+                Some(ValueType::I64),
+                Some(ValueType::Agg),
+                None
+            ])),
+            Shape::Tuple(vec![
+                Shape::Value(ValueType::I64),
+                Shape::Unknown,
+                Shape::Unknown
+            ])
+        );
+        let value = JsonConverter::new(&TypeTable::default())
+            .from_json(
+                &AggShape::tuple([Some(ValueType::Str)]).into(),
+                &json!(["x"]),
+                &mut AggHeap::new(),
+            )
+            .unwrap();
+        assert!(matches!(value, Value::Agg(_)));
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use core::fmt;
+
+use execution_tape::aggregates::AggError;
+use execution_tape::program::ValueType;
+
+/// A JSON conversion error, located by the JSON path of the offending value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonError {
+    /// Path from the root (`$`) to the offending value, e.g. `$.items[2].price`.
+    pub path: String,
+    /// What went wrong.
+    pub kind: JsonErrorKind,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// The kind of a [`JsonError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonErrorKind {
+    /// The JSON value has the wrong kind.
+    TypeMismatch {
+        /// Expected JSON kind (e.g. `"integer"`, `"decimal string"`).
+        expected: &'static str,
+        /// Actual JSON kind.
+        found: &'static str,
+    },
+    /// An integer does not fit the target type.
+    IntOutOfRange,
+    /// A float is NaN or infinite, which JSON cannot represent.
+    NonFiniteFloat,
+    /// A decimal string is malformed or does not fit a `Decimal`.
+    InvalidDecimal,
+    /// A bytes string is not valid hex.
+    InvalidBytes,
+    /// A tuple has the wrong number of elements.
+    LengthMismatch {
+        /// Expected element count.
+        expected: usize,
+        /// Actual element count.
+        actual: usize,
+    },
+    /// A struct field is missing from the JSON object.
+    MissingField(String),
+    /// The JSON object has a key that is not a field of the struct.
+    UnknownField(String),
+    /// The shape of an aggregate is not known, so JSON cannot be converted into it.
+    UnknownShape,
+    /// The value type has no JSON representation.
+    Unsupported(ValueType),
+    /// A struct type id is not in the type table.
+    BadTypeId(u32),
+    /// An array element type id is not in the type table.
+    BadElemTypeId(u32),
+    /// Aggregate heap error.
+    Agg(AggError),
+}
+
+impl fmt::Display for JsonErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            Self::IntOutOfRange => write!(f, "integer out of range"),
+            Self::NonFiniteFloat => write!(f, "float is not finite"),
+            Self::InvalidDecimal => write!(f, "invalid decimal"),
+            Self::InvalidBytes => write!(f, "invalid hex bytes"),
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "expected {expected} elements, found {actual}")
+            }
+            Self::MissingField(name) => write!(f, "missing field `{name}`"),
+            Self::UnknownField(name) => write!(f, "unknown field `{name}`"),
+            Self::UnknownShape => write!(f, "aggregate shape is unknown"),
+            Self::Unsupported(ty) => write!(f, "{ty:?} values have no JSON representation"),
+            Self::BadTypeId(id) => write!(f, "type id {id} out of bounds"),
+            Self::BadElemTypeId(id) => write!(f, "elem type id {id} out of bounds"),
+            Self::Agg(e) => write!(f, "aggregate error: {e}"),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2026 the Execution Tape Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Type-directed JSON conversion for `execution_tape` values.
+//!
+//! This crate is `std`-only and keeps `execution_tape` itself free of JSON dependencies.
+//! A [`JsonConverter`] converts [`serde_json::Value`]s into [`Value`]s of a given [`Shape`]
+//! (built from a [`ValueType`] or an [`AggShape`]), allocating tuples, structs and arrays in an
+//! [`AggHeap`], and converts values back into JSON. Struct fields are matched by the field names
+//! of the program's [`TypeTable`]. Errors carry the JSON path of the offending value.
+//!
+//! ## Representation
+//!
+//! | value type | JSON                                                                |
+//! |------------|---------------------------------------------------------------------|
+//! | `Unit`     | `null`                                                              |
+//! | `Bool`     | boolean                                                             |
+//! | `I64`      | integer                                                             |
+//! | `U64`      | non-negative integer                                                |
+//! | `F64`      | number (NaN and infinities are rejected)                            |
+//! | `Decimal`  | string with exactly `scale` fractional digits, e.g. `"-12.50"`      |
+//! | `Bytes`    | lowercase hex string                                                |
+//! | `Str`      | string                                                              |
+//! | tuple      | array                                                               |
+//! | struct     | object keyed by field name (all fields required, no extra keys)     |
+//! | array      | array                                                               |
+//!
+//! Decimals are strings so that they round-trip without loss; the number of fractional digits is
+//! the scale. Host objects, functions and closures have no JSON representation.
+//!
+//! ## Example
+//! ```
+//! use execution_tape::aggregates::AggHeap;
+//! use execution_tape::program::{StructTypeDef, TypeId, TypeTable, TypeTableDef, ValueType};
+//! use execution_tape_json::{JsonConverter, Shape};
+//! use serde_json::json;
+//!
+//! let types = TypeTable::from(TypeTableDef {
+//!     structs: vec![StructTypeDef {
+//!         field_names: vec!["name".into(), "price".into()],
+//!         field_types: vec![ValueType::Str, ValueType::Decimal],
+//!     }],
+//!     array_elems: vec![],
+//! });
+//! let conv = JsonConverter::new(&types);
+//!
+//! let mut heap = AggHeap::new();
+//! let json = json!({ "name": "widget", "price": "9.99" });
+//! let value = conv.from_json(&Shape::Struct(TypeId(0)), &json, &mut heap)?;
+//! assert_eq!(conv.to_json(&value, &heap)?, json);
+//!
+//! let err = conv
+//!     .from_json(&Shape::Struct(TypeId(0)), &json!({ "name": 1, "price": "1" }), &mut heap)
+//!     .unwrap_err();
+//! assert_eq!(err.to_string(), "$.name: expected string, found integer");
+//! # Ok::<(), execution_tape_json::JsonError>(())
+//! ```
+
+mod convert;
+mod error;
+
+pub use convert::{JsonConverter, Shape};
+pub use error::{JsonError, JsonErrorKind};
+
+#[cfg(doc)]
+use execution_tape::{
+    aggregates::AggHeap,
+    program::{AggShape, TypeTable, ValueType},
+    value::Value,
+};
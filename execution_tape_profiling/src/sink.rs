@@ -4,8 +4,11 @@
 use crate::resolver::{
     DefaultLabelResolver, LabelResolver, default_call_frame_label, default_host_call_label,
 };
-use execution_tape::program::Program;
+use execution_tape::program::{HostSigId, Program, SpanId, SpanInfo};
 use execution_tape::trace::{ScopeKind, TraceMask, TraceSink};
+use execution_tape::value::FuncId;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::string::String;
 use std::vec::Vec;
 
@@ -15,14 +18,33 @@ struct ScopeEntry {
     kind: ScopeKind,
     depth: usize,
     // Keep the label alive for backends that may borrow it.
-    label: String,
+    label: Rc<str>,
     guard: Option<BackendGuard>,
 }
 
+/// What a scope label is resolved from: the scope's function or host signature.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum LabelKey {
+    CallFrame(FuncId),
+    HostCall(HostSigId),
+}
+
+impl From<ScopeKind> for LabelKey {
+    fn from(kind: ScopeKind) -> Self {
+        match kind {
+            ScopeKind::CallFrame { func } => Self::CallFrame(func),
+            ScopeKind::HostCall { host_sig, .. } => Self::HostCall(host_sig),
+        }
+    }
+}
+
 /// A `TraceSink` that emits Tracy scopes via `tracy-client`.
 pub struct ProfilingTraceSink<R = DefaultLabelResolver> {
     resolver: R,
     stack: Vec<ScopeEntry>,
+    // Labels resolved during the current run, by function/host signature and span. Cleared when
+    // the outermost scope exits, since the next run may use a different program.
+    labels: HashMap<(LabelKey, Option<u64>), Rc<str>>,
 }
 
 impl ProfilingTraceSink<DefaultLabelResolver> {
@@ -40,12 +62,31 @@ impl<R: LabelResolver> ProfilingTraceSink<R> {
         Self {
             resolver,
             stack: Vec::new(),
+            labels: HashMap::new(),
         }
     }
 
-    fn on_scope_enter(&mut self, program: &Program, kind: ScopeKind, depth: usize, pc: u32) {
-        let label = self.resolve_label(program, kind);
-        let guard = self.start_scope(kind, &label, pc);
+    fn on_scope_enter(
+        &mut self,
+        program: &Program,
+        kind: ScopeKind,
+        depth: usize,
+        pc: u32,
+        span_id: Option<u64>,
+    ) {
+        let source = span_id
+            .and_then(|id| SpanId::try_from(id).ok())
+            .and_then(|span| program.span_info(span));
+        let label = match self.labels.get(&(kind.into(), span_id)) {
+            Some(label) => Rc::clone(label),
+            None => {
+                let label: Rc<str> = self.scope_label(program, kind, source.as_ref()).into();
+                self.labels
+                    .insert((kind.into(), span_id), Rc::clone(&label));
+                label
+            }
+        };
+        let guard = self.start_scope(kind, &label, pc, source.as_ref());
         self.stack.push(ScopeEntry {
             kind,
             depth,
@@ -67,12 +108,30 @@ impl<R: LabelResolver> ProfilingTraceSink<R> {
                 } = entry;
                 let _ = (_label, _guard);
             }
+            if self.stack.is_empty() {
+                self.labels.clear();
+            }
             return;
         }
         // If the stack got out of sync, drop any active scopes to avoid leaking.
         self.drop_active_scopes();
     }
 
+    /// Resolves the scope label, suffixed with the source-map location of the scope's span (if the
+    /// program has one).
+    fn scope_label(
+        &mut self,
+        program: &Program,
+        kind: ScopeKind,
+        source: Option<&SpanInfo<'_>>,
+    ) -> String {
+        let label = self.resolve_label(program, kind);
+        match source {
+            Some(info) => format!("{label} @ {info}"),
+            None => label,
+        }
+    }
+
     fn resolve_label(&mut self, program: &Program, kind: ScopeKind) -> String {
         match kind {
             ScopeKind::CallFrame { func } => self
@@ -86,13 +145,25 @@ impl<R: LabelResolver> ProfilingTraceSink<R> {
         }
     }
 
-    fn start_scope(&self, kind: ScopeKind, label: &str, pc: u32) -> Option<BackendGuard> {
+    /// Starts a backend scope. With source info, the scope's source location is the span's file
+    /// and line; otherwise it is `execution_tape` at line `pc`.
+    fn start_scope(
+        &self,
+        kind: ScopeKind,
+        label: &str,
+        pc: u32,
+        source: Option<&SpanInfo<'_>>,
+    ) -> Option<BackendGuard> {
         let function_name = match kind {
             ScopeKind::CallFrame { .. } => "execution_tape.call_frame",
             ScopeKind::HostCall { .. } => "execution_tape.host_call",
         };
+        let (file, line) = match source {
+            Some(info) => (info.file, info.range.start_line),
+            None => ("execution_tape", pc),
+        };
         let client = tracy_client::Client::running()?;
-        Some(client.span_alloc(Some(label), function_name, "execution_tape", pc, 0))
+        Some(client.span_alloc(Some(label), function_name, file, line, 0))
     }
 
     // Drop in LIFO order so nested spans close inner-to-outer.
@@ -105,6 +176,7 @@ impl<R: LabelResolver> ProfilingTraceSink<R> {
             } = entry;
             let _ = (_label, _guard);
         }
+        self.labels.clear();
     }
 }
 
@@ -118,11 +190,11 @@ impl<R: LabelResolver> TraceSink for ProfilingTraceSink<R> {
         program: &Program,
         kind: ScopeKind,
         depth: usize,
-        _func: execution_tape::value::FuncId,
+        _func: FuncId,
         pc: u32,
-        _span_id: Option<u64>,
+        span_id: Option<u64>,
     ) {
-        self.on_scope_enter(program, kind, depth, pc);
+        self.on_scope_enter(program, kind, depth, pc, span_id);
     }
 
     fn scope_exit(
@@ -130,7 +202,7 @@ impl<R: LabelResolver> TraceSink for ProfilingTraceSink<R> {
         _program: &Program,
         kind: ScopeKind,
         depth: usize,
-        _func: execution_tape::value::FuncId,
+        _func: FuncId,
         _pc: u32,
         _span_id: Option<u64>,
     ) {
@@ -151,19 +223,72 @@ impl<R> std::fmt::Debug for ProfilingTraceSink<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ProfilingTraceSink")
             .field("stack_depth", &self.stack.len())
+            .field("cached_labels", &self.labels.len())
             .finish_non_exhaustive()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ProfilingTraceSink;
+    use super::{LabelKey, ProfilingTraceSink};
+    use execution_tape::asm::ProgramBuilder;
+    use execution_tape::program::{SourceRange, SpanId};
     use execution_tape::trace::ScopeKind;
     use execution_tape::value::FuncId;
+    use std::rc::Rc;
 
     #[test]
     fn start_scope_without_tracy_client_does_not_panic() {
         let sink = ProfilingTraceSink::new();
-        let _guard = sink.start_scope(ScopeKind::CallFrame { func: FuncId(0) }, "test", 0);
+        let _guard = sink.start_scope(ScopeKind::CallFrame { func: FuncId(0) }, "test", 0, None);
+    }
+
+    #[test]
+    fn labels_include_source_map_location() {
+        let mut pb = ProgramBuilder::new();
+        let span = SpanId::try_from(3).unwrap();
+        pb.set_span_info(
+            span,
+            "main.graph",
+            SourceRange {
+                start_line: 4,
+                start_col: 2,
+                end_line: 4,
+                end_col: 10,
+            },
+            Some("node-3"),
+        );
+        let program = pb.build();
+        let kind = ScopeKind::CallFrame { func: FuncId(0) };
+
+        let mut sink = ProfilingTraceSink::new();
+        let info = program.span_info(span);
+        assert_eq!(
+            sink.scope_label(&program, kind, info.as_ref()),
+            "func:0 @ main.graph:4:2 (node node-3)"
+        );
+        assert_eq!(sink.scope_label(&program, kind, None), "func:0");
+    }
+
+    #[test]
+    fn labels_are_cached_for_the_current_run() {
+        let program = ProgramBuilder::new().build();
+        let outer = ScopeKind::CallFrame { func: FuncId(0) };
+        let inner = ScopeKind::CallFrame { func: FuncId(1) };
+
+        let mut sink = ProfilingTraceSink::new();
+        sink.on_scope_enter(&program, outer, 0, 0, None);
+        sink.on_scope_enter(&program, inner, 1, 0, Some(3));
+        sink.on_scope_exit(inner, 1);
+        sink.on_scope_enter(&program, inner, 1, 0, Some(3));
+        assert_eq!(sink.labels.len(), 2);
+        assert!(Rc::ptr_eq(
+            &sink.stack[1].label,
+            &sink.labels[&(LabelKey::CallFrame(FuncId(1)), Some(3))]
+        ));
+
+        sink.on_scope_exit(inner, 1);
+        sink.on_scope_exit(outer, 0);
+        assert!(sink.labels.is_empty());
     }
 }
@@ -640,8 +640,14 @@ fn build_host_call_loop(iters: u64) -> execution_tape::verifier::VerifiedProgram
 fn wide_open_limits() -> Limits {
     Limits {
         fuel: u64::MAX,
+        cost_model: None,
         max_call_depth: 1024,
         max_host_calls: u64::MAX,
+        max_regs_total: usize::MAX,
+        max_arena_bytes: usize::MAX,
+        max_agg_nodes: usize::MAX,
+        max_agg_values: usize::MAX,
+        capture_backtrace: false,
     }
 }
 